@@ -63,6 +63,16 @@ async fn main() -> Result<()> {
                         .arg(Arg::new("name").help("Collection name").required(true))
                         .arg(Arg::new("confirm").long("confirm").help("Skip confirmation prompt").action(clap::ArgAction::SetTrue))
                 )
+                .subcommand(
+                    Command::new("evaluate")
+                        .about("Measure index search recall against exact search")
+                        .arg(Arg::new("name").help("Collection name").required(true))
+                        .arg(Arg::new("ef-search").long("ef-search").help("Comma-separated ef_search values (default: the collection's)").value_delimiter(','))
+                        .arg(Arg::new("limit").long("limit").short('l').help("Results per query, the k of recall@k").default_value("10"))
+                        .arg(Arg::new("sample").long("sample").help("Stored vectors to query with").default_value("100"))
+                        .arg(Arg::new("queries").long("queries").help("JSON file with an array of query vectors, instead of sampling"))
+                        .arg(Arg::new("using").long("using").help("Named vector space to evaluate"))
+                )
         )
         .subcommand(
             Command::new("vectors")
@@ -89,6 +99,7 @@ async fn main() -> Result<()> {
                         .arg(Arg::new("vector").help("Query vector as JSON array").required(true))
                         .arg(Arg::new("limit").long("limit").short('l').help("Number of results").default_value("10"))
                         .arg(Arg::new("ef-search").long("ef-search").help("EF search parameter"))
+                        .arg(Arg::new("exact").long("exact").help("Scan every stored vector instead of searching the index").action(clap::ArgAction::SetTrue))
                 )
                 .subcommand(
                     Command::new("get")
@@ -178,6 +189,9 @@ async fn handle_collections_command(client: &dyn VectorDbClient, matches: &ArgMa
                 vector_type: VectorType::Float32,
                 index_config: IndexConfig::default(),
                 quantization: None,
+                sparse_vectors: None,
+                vectors: None,
+                multivectors: None,
             };
 
             client.create_collection(&config).await?;
@@ -206,6 +220,10 @@ async fn handle_collections_command(client: &dyn VectorDbClient, matches: &ArgMa
                     println!("  Vector Type: {:?}", config.vector_type);
                     println!("  Vector Count: {}", stats.vector_count);
                     println!("  Memory Usage: {} bytes", stats.memory_usage);
+                    println!("    Vectors: {} bytes", stats.memory.vectors);
+                    println!("    Index: {} bytes", stats.memory.index);
+                    println!("    Payload: {} bytes", stats.memory.payload);
+                    println!("    Quantized: {} bytes", stats.memory.quantized);
                     println!("  Index Size: {} bytes", stats.index_size);
                 }
                 Err(e) => {
@@ -230,6 +248,68 @@ async fn handle_collections_command(client: &dyn VectorDbClient, matches: &ArgMa
             client.delete_collection(name).await?;
             println!("{}", format!("✓ Collection '{}' deleted successfully", name).green());
         }
+        Some(("evaluate", sub_matches)) => {
+            let name = sub_matches.get_one::<String>("name").unwrap();
+            let ef_search = sub_matches
+                .get_many::<String>("ef-search")
+                .unwrap_or_default()
+                .map(|ef| ef.parse())
+                .collect::<std::result::Result<Vec<usize>, _>>()?;
+            let queries: Vec<Vec<f32>> = match sub_matches.get_one::<String>("queries") {
+                Some(path) => serde_json::from_str(&std::fs::read_to_string(path)?)?,
+                None => Vec::new(),
+            };
+
+            let request = vectordb_common::search_api::EvaluateRequest {
+                collection: name.clone(),
+                queries,
+                sample: sub_matches.get_one::<String>("sample").unwrap().parse()?,
+                limit: sub_matches.get_one::<String>("limit").unwrap().parse()?,
+                ef_search,
+                using: sub_matches.get_one::<String>("using").cloned(),
+            };
+            let report = client.evaluate(&request).await?;
+
+            println!("{}", format!("Recall@{} over {} queries", report.limit, report.queries).bold());
+            println!(
+                "  Exact search: p50 {:.2} ms, p95 {:.2} ms, p99 {:.2} ms",
+                report.exact_latency.p50_ms, report.exact_latency.p95_ms, report.exact_latency.p99_ms
+            );
+
+            #[derive(Tabled)]
+            struct EvaluationTable {
+                #[tabled(rename = "EF Search")]
+                ef_search: usize,
+                #[tabled(rename = "Recall")]
+                recall: String,
+                #[tabled(rename = "p50 ms")]
+                p50: String,
+                #[tabled(rename = "p95 ms")]
+                p95: String,
+                #[tabled(rename = "p99 ms")]
+                p99: String,
+                #[tabled(rename = "Visited (mean)")]
+                visited_mean: String,
+                #[tabled(rename = "Visited (max)")]
+                visited_max: usize,
+            }
+
+            let table_data: Vec<EvaluationTable> = report
+                .runs
+                .into_iter()
+                .map(|run| EvaluationTable {
+                    ef_search: run.ef_search,
+                    recall: format!("{:.4}", run.recall),
+                    p50: format!("{:.2}", run.latency.p50_ms),
+                    p95: format!("{:.2}", run.latency.p95_ms),
+                    p99: format!("{:.2}", run.latency.p99_ms),
+                    visited_mean: format!("{:.1}", run.visited_mean),
+                    visited_max: run.visited_max,
+                })
+                .collect();
+
+            println!("{}", Table::new(table_data));
+        }
         _ => {
             println!("{}", "No collections subcommand provided".yellow());
         }
@@ -263,6 +343,9 @@ async fn handle_vectors_command(client: &dyn VectorDbClient, matches: &ArgMatche
                 id,
                 data: vector_data,
                 metadata,
+                sparse: None,
+                vectors: None,
+                multivectors: None,
             };
 
             client.insert(collection, &vector).await?;
@@ -273,6 +356,7 @@ async fn handle_vectors_command(client: &dyn VectorDbClient, matches: &ArgMatche
             let vector_str = sub_matches.get_one::<String>("vector").unwrap();
             let limit: usize = sub_matches.get_one::<String>("limit").unwrap().parse()?;
             let ef_search = sub_matches.get_one::<String>("ef-search").map(|s| s.parse().unwrap());
+            let exact = sub_matches.get_flag("exact");
 
             let query_vector: Vec<f32> = serde_json::from_str(vector_str)?;
 
@@ -282,6 +366,11 @@ async fn handle_vectors_command(client: &dyn VectorDbClient, matches: &ArgMatche
                 limit,
                 ef_search,
                 filter: None,
+                using: None,
+                multivector: None,
+                rescore: None,
+                oversampling: None,
+                exact,
             };
 
             let results = client.query(&request).await?;
@@ -361,6 +450,10 @@ async fn handle_stats_command(client: &dyn VectorDbClient) -> Result<()> {
     println!("  Memory Usage: {} bytes ({:.2} MB)", 
              stats.memory_usage, 
              stats.memory_usage as f64 / 1024.0 / 1024.0);
+    println!("    Vectors: {} bytes", stats.memory.vectors);
+    println!("    Index: {} bytes", stats.memory.index);
+    println!("    Payload: {} bytes", stats.memory.payload);
+    println!("    Quantized: {} bytes", stats.memory.quantized);
     println!("  Disk Usage: {} bytes ({:.2} MB)", 
              stats.disk_usage, 
              stats.disk_usage as f64 / 1024.0 / 1024.0);
@@ -1,22 +1,131 @@
 use crate::{VectorDbClient, ClientConfig, ServerStats};
 use vectordb_common::{Result, VectorDbError};
-use vectordb_common::types::{Vector, VectorId, CollectionId, IndexConfig};
+use vectordb_common::types::{Vector, VectorId, CollectionId};
 use vectordb_common::types::{CollectionConfig as CommonCollectionConfig, CollectionStats as CommonCollectionStats};
 use vectordb_common::types::{QueryRequest, QueryResult};
 use vectordb_proto::{vector_db_client::VectorDbClient as ProtoClient};
 use vectordb_proto::{
     CreateCollectionRequest, DeleteCollectionRequest, GetCollectionInfoRequest,
-    ListCollectionsRequest, InsertRequest, DeleteRequest, BatchInsertRequest,
+    ListCollectionsRequest, InsertRequest, DeleteRequest, GetRequest, BatchInsertRequest,
     UpdateRequest, GetStatsRequest, HealthRequest,
-    Vector as ProtoVector
+    StreamInsertRequest, StreamInsertResponse, Vector as ProtoVector, payload_from_proto,
+    payload_to_proto,
 };
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
 use tonic::transport::{Channel, Endpoint};
-use tonic::{Request, Status};
+use tonic::{Request, Status, Streaming};
 use std::collections::HashMap;
 use std::time::Duration;
 use tracing::{info, warn, instrument};
 use uuid::Uuid;
 
+/// Named sparse vectors of `vector` in proto form
+fn sparse_to_proto(vector: &Vector) -> HashMap<String, vectordb_proto::SparseVector> {
+    vector
+        .sparse
+        .iter()
+        .flatten()
+        .map(|(name, sparse)| (name.clone(), sparse.clone().into()))
+        .collect()
+}
+
+/// Named dense vectors of `vector` in proto form
+fn named_vectors_to_proto(vector: &Vector) -> HashMap<String, vectordb_proto::DenseVector> {
+    vector
+        .vectors
+        .iter()
+        .flatten()
+        .map(|(name, data)| (name.clone(), vectordb_proto::DenseVector { data: data.clone() }))
+        .collect()
+}
+
+/// Named multi-vectors of `vector` in proto form
+fn multivectors_to_proto(vector: &Vector) -> HashMap<String, vectordb_proto::MultiVector> {
+    vector
+        .multivectors
+        .iter()
+        .flatten()
+        .map(|(name, rows)| (name.clone(), rows.clone().into()))
+        .collect()
+}
+
+/// Proto form of `vector`
+fn vector_to_proto(vector: &Vector) -> ProtoVector {
+    ProtoVector {
+        id: vector.id.to_string(),
+        data: vector.data.clone(),
+        metadata: payload_to_proto(vector.metadata.clone()),
+        sparse: sparse_to_proto(vector),
+        vectors: named_vectors_to_proto(vector),
+        multivectors: multivectors_to_proto(vector),
+    }
+}
+
+/// Server's acknowledgement of one batch sent through an [`InsertSink`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchAck {
+    /// Id [`InsertSink::send`] returned for the batch
+    pub batch_id: u64,
+    pub inserted_count: usize,
+    /// Why the batch was rejected, `None` when it was written
+    pub error: Option<String>,
+}
+
+/// Sending half of a bulk ingestion stream opened by [`GrpcClient::stream_insert`]
+///
+/// Dropping it, or calling [`InsertSink::close`], ends the stream once the
+/// batches already sent have been delivered.
+pub struct InsertSink {
+    collection: String,
+    next_batch_id: u64,
+    sender: mpsc::Sender<StreamInsertRequest>,
+}
+
+impl InsertSink {
+    /// Queue `vectors` as one batch and return the id its [`BatchAck`] will
+    /// carry. Waits while the stream's buffer of unsent batches is full.
+    pub async fn send(&mut self, vectors: &[Vector]) -> Result<u64> {
+        let batch_id = self.next_batch_id;
+        let batch = StreamInsertRequest {
+            collection_name: self.collection.clone(),
+            batch_id,
+            vectors: vectors.iter().map(vector_to_proto).collect(),
+        };
+
+        self.sender.send(batch).await.map_err(|_| VectorDbError::NetworkError {
+            message: "Bulk ingestion stream closed".to_string(),
+        })?;
+        self.next_batch_id += 1;
+
+        Ok(batch_id)
+    }
+
+    /// End the stream once the batches already sent have been delivered
+    pub fn close(self) {}
+}
+
+/// Receiving half of a bulk ingestion stream opened by [`GrpcClient::stream_insert`]
+pub struct InsertAcks {
+    acks: Streaming<StreamInsertResponse>,
+}
+
+impl InsertAcks {
+    /// Next batch acknowledgement, in the order batches were sent; `None`
+    /// once the sink is closed and every batch has been acknowledged
+    pub async fn next(&mut self) -> Result<Option<BatchAck>> {
+        let ack = self.acks.message().await.map_err(|status| VectorDbError::NetworkError {
+            message: format!("Bulk ingestion stream failed: {}", status),
+        })?;
+
+        Ok(ack.map(|ack| BatchAck {
+            batch_id: ack.batch_id,
+            inserted_count: ack.inserted_count as usize,
+            error: if ack.success { None } else { Some(ack.message) },
+        }))
+    }
+}
+
 /// gRPC client implementation
 pub struct GrpcClient {
     client: ProtoClient<Channel>,
@@ -51,6 +160,35 @@ impl GrpcClient {
         Ok(Self { client, config })
     }
 
+    /// Open a bulk ingestion stream into `collection`
+    ///
+    /// Batches sent on the returned [`InsertSink`] are written in order and
+    /// acknowledged one by one on the returned [`InsertAcks`]. At most
+    /// `buffer` batches wait to be sent before [`InsertSink::send`] blocks;
+    /// past that the server only reads a batch once it has written the
+    /// previous one, so ingestion runs at the speed of the store. The stream
+    /// is not retried: read the acks to find out which batches to resend.
+    pub async fn stream_insert(&self, collection: &str, buffer: usize) -> Result<(InsertSink, InsertAcks)> {
+        let (sender, rx) = mpsc::channel(buffer.max(1));
+
+        let mut client = self.client.clone();
+        let acks = client
+            .stream_insert(Request::new(ReceiverStream::new(rx)))
+            .await
+            .map_err(|status| VectorDbError::NetworkError {
+                message: format!("Failed to open bulk ingestion stream: {}", status),
+            })?
+            .into_inner();
+
+        let sink = InsertSink {
+            collection: collection.to_string(),
+            next_batch_id: 0,
+            sender,
+        };
+
+        Ok((sink, InsertAcks { acks }))
+    }
+
     /// Execute with retry logic
     async fn with_retry<T, F, Fut>(&self, operation: F) -> Result<T>
     where
@@ -103,12 +241,22 @@ impl VectorDbClient for GrpcClient {
             dimension: config.dimension as u32,
             distance_metric: config.distance_metric.into(),
             vector_type: config.vector_type.into(),
-            index_config: Some(vectordb_proto::IndexConfig {
-                max_connections: config.index_config.max_connections as u32,
-                ef_construction: config.index_config.ef_construction as u32,
-                ef_search: config.index_config.ef_search as u32,
-                max_layer: config.index_config.max_layer as u32,
-            }),
+            index_config: Some(config.index_config.clone().into()),
+            sparse_vectors: config.sparse_vectors
+                .iter()
+                .flatten()
+                .map(|(name, params)| (name.clone(), params.clone().into()))
+                .collect(),
+            vectors: config.vectors
+                .iter()
+                .flatten()
+                .map(|(name, params)| (name.clone(), params.clone().into()))
+                .collect(),
+            multivectors: config.multivectors
+                .iter()
+                .flatten()
+                .map(|(name, params)| (name.clone(), params.clone().into()))
+                .collect(),
         };
 
         let request = CreateCollectionRequest {
@@ -189,15 +337,38 @@ impl VectorDbClient for GrpcClient {
             dimension: proto_config.dimension as usize,
             distance_metric: proto_config.distance_metric().into(),
             vector_type: proto_config.vector_type().into(),
-            index_config: proto_config.index_config.map_or(IndexConfig::default(), |ic| {
-                IndexConfig {
-                    max_connections: ic.max_connections as usize,
-                    ef_construction: ic.ef_construction as usize,
-                    ef_search: ic.ef_search as usize,
-                    max_layer: ic.max_layer as usize,
-                }
-            }),
+            index_config: proto_config.index_config.map(Into::into).unwrap_or_default(),
             quantization: None,
+            sparse_vectors: if proto_config.sparse_vectors.is_empty() {
+                None
+            } else {
+                Some(
+                    proto_config.sparse_vectors
+                        .into_iter()
+                        .map(|(name, params)| (name, params.into()))
+                        .collect(),
+                )
+            },
+            vectors: if proto_config.vectors.is_empty() {
+                None
+            } else {
+                Some(
+                    proto_config.vectors
+                        .into_iter()
+                        .map(|(name, params)| (name, params.into()))
+                        .collect(),
+                )
+            },
+            multivectors: if proto_config.multivectors.is_empty() {
+                None
+            } else {
+                Some(
+                    proto_config.multivectors
+                        .into_iter()
+                        .map(|(name, params)| (name, params.into()))
+                        .collect(),
+                )
+            },
         };
 
         let stats = CommonCollectionStats {
@@ -206,22 +377,65 @@ impl VectorDbClient for GrpcClient {
             dimension: proto_stats.dimension as usize,
             index_size: proto_stats.index_size as usize,
             memory_usage: proto_stats.memory_usage as usize,
+            memory: proto_stats.memory.map(Into::into).unwrap_or_default(),
+            quantization: proto_stats.quantization.map(Into::into),
         };
 
         Ok((config, stats))
     }
 
+    #[instrument(skip(self))]
+    async fn train_quantizer(&self, collection: &str) -> Result<vectordb_common::quantization::TrainingStatus> {
+        let request = vectordb_proto::TrainQuantizerRequest {
+            collection_name: collection.to_string(),
+        };
+
+        let response = self.with_retry(|| async {
+            let mut client = self.client.clone();
+            client.train_quantizer(Request::new(request.clone())).await
+        }).await?;
+
+        response
+            .into_inner()
+            .status
+            .map(Into::into)
+            .ok_or_else(|| VectorDbError::NetworkError {
+                message: "Missing training status in response".to_string(),
+            })
+    }
+
+    #[instrument(skip(self, request))]
+    async fn evaluate(&self, request: &vectordb_common::search_api::EvaluateRequest) -> Result<vectordb_common::search_api::EvaluateResponse> {
+        let proto_request = vectordb_proto::EvaluateRequest {
+            collection_name: request.collection.clone(),
+            queries: request
+                .queries
+                .iter()
+                .map(|query| vectordb_proto::DenseVector { data: query.clone() })
+                .collect(),
+            sample: Some(request.sample as u32),
+            limit: Some(request.limit as u32),
+            ef_search: request.ef_search.iter().map(|&ef| ef as u32).collect(),
+            using: request.using.clone(),
+        };
+
+        let response = self.with_retry(|| async {
+            let mut client = self.client.clone();
+            client.evaluate(Request::new(proto_request.clone())).await
+        }).await?;
+
+        let response = response.into_inner();
+        Ok(vectordb_common::search_api::EvaluateResponse {
+            queries: response.queries as usize,
+            limit: response.limit as usize,
+            exact_latency: response.exact_latency.map(Into::into).unwrap_or_default(),
+            runs: response.runs.into_iter().map(Into::into).collect(),
+        })
+    }
+
     #[instrument(skip(self, vector))]
     async fn insert(&self, collection: &str, vector: &Vector) -> Result<()> {
-        let proto_vector = vectordb_proto::Vector {
-            id: vector.id.to_string(),
-            data: vector.data.clone(),
-            metadata: vector.metadata.as_ref().map_or(HashMap::new(), |meta| {
-                meta.iter()
-                    .map(|(k, v)| (k.clone(), v.to_string()))
-                    .collect()
-            }),
-        };
+        let proto_vector = vector_to_proto(vector);
 
         let request = InsertRequest {
             collection_name: collection.to_string(),
@@ -245,18 +459,7 @@ impl VectorDbClient for GrpcClient {
 
     #[instrument(skip(self, vectors))]
     async fn batch_insert(&self, collection: &str, vectors: &[Vector]) -> Result<()> {
-        let proto_vectors: Vec<ProtoVector> = vectors
-            .iter()
-            .map(|v| ProtoVector {
-                id: v.id.to_string(),
-                data: v.data.clone(),
-                metadata: v.metadata.as_ref().map_or(HashMap::new(), |meta| {
-                    meta.iter()
-                        .map(|(k, v)| (k.clone(), v.to_string()))
-                        .collect()
-                }),
-            })
-            .collect();
+        let proto_vectors: Vec<ProtoVector> = vectors.iter().map(vector_to_proto).collect();
 
         let request = BatchInsertRequest {
             collection_name: collection.to_string(),
@@ -286,7 +489,12 @@ impl VectorDbClient for GrpcClient {
             query_vector: request.vector.clone(),
             limit: request.limit as u32,
             ef_search: request.ef_search.map(|ef| ef as u32),
-            filter: HashMap::new(), // Filter conversion not yet supported
+            filter: request.filter.clone().map(Into::into),
+            using: request.using.clone(),
+            multivector: request.multivector.clone().map(Into::into),
+            rescore: request.rescore,
+            oversampling: request.oversampling,
+            exact: request.exact,
         };
 
         let response = self.with_retry(|| async {
@@ -304,16 +512,7 @@ impl VectorDbClient for GrpcClient {
                     message: format!("Invalid UUID in response: {}", r.id),
                 })?;
 
-                let metadata = if r.metadata.is_empty() {
-                    None
-                } else {
-                    Some(
-                        r.metadata
-                            .into_iter()
-                            .map(|(k, v)| (k, serde_json::Value::String(v)))
-                            .collect(),
-                    )
-                };
+                let metadata = payload_from_proto(r.metadata);
 
                 Ok(QueryResult {
                     id,
@@ -327,25 +526,76 @@ impl VectorDbClient for GrpcClient {
     }
 
     #[instrument(skip(self))]
-    async fn get(&self, _collection: &str, _id: &VectorId) -> Result<Option<Vector>> {
-        // gRPC service doesn't currently support get by ID
-        // This would need to be added to the proto definition
-        Err(VectorDbError::Internal {
-            message: "Get by ID not supported in gRPC client".to_string(),
-        })
+    async fn get(&self, collection: &str, id: &VectorId) -> Result<Option<Vector>> {
+        let request = GetRequest {
+            collection_name: collection.to_string(),
+            vector_id: id.to_string(),
+        };
+
+        let response = self.with_retry(|| async {
+            let mut client = self.client.clone();
+            client.get(Request::new(request.clone())).await
+        }).await?;
+
+        let Some(proto_vector) = response.into_inner().vector else {
+            return Ok(None);
+        };
+
+        let id = Uuid::parse_str(&proto_vector.id).map_err(|_| VectorDbError::Internal {
+            message: format!("Invalid UUID in response: {}", proto_vector.id),
+        })?;
+
+        let metadata = payload_from_proto(proto_vector.metadata);
+
+        let sparse = if proto_vector.sparse.is_empty() {
+            None
+        } else {
+            Some(
+                proto_vector
+                    .sparse
+                    .into_iter()
+                    .map(|(name, sparse)| (name, sparse.into()))
+                    .collect(),
+            )
+        };
+
+        let vectors = if proto_vector.vectors.is_empty() {
+            None
+        } else {
+            Some(
+                proto_vector
+                    .vectors
+                    .into_iter()
+                    .map(|(name, vector)| (name, vector.data))
+                    .collect(),
+            )
+        };
+
+        let multivectors = if proto_vector.multivectors.is_empty() {
+            None
+        } else {
+            Some(
+                proto_vector
+                    .multivectors
+                    .into_iter()
+                    .map(|(name, rows)| (name, rows.into()))
+                    .collect(),
+            )
+        };
+
+        Ok(Some(Vector {
+            id,
+            data: proto_vector.data,
+            metadata,
+            sparse,
+            vectors,
+            multivectors,
+        }))
     }
 
     #[instrument(skip(self, vector))]
     async fn update(&self, collection: &str, vector: &Vector) -> Result<()> {
-        let proto_vector = vectordb_proto::Vector {
-            id: vector.id.to_string(),
-            data: vector.data.clone(),
-            metadata: vector.metadata.as_ref().map_or(HashMap::new(), |meta| {
-                meta.iter()
-                    .map(|(k, v)| (k.clone(), v.to_string()))
-                    .collect()
-            }),
-        };
+        let proto_vector = vector_to_proto(vector);
 
         let request = UpdateRequest {
             collection_name: collection.to_string(),
@@ -383,6 +633,77 @@ impl VectorDbClient for GrpcClient {
         Ok(response.success)
     }
 
+    #[instrument(skip(self, request))]
+    async fn set_payload(&self, request: &vectordb_common::search_api::SetPayloadRequest) -> Result<vectordb_common::search_api::UpdatePayloadResponse> {
+        let proto_request = vectordb_proto::SetPayloadRequest {
+            collection_name: request.collection.clone(),
+            points: Some(request.points.clone().into()),
+            payload: payload_to_proto(Some(request.payload.clone())),
+        };
+
+        let response = self.with_retry(|| async {
+            let mut client = self.client.clone();
+            client.set_payload(Request::new(proto_request.clone())).await
+        }).await?;
+
+        Ok(vectordb_common::search_api::UpdatePayloadResponse {
+            updated: response.into_inner().updated as usize,
+        })
+    }
+
+    #[instrument(skip(self, request))]
+    async fn overwrite_payload(&self, request: &vectordb_common::search_api::SetPayloadRequest) -> Result<vectordb_common::search_api::UpdatePayloadResponse> {
+        let proto_request = vectordb_proto::SetPayloadRequest {
+            collection_name: request.collection.clone(),
+            points: Some(request.points.clone().into()),
+            payload: payload_to_proto(Some(request.payload.clone())),
+        };
+
+        let response = self.with_retry(|| async {
+            let mut client = self.client.clone();
+            client.overwrite_payload(Request::new(proto_request.clone())).await
+        }).await?;
+
+        Ok(vectordb_common::search_api::UpdatePayloadResponse {
+            updated: response.into_inner().updated as usize,
+        })
+    }
+
+    #[instrument(skip(self, request))]
+    async fn delete_payload_keys(&self, request: &vectordb_common::search_api::DeletePayloadKeysRequest) -> Result<vectordb_common::search_api::UpdatePayloadResponse> {
+        let proto_request = vectordb_proto::DeletePayloadKeysRequest {
+            collection_name: request.collection.clone(),
+            points: Some(request.points.clone().into()),
+            keys: request.keys.clone(),
+        };
+
+        let response = self.with_retry(|| async {
+            let mut client = self.client.clone();
+            client.delete_payload_keys(Request::new(proto_request.clone())).await
+        }).await?;
+
+        Ok(vectordb_common::search_api::UpdatePayloadResponse {
+            updated: response.into_inner().updated as usize,
+        })
+    }
+
+    #[instrument(skip(self, request))]
+    async fn clear_payload(&self, request: &vectordb_common::search_api::ClearPayloadRequest) -> Result<vectordb_common::search_api::UpdatePayloadResponse> {
+        let proto_request = vectordb_proto::ClearPayloadRequest {
+            collection_name: request.collection.clone(),
+            points: Some(request.points.clone().into()),
+        };
+
+        let response = self.with_retry(|| async {
+            let mut client = self.client.clone();
+            client.clear_payload(Request::new(proto_request.clone())).await
+        }).await?;
+
+        Ok(vectordb_common::search_api::UpdatePayloadResponse {
+            updated: response.into_inner().updated as usize,
+        })
+    }
+
     #[instrument(skip(self))]
     async fn get_stats(&self) -> Result<ServerStats> {
         let request = GetStatsRequest {};
@@ -403,6 +724,7 @@ impl VectorDbClient for GrpcClient {
             memory_usage: stats.memory_usage,
             disk_usage: stats.disk_usage,
             uptime_seconds: stats.uptime_seconds,
+            memory: stats.memory.map(Into::into).unwrap_or_default(),
         })
     }
 
@@ -427,7 +749,7 @@ impl VectorDbClient for GrpcClient {
             collection_name: request.collection.clone(),
             positive_ids: request.positive.iter().map(|id| id.to_string()).collect(),
             negative_ids: request.negative.iter().map(|id| id.to_string()).collect(),
-            filter_json: request.filter.as_ref().map(|f| serde_json::to_string(f).unwrap_or_default()),
+            filter: request.filter.clone().map(Into::into),
             limit: request.limit as u32,
             offset: request.offset as u32,
             strategy: match request.strategy {
@@ -450,16 +772,7 @@ impl VectorDbClient for GrpcClient {
                     message: format!("Invalid UUID in response: {}", r.id),
                 })?;
 
-                let metadata = if r.metadata.is_empty() {
-                    None
-                } else {
-                    Some(
-                        r.metadata
-                            .into_iter()
-                            .map(|(k, v)| (k, serde_json::Value::String(v)))
-                            .collect(),
-                    )
-                };
+                let metadata = payload_from_proto(r.metadata);
 
                 Ok(QueryResult {
                     id,
@@ -498,7 +811,7 @@ impl VectorDbClient for GrpcClient {
                     negative_id: pair.negative.to_string(),
                 })
                 .collect(),
-            filter_json: request.filter.as_ref().map(|f| serde_json::to_string(f).unwrap_or_default()),
+            filter: request.filter.clone().map(Into::into),
             limit: request.limit as u32,
             offset: request.offset as u32,
         };
@@ -517,16 +830,7 @@ impl VectorDbClient for GrpcClient {
                     message: format!("Invalid UUID in response: {}", r.id),
                 })?;
 
-                let metadata = if r.metadata.is_empty() {
-                    None
-                } else {
-                    Some(
-                        r.metadata
-                            .into_iter()
-                            .map(|(k, v)| (k, serde_json::Value::String(v)))
-                            .collect(),
-                    )
-                };
+                let metadata = payload_from_proto(r.metadata);
 
                 Ok(QueryResult {
                     id,
@@ -543,11 +847,12 @@ impl VectorDbClient for GrpcClient {
     async fn scroll(&self, request: &vectordb_common::search_api::ScrollRequest) -> Result<vectordb_common::search_api::ScrollResponse> {
         let proto_request = vectordb_proto::ScrollRequest {
             collection_name: request.collection.clone(),
-            filter_json: request.filter.as_ref().map(|f| serde_json::to_string(f).unwrap_or_default()),
+            filter: request.filter.clone().map(Into::into),
             limit: request.limit as u32,
             offset: request.offset.clone(),
             with_vectors: request.with_vectors,
             with_payload: request.with_payload,
+            order_by: request.order_by.clone().map(Into::into),
         };
 
         let response = self.with_retry(|| async {
@@ -564,16 +869,7 @@ impl VectorDbClient for GrpcClient {
                     message: format!("Invalid UUID in response: {}", p.id),
                 })?;
 
-                let payload = if p.payload.is_empty() {
-                    None
-                } else {
-                    Some(
-                        p.payload
-                            .into_iter()
-                            .map(|(k, v)| (k, serde_json::Value::String(v)))
-                            .collect(),
-                    )
-                };
+                let payload = payload_from_proto(p.payload);
 
                 Ok(vectordb_common::search_api::ScoredPoint {
                     id,
@@ -594,7 +890,7 @@ impl VectorDbClient for GrpcClient {
     async fn count(&self, request: &vectordb_common::search_api::CountRequest) -> Result<vectordb_common::search_api::CountResponse> {
         let proto_request = vectordb_proto::CountRequest {
             collection_name: request.collection.clone(),
-            filter_json: request.filter.as_ref().map(|f| serde_json::to_string(f).unwrap_or_default()),
+            filter: request.filter.clone().map(Into::into),
             exact: request.exact,
         };
 
@@ -609,6 +905,35 @@ impl VectorDbClient for GrpcClient {
         })
     }
 
+    #[instrument(skip(self))]
+    async fn facet(&self, request: &vectordb_common::search_api::FacetRequest) -> Result<vectordb_common::search_api::FacetResponse> {
+        let proto_request = vectordb_proto::FacetRequest {
+            collection_name: request.collection.clone(),
+            field: request.field.clone(),
+            filter: request.filter.clone().map(Into::into),
+            limit: Some(request.limit as u32),
+            exact: request.exact,
+        };
+
+        let response = self.with_retry(|| async {
+            let mut client = self.client.clone();
+            client.facet(Request::new(proto_request.clone())).await
+        }).await?;
+
+        let response = response.into_inner();
+        Ok(vectordb_common::search_api::FacetResponse {
+            field: response.field,
+            values: response
+                .values
+                .into_iter()
+                .map(|v| vectordb_common::search_api::FacetValue {
+                    value: v.value.map_or(serde_json::Value::Null, Into::into),
+                    count: v.count as usize,
+                })
+                .collect(),
+        })
+    }
+
     #[instrument(skip(self))]
     async fn batch_search(&self, request: &vectordb_common::search_api::BatchSearchRequest) -> Result<Vec<Vec<QueryResult>>> {
         let proto_request = vectordb_proto::BatchSearchRequest {
@@ -618,7 +943,7 @@ impl VectorDbClient for GrpcClient {
                 .iter()
                 .map(|s| vectordb_proto::SearchQuery {
                     vector: s.vector.clone(),
-                    filter_json: s.filter.as_ref().map(|f| serde_json::to_string(f).unwrap_or_default()),
+                    filter: s.filter.clone().map(Into::into),
                     limit: s.limit as u32,
                     offset: s.offset as u32,
                 })
@@ -643,16 +968,7 @@ impl VectorDbClient for GrpcClient {
                             message: format!("Invalid UUID in response: {}", r.id),
                         })?;
 
-                        let metadata = if r.metadata.is_empty() {
-                            None
-                        } else {
-                            Some(
-                                r.metadata
-                                    .into_iter()
-                                    .map(|(k, v)| (k, serde_json::Value::String(v)))
-                                    .collect(),
-                            )
-                        };
+                        let metadata = payload_from_proto(r.metadata);
 
                         Ok(QueryResult {
                             id,
@@ -667,6 +983,110 @@ impl VectorDbClient for GrpcClient {
         Ok(batch_results)
     }
 
+    #[instrument(skip(self))]
+    async fn search_groups(&self, request: &vectordb_common::search_api::GroupByRequest) -> Result<vectordb_common::search_api::GroupByResponse> {
+        let proto_request = vectordb_proto::SearchGroupsRequest {
+            collection_name: request.collection.clone(),
+            vector: request.vector.clone(),
+            group_by: request.group_by.clone(),
+            group_size: request.group_size as u32,
+            limit: request.limit as u32,
+            filter: request.filter.clone().map(Into::into),
+            with_lookup: request.with_lookup.as_ref().map(|lookup| vectordb_proto::WithLookup {
+                collection: lookup.collection.clone(),
+                with_payload: lookup.with_payload,
+                with_vectors: lookup.with_vectors,
+            }),
+        };
+
+        let response = self.with_retry(|| async {
+            let mut client = self.client.clone();
+            client.search_groups(Request::new(proto_request.clone())).await
+        }).await?;
+
+        let parse_id = |id: &str| {
+            Uuid::parse_str(id).map_err(|_| VectorDbError::Internal {
+                message: format!("Invalid UUID in response: {}", id),
+            })
+        };
+
+        let groups = response
+            .into_inner()
+            .groups
+            .into_iter()
+            .map(|group| {
+                let hits = group
+                    .hits
+                    .into_iter()
+                    .map(|r| {
+                        Ok(QueryResult {
+                            id: parse_id(&r.id)?,
+                            distance: r.distance,
+                            metadata: payload_from_proto(r.metadata),
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+
+                let lookup = group
+                    .lookup
+                    .map(|p| {
+                        Ok::<_, VectorDbError>(vectordb_common::search_api::ScoredPoint {
+                            id: parse_id(&p.id)?,
+                            score: p.score,
+                            vector: if p.vector.is_empty() { None } else { Some(p.vector) },
+                            payload: payload_from_proto(p.payload),
+                        })
+                    })
+                    .transpose()?;
+
+                Ok(vectordb_common::search_api::PointGroup {
+                    id: group.id.map_or(serde_json::Value::Null, Into::into),
+                    hits,
+                    lookup,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(vectordb_common::search_api::GroupByResponse { groups })
+    }
+
+    #[instrument(skip(self, request))]
+    async fn hybrid_search(&self, request: &vectordb_common::HybridSearchRequest) -> Result<Vec<vectordb_common::search_api::ScoredPoint>> {
+        let proto_request = vectordb_proto::HybridSearchRequest {
+            collection_name: request.collection.clone(),
+            dense: request.dense.clone().unwrap_or_default(),
+            sparse: request.sparse.clone().map(Into::into),
+            sparse_name: request.sparse_name.clone(),
+            fusion: vectordb_proto::FusionMethod::from(request.fusion) as i32,
+            limit: request.limit as u32,
+            filter: request.filter.clone().map(Into::into),
+        };
+
+        let response = self.with_retry(|| async {
+            let mut client = self.client.clone();
+            client.hybrid_search(Request::new(proto_request.clone())).await
+        }).await?;
+
+        response
+            .into_inner()
+            .points
+            .into_iter()
+            .map(|p| {
+                let id = Uuid::parse_str(&p.id).map_err(|_| VectorDbError::Internal {
+                    message: format!("Invalid UUID in response: {}", p.id),
+                })?;
+                let payload = payload_from_proto(p.payload);
+
+                Ok(vectordb_common::search_api::ScoredPoint {
+                    id,
+                    score: p.score,
+                    vector: if p.vector.is_empty() { None } else { Some(p.vector) },
+                    payload,
+                })
+            })
+            .collect()
+    }
+
     // Snapshot Management APIs
 
     #[instrument(skip(self))]
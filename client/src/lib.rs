@@ -24,6 +24,12 @@ pub trait VectorDbClient: Send + Sync {
     /// Get collection information
     async fn get_collection_info(&self, name: &str) -> Result<(CollectionConfig, CollectionStats)>;
 
+    /// Train a product quantized collection's codebooks now instead of waiting for its threshold
+    async fn train_quantizer(&self, collection: &str) -> Result<vectordb_common::quantization::TrainingStatus>;
+
+    /// Measure the recall of a collection's index search against exact search
+    async fn evaluate(&self, request: &vectordb_common::search_api::EvaluateRequest) -> Result<vectordb_common::search_api::EvaluateResponse>;
+
     /// Insert a single vector
     async fn insert(&self, collection: &str, vector: &Vector) -> Result<()>;
 
@@ -42,6 +48,18 @@ pub trait VectorDbClient: Send + Sync {
     /// Delete a vector
     async fn delete(&self, collection: &str, id: &VectorId) -> Result<bool>;
 
+    /// Merge keys into the payloads of points selected by ID or filter
+    async fn set_payload(&self, request: &vectordb_common::search_api::SetPayloadRequest) -> Result<vectordb_common::search_api::UpdatePayloadResponse>;
+
+    /// Replace the payloads of points selected by ID or filter
+    async fn overwrite_payload(&self, request: &vectordb_common::search_api::SetPayloadRequest) -> Result<vectordb_common::search_api::UpdatePayloadResponse>;
+
+    /// Remove keys from the payloads of points selected by ID or filter
+    async fn delete_payload_keys(&self, request: &vectordb_common::search_api::DeletePayloadKeysRequest) -> Result<vectordb_common::search_api::UpdatePayloadResponse>;
+
+    /// Remove the payloads of points selected by ID or filter
+    async fn clear_payload(&self, request: &vectordb_common::search_api::ClearPayloadRequest) -> Result<vectordb_common::search_api::UpdatePayloadResponse>;
+
     /// Get server statistics
     async fn get_stats(&self) -> Result<ServerStats>;
 
@@ -62,9 +80,18 @@ pub trait VectorDbClient: Send + Sync {
     /// Count vectors matching filter criteria
     async fn count(&self, request: &vectordb_common::search_api::CountRequest) -> Result<vectordb_common::search_api::CountResponse>;
 
+    /// Most frequent values of a payload field among matching vectors
+    async fn facet(&self, request: &vectordb_common::search_api::FacetRequest) -> Result<vectordb_common::search_api::FacetResponse>;
+
     /// Batch search - execute multiple queries in one request
     async fn batch_search(&self, request: &vectordb_common::search_api::BatchSearchRequest) -> Result<Vec<Vec<QueryResult>>>;
 
+    /// Search with results grouped by a payload field
+    async fn search_groups(&self, request: &vectordb_common::search_api::GroupByRequest) -> Result<vectordb_common::search_api::GroupByResponse>;
+
+    /// Dense and sparse search fused into one ranking
+    async fn hybrid_search(&self, request: &vectordb_common::HybridSearchRequest) -> Result<Vec<vectordb_common::search_api::ScoredPoint>>;
+
     // Snapshot Management APIs
 
     /// Create a snapshot of a collection
@@ -91,6 +118,8 @@ pub struct ServerStats {
     pub memory_usage: u64,
     pub disk_usage: u64,
     pub uptime_seconds: u64,
+    #[serde(default)]
+    pub memory: vectordb_common::types::MemoryBreakdown,
 }
 
 /// Create a client based on configuration
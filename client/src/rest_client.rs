@@ -3,7 +3,7 @@ use vectordb_common::{Result, VectorDbError};
 use vectordb_common::types::*;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::time::Duration;
 use tracing::{info, warn, instrument};
 
@@ -144,6 +144,9 @@ impl VectorDbClient for RestClient {
             vector_type: VectorType,
             index_config: Option<IndexConfig>,
             quantization: Option<vectordb_common::quantization::QuantizationConfig>,
+            sparse_vectors: Option<BTreeMap<String, vectordb_common::SparseVectorParams>>,
+            vectors: Option<BTreeMap<String, VectorParams>>,
+            multivectors: Option<BTreeMap<String, MultiVectorParams>>,
         }
 
         let request_body = CreateCollectionRequest {
@@ -153,6 +156,9 @@ impl VectorDbClient for RestClient {
             vector_type: config.vector_type,
             index_config: Some(config.index_config.clone()),
             quantization: config.quantization.clone(),
+            sparse_vectors: config.sparse_vectors.clone(),
+            vectors: config.vectors.clone(),
+            multivectors: config.multivectors.clone(),
         };
 
         let request = self.client
@@ -186,6 +192,23 @@ impl VectorDbClient for RestClient {
         self.request_with_retry::<(CollectionConfig, CollectionStats)>(request).await
     }
 
+    #[instrument(skip(self))]
+    async fn train_quantizer(&self, collection: &str) -> Result<vectordb_common::quantization::TrainingStatus> {
+        let request = self.client
+            .post(&format!("{}/collections/{}/quantization/train", self.base_url, collection));
+
+        self.request_with_retry::<vectordb_common::quantization::TrainingStatus>(request).await
+    }
+
+    #[instrument(skip(self, request))]
+    async fn evaluate(&self, request: &vectordb_common::search_api::EvaluateRequest) -> Result<vectordb_common::search_api::EvaluateResponse> {
+        let http_request = self.client
+            .post(&format!("{}/collections/{}/evaluate", self.base_url, request.collection))
+            .json(request);
+
+        self.request_with_retry::<vectordb_common::search_api::EvaluateResponse>(http_request).await
+    }
+
     #[instrument(skip(self, vector))]
     async fn insert(&self, collection: &str, vector: &Vector) -> Result<()> {
         #[derive(Serialize)]
@@ -193,12 +216,18 @@ impl VectorDbClient for RestClient {
             id: Option<String>,
             data: Vec<f32>,
             metadata: Option<HashMap<String, serde_json::Value>>,
+            sparse: Option<BTreeMap<String, vectordb_common::SparseVector>>,
+            vectors: Option<BTreeMap<String, Vec<f32>>>,
+            multivectors: Option<BTreeMap<String, Vec<Vec<f32>>>>,
         }
 
         let request_body = InsertVectorRequest {
             id: Some(vector.id.to_string()),
             data: vector.data.clone(),
             metadata: vector.metadata.clone(),
+            sparse: vector.sparse.clone(),
+            vectors: vector.vectors.clone(),
+            multivectors: vector.multivectors.clone(),
         };
 
         let request = self.client
@@ -215,6 +244,9 @@ impl VectorDbClient for RestClient {
             id: Option<String>,
             data: Vec<f32>,
             metadata: Option<HashMap<String, serde_json::Value>>,
+            sparse: Option<BTreeMap<String, vectordb_common::SparseVector>>,
+            vectors: Option<BTreeMap<String, Vec<f32>>>,
+            multivectors: Option<BTreeMap<String, Vec<Vec<f32>>>>,
         }
 
         #[derive(Serialize)]
@@ -229,6 +261,9 @@ impl VectorDbClient for RestClient {
                     id: Some(v.id.to_string()),
                     data: v.data.clone(),
                     metadata: v.metadata.clone(),
+                    sparse: v.sparse.clone(),
+                    vectors: v.vectors.clone(),
+                    multivectors: v.multivectors.clone(),
                 })
                 .collect(),
         };
@@ -248,6 +283,11 @@ impl VectorDbClient for RestClient {
             limit: Option<usize>,
             ef_search: Option<usize>,
             filter: Option<HashMap<String, serde_json::Value>>,
+            using: Option<String>,
+            multivector: Option<MultiVectorQuery>,
+            rescore: Option<bool>,
+            oversampling: Option<f32>,
+            exact: bool,
         }
 
         // TODO: Convert Filter to JSON HashMap format
@@ -256,6 +296,11 @@ impl VectorDbClient for RestClient {
             limit: Some(request.limit),
             ef_search: request.ef_search,
             filter: None, // Filter conversion not yet supported
+            using: request.using.clone(),
+            multivector: request.multivector.clone(),
+            rescore: request.rescore,
+            oversampling: request.oversampling,
+            exact: request.exact,
         };
 
         let http_request = self.client
@@ -280,12 +325,18 @@ impl VectorDbClient for RestClient {
             id: Option<String>,
             data: Vec<f32>,
             metadata: Option<HashMap<String, serde_json::Value>>,
+            sparse: Option<BTreeMap<String, vectordb_common::SparseVector>>,
+            vectors: Option<BTreeMap<String, Vec<f32>>>,
+            multivectors: Option<BTreeMap<String, Vec<Vec<f32>>>>,
         }
 
         let request_body = InsertVectorRequest {
             id: None, // ID is in the URL path
             data: vector.data.clone(),
             metadata: vector.metadata.clone(),
+            sparse: vector.sparse.clone(),
+            vectors: vector.vectors.clone(),
+            multivectors: vector.multivectors.clone(),
         };
 
         let request = self.client
@@ -303,6 +354,42 @@ impl VectorDbClient for RestClient {
         self.request_with_retry::<bool>(request).await
     }
 
+    #[instrument(skip(self, request))]
+    async fn set_payload(&self, request: &vectordb_common::search_api::SetPayloadRequest) -> Result<vectordb_common::search_api::UpdatePayloadResponse> {
+        let http_request = self.client
+            .post(&format!("{}/collections/{}/points/payload", self.base_url, request.collection))
+            .json(request);
+
+        self.request_with_retry::<vectordb_common::search_api::UpdatePayloadResponse>(http_request).await
+    }
+
+    #[instrument(skip(self, request))]
+    async fn overwrite_payload(&self, request: &vectordb_common::search_api::SetPayloadRequest) -> Result<vectordb_common::search_api::UpdatePayloadResponse> {
+        let http_request = self.client
+            .put(&format!("{}/collections/{}/points/payload", self.base_url, request.collection))
+            .json(request);
+
+        self.request_with_retry::<vectordb_common::search_api::UpdatePayloadResponse>(http_request).await
+    }
+
+    #[instrument(skip(self, request))]
+    async fn delete_payload_keys(&self, request: &vectordb_common::search_api::DeletePayloadKeysRequest) -> Result<vectordb_common::search_api::UpdatePayloadResponse> {
+        let http_request = self.client
+            .post(&format!("{}/collections/{}/points/payload/delete", self.base_url, request.collection))
+            .json(request);
+
+        self.request_with_retry::<vectordb_common::search_api::UpdatePayloadResponse>(http_request).await
+    }
+
+    #[instrument(skip(self, request))]
+    async fn clear_payload(&self, request: &vectordb_common::search_api::ClearPayloadRequest) -> Result<vectordb_common::search_api::UpdatePayloadResponse> {
+        let http_request = self.client
+            .post(&format!("{}/collections/{}/points/payload/clear", self.base_url, request.collection))
+            .json(request);
+
+        self.request_with_retry::<vectordb_common::search_api::UpdatePayloadResponse>(http_request).await
+    }
+
     #[instrument(skip(self))]
     async fn get_stats(&self) -> Result<ServerStats> {
         let request = self.client
@@ -360,6 +447,15 @@ impl VectorDbClient for RestClient {
         self.request_with_retry::<vectordb_common::search_api::CountResponse>(http_request).await
     }
 
+    #[instrument(skip(self))]
+    async fn facet(&self, request: &vectordb_common::search_api::FacetRequest) -> Result<vectordb_common::search_api::FacetResponse> {
+        let http_request = self.client
+            .post(&format!("{}/collections/{}/points/facet", self.base_url, request.collection))
+            .json(request);
+
+        self.request_with_retry::<vectordb_common::search_api::FacetResponse>(http_request).await
+    }
+
     #[instrument(skip(self))]
     async fn batch_search(&self, request: &vectordb_common::search_api::BatchSearchRequest) -> Result<Vec<Vec<QueryResult>>> {
         let http_request = self.client
@@ -369,6 +465,24 @@ impl VectorDbClient for RestClient {
         self.request_with_retry::<Vec<Vec<QueryResult>>>(http_request).await
     }
 
+    #[instrument(skip(self))]
+    async fn search_groups(&self, request: &vectordb_common::search_api::GroupByRequest) -> Result<vectordb_common::search_api::GroupByResponse> {
+        let http_request = self.client
+            .post(&format!("{}/collections/{}/points/search/groups", self.base_url, request.collection))
+            .json(request);
+
+        self.request_with_retry::<vectordb_common::search_api::GroupByResponse>(http_request).await
+    }
+
+    #[instrument(skip(self, request))]
+    async fn hybrid_search(&self, request: &vectordb_common::HybridSearchRequest) -> Result<Vec<vectordb_common::search_api::ScoredPoint>> {
+        let http_request = self.client
+            .post(&format!("{}/collections/{}/points/search/hybrid", self.base_url, request.collection))
+            .json(request);
+
+        self.request_with_retry::<Vec<vectordb_common::search_api::ScoredPoint>>(http_request).await
+    }
+
     // Snapshot Management APIs
 
     #[instrument(skip(self))]
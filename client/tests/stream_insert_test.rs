@@ -0,0 +1,134 @@
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::transport::server::TcpIncoming;
+use tonic::transport::Server;
+use uuid::Uuid;
+use vectordb_client::{ClientConfig, GrpcClient};
+use vectordb_common::types::*;
+use vectordb_proto::vector_db_client::VectorDbClient as ProtoClient;
+use vectordb_proto::vector_db_server::VectorDbServer;
+use vectordb_proto::{StreamInsertRequest, Vector as ProtoVector};
+use vectordb_server::VectorDbService;
+use vectordb_vectorstore::VectorStore;
+
+/// A store with a two-dimensional "stream" collection, served over gRPC on a
+/// local port; returns the store and the endpoint to connect to
+async fn serve(temp_dir: &tempfile::TempDir) -> (Arc<VectorStore>, String) {
+    let store = Arc::new(VectorStore::new(temp_dir.path()).await.unwrap());
+    store
+        .create_collection(&CollectionConfig {
+            name: "stream".to_string(),
+            dimension: 2,
+            distance_metric: DistanceMetric::Euclidean,
+            vector_type: VectorType::Float32,
+            index_config: IndexConfig::default(),
+            quantization: None,
+            sparse_vectors: None,
+            vectors: None,
+            multivectors: None,
+        })
+        .await
+        .unwrap();
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = format!("http://{}", listener.local_addr().unwrap());
+    let incoming = TcpIncoming::from_listener(listener, true, None).unwrap();
+    tokio::spawn(
+        Server::builder()
+            .add_service(VectorDbServer::new(VectorDbService::new(store.clone())))
+            .serve_with_incoming(incoming),
+    );
+
+    (store, endpoint)
+}
+
+fn point(dimension: usize) -> Vector {
+    Vector {
+        id: Uuid::new_v4(),
+        data: vec![1.0; dimension],
+        metadata: None,
+        sparse: None,
+        vectors: None,
+        multivectors: None,
+    }
+}
+
+async fn stored(store: &VectorStore) -> usize {
+    store.get_collection_stats("stream").await.unwrap().unwrap().vector_count
+}
+
+#[tokio::test]
+async fn test_insert_sink_batches_are_acked_in_order() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let (store, endpoint) = serve(&temp_dir).await;
+    let client = GrpcClient::new(ClientConfig::grpc(endpoint)).await.unwrap();
+
+    let (mut sink, mut acks) = client.stream_insert("stream", 2).await.unwrap();
+    let mut sent = Vec::new();
+    for batch in 0..6 {
+        // The third batch has vectors of the wrong dimension
+        let dimension = if batch == 2 { 3 } else { 2 };
+        let vectors: Vec<Vector> = (0..3).map(|_| point(dimension)).collect();
+        sent.push(sink.send(&vectors).await.unwrap());
+    }
+    assert_eq!(sent, vec![0, 1, 2, 3, 4, 5]);
+
+    // Closing the sink ends the ack stream once every batch is acknowledged
+    sink.close();
+    let mut received = Vec::new();
+    while let Some(ack) = tokio::time::timeout(Duration::from_secs(10), acks.next()).await.unwrap().unwrap() {
+        received.push(ack);
+    }
+
+    let ids: Vec<u64> = received.iter().map(|ack| ack.batch_id).collect();
+    assert_eq!(ids, sent);
+    for ack in &received {
+        if ack.batch_id == 2 {
+            assert!(ack.error.is_some());
+            assert_eq!(ack.inserted_count, 0);
+        } else {
+            assert_eq!(ack.error, None);
+            assert_eq!(ack.inserted_count, 3);
+        }
+    }
+    assert_eq!(stored(&store).await, 15);
+}
+
+#[tokio::test]
+async fn test_stream_insert_rejects_bad_batch_and_continues() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let (store, endpoint) = serve(&temp_dir).await;
+    let mut client = ProtoClient::connect(endpoint).await.unwrap();
+
+    let batch = |batch_id: u64, id: String| StreamInsertRequest {
+        collection_name: "stream".to_string(),
+        batch_id,
+        vectors: vec![ProtoVector {
+            id,
+            data: vec![1.0, 2.0],
+            ..Default::default()
+        }],
+    };
+
+    let (sender, rx) = mpsc::channel(4);
+    let mut acks = client.stream_insert(ReceiverStream::new(rx)).await.unwrap().into_inner();
+    sender.send(batch(7, Uuid::new_v4().to_string())).await.unwrap();
+    sender.send(batch(8, "not-a-uuid".to_string())).await.unwrap();
+    sender.send(batch(9, Uuid::new_v4().to_string())).await.unwrap();
+    drop(sender);
+
+    let mut received = Vec::new();
+    while let Some(ack) = tokio::time::timeout(Duration::from_secs(10), acks.message()).await.unwrap().unwrap() {
+        received.push(ack);
+    }
+
+    let outcomes: Vec<(u64, bool, u32)> = received
+        .iter()
+        .map(|ack| (ack.batch_id, ack.success, ack.inserted_count))
+        .collect();
+    assert_eq!(outcomes, vec![(7, true, 1), (8, false, 0), (9, true, 1)]);
+    assert!(received[1].message.contains("Invalid vector ID"));
+    assert_eq!(stored(&store).await, 2);
+}
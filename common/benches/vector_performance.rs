@@ -48,6 +48,7 @@ fn bench_hnsw_operations(c: &mut Criterion) {
         ef_construction: 200,
         ef_search: 50,
         max_layer: 16,
+        ..IndexConfig::default()
     };
     
     // Benchmark vector insertion
@@ -13,6 +13,27 @@ pub fn distance(a: &[f32], b: &[f32], metric: DistanceMetric) -> f32 {
     }
 }
 
+/// Late-interaction (ColBERT-style) MaxSim score of a query matrix against a document matrix
+///
+/// Every query row is matched with its most similar document row and the
+/// similarities are summed, so higher is better. Similarity is cosine
+/// similarity or dot product for those metrics and negated distance otherwise.
+pub fn max_sim(query: &[Vec<f32>], document: &[Vec<f32>], metric: DistanceMetric) -> f32 {
+    query
+        .iter()
+        .map(|q| {
+            document
+                .iter()
+                .map(|d| match metric {
+                    DistanceMetric::Cosine => cosine_similarity(q, d),
+                    DistanceMetric::DotProduct => dot_product(q, d),
+                    DistanceMetric::Euclidean | DistanceMetric::Manhattan => -distance(q, d, metric),
+                })
+                .fold(f32::NEG_INFINITY, f32::max)
+        })
+        .sum()
+}
+
 /// Calculate cosine similarity between two vectors
 pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
     let dot = simd::dot_product(a, b);
@@ -73,6 +94,22 @@ mod tests {
         assert!((euclidean_distance(&a, &b) - 5.0).abs() < 1e-6);
     }
 
+    #[test]
+    fn test_max_sim() {
+        let query = vec![vec![1.0, 0.0], vec![0.0, 1.0]];
+        // Each query row picks its best match: 1.0 for the first, 0.5 for the second
+        let document = vec![vec![1.0, 0.0], vec![0.0, 0.5], vec![0.3, 0.3]];
+        assert!((max_sim(&query, &document, DistanceMetric::DotProduct) - 1.5).abs() < 1e-6);
+
+        // Extra document rows can only help, whatever their order
+        let mut longer = document.clone();
+        longer.insert(0, vec![0.0, 2.0]);
+        assert!((max_sim(&query, &longer, DistanceMetric::DotProduct) - 3.0).abs() < 1e-6);
+
+        assert!((max_sim(&query, &document, DistanceMetric::Cosine) - 2.0).abs() < 1e-6);
+        assert!((max_sim(&query, &document, DistanceMetric::Euclidean) + 0.5).abs() < 1e-6);
+    }
+
     #[test]
     fn test_normalize() {
         let mut v = vec![3.0, 4.0];
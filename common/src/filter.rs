@@ -151,6 +151,29 @@ pub struct IsNull {
 pub fn evaluate_filter(
     filter: &Filter,
     metadata: &Option<HashMap<String, serde_json::Value>>,
+) -> bool {
+    filter_matches(filter, metadata.as_ref())
+}
+
+/// Decides a full-text condition for one record, or `None` to use the default match
+pub type TextMatcher<'a> = dyn Fn(&MatchText) -> Option<bool> + 'a;
+
+/// Evaluate filter against borrowed metadata
+pub fn filter_matches(
+    filter: &Filter,
+    metadata: Option<&HashMap<String, serde_json::Value>>,
+) -> bool {
+    filter_matches_with(filter, metadata, &|_| None)
+}
+
+/// Evaluate filter against borrowed metadata, deferring `MatchText` to `match_text`
+///
+/// Text indexes use this to apply their tokenizer in place of the built-in
+/// substring match.
+pub fn filter_matches_with(
+    filter: &Filter,
+    metadata: Option<&HashMap<String, serde_json::Value>>,
+    match_text: &TextMatcher<'_>,
 ) -> bool {
     let metadata = match metadata {
         Some(m) => m,
@@ -159,18 +182,18 @@ pub fn evaluate_filter(
 
     match filter {
         Filter::Must(conditions) => {
-            conditions.iter().all(|c| evaluate_condition(c, metadata))
+            conditions.iter().all(|c| evaluate_condition(c, metadata, match_text))
         }
         Filter::Should(conditions) => {
-            conditions.iter().any(|c| evaluate_condition(c, metadata))
+            conditions.iter().any(|c| evaluate_condition(c, metadata, match_text))
         }
         Filter::MustNot(conditions) => {
-            !conditions.iter().any(|c| evaluate_condition(c, metadata))
+            !conditions.iter().any(|c| evaluate_condition(c, metadata, match_text))
         }
         Filter::MinShould { conditions, min_count } => {
             let matches = conditions
                 .iter()
-                .filter(|c| evaluate_condition(c, metadata))
+                .filter(|c| evaluate_condition(c, metadata, match_text))
                 .count();
             matches >= *min_count
         }
@@ -181,10 +204,11 @@ pub fn evaluate_filter(
 fn evaluate_condition(
     condition: &Condition,
     metadata: &HashMap<String, serde_json::Value>,
+    match_text: &TextMatcher<'_>,
 ) -> bool {
     match condition {
-        Condition::Match(field_cond) => evaluate_field_condition(field_cond, metadata),
-        Condition::Filter(filter) => evaluate_filter(filter, &Some(metadata.clone())),
+        Condition::Match(field_cond) => evaluate_field_condition(field_cond, metadata, match_text),
+        Condition::Filter(filter) => filter_matches_with(filter, Some(metadata), match_text),
     }
 }
 
@@ -192,6 +216,7 @@ fn evaluate_condition(
 fn evaluate_field_condition(
     condition: &FieldCondition,
     metadata: &HashMap<String, serde_json::Value>,
+    match_text: &TextMatcher<'_>,
 ) -> bool {
     match condition {
         FieldCondition::MatchKeyword(match_kw) => {
@@ -200,8 +225,8 @@ fn evaluate_field_condition(
         FieldCondition::MatchAny(match_any) => {
             evaluate_match_any(match_any, metadata)
         }
-        FieldCondition::MatchText(match_text) => {
-            evaluate_match_text(match_text, metadata)
+        FieldCondition::MatchText(text) => {
+            match_text(text).unwrap_or_else(|| evaluate_match_text(text, metadata))
         }
         FieldCondition::Range(range) => {
             evaluate_range(range, metadata)
@@ -290,8 +315,8 @@ fn evaluate_match_text(
 ) -> bool {
     if let Some(value) = metadata.get(&match_text.key) {
         if let Some(text) = value.as_str() {
-            // Simple case-insensitive substring match
-            // TODO: Implement proper full-text search with tokenization
+            // Case-insensitive substring match; fields with a text index
+            // are matched by token instead (see `filter_matches_with`)
             return text.to_lowercase().contains(&match_text.text.to_lowercase());
         }
     }
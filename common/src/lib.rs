@@ -5,6 +5,7 @@ pub mod simd;
 pub mod gpu;
 pub mod filter;
 pub mod quantization;
+pub mod packed;
 pub mod sparse;
 pub mod search_api;
 
@@ -13,5 +14,6 @@ pub use types::*;
 pub use distance::*;
 pub use filter::*;
 pub use quantization::*;
+pub use packed::PackedVector;
 pub use sparse::*;
 pub use search_api::*;
\ No newline at end of file
@@ -0,0 +1,98 @@
+//! Dense vectors held in a collection's element type
+//!
+//! The API always speaks `f32`. Collections declared as `Float16` or `Int8`
+//! pack vectors on the way into storage and unpack them on the way out, so
+//! values read back are rounded to what the element type can hold.
+
+use half::f16;
+use half::slice::HalfFloatSliceExt;
+use serde::{Deserialize, Serialize};
+use crate::types::VectorType;
+
+/// A dense vector in one of the supported element types
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PackedVector {
+    Float32(Vec<f32>),
+    Float16(Vec<f16>),
+    /// Symmetric int8 codes; each component is its code times `scale`
+    Int8 { codes: Vec<i8>, scale: f32 },
+}
+
+impl PackedVector {
+    pub fn pack(vector: &[f32], vector_type: VectorType) -> Self {
+        match vector_type {
+            VectorType::Float32 => PackedVector::Float32(vector.to_vec()),
+            VectorType::Float16 => PackedVector::Float16(to_f16(vector)),
+            VectorType::Int8 => {
+                let (codes, scale) = to_int8(vector);
+                PackedVector::Int8 { codes, scale }
+            }
+        }
+    }
+
+    pub fn unpack(&self) -> Vec<f32> {
+        match self {
+            PackedVector::Float32(values) => values.clone(),
+            PackedVector::Float16(values) => values.to_f32_vec(),
+            PackedVector::Int8 { codes, scale } => codes.iter().map(|&code| code as f32 * scale).collect(),
+        }
+    }
+
+    pub fn vector_type(&self) -> VectorType {
+        match self {
+            PackedVector::Float32(_) => VectorType::Float32,
+            PackedVector::Float16(_) => VectorType::Float16,
+            PackedVector::Int8 { .. } => VectorType::Int8,
+        }
+    }
+}
+
+/// `vector` rounded to half precision
+pub fn to_f16(vector: &[f32]) -> Vec<f16> {
+    let mut packed = vec![f16::ZERO; vector.len()];
+    packed.convert_from_f32_slice(vector);
+    packed
+}
+
+/// Symmetric int8 codes of `vector` and the scale that maps them back
+///
+/// The scale is picked per vector so its largest component maps to ±127.
+pub fn to_int8(vector: &[f32]) -> (Vec<i8>, f32) {
+    let max = vector.iter().fold(0.0f32, |max, v| max.max(v.abs()));
+    let scale = if max > 0.0 { max / 127.0 } else { 1.0 };
+    let codes = vector
+        .iter()
+        .map(|&v| (v / scale).round().clamp(-127.0, 127.0) as i8)
+        .collect();
+    (codes, scale)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_roundtrip() {
+        let vector = vec![0.1, -2.5, 3.75, 0.0, 1000.0];
+
+        let float32 = PackedVector::pack(&vector, VectorType::Float32);
+        assert_eq!(float32.unpack(), vector);
+
+        let float16 = PackedVector::pack(&vector, VectorType::Float16);
+        assert_eq!(float16.vector_type(), VectorType::Float16);
+        for (unpacked, original) in float16.unpack().iter().zip(&vector) {
+            assert!((unpacked - original).abs() <= original.abs() * 1e-3, "{} vs {}", unpacked, original);
+        }
+
+        // Int8 error is bounded by half a step of the largest component
+        let int8 = PackedVector::pack(&vector, VectorType::Int8);
+        let step = 1000.0 / 127.0;
+        for (unpacked, original) in int8.unpack().iter().zip(&vector) {
+            assert!((unpacked - original).abs() <= step / 2.0, "{} vs {}", unpacked, original);
+        }
+        assert!((int8.unpack()[4] - 1000.0).abs() < 1e-3);
+
+        let zeros = PackedVector::pack(&[0.0, 0.0], VectorType::Int8);
+        assert_eq!(zeros.unpack(), vec![0.0, 0.0]);
+    }
+}
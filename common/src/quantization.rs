@@ -24,6 +24,17 @@ pub struct ScalarQuantizationConfig {
     /// Always keep original vectors for rescoring
     #[serde(default = "default_true")]
     pub always_ram: bool,
+    /// Vectors held at full precision before the int8 range is fitted to
+    /// them, for metrics whose vectors are not normalized first
+    #[serde(default = "default_fit_threshold")]
+    pub fit_threshold: usize,
+}
+
+/// Vectors an index fits its scalar quantizer to, unless configured otherwise
+pub const DEFAULT_FIT_THRESHOLD: usize = 256;
+
+fn default_fit_threshold() -> usize {
+    DEFAULT_FIT_THRESHOLD
 }
 
 fn default_scalar_type() -> ScalarType {
@@ -53,14 +64,45 @@ pub struct ProductQuantizationConfig {
     /// Always keep original vectors for rescoring
     #[serde(default)]
     pub always_ram: bool,
+    /// Collection size at which the codebooks are trained automatically
+    #[serde(default = "default_training_threshold")]
+    pub training_threshold: usize,
+    /// Most vectors sampled from the collection to train the codebooks on
+    #[serde(default = "default_training_sample")]
+    pub training_sample: usize,
+}
+
+fn default_training_threshold() -> usize {
+    10_000
+}
+
+fn default_training_sample() -> usize {
+    10_000
+}
+
+/// Training state of a collection's product quantizer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum TrainingStatus {
+    /// Vectors are held at full precision until the collection reaches `threshold`
+    Untrained { vectors: usize, threshold: usize },
+    /// Codebooks were trained on `sample_size` vectors and every vector is held as codes
+    Trained { sample_size: usize },
 }
 
 /// Binary quantization configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BinaryQuantizationConfig {
-    /// Always keep original vectors for rescoring (recommended)
+    /// Keep the bit codes in RAM; otherwise they are memory-mapped from disk
     #[serde(default = "default_true")]
     pub always_ram: bool,
+    /// Candidates fetched per result for rescoring, for queries that do not set their own
+    #[serde(default = "default_binary_oversampling")]
+    pub oversampling: f32,
+}
+
+fn default_binary_oversampling() -> f32 {
+    3.0
 }
 
 /// Quantized vector representation
@@ -89,21 +131,7 @@ pub enum QuantizedVector {
 impl QuantizedVector {
     /// Create scalar quantized vector from float32
     pub fn scalar_quantize(vector: &[f32]) -> Self {
-        let min = vector.iter().cloned().fold(f32::INFINITY, f32::min);
-        let max = vector.iter().cloned().fold(f32::NEG_INFINITY, f32::max);
-
-        let range = max - min;
-        let scale = if range > 0.0 { 255.0 / range } else { 1.0 };
-
-        let codes: Vec<i8> = vector
-            .iter()
-            .map(|&v| {
-                let normalized = ((v - min) * scale) as i32;
-                (normalized.clamp(0, 255) as i32 - 128) as i8
-            })
-            .collect();
-
-        QuantizedVector::ScalarInt8 { codes, min, max }
+        ScalarQuantizer::fit(std::iter::once(vector)).quantize(vector)
     }
 
     /// Dequantize scalar int8 back to float32
@@ -180,6 +208,65 @@ impl QuantizedVector {
     }
 }
 
+/// Scalar int8 quantizer over one shared value range
+///
+/// `QuantizedVector::scalar_quantize` picks a range per vector, which makes
+/// codes of different vectors incomparable. An index fits one quantizer to
+/// its data instead, so distances can be computed on the codes directly.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ScalarQuantizer {
+    pub min: f32,
+    pub max: f32,
+}
+
+impl ScalarQuantizer {
+    pub fn new(min: f32, max: f32) -> Self {
+        Self { min, max }
+    }
+
+    /// Quantizer covering every component of `vectors`
+    pub fn fit<'a>(vectors: impl IntoIterator<Item = &'a [f32]>) -> Self {
+        let (min, max) = vectors
+            .into_iter()
+            .flatten()
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), &v| (min.min(v), max.max(v)));
+
+        if min > max {
+            // No components at all
+            Self::new(0.0, 0.0)
+        } else {
+            Self::new(min, max)
+        }
+    }
+
+    /// Width of one code step; code-space Euclidean distances times this
+    /// approximate the distances between the original vectors
+    pub fn step(&self) -> f32 {
+        let range = self.max - self.min;
+        if range > 0.0 { range / 255.0 } else { 1.0 }
+    }
+
+    /// int8 codes of `vector`; values outside the range are clamped to it
+    pub fn encode(&self, vector: &[f32]) -> Vec<i8> {
+        let step = self.step();
+        vector
+            .iter()
+            .map(|&v| {
+                let normalized = ((v - self.min) / step).round() as i32;
+                (normalized.clamp(0, 255) - 128) as i8
+            })
+            .collect()
+    }
+
+    pub fn quantize(&self, vector: &[f32]) -> QuantizedVector {
+        QuantizedVector::ScalarInt8 {
+            codes: self.encode(vector),
+            min: self.min,
+            max: self.max,
+        }
+    }
+}
+
 /// Fast distance computation for quantized vectors
 pub fn quantized_distance(
     a: &QuantizedVector,
@@ -248,7 +335,7 @@ pub enum QuantizedDistanceType {
 }
 
 /// Fast int8 distance computation
-fn scalar_int8_distance(
+pub fn scalar_int8_distance(
     a: &[i8],
     b: &[i8],
     distance_type: QuantizedDistanceType,
@@ -295,17 +382,11 @@ fn scalar_int8_distance(
 
 /// Ultra-fast binary hamming distance using bit operations
 fn binary_hamming_distance(a: &[u8], b: &[u8]) -> f32 {
-    let mut distance = 0u32;
-
-    for (&a_byte, &b_byte) in a.iter().zip(b.iter()) {
-        // XOR to find differing bits, then count them
-        distance += (a_byte ^ b_byte).count_ones();
-    }
-
-    distance as f32
+    crate::simd::hamming_distance(a, b) as f32
 }
 
 /// Product Quantization encoder
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProductQuantizer {
     num_segments: usize,
     num_centroids: usize,
@@ -317,6 +398,11 @@ pub struct ProductQuantizer {
 impl ProductQuantizer {
     /// Create a new product quantizer
     pub fn new(dimension: usize, num_segments: usize, num_centroids: usize) -> Result<Self> {
+        if num_segments == 0 {
+            return Err(VectorDbError::Configuration {
+                message: "num_segments must be at least 1".to_string(),
+            });
+        }
         if dimension % num_segments != 0 {
             return Err(VectorDbError::Configuration {
                 message: format!(
@@ -326,6 +412,12 @@ impl ProductQuantizer {
             });
         }
 
+        if num_centroids == 0 || num_centroids > 256 {
+            return Err(VectorDbError::Configuration {
+                message: format!("num_centroids must be between 1 and 256, got {}", num_centroids),
+            });
+        }
+
         let segment_size = dimension / num_segments;
 
         Ok(Self {
@@ -417,10 +509,51 @@ impl ProductQuantizer {
 
         result
     }
+
+    pub fn num_segments(&self) -> usize {
+        self.num_segments
+    }
+
+    pub fn is_trained(&self) -> bool {
+        !self.codebooks.is_empty()
+    }
+
+    /// Bytes held by the codebooks
+    pub fn memory_size(&self) -> usize {
+        self.codebooks.iter().flatten().map(|c| c.len() * std::mem::size_of::<f32>()).sum()
+    }
+
+    /// Squared distances from each sub-vector of `query` to every centroid of its segment
+    ///
+    /// Computed once per query, after which `asymmetric_distance` scores any
+    /// encoded vector with one table lookup per segment.
+    pub fn distance_table(&self, query: &[f32]) -> Vec<Vec<f32>> {
+        self.codebooks
+            .iter()
+            .enumerate()
+            .map(|(segment_idx, centroids)| {
+                let start_dim = segment_idx * self.segment_size;
+                let sub_query = &query[start_dim..start_dim + self.segment_size];
+                centroids
+                    .iter()
+                    .map(|centroid| crate::distance::euclidean_distance(sub_query, centroid).powi(2))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Squared Euclidean distance from the query of `table` to the vector encoded as `codes`
+    pub fn asymmetric_distance(table: &[Vec<f32>], codes: &[u8]) -> f32 {
+        table
+            .iter()
+            .zip(codes)
+            .map(|(distances, &code)| distances[code as usize])
+            .sum()
+    }
 }
 
-/// Simple k-means clustering for PQ
-fn simple_kmeans(vectors: &[Vec<f32>], k: usize, max_iterations: usize) -> Result<Vec<Vec<f32>>> {
+/// Simple k-means clustering, for PQ codebooks and IVF centroids
+pub fn simple_kmeans(vectors: &[Vec<f32>], k: usize, max_iterations: usize) -> Result<Vec<Vec<f32>>> {
     if vectors.is_empty() || k == 0 {
         return Err(VectorDbError::Configuration {
             message: "Invalid k-means parameters".to_string(),
@@ -510,6 +643,27 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_scalar_quantizer_shares_range() {
+        let a = vec![0.0, 1.0, 2.0];
+        let b = vec![2.0, 1.0, 0.0];
+        let far = vec![10.0, 10.0, 10.0];
+        let quantizer = ScalarQuantizer::fit([a.as_slice(), b.as_slice(), far.as_slice()]);
+        assert_eq!(quantizer, ScalarQuantizer::new(0.0, 10.0));
+
+        // Code distances scaled by the step approximate the float distances
+        let approx = scalar_int8_distance(
+            &quantizer.encode(&a),
+            &quantizer.encode(&far),
+            QuantizedDistanceType::Euclidean,
+        ) * quantizer.step();
+        let exact = crate::distance::euclidean_distance(&a, &far);
+        assert!((approx - exact).abs() < 0.1, "{} vs {}", approx, exact);
+
+        // Out-of-range values are clamped
+        assert_eq!(quantizer.encode(&[-5.0, 50.0]), vec![-128, 127]);
+    }
+
     #[test]
     fn test_binary_quantization() {
         let vector = vec![1.0, -1.0, 2.0, -2.0, 0.5, -0.5];
@@ -528,7 +682,7 @@ mod tests {
         let qb = QuantizedVector::binary_quantize(&b);
 
         let distance = quantized_distance(&qa, &qb, QuantizedDistanceType::Euclidean);
-        assert!(distance >= 0.0);
+        assert_eq!(distance, 2.0);
     }
 
     #[test]
@@ -548,5 +702,14 @@ mod tests {
         // Codes should be much smaller than original
         assert_eq!(codes.len(), 4); // 4 segments
         assert!(codes.len() < test_vector.len() * 4);
+
+        // The distance table gives the exact distance to the decoded vector
+        let query: Vec<f32> = (0..128).map(|_| rand::random::<f32>()).collect();
+        let table = pq.distance_table(&query);
+        let adc = ProductQuantizer::asymmetric_distance(&table, &codes);
+        let decoded = crate::distance::euclidean_distance(&query, &pq.decode(&codes)).powi(2);
+        assert!((adc - decoded).abs() < 1e-3, "{} vs {}", adc, decoded);
+
+        assert!(ProductQuantizer::new(128, 4, 300).is_err());
     }
 }
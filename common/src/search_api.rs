@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use crate::filter::Filter;
+use crate::types::QueryResult;
 
 /// Recommendation API request - find vectors similar to positive examples
 /// and dissimilar to negative examples
@@ -83,7 +84,7 @@ pub struct ScrollRequest {
     pub filter: Option<Filter>,
     /// Number of results per page
     pub limit: usize,
-    /// Scroll offset or cursor
+    /// Opaque cursor from the previous page's `next_offset`
     pub offset: Option<String>,
     /// Whether to include vectors in response
     #[serde(default = "default_true")]
@@ -91,6 +92,30 @@ pub struct ScrollRequest {
     /// Whether to include payload/metadata
     #[serde(default = "default_true")]
     pub with_payload: bool,
+    /// Order by an indexed payload field instead of by point ID
+    #[serde(default)]
+    pub order_by: Option<OrderBy>,
+}
+
+/// Payload field to order scroll results by
+///
+/// Points with equal values are ordered by ID; points without an indexable
+/// value for the field are left out.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderBy {
+    /// Field with a keyword, integer, float or bool payload index
+    pub key: String,
+    #[serde(default)]
+    pub direction: Direction,
+}
+
+/// Sort direction
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    #[default]
+    Asc,
+    Desc,
 }
 
 fn default_true() -> bool {
@@ -101,10 +126,38 @@ fn default_true() -> bool {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScrollResponse {
     pub points: Vec<ScoredPoint>,
-    /// Next scroll offset (None if end reached)
+    /// Cursor of the next page (None if end reached)
     pub next_offset: Option<String>,
 }
 
+/// Export request - stream every point of a collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportRequest {
+    /// Over REST, taken from the path
+    #[serde(default)]
+    pub collection: String,
+    /// Optional filter
+    #[serde(default)]
+    pub filter: Option<Filter>,
+    /// Whether to include dense, named, sparse and multi-vectors
+    #[serde(default = "default_true")]
+    pub with_vectors: bool,
+    /// Whether to include payload/metadata
+    #[serde(default = "default_true")]
+    pub with_payload: bool,
+    /// Records read from storage at a time, which bounds the export's memory
+    #[serde(default = "default_export_batch_size")]
+    pub batch_size: usize,
+}
+
+impl ExportRequest {
+    pub const DEFAULT_BATCH_SIZE: usize = 256;
+}
+
+fn default_export_batch_size() -> usize {
+    ExportRequest::DEFAULT_BATCH_SIZE
+}
+
 /// Scored point in results
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScoredPoint {
@@ -133,6 +186,50 @@ pub struct CountResponse {
     pub count: usize,
 }
 
+/// Points a payload operation applies to: `{"ids": [...]}` or `{"filter": {...}}`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PointSelector {
+    Ids(Vec<Uuid>),
+    Filter(Filter),
+}
+
+/// Set payload request - merge keys into, or overwrite, the selected points' payloads
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetPayloadRequest {
+    /// Over REST, taken from the path
+    #[serde(default)]
+    pub collection: String,
+    pub points: PointSelector,
+    pub payload: std::collections::HashMap<String, serde_json::Value>,
+}
+
+/// Delete payload keys request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeletePayloadKeysRequest {
+    /// Over REST, taken from the path
+    #[serde(default)]
+    pub collection: String,
+    pub points: PointSelector,
+    pub keys: Vec<String>,
+}
+
+/// Clear payload request - remove the selected points' payloads entirely
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClearPayloadRequest {
+    /// Over REST, taken from the path
+    #[serde(default)]
+    pub collection: String,
+    pub points: PointSelector,
+}
+
+/// Payload operation response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdatePayloadResponse {
+    /// Selected points that exist; IDs of missing points are skipped
+    pub updated: usize,
+}
+
 /// Batch search request - multiple queries in one request
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BatchSearchRequest {
@@ -183,6 +280,38 @@ pub struct GroupByRequest {
     /// Number of groups
     pub limit: usize,
     pub filter: Option<Filter>,
+    /// Attach a record from another collection to each group
+    #[serde(default)]
+    pub with_lookup: Option<WithLookup>,
+}
+
+/// Lookup of a group's parent record, whose ID is the group key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WithLookup {
+    /// Collection holding the parent records
+    pub collection: String,
+    #[serde(default = "default_true")]
+    pub with_payload: bool,
+    #[serde(default)]
+    pub with_vectors: bool,
+}
+
+/// Group by response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupByResponse {
+    pub groups: Vec<PointGroup>,
+}
+
+/// Best hits sharing one value of the group by field
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PointGroup {
+    /// Value of the group by field
+    pub id: serde_json::Value,
+    /// Hits ordered by distance, at most `group_size` of them
+    pub hits: Vec<QueryResult>,
+    /// Parent record, if a lookup was requested and the record exists
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lookup: Option<ScoredPoint>,
 }
 
 /// Facet request - get distribution of values for a field
@@ -196,10 +325,17 @@ pub struct FacetRequest {
     /// Maximum number of facet values
     #[serde(default = "default_facet_limit")]
     pub limit: usize,
+    /// Count every matching point instead of reading payload index postings
+    #[serde(default)]
+    pub exact: bool,
+}
+
+impl FacetRequest {
+    pub const DEFAULT_LIMIT: usize = 10;
 }
 
 fn default_facet_limit() -> usize {
-    10
+    FacetRequest::DEFAULT_LIMIT
 }
 
 /// Facet response
@@ -216,6 +352,97 @@ pub struct FacetValue {
     pub count: usize,
 }
 
+/// Evaluate request - measure the recall of index search against exact search
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvaluateRequest {
+    /// Over REST, taken from the path
+    #[serde(default)]
+    pub collection: String,
+    /// Query vectors; when empty, `sample` stored vectors are used instead
+    #[serde(default)]
+    pub queries: Vec<Vec<f32>>,
+    /// Number of stored vectors to query with when no queries are supplied
+    #[serde(default = "default_evaluate_sample")]
+    pub sample: usize,
+    /// Results per query, the k of recall@k
+    #[serde(default = "default_evaluate_limit")]
+    pub limit: usize,
+    /// `ef_search` values to evaluate; the collection's own when empty
+    #[serde(default)]
+    pub ef_search: Vec<usize>,
+    /// Named vector space to evaluate; the default vector when `None`
+    #[serde(default)]
+    pub using: Option<String>,
+}
+
+impl EvaluateRequest {
+    pub const DEFAULT_SAMPLE: usize = 100;
+    pub const DEFAULT_LIMIT: usize = 10;
+}
+
+fn default_evaluate_sample() -> usize {
+    EvaluateRequest::DEFAULT_SAMPLE
+}
+
+fn default_evaluate_limit() -> usize {
+    EvaluateRequest::DEFAULT_LIMIT
+}
+
+/// Evaluate response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvaluateResponse {
+    /// Number of queries run at each `ef_search`
+    pub queries: usize,
+    pub limit: usize,
+    /// Latency of the exact searches that give the ground truth
+    pub exact_latency: LatencySummary,
+    /// One run per evaluated `ef_search`, in request order
+    pub runs: Vec<EvaluationRun>,
+}
+
+/// Index search quality at one `ef_search`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvaluationRun {
+    pub ef_search: usize,
+    /// Mean fraction of the exact results the index search found
+    pub recall: f64,
+    pub latency: LatencySummary,
+    /// Mean number of vectors a query was compared against
+    pub visited_mean: f64,
+    pub visited_max: usize,
+}
+
+/// Latency distribution in milliseconds
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LatencySummary {
+    pub mean_ms: f64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+impl LatencySummary {
+    /// Summarize latency samples by nearest rank
+    pub fn from_samples(mut samples_ms: Vec<f64>) -> Self {
+        if samples_ms.is_empty() {
+            return Self::default();
+        }
+        samples_ms.sort_by(f64::total_cmp);
+        let percentile = |p: f64| {
+            let rank = (p / 100.0 * samples_ms.len() as f64).ceil() as usize;
+            samples_ms[rank.clamp(1, samples_ms.len()) - 1]
+        };
+        Self {
+            mean_ms: samples_ms.iter().sum::<f64>() / samples_ms.len() as f64,
+            p50_ms: percentile(50.0),
+            p95_ms: percentile(95.0),
+            p99_ms: percentile(99.0),
+            max_ms: samples_ms[samples_ms.len() - 1],
+        }
+    }
+}
+
 // Helper functions for recommendation search
 
 /// Compute average vector from multiple vectors
@@ -348,4 +575,17 @@ mod tests {
         // Applied to target (0, 0): result = (1, 1)
         assert_eq!(result, vec![1.0, 1.0]);
     }
+
+    #[test]
+    fn test_latency_summary() {
+        let summary = LatencySummary::from_samples((1..=100).rev().map(f64::from).collect());
+        assert_eq!(summary.p50_ms, 50.0);
+        assert_eq!(summary.p95_ms, 95.0);
+        assert_eq!(summary.p99_ms, 99.0);
+        assert_eq!(summary.max_ms, 100.0);
+        assert!((summary.mean_ms - 50.5).abs() < 1e-9);
+
+        assert_eq!(LatencySummary::from_samples(vec![3.0]).p50_ms, 3.0);
+        assert_eq!(LatencySummary::from_samples(Vec::new()).max_ms, 0.0);
+    }
 }
@@ -3,6 +3,8 @@
 
 #[cfg(target_arch = "x86_64")]
 use core::arch::x86_64::*;
+use half::f16;
+use half::slice::HalfFloatSliceExt;
 
 /// Calculate dot product between two vectors using SIMD
 pub fn dot_product(a: &[f32], b: &[f32]) -> f32 {
@@ -209,6 +211,129 @@ fn euclidean_distance_scalar(a: &[f32], b: &[f32]) -> f32 {
         .sqrt()
 }
 
+/// Components widened to f32 at a time by the f16 kernels
+const F16_CHUNK: usize = 64;
+
+/// Run `kernel` over matching chunks of two f16 vectors widened to f32
+#[inline]
+fn for_f16_chunks(a: &[f16], b: &[f16], mut kernel: impl FnMut(&[f32], &[f32])) {
+    assert_eq!(a.len(), b.len(), "Vector dimensions must match");
+
+    let mut wide_a = [0.0f32; F16_CHUNK];
+    let mut wide_b = [0.0f32; F16_CHUNK];
+    for (chunk_a, chunk_b) in a.chunks(F16_CHUNK).zip(b.chunks(F16_CHUNK)) {
+        let wide_a = &mut wide_a[..chunk_a.len()];
+        let wide_b = &mut wide_b[..chunk_b.len()];
+        chunk_a.convert_to_f32_slice(wide_a);
+        chunk_b.convert_to_f32_slice(wide_b);
+        kernel(wide_a, wide_b);
+    }
+}
+
+/// Dot product of two half precision vectors
+pub fn dot_product_f16(a: &[f16], b: &[f16]) -> f32 {
+    let mut sum = 0.0;
+    for_f16_chunks(a, b, |a, b| sum += dot_product(a, b));
+    sum
+}
+
+/// Euclidean distance between two half precision vectors
+pub fn euclidean_distance_f16(a: &[f16], b: &[f16]) -> f32 {
+    let mut sum = 0.0;
+    for_f16_chunks(a, b, |a, b| sum += euclidean_distance(a, b).powi(2));
+    sum.sqrt()
+}
+
+/// Cosine distance (1 - cosine similarity) between two half precision vectors
+pub fn cosine_distance_f16(a: &[f16], b: &[f16]) -> f32 {
+    let (mut dot, mut norm_a, mut norm_b) = (0.0, 0.0, 0.0);
+    for_f16_chunks(a, b, |a, b| {
+        dot += dot_product(a, b);
+        norm_a += dot_product(a, a);
+        norm_b += dot_product(b, b);
+    });
+
+    if norm_a == 0.0 || norm_b == 0.0 {
+        1.0
+    } else {
+        1.0 - dot / (norm_a * norm_b).sqrt()
+    }
+}
+
+/// Count the bits that differ between two packed bit vectors using SIMD
+pub fn hamming_distance(a: &[u8], b: &[u8]) -> u32 {
+    assert_eq!(a.len(), b.len(), "Vector dimensions must match");
+
+    #[cfg(all(target_arch = "x86_64", target_feature = "avx2"))]
+    {
+        unsafe { hamming_distance_avx2(a, b) }
+    }
+
+    #[cfg(not(all(target_arch = "x86_64", target_feature = "avx2")))]
+    {
+        hamming_distance_scalar(a, b)
+    }
+}
+
+/// AVX2 implementation (32 bytes at once), counting bits per nibble with a
+/// shuffle lookup table
+#[cfg(all(target_arch = "x86_64", target_feature = "avx2"))]
+#[target_feature(enable = "avx2")]
+#[inline]
+unsafe fn hamming_distance_avx2(a: &[u8], b: &[u8]) -> u32 {
+    const LANES: usize = 32;
+    let len = a.len();
+    let simd_len = len - (len % LANES);
+
+    let lookup = _mm256_setr_epi8(
+        0, 1, 1, 2, 1, 2, 2, 3, 1, 2, 2, 3, 2, 3, 3, 4,
+        0, 1, 1, 2, 1, 2, 2, 3, 1, 2, 2, 3, 2, 3, 3, 4,
+    );
+    let low_mask = _mm256_set1_epi8(0x0f);
+    let mut sum = _mm256_setzero_si256();
+
+    for i in (0..simd_len).step_by(LANES) {
+        let va = _mm256_loadu_si256(a.as_ptr().add(i) as *const __m256i);
+        let vb = _mm256_loadu_si256(b.as_ptr().add(i) as *const __m256i);
+        let diff = _mm256_xor_si256(va, vb);
+        let lo = _mm256_and_si256(diff, low_mask);
+        let hi = _mm256_and_si256(_mm256_srli_epi16(diff, 4), low_mask);
+        let counts = _mm256_add_epi8(_mm256_shuffle_epi8(lookup, lo), _mm256_shuffle_epi8(lookup, hi));
+        // Sum the byte counts into four 64-bit lanes
+        sum = _mm256_add_epi64(sum, _mm256_sad_epu8(counts, _mm256_setzero_si256()));
+    }
+
+    let mut lanes = [0u64; 4];
+    _mm256_storeu_si256(lanes.as_mut_ptr() as *mut __m256i, sum);
+    let result = lanes.iter().sum::<u64>() as u32;
+
+    // Handle remaining bytes
+    result + hamming_distance_scalar(&a[simd_len..], &b[simd_len..])
+}
+
+/// Scalar fallback, eight bytes per popcount
+#[inline]
+fn hamming_distance_scalar(a: &[u8], b: &[u8]) -> u32 {
+    let mut a_words = a.chunks_exact(8);
+    let mut b_words = b.chunks_exact(8);
+
+    let mut distance: u32 = a_words
+        .by_ref()
+        .zip(b_words.by_ref())
+        .map(|(x, y)| {
+            let x = u64::from_ne_bytes(x.try_into().unwrap());
+            let y = u64::from_ne_bytes(y.try_into().unwrap());
+            (x ^ y).count_ones()
+        })
+        .sum();
+
+    for (x, y) in a_words.remainder().iter().zip(b_words.remainder()) {
+        distance += (x ^ y).count_ones();
+    }
+
+    distance
+}
+
 /// Calculate Manhattan distance between two vectors
 pub fn manhattan_distance(a: &[f32], b: &[f32]) -> f32 {
     assert_eq!(a.len(), b.len(), "Vector dimensions must match");
@@ -285,6 +410,30 @@ mod tests {
         assert!((result - 7.0).abs() < 1e-5);
     }
 
+    #[test]
+    fn test_f16_kernels() {
+        let a: Vec<f32> = (0..150).map(|i| (i as f32 * 0.37).sin()).collect();
+        let b: Vec<f32> = (0..150).map(|i| (i as f32 * 0.11).cos()).collect();
+        let (a16, b16) = (crate::packed::to_f16(&a), crate::packed::to_f16(&b));
+
+        assert!((dot_product_f16(&a16, &b16) - dot_product(&a, &b)).abs() < 0.05);
+        assert!((euclidean_distance_f16(&a16, &b16) - euclidean_distance(&a, &b)).abs() < 0.01);
+        let cosine = 1.0 - crate::distance::cosine_similarity(&a, &b);
+        assert!((cosine_distance_f16(&a16, &b16) - cosine).abs() < 1e-3);
+        assert!(cosine_distance_f16(&a16, &a16).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_hamming_distance() {
+        let a: Vec<u8> = (0..100u32).map(|i| (i * 37 % 256) as u8).collect();
+        let b: Vec<u8> = (0..100u32).map(|i| (i * 91 % 256) as u8).collect();
+        let expected: u32 = a.iter().zip(&b).map(|(x, y)| (x ^ y).count_ones()).sum();
+
+        assert_eq!(hamming_distance(&a, &b), expected);
+        assert_eq!(hamming_distance_scalar(&a, &b), expected);
+        assert_eq!(hamming_distance(&a, &a), 0);
+    }
+
     #[test]
     fn test_batch_distances() {
         let query = vec![1.0, 0.0, 0.0];
@@ -46,6 +46,25 @@ impl SparseVector {
         dense
     }
 
+    /// Check that indices and values pair up, indices are unique and values are finite
+    pub fn validate(&self) -> std::result::Result<(), String> {
+        if self.indices.len() != self.values.len() {
+            return Err(format!(
+                "Sparse vector has {} indices but {} values",
+                self.indices.len(),
+                self.values.len()
+            ));
+        }
+        let mut seen = std::collections::HashSet::with_capacity(self.indices.len());
+        if let Some(index) = self.indices.iter().find(|&&idx| !seen.insert(idx)) {
+            return Err(format!("Sparse vector repeats index {}", index));
+        }
+        if self.values.iter().any(|v| !v.is_finite()) {
+            return Err("Sparse vector values must be finite".to_string());
+        }
+        Ok(())
+    }
+
     /// Get number of non-zero elements
     pub fn nnz(&self) -> usize {
         self.indices.len()
@@ -143,6 +162,9 @@ pub struct HybridSearchRequest {
     pub dense: Option<Vec<f32>>,
     /// Sparse vector query (keyword/BM25 search)
     pub sparse: Option<SparseVector>,
+    /// Sparse vector space to search; may be omitted when the collection has only one
+    #[serde(default)]
+    pub sparse_name: Option<String>,
     /// Fusion method for combining scores
     #[serde(default = "default_fusion")]
     pub fusion: FusionMethod,
@@ -168,7 +190,26 @@ pub enum FusionMethod {
     DistributionBasedScoreFusion,
 }
 
+/// Scoring applied to a named sparse vector space
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SparseModifier {
+    /// Dot product of query and stored weights
+    #[default]
+    None,
+    /// Stored weights are term frequencies, scored with BM25 over the collection
+    Bm25,
+}
+
+/// Configuration of a named sparse vector space
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SparseVectorParams {
+    #[serde(default)]
+    pub modifier: SparseModifier,
+}
+
 /// BM25 scorer for text-based sparse vectors
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BM25 {
     k1: f32,
     b: f32,
@@ -208,6 +249,48 @@ impl BM25 {
         self.avg_doc_length = (total_length + sparse_vec.nnz() as f32) / self.doc_count as f32;
     }
 
+    /// Remove a previously added document from corpus statistics
+    pub fn remove_document(&mut self, sparse_vec: &SparseVector) {
+        if self.doc_count == 0 {
+            return;
+        }
+
+        for idx in &sparse_vec.indices {
+            if let Some(df) = self.doc_frequencies.get_mut(idx) {
+                *df -= 1;
+                if *df == 0 {
+                    self.doc_frequencies.remove(idx);
+                }
+            }
+        }
+
+        let total_length = self.avg_doc_length * self.doc_count as f32 - sparse_vec.nnz() as f32;
+        self.doc_count -= 1;
+        self.avg_doc_length = if self.doc_count == 0 {
+            0.0
+        } else {
+            total_length.max(0.0) / self.doc_count as f32
+        };
+    }
+
+    /// Number of documents in corpus statistics
+    pub fn doc_count(&self) -> usize {
+        self.doc_count
+    }
+
+    /// BM25 weight of one term occurring `doc_tf` times in a document of `doc_length` terms
+    pub fn term_score(&self, term_id: u32, doc_tf: f32, doc_length: usize) -> f32 {
+        // IDF calculation
+        let df = self.doc_frequencies.get(&term_id).copied().unwrap_or(1);
+        let idf = ((self.doc_count as f32 - df as f32 + 0.5) / (df as f32 + 0.5) + 1.0).ln();
+
+        // BM25 formula
+        let normalized_tf = doc_tf * (self.k1 + 1.0)
+            / (doc_tf + self.k1 * (1.0 - self.b + self.b * doc_length as f32 / self.avg_doc_length));
+
+        idf * normalized_tf
+    }
+
     /// Compute BM25 score for a query against a document
     pub fn score(&self, query: &SparseVector, document: &SparseVector, doc_length: usize) -> f32 {
         let mut score = 0.0;
@@ -222,15 +305,7 @@ impl BM25 {
 
         for (&term_id, &query_tf) in query.indices.iter().zip(query.values.iter()) {
             if let Some(&doc_tf) = doc_terms.get(&term_id) {
-                // IDF calculation
-                let df = self.doc_frequencies.get(&term_id).copied().unwrap_or(1);
-                let idf = ((self.doc_count as f32 - df as f32 + 0.5) / (df as f32 + 0.5) + 1.0).ln();
-
-                // BM25 formula
-                let normalized_tf = doc_tf * (self.k1 + 1.0)
-                    / (doc_tf + self.k1 * (1.0 - self.b + self.b * doc_length as f32 / self.avg_doc_length));
-
-                score += idf * normalized_tf * query_tf;
+                score += self.term_score(term_id, doc_tf, doc_length) * query_tf;
             }
         }
 
@@ -337,13 +412,40 @@ fn reciprocal_rank_fusion(
 }
 
 /// Distribution-Based Score Fusion (DBSF)
+///
+/// Each list is normalized against its own score distribution, mapping
+/// mean ± 3 standard deviations onto 0-1, and normalized scores are summed.
 fn distribution_based_fusion(
     dense_results: Vec<(uuid::Uuid, f32)>,
     sparse_results: Vec<(uuid::Uuid, f32)>,
 ) -> Vec<(uuid::Uuid, f32)> {
-    // For now, use relative score fusion
-    // TODO: Implement proper DBSF with score distribution analysis
-    relative_score_fusion(dense_results, sparse_results)
+    let mut scores: HashMap<uuid::Uuid, f32> = HashMap::new();
+
+    for results in [dense_results, sparse_results] {
+        if results.is_empty() {
+            continue;
+        }
+
+        let count = results.len() as f32;
+        let mean = results.iter().map(|(_, s)| s).sum::<f32>() / count;
+        let variance = results.iter().map(|(_, s)| (s - mean).powi(2)).sum::<f32>() / count;
+        let low = mean - 3.0 * variance.sqrt();
+        let high = mean + 3.0 * variance.sqrt();
+
+        for (id, score) in results {
+            let normalized = if high > low {
+                ((score - low) / (high - low)).clamp(0.0, 1.0)
+            } else {
+                0.5
+            };
+            *scores.entry(id).or_insert(0.0) += normalized;
+        }
+    }
+
+    let mut results: Vec<(uuid::Uuid, f32)> = scores.into_iter().collect();
+    results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+    results
 }
 
 #[cfg(test)]
@@ -395,6 +497,39 @@ mod tests {
         assert!(score > 0.0);
     }
 
+    #[test]
+    fn test_bm25_remove_document() {
+        let mut bm25 = BM25::default();
+
+        let doc1 = SparseVector::new(vec![0, 1, 2], vec![3.0, 2.0, 1.0]);
+        let doc2 = SparseVector::new(vec![1, 2, 3], vec![1.0, 2.0, 1.0]);
+        let query = SparseVector::new(vec![1, 3], vec![1.0, 1.0]);
+
+        bm25.add_document(&doc2);
+        let alone = bm25.score(&query, &doc2, doc2.nnz());
+
+        bm25.add_document(&doc1);
+        bm25.remove_document(&doc1);
+
+        assert_eq!(bm25.doc_count(), 1);
+        assert!((bm25.score(&query, &doc2, doc2.nnz()) - alone).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_distribution_based_fusion() {
+        let ids: Vec<uuid::Uuid> = (0..3).map(|_| uuid::Uuid::new_v4()).collect();
+        // Dense scores are tightly packed, sparse scores spread out; DBSF puts
+        // both on the same footing, so the point ranked well in both wins
+        let dense = vec![(ids[0], 0.91), (ids[1], 0.90), (ids[2], 0.89)];
+        let sparse = vec![(ids[1], 12.0), (ids[0], 3.0)];
+
+        let fused = distribution_based_fusion(dense, sparse);
+
+        assert_eq!(fused.len(), 3);
+        assert_eq!(fused[0].0, ids[1]);
+        assert_eq!(fused[2].0, ids[2]);
+    }
+
     #[test]
     fn test_reciprocal_rank_fusion() {
         let dense = vec![
@@ -1,7 +1,9 @@
 use serde::{Deserialize, Serialize, Deserializer, Serializer};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use uuid::Uuid;
 
+use crate::sparse::{SparseVector, SparseVectorParams};
+
 /// Unique identifier for vectors
 pub type VectorId = Uuid;
 
@@ -9,6 +11,9 @@ pub type VectorId = Uuid;
 pub type CollectionId = String;
 
 /// Vector data types supported by the database
+///
+/// The element type a collection stores its dense vectors as, on disk and in
+/// its search index. The API always takes and returns `f32`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum VectorType {
     Float32,
@@ -16,6 +21,17 @@ pub enum VectorType {
     Int8,
 }
 
+impl VectorType {
+    /// Bytes each vector component takes
+    pub fn element_size(&self) -> usize {
+        match self {
+            VectorType::Float32 => 4,
+            VectorType::Float16 => 2,
+            VectorType::Int8 => 1,
+        }
+    }
+}
+
 /// Distance metrics for vector similarity
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DistanceMetric {
@@ -67,9 +83,20 @@ impl<'de> Deserialize<'de> for Metadata {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Vector {
     pub id: VectorId,
+    /// The default vector; empty for a point with only named vectors
+    #[serde(default)]
     pub data: Vec<f32>,
     #[serde(with = "metadata_serde")]
     pub metadata: Option<HashMap<String, serde_json::Value>>,
+    /// Named sparse vectors, for collections that declare sparse vector spaces
+    #[serde(default)]
+    pub sparse: Option<BTreeMap<String, SparseVector>>,
+    /// Named dense vectors, any subset of the collection's named vector spaces
+    #[serde(default)]
+    pub vectors: Option<BTreeMap<String, Vec<f32>>>,
+    /// Named multi-vectors: one row per token embedding, any number of rows
+    #[serde(default)]
+    pub multivectors: Option<BTreeMap<String, Vec<Vec<f32>>>>,
 }
 
 /// Custom serde module for metadata that works with both bincode and JSON
@@ -87,10 +114,13 @@ mod metadata_serde {
             // For JSON (human-readable): serialize as native object
             metadata.serialize(serializer)
         } else {
-            // For bincode (non-human-readable): serialize as JSON string
+            // For bincode (non-human-readable): serialize as JSON string.
+            // Keys are sorted so the same metadata always encodes to the same
+            // bytes; the WAL checksums operations by re-serializing them.
             match metadata {
                 Some(map) => {
-                    let json_string = serde_json::to_string(map)
+                    let sorted: std::collections::BTreeMap<_, _> = map.iter().collect();
+                    let json_string = serde_json::to_string(&sorted)
                         .map_err(serde::ser::Error::custom)?;
                     serializer.serialize_some(&json_string)
                 }
@@ -123,6 +153,69 @@ mod metadata_serde {
     }
 }
 
+/// `metadata_serde` for a payload that is always present
+mod payload_serde {
+    use super::*;
+
+    pub fn serialize<S>(payload: &HashMap<String, serde_json::Value>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if serializer.is_human_readable() {
+            return payload.serialize(serializer);
+        }
+        let sorted: BTreeMap<_, _> = payload.iter().collect();
+        let json_string = serde_json::to_string(&sorted).map_err(serde::ser::Error::custom)?;
+        serializer.serialize_str(&json_string)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<HashMap<String, serde_json::Value>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            return HashMap::deserialize(deserializer);
+        }
+        let json_string = String::deserialize(deserializer)?;
+        serde_json::from_str(&json_string).map_err(serde::de::Error::custom)
+    }
+}
+
+/// A change to points' payloads that leaves their vectors alone
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PayloadUpdate {
+    /// Merge keys into the payload, replacing the values of keys already set
+    Set(#[serde(with = "payload_serde")] HashMap<String, serde_json::Value>),
+    /// Replace the whole payload
+    Overwrite(#[serde(with = "payload_serde")] HashMap<String, serde_json::Value>),
+    /// Remove keys from the payload
+    DeleteKeys(Vec<String>),
+    /// Remove the payload entirely
+    Clear,
+}
+
+impl PayloadUpdate {
+    /// Apply the update to a point's payload
+    pub fn apply(&self, payload: &mut Option<HashMap<String, serde_json::Value>>) {
+        match self {
+            PayloadUpdate::Set(values) => {
+                let payload = payload.get_or_insert_with(HashMap::new);
+                payload.extend(values.iter().map(|(k, v)| (k.clone(), v.clone())));
+            }
+            PayloadUpdate::Overwrite(values) => *payload = Some(values.clone()),
+            PayloadUpdate::DeleteKeys(keys) => {
+                if let Some(payload) = payload {
+                    for key in keys {
+                        payload.remove(key);
+                    }
+                }
+            }
+            PayloadUpdate::Clear => *payload = None,
+        }
+    }
+}
+
 /// Collection configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CollectionConfig {
@@ -132,17 +225,76 @@ pub struct CollectionConfig {
     pub vector_type: VectorType,
     pub index_config: IndexConfig,
     /// Optional quantization configuration for memory reduction
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
     pub quantization: Option<crate::quantization::QuantizationConfig>,
+    /// Named sparse vector spaces, each backed by an inverted index
+    #[serde(default)]
+    pub sparse_vectors: Option<BTreeMap<String, SparseVectorParams>>,
+    /// Named dense vector spaces, next to the default one described above
+    #[serde(default)]
+    pub vectors: Option<BTreeMap<String, VectorParams>>,
+    /// Named multi-vector spaces, scored by MaxSim rather than indexed
+    #[serde(default)]
+    pub multivectors: Option<BTreeMap<String, MultiVectorParams>>,
+}
+
+impl CollectionConfig {
+    /// Whether the collection declares any named dense, sparse or multi-vector space
+    pub fn has_named_spaces(&self) -> bool {
+        self.vectors.as_ref().is_some_and(|spaces| !spaces.is_empty())
+            || self.sparse_vectors.as_ref().is_some_and(|spaces| !spaces.is_empty())
+            || self.multivectors.as_ref().is_some_and(|spaces| !spaces.is_empty())
+    }
+
+    /// Check a point's default vector against the collection's dimension
+    ///
+    /// With named spaces declared a point may leave it empty and carry only
+    /// named vectors; such points are kept out of the default index.
+    pub fn validate_default_vector(&self, data: &[f32]) -> crate::Result<()> {
+        if data.len() == self.dimension || (data.is_empty() && self.has_named_spaces()) {
+            return Ok(());
+        }
+        Err(crate::VectorDbError::InvalidDimension {
+            expected: self.dimension,
+            actual: data.len(),
+        })
+    }
+}
+
+/// A named dense vector space, searched through its own HNSW index
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VectorParams {
+    pub dimension: usize,
+    pub distance_metric: DistanceMetric,
+    /// Defaults to the collection's index configuration
+    #[serde(default)]
+    pub index_config: Option<IndexConfig>,
 }
 
-/// HNSW index configuration
+/// A named multi-vector space for late-interaction (ColBERT-style) scoring
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MultiVectorParams {
+    /// Dimension of every row
+    pub dimension: usize,
+    /// Metric comparing a query row with a document row inside MaxSim
+    pub distance_metric: DistanceMetric,
+}
+
+/// Index configuration
+///
+/// The HNSW parameters only apply to `hnsw` indexes and the IVF ones to
+/// `ivf_flat` and `ivf_pq` indexes.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IndexConfig {
     pub max_connections: usize,
     pub ef_construction: usize,
     pub ef_search: usize,
     pub max_layer: usize,
+    /// Structure of the index over the dense vectors
+    #[serde(default)]
+    pub index_type: IndexType,
+    #[serde(default)]
+    pub ivf: IvfConfig,
 }
 
 impl Default for IndexConfig {
@@ -152,10 +304,180 @@ impl Default for IndexConfig {
             ef_construction: 200,
             ef_search: 50,
             max_layer: 16,
+            index_type: IndexType::default(),
+            ivf: IvfConfig::default(),
         }
     }
 }
 
+/// Structure of a dense vector index, trading recall, latency and memory
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexType {
+    /// Navigable small-world graph: fast approximate search
+    #[default]
+    Hnsw,
+    /// Every vector is scanned: exact, for small collections
+    Flat,
+    /// Vectors bucketed by their nearest centroid; a query scans the
+    /// `nprobe` closest buckets exactly
+    IvfFlat,
+    /// As `ivf_flat`, holding product quantization codes of each vector's
+    /// offset from its centroid instead of the vector
+    IvfPq,
+}
+
+/// Inverted file (IVF) index configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IvfConfig {
+    /// Number of centroids, and so of buckets
+    #[serde(default = "default_nlist")]
+    pub nlist: usize,
+    /// Buckets scanned per query, closest centroid first
+    #[serde(default = "default_nprobe")]
+    pub nprobe: usize,
+    /// Collection size at which centroids are trained; smaller collections are scanned exactly
+    #[serde(default = "default_ivf_training_threshold")]
+    pub training_threshold: usize,
+    /// Product quantization segments for `ivf_pq`; must divide the dimension.
+    /// Defaults to segments of about four dimensions.
+    #[serde(default)]
+    pub pq_segments: Option<usize>,
+    /// Centroids per product quantization segment for `ivf_pq`, at most 256
+    #[serde(default = "default_pq_centroids")]
+    pub pq_centroids: usize,
+}
+
+impl IvfConfig {
+    /// Product quantization segments used for a `dimension`-wide vector
+    pub fn segments_for(&self, dimension: usize) -> usize {
+        self.pq_segments.unwrap_or_else(|| {
+            (1..=(dimension / 4).max(1))
+                .rev()
+                .find(|segments| dimension.is_multiple_of(*segments))
+                .unwrap_or(1)
+        })
+    }
+}
+
+impl Default for IvfConfig {
+    fn default() -> Self {
+        Self {
+            nlist: default_nlist(),
+            nprobe: default_nprobe(),
+            training_threshold: default_ivf_training_threshold(),
+            pq_segments: None,
+            pq_centroids: default_pq_centroids(),
+        }
+    }
+}
+
+fn default_nlist() -> usize {
+    256
+}
+
+fn default_nprobe() -> usize {
+    16
+}
+
+fn default_ivf_training_threshold() -> usize {
+    10_000
+}
+
+fn default_pq_centroids() -> usize {
+    256
+}
+
+/// Value type of an indexed payload field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PayloadFieldType {
+    /// Exact string values, for keyword matches
+    Keyword,
+    /// Integers, for integer matches and ranges
+    Integer,
+    /// Any number, for ranges
+    Float,
+    /// Booleans
+    Bool,
+    /// `{"lat": .., "lon": ..}` objects, for radius and bounding box filters
+    Geo,
+    /// Tokenized strings, for full-text matches
+    Text,
+}
+
+/// How a text index splits strings into tokens
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenizerType {
+    /// Split on whitespace only, keeping punctuation
+    Whitespace,
+    /// Split on anything that is not a letter or digit
+    #[default]
+    Word,
+    /// Words, indexed by every prefix so partial words match
+    Prefix,
+    /// Character n-grams of each word, so any part of a word matches
+    Ngram,
+}
+
+/// Built-in stop word lists
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StopWordsLanguage {
+    English,
+}
+
+/// Text index configuration
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextIndexParams {
+    #[serde(default)]
+    pub tokenizer: TokenizerType,
+    #[serde(default = "default_lowercase")]
+    pub lowercase: bool,
+    /// Shortest token kept; for `ngram` the smallest n-gram (default 3)
+    #[serde(default)]
+    pub min_token_len: Option<usize>,
+    /// Longest token kept; for `prefix` the longest prefix, for `ngram` the largest n-gram (default 3)
+    #[serde(default)]
+    pub max_token_len: Option<usize>,
+    /// Built-in stop word list to drop
+    #[serde(default)]
+    pub stop_words_language: Option<StopWordsLanguage>,
+    /// Extra words to drop
+    #[serde(default)]
+    pub stop_words: Vec<String>,
+}
+
+fn default_lowercase() -> bool {
+    true
+}
+
+impl Default for TextIndexParams {
+    fn default() -> Self {
+        Self {
+            tokenizer: TokenizerType::default(),
+            lowercase: true,
+            min_token_len: None,
+            max_token_len: None,
+            stop_words_language: None,
+            stop_words: Vec::new(),
+        }
+    }
+}
+
+/// A payload field index declared on a collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PayloadIndexInfo {
+    pub field_name: String,
+    pub field_type: PayloadFieldType,
+    /// Number of vectors with an indexed value for the field
+    pub indexed_vectors: usize,
+    /// Tokenizer settings, for text fields
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text_params: Option<TextIndexParams>,
+}
+
 /// Query request
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QueryRequest {
@@ -164,6 +486,50 @@ pub struct QueryRequest {
     pub limit: usize,
     pub ef_search: Option<usize>,
     pub filter: Option<crate::filter::Filter>,
+    /// Named vector space to search; the default vector when `None`
+    #[serde(default)]
+    pub using: Option<String>,
+    /// Score results against a multi-vector space by MaxSim
+    #[serde(default)]
+    pub multivector: Option<MultiVectorQuery>,
+    /// On a quantized collection, re-rank candidates by their original
+    /// vectors; defaults to true
+    #[serde(default)]
+    pub rescore: Option<bool>,
+    /// On a quantized collection, fetch `limit * oversampling` candidates for
+    /// rescoring; defaults to 1.0, or to the collection's own setting under
+    /// binary quantization
+    #[serde(default)]
+    pub oversampling: Option<f32>,
+    /// Bypass the index and scan every stored vector, for ground truth when
+    /// measuring recall
+    #[serde(default)]
+    pub exact: bool,
+}
+
+/// Late-interaction query against a multi-vector space
+///
+/// Results are ordered by MaxSim score, reported negated as the result
+/// distance so that smaller still means closer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MultiVectorQuery {
+    /// Multi-vector space to score against
+    pub using: String,
+    /// Query token embeddings
+    pub vectors: Vec<Vec<f32>>,
+    #[serde(default)]
+    pub mode: MultiVectorMode,
+}
+
+/// How a multi-vector query finds its candidates
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MultiVectorMode {
+    /// Rerank the candidates of the dense search
+    #[default]
+    Rescore,
+    /// Score every point in the collection; the dense query vector is ignored
+    BruteForce,
 }
 
 /// Query result
@@ -190,6 +556,40 @@ pub struct CollectionStats {
     pub dimension: usize,
     pub index_size: usize,
     pub memory_usage: usize,
+    /// Estimated in-memory footprint by component; sums to `memory_usage`
+    #[serde(default)]
+    pub memory: MemoryBreakdown,
+    /// Product quantizer training state, for product quantized collections
+    #[serde(default)]
+    pub quantization: Option<crate::quantization::TrainingStatus>,
+}
+
+/// Estimated memory usage in bytes, split by what holds it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemoryBreakdown {
+    /// Raw vector data held by the search index
+    pub vectors: usize,
+    /// HNSW graph structure (nodes, links, ID mappings)
+    pub index: usize,
+    /// Vector metadata
+    pub payload: usize,
+    /// Quantized vector codes
+    pub quantized: usize,
+}
+
+impl MemoryBreakdown {
+    pub fn total(&self) -> usize {
+        self.vectors + self.index + self.payload + self.quantized
+    }
+}
+
+impl std::ops::AddAssign for MemoryBreakdown {
+    fn add_assign(&mut self, other: Self) {
+        self.vectors += other.vectors;
+        self.index += other.index;
+        self.payload += other.payload;
+        self.quantized += other.quantized;
+    }
 }
 
 // Protocol buffer conversions
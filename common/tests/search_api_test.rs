@@ -81,6 +81,7 @@ fn test_scroll_request_defaults() {
         offset: None,
         with_vectors: true,
         with_payload: true,
+        order_by: None,
     };
 
     assert!(request.with_vectors);
@@ -97,7 +97,7 @@ fn test_multi_vector_from_sparse() {
 fn test_bm25_default_params() {
     let bm25 = BM25::default();
     // Just verify it creates without panicking
-    assert_eq!(bm25.doc_count, 0);
+    assert_eq!(bm25.doc_count(), 0);
 }
 
 #[test]
@@ -108,10 +108,10 @@ fn test_bm25_add_document() {
     let doc2 = SparseVector::new(vec![2, 3, 4], vec![1.0, 1.0, 1.0]);
 
     bm25.add_document(&doc1);
-    assert_eq!(bm25.doc_count, 1);
+    assert_eq!(bm25.doc_count(), 1);
 
     bm25.add_document(&doc2);
-    assert_eq!(bm25.doc_count, 2);
+    assert_eq!(bm25.doc_count(), 2);
 }
 
 #[test]
@@ -143,6 +143,7 @@ fn test_hybrid_search_request_serialization() {
         collection: "test".to_string(),
         dense: Some(vec![1.0, 2.0, 3.0]),
         sparse: Some(SparseVector::new(vec![0, 1], vec![1.0, 2.0])),
+        sparse_name: None,
         fusion: FusionMethod::ReciprocalRankFusion,
         limit: 10,
         filter: None,
@@ -0,0 +1,413 @@
+//! Binary quantization index
+//!
+//! A flat index holding each vector as one bit per dimension, set where the
+//! component is above the vector's mean. Queries are encoded the same way and
+//! every code is scored by its Hamming distance to the query, counted with
+//! SIMD popcounts. The distances are only a ranking signal, so callers rescore
+//! the candidates against the original vectors.
+
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::path::Path;
+use memmap2::MmapMut;
+use serde::{Deserialize, Serialize};
+use vectordb_common::quantization::QuantizedVector;
+use vectordb_common::simd::hamming_distance;
+use vectordb_common::types::*;
+use vectordb_common::{Result, VectorDbError};
+use crate::{SearchFilter, SearchResult};
+
+/// File the bit codes are mapped from when they are not kept in RAM
+const CODES_FILE: &str = "binary_codes.bin";
+
+/// Slots a memory-mapped code file has room for when created
+const INITIAL_MAPPED_SLOTS: usize = 1024;
+
+/// Serialized form of a `BinaryIndex`
+#[derive(Serialize, Deserialize)]
+struct BinaryDump {
+    dimension: usize,
+    codes: Vec<(VectorId, Vec<u8>)>,
+    /// Metadata map as JSON, since bincode cannot round-trip `serde_json::Value`
+    metadata_json: String,
+}
+
+/// Where an index's bit codes are held
+enum CodeStore {
+    Ram(Vec<u8>),
+    /// A file mapped into memory, which the OS may page out. It is unlinked
+    /// as soon as it is mapped, so it lives only as long as the index.
+    Mapped { file: File, map: MmapMut, len: usize },
+}
+
+impl CodeStore {
+    fn mapped(dir: &Path, capacity: usize) -> Result<Self> {
+        let path = dir.join(CODES_FILE);
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&path)?;
+        file.set_len(capacity as u64)?;
+        // SAFETY: the file was just created for this index and is unlinked
+        // below, so nothing else can resize or write it
+        let map = unsafe { MmapMut::map_mut(&file)? };
+        if let Err(e) = std::fs::remove_file(&path) {
+            tracing::warn!("Failed to unlink binary code file {}: {}", path.display(), e);
+        }
+        Ok(CodeStore::Mapped { file, map, len: 0 })
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        match self {
+            CodeStore::Ram(codes) => codes,
+            CodeStore::Mapped { map, len, .. } => &map[..*len],
+        }
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [u8] {
+        match self {
+            CodeStore::Ram(codes) => codes,
+            CodeStore::Mapped { map, len, .. } => &mut map[..*len],
+        }
+    }
+
+    /// Append `additional` zeroed bytes, doubling a mapped file when it is full
+    fn grow(&mut self, additional: usize) -> Result<()> {
+        match self {
+            CodeStore::Ram(codes) => codes.resize(codes.len() + additional, 0),
+            CodeStore::Mapped { file, map, len } => {
+                let needed = *len + additional;
+                if needed > map.len() {
+                    file.set_len(needed.max(map.len() * 2) as u64)?;
+                    // SAFETY: as in `mapped`, the file is private to this index
+                    *map = unsafe { MmapMut::map_mut(&*file)? };
+                }
+                *len = needed;
+            }
+        }
+        Ok(())
+    }
+
+    fn clear(&mut self) {
+        match self {
+            CodeStore::Ram(codes) => codes.clear(),
+            CodeStore::Mapped { len, .. } => *len = 0,
+        }
+    }
+
+    /// Bytes of codes held on the heap
+    fn ram_size(&self) -> usize {
+        match self {
+            CodeStore::Ram(codes) => codes.len(),
+            CodeStore::Mapped { .. } => 0,
+        }
+    }
+}
+
+pub struct BinaryIndex {
+    dimension: usize,
+    /// Bytes per code, one bit per dimension
+    code_size: usize,
+    /// Codes of every slot back to back
+    codes: CodeStore,
+    /// Vector held in each slot; `None` for a slot freed by a delete
+    slots: Vec<Option<VectorId>>,
+    id_to_slot: HashMap<VectorId, usize>,
+    free_slots: Vec<usize>,
+    metadata: HashMap<VectorId, HashMap<String, serde_json::Value>>,
+}
+
+impl BinaryIndex {
+    /// Index holding its bit codes in RAM
+    pub fn new(dimension: usize) -> Self {
+        Self {
+            dimension,
+            code_size: dimension.div_ceil(8),
+            codes: CodeStore::Ram(Vec::new()),
+            slots: Vec::new(),
+            id_to_slot: HashMap::new(),
+            free_slots: Vec::new(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    /// Index holding its bit codes in a memory-mapped file created in `dir`
+    pub fn mapped(dimension: usize, dir: &Path) -> Result<Self> {
+        let mut index = Self::new(dimension);
+        index.codes = CodeStore::mapped(dir, index.code_size.max(1) * INITIAL_MAPPED_SLOTS)?;
+        Ok(index)
+    }
+
+    /// Whether the bit codes are held in RAM rather than mapped from disk
+    pub fn is_in_ram(&self) -> bool {
+        matches!(self.codes, CodeStore::Ram(_))
+    }
+
+    fn check_dimension(&self, vector: &[f32]) -> Result<()> {
+        if vector.len() != self.dimension {
+            return Err(VectorDbError::InvalidDimension {
+                expected: self.dimension,
+                actual: vector.len(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Hold `code` under `id`, replacing whatever was held for it
+    fn store_code(&mut self, id: VectorId, code: &[u8]) -> Result<()> {
+        let slot = match self.id_to_slot.get(&id) {
+            Some(&slot) => slot,
+            None => {
+                let slot = match self.free_slots.pop() {
+                    Some(slot) => slot,
+                    None => {
+                        self.codes.grow(self.code_size)?;
+                        self.slots.push(None);
+                        self.slots.len() - 1
+                    }
+                };
+                self.slots[slot] = Some(id);
+                self.id_to_slot.insert(id, slot);
+                slot
+            }
+        };
+        self.codes.as_mut_slice()[slot * self.code_size..(slot + 1) * self.code_size].copy_from_slice(code);
+        Ok(())
+    }
+
+    /// Nearest `limit` vectors accepted by `accept` by Hamming distance, closest first
+    fn scan(&self, query: &[f32], limit: usize, accept: &dyn Fn(&VectorId) -> bool) -> Vec<(VectorId, u32)> {
+        let query = encode(query);
+        let codes = self.codes.as_slice();
+
+        let mut hits: Vec<(VectorId, u32)> = self
+            .slots
+            .iter()
+            .enumerate()
+            .filter_map(|(slot, id)| Some(((*id)?, slot)))
+            .filter(|(id, _)| accept(id))
+            .map(|(id, slot)| {
+                let code = &codes[slot * self.code_size..(slot + 1) * self.code_size];
+                (id, hamming_distance(&query, code))
+            })
+            .collect();
+
+        if hits.len() > limit {
+            hits.select_nth_unstable_by_key(limit, |hit| hit.1);
+            hits.truncate(limit);
+        }
+        hits.sort_by_key(|hit| hit.1);
+        hits
+    }
+
+    fn results(&self, hits: Vec<(VectorId, u32)>) -> Vec<SearchResult> {
+        hits.into_iter()
+            .map(|(id, distance)| SearchResult {
+                id,
+                distance: distance as f32,
+                metadata: self.metadata.get(&id).cloned(),
+            })
+            .collect()
+    }
+}
+
+/// Packed bits of `vector`, set where a component is above the vector's mean
+fn encode(vector: &[f32]) -> Vec<u8> {
+    match QuantizedVector::binary_quantize(vector) {
+        QuantizedVector::Binary { bits, .. } => bits,
+        _ => unreachable!("binary_quantize always returns binary codes"),
+    }
+}
+
+impl super::VectorIndex for BinaryIndex {
+    fn insert(
+        &mut self,
+        id: VectorId,
+        vector: &[f32],
+        metadata: Option<HashMap<String, serde_json::Value>>,
+    ) -> Result<()> {
+        self.check_dimension(vector)?;
+        self.store_code(id, &encode(vector))?;
+
+        match metadata {
+            Some(meta) => self.metadata.insert(id, meta),
+            None => self.metadata.remove(&id),
+        };
+        Ok(())
+    }
+
+    fn search(&self, query: &[f32], limit: usize, _ef: Option<usize>) -> Result<Vec<SearchResult>> {
+        self.check_dimension(query)?;
+        Ok(self.results(self.scan(query, limit, &|_| true)))
+    }
+
+    fn search_filtered(
+        &self,
+        query: &[f32],
+        limit: usize,
+        _ef: Option<usize>,
+        filter: &SearchFilter<'_>,
+    ) -> Result<Vec<SearchResult>> {
+        self.check_dimension(query)?;
+        let hits = self.scan(query, limit, &|id| filter(id, self.metadata.get(id)));
+        Ok(self.results(hits))
+    }
+
+    fn delete(&mut self, id: &VectorId) -> Result<bool> {
+        self.metadata.remove(id);
+        match self.id_to_slot.remove(id) {
+            Some(slot) => {
+                self.slots[slot] = None;
+                self.free_slots.push(slot);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    fn set_metadata(&mut self, id: &VectorId, metadata: Option<HashMap<String, serde_json::Value>>) -> bool {
+        if !self.id_to_slot.contains_key(id) {
+            return false;
+        }
+        match metadata {
+            Some(meta) => self.metadata.insert(*id, meta),
+            None => self.metadata.remove(id),
+        };
+        true
+    }
+
+    fn stats(&self) -> super::IndexStats {
+        const MAPPING_SIZE: usize = 2 * (std::mem::size_of::<VectorId>() + std::mem::size_of::<usize>());
+
+        let memory = MemoryBreakdown {
+            vectors: 0,
+            index: self.slots.len() * std::mem::size_of::<Option<VectorId>>() + self.id_to_slot.len() * MAPPING_SIZE,
+            payload: self.metadata.values().map(crate::payload_size).sum(),
+            quantized: self.codes.ram_size(),
+        };
+
+        super::IndexStats {
+            vector_count: self.id_to_slot.len(),
+            memory_usage: memory.total(),
+            memory,
+            dimension: self.dimension,
+            max_layer: 0,
+            avg_connections: 0.0,
+        }
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>> {
+        let codes = self.codes.as_slice();
+        let dump = BinaryDump {
+            dimension: self.dimension,
+            codes: self
+                .id_to_slot
+                .iter()
+                .map(|(id, &slot)| (*id, codes[slot * self.code_size..(slot + 1) * self.code_size].to_vec()))
+                .collect(),
+            metadata_json: serde_json::to_string(&self.metadata)?,
+        };
+        bincode::serialize(&dump).map_err(|e| VectorDbError::Serialization(e.to_string()))
+    }
+
+    fn deserialize(&mut self, data: &[u8]) -> Result<()> {
+        let dump: BinaryDump = bincode::deserialize(data)
+            .map_err(|e| VectorDbError::Serialization(e.to_string()))?;
+
+        if dump.dimension != self.dimension {
+            return Err(VectorDbError::IndexError {
+                message: format!(
+                    "Binary dump is for dimension {} but index is {}",
+                    dump.dimension, self.dimension
+                ),
+            });
+        }
+
+        self.codes.clear();
+        self.slots.clear();
+        self.id_to_slot.clear();
+        self.free_slots.clear();
+        for (id, code) in dump.codes {
+            self.store_code(id, &code)?;
+        }
+        self.metadata = serde_json::from_str(&dump.metadata_json)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VectorIndex;
+    use uuid::Uuid;
+
+    fn vectors() -> Vec<(VectorId, Vec<f32>)> {
+        (0..300u32)
+            .map(|i| {
+                let vector = (0..64).map(|d| if (i >> (d % 9)) & 1 == 1 { 1.0 } else { -1.0 }).collect();
+                (Uuid::new_v4(), vector)
+            })
+            .collect()
+    }
+
+    fn check_index(mut index: BinaryIndex) {
+        let vectors = vectors();
+        index
+            .batch_insert(vectors.iter().map(|(id, v)| (*id, v.clone(), None)).collect())
+            .unwrap();
+        assert_eq!(index.stats().vector_count, 300);
+
+        // Vectors with the same sign pattern share a code
+        let results = index.search(&vectors[42].1, 2, None).unwrap();
+        assert_eq!(results[0].distance, 0.0);
+        assert!(results.iter().any(|r| r.id == vectors[42].0));
+
+        // Deleted slots are reused and never returned
+        assert!(index.delete(&vectors[42].0).unwrap());
+        let replacement = Uuid::new_v4();
+        index.insert(replacement, &vectors[7].1, None).unwrap();
+        let results = index
+            .search_filtered(&vectors[42].1, 300, None, &|id, _| *id != replacement)
+            .unwrap();
+        assert_eq!(results.len(), 299);
+        assert!(results.iter().all(|r| r.id != vectors[42].0));
+        assert!(results.windows(2).all(|w| w[0].distance <= w[1].distance));
+
+        let mut restored = BinaryIndex::new(64);
+        restored.deserialize(&index.serialize().unwrap()).unwrap();
+        assert_eq!(restored.stats().vector_count, 300);
+        assert_eq!(restored.search(&vectors[7].1, 1, None).unwrap()[0].distance, 0.0);
+    }
+
+    #[test]
+    fn test_binary_index_in_ram() {
+        let index = BinaryIndex::new(64);
+        assert!(index.is_in_ram());
+        check_index(index);
+    }
+
+    #[test]
+    fn test_binary_index_mapped() {
+        let dir = tempfile::tempdir().unwrap();
+        let index = BinaryIndex::mapped(64, dir.path()).unwrap();
+        assert!(!index.is_in_ram());
+        // The code file is unlinked once mapped
+        assert!(!dir.path().join(CODES_FILE).exists());
+        assert_eq!(index.stats().memory.quantized, 0);
+        check_index(index);
+
+        // Growing past the initial mapping keeps earlier codes
+        let mut index = BinaryIndex::mapped(64, dir.path()).unwrap();
+        let vectors = vectors();
+        for i in 0..INITIAL_MAPPED_SLOTS * 3 {
+            let (id, vector) = &vectors[i % vectors.len()];
+            let id = if i < vectors.len() { *id } else { Uuid::new_v4() };
+            index.insert(id, vector, None).unwrap();
+        }
+        assert_eq!(index.stats().vector_count, INITIAL_MAPPED_SLOTS * 3);
+        let results = index.search(&vectors[42].1, 1, None).unwrap();
+        assert_eq!(results[0].distance, 0.0);
+    }
+}
@@ -0,0 +1,404 @@
+//! Flat (exact) index
+//!
+//! Holds every vector in the collection's element type, back to back, and
+//! scans all of them per query. Search is exact and needs no training, which
+//! suits small collections and validating the recall of approximate indexes.
+
+use std::collections::HashMap;
+use half::f16;
+use half::slice::HalfFloatSliceExt;
+use serde::{Deserialize, Serialize};
+use vectordb_common::distance::{dot_product, euclidean_distance, normalize};
+use vectordb_common::packed::{to_f16, to_int8};
+use vectordb_common::types::*;
+use vectordb_common::{Result, VectorDbError};
+use crate::{SearchFilter, SearchResult};
+
+/// Vectors of one dimension held back to back in an element type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) enum VectorSlab {
+    Float32(Vec<f32>),
+    Float16(Vec<f16>),
+    /// Symmetric int8 codes with one scale per vector
+    Int8 { codes: Vec<i8>, scales: Vec<f32> },
+}
+
+impl VectorSlab {
+    pub(crate) fn new(vector_type: VectorType) -> Self {
+        match vector_type {
+            VectorType::Float32 => VectorSlab::Float32(Vec::new()),
+            VectorType::Float16 => VectorSlab::Float16(Vec::new()),
+            VectorType::Int8 => VectorSlab::Int8 { codes: Vec::new(), scales: Vec::new() },
+        }
+    }
+
+    pub(crate) fn vector_type(&self) -> VectorType {
+        match self {
+            VectorSlab::Float32(_) => VectorType::Float32,
+            VectorSlab::Float16(_) => VectorType::Float16,
+            VectorSlab::Int8 { .. } => VectorType::Int8,
+        }
+    }
+
+    /// Store `vector` in `slot`, which is either taken or the next free one
+    pub(crate) fn set(&mut self, slot: usize, vector: &[f32]) {
+        let dimension = vector.len();
+        let range = slot * dimension..(slot + 1) * dimension;
+        match self {
+            VectorSlab::Float32(values) => {
+                if values.len() < range.end {
+                    values.resize(range.end, 0.0);
+                }
+                values[range].copy_from_slice(vector);
+            }
+            VectorSlab::Float16(values) => {
+                if values.len() < range.end {
+                    values.resize(range.end, f16::ZERO);
+                }
+                values[range].copy_from_slice(&to_f16(vector));
+            }
+            VectorSlab::Int8 { codes, scales } => {
+                if codes.len() < range.end {
+                    codes.resize(range.end, 0);
+                    scales.resize(slot + 1, 0.0);
+                }
+                let (vector_codes, scale) = to_int8(vector);
+                codes[range].copy_from_slice(&vector_codes);
+                scales[slot] = scale;
+            }
+        }
+    }
+
+    /// The vector in `slot`, widened into `buffer` unless it is held as f32
+    pub(crate) fn get<'a>(&'a self, slot: usize, buffer: &'a mut [f32]) -> &'a [f32] {
+        let dimension = buffer.len();
+        let range = slot * dimension..(slot + 1) * dimension;
+        match self {
+            VectorSlab::Float32(values) => &values[range],
+            VectorSlab::Float16(values) => {
+                values[range].convert_to_f32_slice(buffer);
+                buffer
+            }
+            VectorSlab::Int8 { codes, scales } => {
+                for (value, &code) in buffer.iter_mut().zip(&codes[range]) {
+                    *value = code as f32 * scales[slot];
+                }
+                buffer
+            }
+        }
+    }
+
+    /// Bytes held for the vectors
+    pub(crate) fn memory_size(&self) -> usize {
+        match self {
+            VectorSlab::Float32(values) => values.len() * std::mem::size_of::<f32>(),
+            VectorSlab::Float16(values) => values.len() * std::mem::size_of::<f16>(),
+            VectorSlab::Int8 { codes, scales } => codes.len() + scales.len() * std::mem::size_of::<f32>(),
+        }
+    }
+}
+
+/// Vector as it is stored and compared: normalized unless the metric is Euclidean
+pub(crate) fn prepare(distance_metric: DistanceMetric, vector: &[f32]) -> Vec<f32> {
+    let mut prepared = vector.to_vec();
+    if distance_metric != DistanceMetric::Euclidean {
+        normalize(&mut prepared);
+    }
+    prepared
+}
+
+/// Distance between prepared vectors in the metric every index reports:
+/// Euclidean for Euclidean collections and cosine distance otherwise
+pub(crate) fn prepared_distance(distance_metric: DistanceMetric, a: &[f32], b: &[f32]) -> f32 {
+    if distance_metric == DistanceMetric::Euclidean {
+        euclidean_distance(a, b)
+    } else {
+        1.0 - dot_product(a, b)
+    }
+}
+
+/// Keep the `limit` closest hits, closest first
+pub(crate) fn nearest(mut hits: Vec<(VectorId, f32)>, limit: usize) -> Vec<(VectorId, f32)> {
+    if hits.len() > limit {
+        hits.select_nth_unstable_by(limit, |a, b| a.1.total_cmp(&b.1));
+        hits.truncate(limit);
+    }
+    hits.sort_by(|a, b| a.1.total_cmp(&b.1));
+    hits
+}
+
+/// Serialized form of a `FlatIndex`
+#[derive(Serialize, Deserialize)]
+struct FlatDump {
+    distance_metric: DistanceMetric,
+    dimension: usize,
+    vectors: VectorSlab,
+    slots: Vec<Option<VectorId>>,
+    /// Metadata map as JSON, since bincode cannot round-trip `serde_json::Value`
+    metadata_json: String,
+}
+
+pub struct FlatIndex {
+    distance_metric: DistanceMetric,
+    dimension: usize,
+    vectors: VectorSlab,
+    /// Vector held in each slot; `None` for a slot freed by a delete
+    slots: Vec<Option<VectorId>>,
+    id_to_slot: HashMap<VectorId, usize>,
+    free_slots: Vec<usize>,
+    metadata: HashMap<VectorId, HashMap<String, serde_json::Value>>,
+}
+
+impl FlatIndex {
+    /// Empty index holding vectors as `vector_type`
+    pub fn new(distance_metric: DistanceMetric, dimension: usize, vector_type: VectorType) -> Self {
+        Self {
+            distance_metric,
+            dimension,
+            vectors: VectorSlab::new(vector_type),
+            slots: Vec::new(),
+            id_to_slot: HashMap::new(),
+            free_slots: Vec::new(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    fn check_dimension(&self, vector: &[f32]) -> Result<()> {
+        if vector.len() != self.dimension {
+            return Err(VectorDbError::InvalidDimension {
+                expected: self.dimension,
+                actual: vector.len(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Exact nearest `limit` vectors accepted by `accept`, closest first
+    fn scan(&self, query: &[f32], limit: usize, accept: &dyn Fn(&VectorId) -> bool) -> Vec<(VectorId, f32)> {
+        let query = prepare(self.distance_metric, query);
+        let mut buffer = vec![0.0; self.dimension];
+        let hits = self
+            .slots
+            .iter()
+            .enumerate()
+            .filter_map(|(slot, id)| Some(((*id)?, slot)))
+            .filter(|(id, _)| accept(id))
+            .map(|(id, slot)| {
+                let vector = self.vectors.get(slot, &mut buffer);
+                (id, prepared_distance(self.distance_metric, &query, vector))
+            })
+            .collect();
+        nearest(hits, limit)
+    }
+
+    fn results(&self, hits: Vec<(VectorId, f32)>) -> Vec<SearchResult> {
+        hits.into_iter()
+            .map(|(id, distance)| SearchResult {
+                id,
+                distance,
+                metadata: self.metadata.get(&id).cloned(),
+            })
+            .collect()
+    }
+}
+
+impl super::VectorIndex for FlatIndex {
+    fn insert(
+        &mut self,
+        id: VectorId,
+        vector: &[f32],
+        metadata: Option<HashMap<String, serde_json::Value>>,
+    ) -> Result<()> {
+        self.check_dimension(vector)?;
+
+        let slot = match self.id_to_slot.get(&id) {
+            Some(&slot) => slot,
+            None => {
+                let slot = self.free_slots.pop().unwrap_or_else(|| {
+                    self.slots.push(None);
+                    self.slots.len() - 1
+                });
+                self.slots[slot] = Some(id);
+                self.id_to_slot.insert(id, slot);
+                slot
+            }
+        };
+        self.vectors.set(slot, &prepare(self.distance_metric, vector));
+
+        match metadata {
+            Some(meta) => self.metadata.insert(id, meta),
+            None => self.metadata.remove(&id),
+        };
+        Ok(())
+    }
+
+    fn search(&self, query: &[f32], limit: usize, _ef: Option<usize>) -> Result<Vec<SearchResult>> {
+        self.check_dimension(query)?;
+        Ok(self.results(self.scan(query, limit, &|_| true)))
+    }
+
+    fn search_filtered(
+        &self,
+        query: &[f32],
+        limit: usize,
+        _ef: Option<usize>,
+        filter: &SearchFilter<'_>,
+    ) -> Result<Vec<SearchResult>> {
+        self.check_dimension(query)?;
+        let hits = self.scan(query, limit, &|id| filter(id, self.metadata.get(id)));
+        Ok(self.results(hits))
+    }
+
+    fn delete(&mut self, id: &VectorId) -> Result<bool> {
+        self.metadata.remove(id);
+        match self.id_to_slot.remove(id) {
+            Some(slot) => {
+                self.slots[slot] = None;
+                self.free_slots.push(slot);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    fn set_metadata(&mut self, id: &VectorId, metadata: Option<HashMap<String, serde_json::Value>>) -> bool {
+        if !self.id_to_slot.contains_key(id) {
+            return false;
+        }
+        match metadata {
+            Some(meta) => self.metadata.insert(*id, meta),
+            None => self.metadata.remove(id),
+        };
+        true
+    }
+
+    fn stats(&self) -> super::IndexStats {
+        const MAPPING_SIZE: usize = 2 * (std::mem::size_of::<VectorId>() + std::mem::size_of::<usize>());
+
+        let memory = MemoryBreakdown {
+            vectors: self.vectors.memory_size(),
+            index: self.slots.len() * std::mem::size_of::<Option<VectorId>>() + self.id_to_slot.len() * MAPPING_SIZE,
+            payload: self.metadata.values().map(crate::payload_size).sum(),
+            quantized: 0,
+        };
+
+        super::IndexStats {
+            vector_count: self.id_to_slot.len(),
+            memory_usage: memory.total(),
+            memory,
+            dimension: self.dimension,
+            max_layer: 0,
+            avg_connections: 0.0,
+        }
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>> {
+        let dump = FlatDump {
+            distance_metric: self.distance_metric,
+            dimension: self.dimension,
+            vectors: self.vectors.clone(),
+            slots: self.slots.clone(),
+            metadata_json: serde_json::to_string(&self.metadata)?,
+        };
+        bincode::serialize(&dump).map_err(|e| VectorDbError::Serialization(e.to_string()))
+    }
+
+    fn deserialize(&mut self, data: &[u8]) -> Result<()> {
+        let dump: FlatDump = bincode::deserialize(data)
+            .map_err(|e| VectorDbError::Serialization(e.to_string()))?;
+
+        if dump.distance_metric != self.distance_metric
+            || dump.dimension != self.dimension
+            || dump.vectors.vector_type() != self.vectors.vector_type()
+        {
+            return Err(VectorDbError::IndexError {
+                message: format!(
+                    "Flat dump is for {:?}/{}/{:?} but index is {:?}/{}/{:?}",
+                    dump.distance_metric,
+                    dump.dimension,
+                    dump.vectors.vector_type(),
+                    self.distance_metric,
+                    self.dimension,
+                    self.vectors.vector_type()
+                ),
+            });
+        }
+
+        self.id_to_slot = dump
+            .slots
+            .iter()
+            .enumerate()
+            .filter_map(|(slot, id)| Some(((*id)?, slot)))
+            .collect();
+        self.free_slots = dump
+            .slots
+            .iter()
+            .enumerate()
+            .filter_map(|(slot, id)| id.is_none().then_some(slot))
+            .collect();
+        self.slots = dump.slots;
+        self.vectors = dump.vectors;
+        self.metadata = serde_json::from_str(&dump.metadata_json)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VectorIndex;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_flat_index_is_exact() {
+        let vectors: Vec<(VectorId, Vec<f32>)> = (0..100)
+            .map(|i| (Uuid::new_v4(), vec![(i % 10) as f32, (i / 10) as f32, 1.0]))
+            .collect();
+
+        for vector_type in [VectorType::Float32, VectorType::Float16, VectorType::Int8] {
+            let mut index = FlatIndex::new(DistanceMetric::Euclidean, 3, vector_type);
+            index
+                .batch_insert(vectors.iter().map(|(id, v)| (*id, v.clone(), None)).collect())
+                .unwrap();
+
+            let results = index.search(&[3.0, 4.0, 1.0], 5, None).unwrap();
+            assert_eq!(results[0].id, vectors[43].0, "{:?}", vector_type);
+            assert!(results[0].distance < 0.05);
+            assert!(results.windows(2).all(|w| w[0].distance <= w[1].distance));
+
+            let stats = index.stats();
+            assert_eq!(stats.vector_count, 100);
+            assert!(stats.memory.vectors >= 100 * 3 * vector_type.element_size());
+
+            // Deleted slots are reused and never returned
+            assert!(index.delete(&vectors[43].0).unwrap());
+            let replacement = Uuid::new_v4();
+            index.insert(replacement, &[9.0, 9.0, 1.0], None).unwrap();
+            assert_eq!(index.stats().vector_count, 100);
+            let results = index
+                .search_filtered(&[3.0, 4.0, 1.0], 100, None, &|id, _| *id != replacement)
+                .unwrap();
+            assert_eq!(results.len(), 99);
+            assert!(results.iter().all(|r| r.id != vectors[43].0));
+
+            let mut restored = FlatIndex::new(DistanceMetric::Euclidean, 3, vector_type);
+            restored.deserialize(&index.serialize().unwrap()).unwrap();
+            assert_eq!(restored.stats().vector_count, 100);
+            assert_eq!(restored.search(&[9.0, 9.0, 1.0], 2, None).unwrap().len(), 2);
+        }
+
+        // Cosine distances, as the other indexes report them
+        let mut index = FlatIndex::new(DistanceMetric::Cosine, 2, VectorType::Float32);
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        index.insert(a, &[1.0, 0.0], None).unwrap();
+        index.insert(b, &[0.0, 2.0], None).unwrap();
+        let results = index.search(&[3.0, 0.0], 2, None).unwrap();
+        assert_eq!(results[0].id, a);
+        assert!(results[0].distance.abs() < 1e-6);
+        assert!((results[1].distance - 1.0).abs() < 1e-6);
+
+        // A dump for another element type is refused
+        let mut float16 = FlatIndex::new(DistanceMetric::Cosine, 2, VectorType::Float16);
+        assert!(float16.deserialize(&index.serialize().unwrap()).is_err());
+    }
+}
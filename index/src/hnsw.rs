@@ -368,6 +368,20 @@ impl VectorIndex for HnswIndex {
         Ok(true)
     }
 
+    fn set_metadata(
+        &mut self,
+        id: &VectorId,
+        metadata: Option<std::collections::HashMap<String, serde_json::Value>>,
+    ) -> bool {
+        match self.nodes.get_mut(id) {
+            Some(mut node) => {
+                node.metadata = metadata;
+                true
+            }
+            None => false,
+        }
+    }
+
     fn stats(&self) -> IndexStats {
         let vector_count = self.nodes.len();
 
@@ -376,6 +390,18 @@ impl VectorIndex for HnswIndex {
             .map(|entry| entry.value().memory_usage())
             .sum();
 
+        let vector_memory = vector_count * self.dimension * std::mem::size_of::<f32>();
+        let payload_memory: usize = self.nodes
+            .iter()
+            .filter_map(|entry| entry.value().metadata.as_ref().map(crate::payload_size))
+            .sum();
+        let memory = MemoryBreakdown {
+            vectors: vector_memory,
+            index: memory_usage.saturating_sub(vector_memory + payload_memory),
+            payload: payload_memory,
+            quantized: 0,
+        };
+
         let max_layer = self.nodes
             .iter()
             .map(|entry| entry.value().layer)
@@ -396,7 +422,8 @@ impl VectorIndex for HnswIndex {
 
         IndexStats {
             vector_count,
-            memory_usage,
+            memory_usage: memory.total(),
+            memory,
             dimension: self.dimension,
             max_layer,
             avg_connections,
@@ -472,6 +499,7 @@ mod tests {
             ef_construction: 200,
             ef_search: 50,
             max_layer: 16,
+            ..IndexConfig::default()
         };
         HnswIndex::new(config, DistanceMetric::Cosine, 3)
     }
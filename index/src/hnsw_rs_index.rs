@@ -1,23 +1,408 @@
 /// Adapter for hnsw_rs library - production-ready HNSW implementation
 use vectordb_common::{Result, VectorDbError};
 use vectordb_common::types::*;
+use vectordb_common::distance::normalize;
+use vectordb_common::packed::to_f16;
+use vectordb_common::simd::{cosine_distance_f16, euclidean_distance_f16};
+use vectordb_common::quantization::{
+    scalar_int8_distance, QuantizationConfig, QuantizedDistanceType, ScalarQuantizer, DEFAULT_FIT_THRESHOLD,
+};
 use hnsw_rs::prelude::*;
-use hnsw_rs::anndists::dist::*;
+use hnsw_rs::hnswio::HnswIo;
+use half::f16;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::cell::Cell;
 use std::sync::Arc;
+use std::path::{Path, PathBuf};
 use parking_lot::RwLock;
-use std::collections::HashMap;
-use crate::SearchResult;
+use std::collections::{HashMap, HashSet};
+use crate::{IndexedVector, SearchFilter, SearchResult};
+
+/// Basename hnsw_rs uses for the `.hnsw.graph` / `.hnsw.data` files it dumps
+const DUMP_BASENAME: &str = "index";
+
+/// Bump when the layout of `HnswRsDump` changes
+const DUMP_VERSION: u32 = 4;
+
+/// Live vectors checked to estimate how selective a search filter is
+const SELECTIVITY_SAMPLE: usize = 1024;
+
+/// Below this estimated fraction of matching vectors, filtered search scans
+/// every point instead of walking the graph
+const BRUTE_FORCE_SELECTIVITY: f64 = 0.01;
+
+/// Upper bound on the candidate list used for filtered graph search
+const MAX_FILTERED_EF: usize = 4096;
+
+/// Full precision vectors under their internal indices
+type FloatPoints = Vec<(Vec<f32>, usize)>;
+
+/// Serialized form of an `HnswRsIndex`: hnsw_rs' own graph and data dumps plus
+/// the ID mappings and metadata we keep alongside the graph
+#[derive(Serialize, Deserialize)]
+struct HnswRsDump {
+    version: u32,
+    distance_metric: DistanceMetric,
+    dimension: usize,
+    element: GraphElement,
+    quantizer: Option<ScalarQuantizer>,
+    unfitted: FloatPoints,
+    next_idx: usize,
+    id_to_idx: Vec<(VectorId, usize)>,
+    /// Metadata map as JSON, since bincode cannot round-trip `serde_json::Value`
+    metadata_json: String,
+    graph: Vec<u8>,
+    data: Vec<u8>,
+}
+
+/// Scratch directory for hnsw_rs file dumps, removed on drop
+struct ScratchDir(PathBuf);
+
+impl ScratchDir {
+    fn new() -> Result<Self> {
+        let path = std::env::temp_dir().join(format!("vectordb-hnsw-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&path)?;
+        Ok(Self(path))
+    }
+
+    fn path(&self) -> &Path {
+        &self.0
+    }
+
+    fn file(&self, extension: &str) -> PathBuf {
+        self.0.join(format!("{}.hnsw.{}", DUMP_BASENAME, extension))
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+thread_local! {
+    /// Distance evaluations made on this thread, to count the points a search visits
+    static EVALUATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+fn count_evaluation() {
+    EVALUATIONS.with(|count| count.set(count.get() + 1));
+}
+
+/// Run `f`, returning how many distances it evaluated on this thread
+fn counting_evaluations<R>(f: impl FnOnce() -> R) -> (R, usize) {
+    let before = EVALUATIONS.with(Cell::get);
+    let result = f();
+    (result, EVALUATIONS.with(Cell::get) - before)
+}
+
+/// hnsw_rs' cosine distance, counting evaluations
+///
+/// hnsw_rs checks a dump's distance by its short type name on load, so this
+/// keeps the name of the distance it wraps.
+#[derive(Debug, Clone, Copy, Default)]
+struct DistCosine;
+
+impl Distance<f32> for DistCosine {
+    fn eval(&self, va: &[f32], vb: &[f32]) -> f32 {
+        count_evaluation();
+        hnsw_rs::prelude::DistCosine.eval(va, vb)
+    }
+}
+
+/// hnsw_rs' Euclidean distance, counting evaluations
+#[derive(Debug, Clone, Copy, Default)]
+struct DistL2;
+
+impl Distance<f32> for DistL2 {
+    fn eval(&self, va: &[f32], vb: &[f32]) -> f32 {
+        count_evaluation();
+        hnsw_rs::prelude::DistL2.eval(va, vb)
+    }
+}
+
+/// Euclidean distance between the int8 codes of scalar quantized vectors
+#[derive(Debug, Clone, Copy, Default)]
+struct DistScalarInt8;
+
+impl Distance<i8> for DistScalarInt8 {
+    fn eval(&self, va: &[i8], vb: &[i8]) -> f32 {
+        count_evaluation();
+        scalar_int8_distance(va, vb, QuantizedDistanceType::Euclidean)
+    }
+}
+
+/// Cosine distance between half precision vectors
+#[derive(Debug, Clone, Copy, Default)]
+struct DistF16Cosine;
+
+impl Distance<f16> for DistF16Cosine {
+    fn eval(&self, va: &[f16], vb: &[f16]) -> f32 {
+        count_evaluation();
+        cosine_distance_f16(va, vb)
+    }
+}
+
+/// Euclidean distance between half precision vectors
+#[derive(Debug, Clone, Copy, Default)]
+struct DistF16L2;
+
+impl Distance<f16> for DistF16L2 {
+    fn eval(&self, va: &[f16], vb: &[f16]) -> f32 {
+        count_evaluation();
+        euclidean_distance_f16(va, vb)
+    }
+}
+
+/// What the points of a graph hold
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum GraphElement {
+    Float32,
+    Float16,
+    /// int8 codes of scalar quantized vectors
+    ScalarInt8,
+}
+
+/// The hnsw_rs graph behind an index
+///
+/// hnsw_rs fixes the stored element type and the distance at compile time, so
+/// each supported combination is its own variant.
+enum Graph {
+    Cosine(Arc<Hnsw<'static, f32, DistCosine>>),
+    Euclidean(Arc<Hnsw<'static, f32, DistL2>>),
+    Float16Cosine(Arc<Hnsw<'static, f16, DistF16Cosine>>),
+    Float16Euclidean(Arc<Hnsw<'static, f16, DistF16L2>>),
+    /// int8 codes of scalar quantized vectors; the originals are not kept
+    ScalarInt8(Arc<Hnsw<'static, i8, DistScalarInt8>>),
+}
+
+impl Graph {
+    fn new(config: &IndexConfig, distance_metric: DistanceMetric, element: GraphElement) -> Self {
+        let max_nb_connection = config.max_connections;
+        let ef_construction = config.ef_construction;
+        let max_layer = 16.min(config.max_layer); // hnsw_rs max is 16
+        let nb_elem = 100000; // Initial capacity estimate
+
+        // Cosine is the default for unsupported metrics
+        let euclidean = distance_metric == DistanceMetric::Euclidean;
+        match element {
+            GraphElement::ScalarInt8 => Graph::ScalarInt8(Arc::new(Hnsw::new(
+                max_nb_connection,
+                nb_elem,
+                max_layer,
+                ef_construction,
+                DistScalarInt8,
+            ))),
+            GraphElement::Float16 if euclidean => Graph::Float16Euclidean(Arc::new(Hnsw::new(
+                max_nb_connection,
+                nb_elem,
+                max_layer,
+                ef_construction,
+                DistF16L2,
+            ))),
+            GraphElement::Float16 => Graph::Float16Cosine(Arc::new(Hnsw::new(
+                max_nb_connection,
+                nb_elem,
+                max_layer,
+                ef_construction,
+                DistF16Cosine,
+            ))),
+            GraphElement::Float32 if euclidean => Graph::Euclidean(Arc::new(Hnsw::new(
+                max_nb_connection,
+                nb_elem,
+                max_layer,
+                ef_construction,
+                DistL2 {},
+            ))),
+            GraphElement::Float32 => Graph::Cosine(Arc::new(Hnsw::new(
+                max_nb_connection,
+                nb_elem,
+                max_layer,
+                ef_construction,
+                DistCosine {},
+            ))),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        match self {
+            Graph::Cosine(hnsw) => hnsw.get_nb_point() == 0,
+            Graph::Euclidean(hnsw) => hnsw.get_nb_point() == 0,
+            Graph::Float16Cosine(hnsw) => hnsw.get_nb_point() == 0,
+            Graph::Float16Euclidean(hnsw) => hnsw.get_nb_point() == 0,
+            Graph::ScalarInt8(hnsw) => hnsw.get_nb_point() == 0,
+        }
+    }
+
+    fn element(&self) -> GraphElement {
+        match self {
+            Graph::Cosine(_) | Graph::Euclidean(_) => GraphElement::Float32,
+            Graph::Float16Cosine(_) | Graph::Float16Euclidean(_) => GraphElement::Float16,
+            Graph::ScalarInt8(_) => GraphElement::ScalarInt8,
+        }
+    }
+}
+
+/// Reload a dumped graph with a `'static` lifetime
+///
+/// hnsw_rs ties the reloaded graph to the loader so it can serve points from a
+/// memory map. We load without mmap, so nothing is borrowed at runtime, but the
+/// loader still has to outlive the graph; leaking it keeps the types simple and
+/// only costs a few bytes per load.
+fn load_graph<T, D>(dir: &Path) -> Result<Arc<Hnsw<'static, T, D>>>
+where
+    T: 'static + Serialize + DeserializeOwned + Clone + Send + Sync + std::fmt::Debug,
+    D: Distance<T> + Default + Send + Sync,
+{
+    let loader: &'static mut HnswIo = Box::leak(Box::new(HnswIo::new(dir, DUMP_BASENAME)));
+    let hnsw = loader.load_hnsw::<T, D>().map_err(|e| VectorDbError::IndexError {
+        message: format!("Failed to load HNSW graph: {}", e),
+    })?;
+    Ok(Arc::new(hnsw))
+}
+
+/// Data IDs in `wanted` whose point in the graph already holds the given data
+fn unchanged_points<T, D, V>(hnsw: &Hnsw<'static, T, D>, wanted: &HashMap<usize, V>) -> HashSet<usize>
+where
+    T: Clone + PartialEq + Send + Sync,
+    D: Distance<T> + Send + Sync,
+    V: AsRef<[T]>,
+{
+    // hnsw_rs' point iterator panics on an empty graph
+    if wanted.is_empty() || hnsw.get_nb_point() == 0 {
+        return HashSet::new();
+    }
+
+    hnsw.get_point_indexation()
+        .into_iter()
+        .filter(|point| {
+            wanted
+                .get(&point.get_origin_id())
+                .is_some_and(|data| data.as_ref() == point.get_v())
+        })
+        .map(|point| point.get_origin_id())
+        .collect()
+}
+
+/// Add points to a graph, in parallel when there is more than one
+fn insert_points<T, D>(hnsw: &Hnsw<'static, T, D>, points: &[(Vec<T>, usize)])
+where
+    T: Clone + Send + Sync,
+    D: Distance<T> + Send + Sync,
+{
+    match points {
+        [(vector, idx)] => hnsw.insert((vector, *idx)),
+        _ => {
+            let data_refs: Vec<(&Vec<T>, usize)> = points.iter().map(|(v, idx)| (v, *idx)).collect();
+            hnsw.parallel_insert(&data_refs);
+        }
+    }
+}
+
+/// Graph search, admitting only points accepted by `accept` into the results
+fn search_graph<T, D>(
+    hnsw: &Hnsw<'static, T, D>,
+    query: &[T],
+    limit: usize,
+    ef: usize,
+    accept: Option<&dyn Fn(usize) -> bool>,
+) -> Vec<(usize, f32)>
+where
+    T: Clone + Send + Sync,
+    D: Distance<T> + Send + Sync,
+{
+    let neighbours = match accept {
+        Some(accept) => {
+            let filter = |id: &DataId| accept(*id);
+            hnsw.search_filter(query, limit, ef, Some(&filter))
+        }
+        None => hnsw.search(query, limit, ef),
+    };
+    neighbours
+        .into_iter()
+        .map(|neighbour| (neighbour.d_id, neighbour.distance))
+        .collect()
+}
+
+/// Exact nearest neighbours among the points accepted by `accept`
+fn scan_points<T, D>(
+    hnsw: &Hnsw<'static, T, D>,
+    query: &[T],
+    limit: usize,
+    accept: &dyn Fn(usize) -> bool,
+) -> Vec<(usize, f32)>
+where
+    T: Clone + Send + Sync,
+    D: Distance<T> + Send + Sync,
+{
+    // hnsw_rs' point iterator panics on an empty graph
+    if hnsw.get_nb_point() == 0 {
+        return Vec::new();
+    }
+
+    let distance = hnsw.get_distance();
+    let mut hits: Vec<(usize, f32)> = hnsw
+        .get_point_indexation()
+        .into_iter()
+        .filter(|point| accept(point.get_origin_id()))
+        .map(|point| (point.get_origin_id(), distance.eval(query, point.get_v())))
+        .collect();
+
+    if hits.len() > limit {
+        hits.select_nth_unstable_by(limit, |a, b| a.1.total_cmp(&b.1));
+        hits.truncate(limit);
+    }
+    hits.sort_by(|a, b| a.1.total_cmp(&b.1));
+    hits
+}
+
+/// Half precision copies of the vectors keyed by internal index
+fn to_f16_points(vectors: &HashMap<usize, &[f32]>) -> HashMap<usize, Vec<f16>> {
+    vectors.iter().map(|(idx, vector)| (*idx, to_f16(vector))).collect()
+}
+
+/// Half precision copies of points about to be inserted
+fn to_f16_batch(points: &[(Vec<f32>, usize)]) -> Vec<(Vec<f16>, usize)> {
+    points.iter().map(|(vector, idx)| (to_f16(vector), *idx)).collect()
+}
+
+/// Point, link and top-layer counts of a graph
+fn graph_shape<T, D>(hnsw: &Hnsw<'static, T, D>) -> (usize, usize, usize)
+where
+    T: Clone + Send + Sync,
+    D: Distance<T> + Send + Sync,
+{
+    // hnsw_rs' point iterator panics on an empty graph
+    if hnsw.get_nb_point() == 0 {
+        return (0, 0, 0);
+    }
+
+    let mut points = 0;
+    let mut links = 0;
+    for point in hnsw.get_point_indexation() {
+        points += 1;
+        links += point.get_neighborhood_id().iter().map(Vec::len).sum::<usize>();
+    }
+    (points, links, hnsw.get_max_level_observed() as usize)
+}
 
 /// Wrapper around hnsw_rs::hnsw::Hnsw for our VectorIndex trait
 pub struct HnswRsIndex {
-    // hnsw_rs requires specific distance types at compile time
-    // We'll use f32 vectors with the appropriate distance metric
-    inner_cosine: Option<Arc<Hnsw<'static, f32, DistCosine>>>,
-    inner_euclidean: Option<Arc<Hnsw<'static, f32, DistL2>>>,
+    graph: Graph,
 
     distance_metric: DistanceMetric,
     dimension: usize,
 
+    /// Maps vectors onto the codes of a scalar int8 graph. Fitted once
+    /// `fit_threshold` vectors have been inserted, or fixed to [-1, 1] when
+    /// vectors are normalized first.
+    quantizer: Arc<RwLock<Option<ScalarQuantizer>>>,
+    fit_threshold: usize,
+    /// Vectors inserted before the quantizer is fitted, under their internal
+    /// indices; searched exactly until they are encoded into the graph
+    unfitted: Arc<RwLock<FloatPoints>>,
+
     // Map UUID to index in HNSW (hnsw_rs uses usize internally)
     id_to_idx: Arc<RwLock<HashMap<VectorId, usize>>>,
     idx_to_id: Arc<RwLock<HashMap<usize, VectorId>>>,
@@ -31,51 +416,85 @@ impl HnswRsIndex {
         distance_metric: DistanceMetric,
         dimension: usize,
     ) -> Self {
-        let max_nb_connection = config.max_connections;
-        let ef_construction = config.ef_construction;
-        let max_layer = 16.min(config.max_layer); // hnsw_rs max is 16
-        let nb_elem = 100000; // Initial capacity estimate
+        Self::with_graph(Graph::new(&config, distance_metric, GraphElement::Float32), distance_metric, dimension)
+    }
 
-        // Create the appropriate HNSW instance based on distance metric
-        let (inner_cosine, inner_euclidean) = match distance_metric {
-            DistanceMetric::Cosine => {
-                let hnsw = Hnsw::<f32, DistCosine>::new(
-                    max_nb_connection,
-                    nb_elem,
-                    max_layer,
-                    ef_construction,
-                    DistCosine {},
-                );
-                (Some(Arc::new(hnsw)), None)
-            },
-            DistanceMetric::Euclidean => {
-                let hnsw = Hnsw::<f32, DistL2>::new(
-                    max_nb_connection,
-                    nb_elem,
-                    max_layer,
-                    ef_construction,
-                    DistL2 {},
-                );
-                (None, Some(Arc::new(hnsw)))
-            },
-            _ => {
-                // Default to cosine for unsupported metrics
-                let hnsw = Hnsw::<f32, DistCosine>::new(
-                    max_nb_connection,
-                    nb_elem,
-                    max_layer,
-                    ef_construction,
-                    DistCosine {},
-                );
-                (Some(Arc::new(hnsw)), None)
-            }
-        };
+    /// Index whose graph keeps vectors in half precision
+    ///
+    /// Takes half the memory of a float index; distances are computed on the
+    /// rounded vectors and are close to, but not exactly, the f32 ones.
+    pub fn new_float16(
+        config: IndexConfig,
+        distance_metric: DistanceMetric,
+        dimension: usize,
+    ) -> Self {
+        Self::with_graph(Graph::new(&config, distance_metric, GraphElement::Float16), distance_metric, dimension)
+    }
+
+    /// Index whose graph keeps only scalar int8 codes of the vectors
+    ///
+    /// Distances are computed on the codes and are approximate. Vectors are
+    /// normalized before quantization for every metric but Euclidean, so
+    /// those are searched by cosine distance, as in a float index. Euclidean
+    /// vectors are held at full precision until `fit_threshold` of them have
+    /// been inserted, then encoded with a quantizer fitted to them.
+    pub fn new_scalar_int8(
+        config: IndexConfig,
+        distance_metric: DistanceMetric,
+        dimension: usize,
+        fit_threshold: usize,
+    ) -> Self {
+        let mut index = Self::with_graph(Graph::new(&config, distance_metric, GraphElement::ScalarInt8), distance_metric, dimension);
+        index.fit_threshold = fit_threshold;
+        if distance_metric != DistanceMetric::Euclidean {
+            *index.quantizer.write() = Some(ScalarQuantizer::new(-1.0, 1.0));
+        }
+        index
+    }
+
+    /// Index holding vectors in the given element type
+    ///
+    /// Int8 vectors are held as scalar int8 codes.
+    pub fn for_vector_type(
+        config: IndexConfig,
+        distance_metric: DistanceMetric,
+        dimension: usize,
+        vector_type: VectorType,
+    ) -> Self {
+        match vector_type {
+            VectorType::Float32 => Self::new(config, distance_metric, dimension),
+            VectorType::Float16 => Self::new_float16(config, distance_metric, dimension),
+            VectorType::Int8 => Self::new_scalar_int8(config, distance_metric, dimension, DEFAULT_FIT_THRESHOLD),
+        }
+    }
+
+    /// Index for a collection's default vector, as set up by its quantization
+    /// config and vector type
+    pub fn for_collection(config: &CollectionConfig) -> Self {
+        match &config.quantization {
+            Some(QuantizationConfig::Scalar(scalar_config)) => Self::new_scalar_int8(
+                config.index_config.clone(),
+                config.distance_metric,
+                config.dimension,
+                scalar_config.fit_threshold,
+            ),
+            _ => Self::for_vector_type(
+                config.index_config.clone(),
+                config.distance_metric,
+                config.dimension,
+                config.vector_type,
+            ),
+        }
+    }
 
+    fn with_graph(graph: Graph, distance_metric: DistanceMetric, dimension: usize) -> Self {
         Self {
-            inner_cosine,
-            inner_euclidean,
+            graph,
             distance_metric,
             dimension,
+            quantizer: Arc::new(RwLock::new(None)),
+            fit_threshold: 0,
+            unfitted: Arc::new(RwLock::new(Vec::new())),
             id_to_idx: Arc::new(RwLock::new(HashMap::new())),
             idx_to_id: Arc::new(RwLock::new(HashMap::new())),
             metadata: Arc::new(RwLock::new(HashMap::new())),
@@ -83,12 +502,206 @@ impl HnswRsIndex {
         }
     }
 
+    /// Whether the graph holds scalar int8 codes rather than the vectors themselves
+    pub fn is_quantized(&self) -> bool {
+        matches!(self.graph, Graph::ScalarInt8(_))
+    }
+
     fn get_next_idx(&self) -> usize {
         let mut idx = self.next_idx.write();
         let current = *idx;
         *idx += 1;
         current
     }
+
+    /// Points left in the graph by deletes and updates
+    ///
+    /// hnsw_rs cannot remove points, so these stay in the graph and are skipped
+    /// at search time until the index is rebuilt.
+    pub fn orphaned_count(&self) -> usize {
+        *self.next_idx.read() - self.id_to_idx.read().len()
+    }
+
+    /// Insert or update vectors, skipping those the graph already holds unchanged
+    ///
+    /// Used when replaying the data file over a reloaded index, where most
+    /// records usually match what was dumped. Metadata is always refreshed.
+    /// Returns the number of vectors added to the graph.
+    pub fn upsert_changed(
+        &mut self,
+        vectors: Vec<IndexedVector>,
+    ) -> Result<usize> {
+        let unchanged = {
+            let id_to_idx = self.id_to_idx.read();
+            let wanted: HashMap<usize, &[f32]> = vectors
+                .iter()
+                .filter_map(|(id, vector, _)| Some((*id_to_idx.get(id)?, vector.as_slice())))
+                .collect();
+
+            match &self.graph {
+                Graph::Cosine(hnsw) => unchanged_points(hnsw, &wanted),
+                Graph::Euclidean(hnsw) => unchanged_points(hnsw, &wanted),
+                Graph::Float16Cosine(hnsw) => unchanged_points(hnsw, &to_f16_points(&wanted)),
+                Graph::Float16Euclidean(hnsw) => unchanged_points(hnsw, &to_f16_points(&wanted)),
+                Graph::ScalarInt8(hnsw) => match *self.quantizer.read() {
+                    Some(quantizer) => {
+                        let codes: HashMap<usize, Vec<i8>> = wanted
+                            .iter()
+                            .map(|(idx, vector)| (*idx, self.encode(&quantizer, vector)))
+                            .collect();
+                        unchanged_points(hnsw, &codes)
+                    }
+                    None => self
+                        .unfitted
+                        .read()
+                        .iter()
+                        .filter(|(vector, idx)| wanted.get(idx).is_some_and(|data| *data == vector.as_slice()))
+                        .map(|(_, idx)| *idx)
+                        .collect(),
+                },
+            }
+        };
+
+        let mut changed = Vec::new();
+        for (id, vector, metadata) in vectors {
+            let is_unchanged = self
+                .id_to_idx
+                .read()
+                .get(&id)
+                .is_some_and(|idx| unchanged.contains(idx));
+
+            if is_unchanged {
+                let mut meta_map = self.metadata.write();
+                match metadata {
+                    Some(meta) => meta_map.insert(id, meta),
+                    None => meta_map.remove(&id),
+                };
+            } else {
+                super::VectorIndex::delete(self, &id)?;
+                changed.push((id, vector, metadata));
+            }
+        }
+
+        let added = changed.len();
+        if added > 0 {
+            super::VectorIndex::batch_insert(self, changed)?;
+        }
+        Ok(added)
+    }
+
+    /// int8 codes of `vector`, normalized first unless the metric is Euclidean
+    fn encode(&self, quantizer: &ScalarQuantizer, vector: &[f32]) -> Vec<i8> {
+        if self.distance_metric == DistanceMetric::Euclidean {
+            return quantizer.encode(vector);
+        }
+        let mut normalized = vector.to_vec();
+        normalize(&mut normalized);
+        quantizer.encode(&normalized)
+    }
+
+    /// Turn a distance between codes back into the index's distance metric
+    fn decode_distance(&self, quantizer: &ScalarQuantizer, distance: f32) -> f32 {
+        let euclidean = distance * quantizer.step();
+        if self.distance_metric == DistanceMetric::Euclidean {
+            euclidean
+        } else {
+            // Cosine distance between unit vectors is half their squared distance
+            euclidean * euclidean / 2.0
+        }
+    }
+
+    /// Add vectors to the graph under their internal indices
+    fn insert_into_graph(&self, points: Vec<(Vec<f32>, usize)>) {
+        // hnsw_rs is thread-safe, so this only needs a shared reference
+        match &self.graph {
+            Graph::Cosine(hnsw) => insert_points(hnsw, &points),
+            Graph::Euclidean(hnsw) => insert_points(hnsw, &points),
+            Graph::Float16Cosine(hnsw) => insert_points(hnsw, &to_f16_batch(&points)),
+            Graph::Float16Euclidean(hnsw) => insert_points(hnsw, &to_f16_batch(&points)),
+            Graph::ScalarInt8(hnsw) => {
+                let mut fitted = self.quantizer.write();
+                let (quantizer, points) = match *fitted {
+                    Some(quantizer) => (quantizer, points),
+                    None => {
+                        // Hold vectors back until there are enough to fit
+                        // the range to, then encode them all with it
+                        let mut unfitted = self.unfitted.write();
+                        unfitted.extend(points);
+                        if unfitted.len() < self.fit_threshold {
+                            return;
+                        }
+                        let quantizer = ScalarQuantizer::fit(unfitted.iter().map(|(v, _)| v.as_slice()));
+                        *fitted = Some(quantizer);
+                        (quantizer, std::mem::take(&mut *unfitted))
+                    }
+                };
+                let codes: Vec<(Vec<i8>, usize)> = points
+                    .iter()
+                    .map(|(vector, idx)| (self.encode(&quantizer, vector), *idx))
+                    .collect();
+                insert_points(hnsw, &codes);
+            }
+        }
+    }
+
+    /// Graph search for `query`, as (internal index, distance) pairs
+    fn search_points(
+        &self,
+        query: &[f32],
+        limit: usize,
+        ef: usize,
+        accept: Option<&dyn Fn(usize) -> bool>,
+    ) -> Vec<(usize, f32)> {
+        match &self.graph {
+            Graph::Cosine(hnsw) => search_graph(hnsw, query, limit, ef, accept),
+            Graph::Euclidean(hnsw) => search_graph(hnsw, query, limit, ef, accept),
+            Graph::Float16Cosine(hnsw) => search_graph(hnsw, &to_f16(query), limit, ef, accept),
+            Graph::Float16Euclidean(hnsw) => search_graph(hnsw, &to_f16(query), limit, ef, accept),
+            Graph::ScalarInt8(hnsw) => {
+                // Nothing is in the graph before the quantizer is fitted
+                let Some(quantizer) = *self.quantizer.read() else {
+                    return self.scan_unfitted(query, limit, accept);
+                };
+                search_graph(hnsw, &self.encode(&quantizer, query), limit, ef, accept)
+                    .into_iter()
+                    .map(|(idx, distance)| (idx, self.decode_distance(&quantizer, distance)))
+                    .collect()
+            }
+        }
+    }
+
+    /// Exact scan for `query` over the points accepted by `accept`
+    fn scan_graph(&self, query: &[f32], limit: usize, accept: &dyn Fn(usize) -> bool) -> Vec<(usize, f32)> {
+        match &self.graph {
+            Graph::Cosine(hnsw) => scan_points(hnsw, query, limit, accept),
+            Graph::Euclidean(hnsw) => scan_points(hnsw, query, limit, accept),
+            Graph::Float16Cosine(hnsw) => scan_points(hnsw, &to_f16(query), limit, accept),
+            Graph::Float16Euclidean(hnsw) => scan_points(hnsw, &to_f16(query), limit, accept),
+            Graph::ScalarInt8(hnsw) => {
+                let Some(quantizer) = *self.quantizer.read() else {
+                    return self.scan_unfitted(query, limit, Some(accept));
+                };
+                scan_points(hnsw, &self.encode(&quantizer, query), limit, accept)
+                    .into_iter()
+                    .map(|(idx, distance)| (idx, self.decode_distance(&quantizer, distance)))
+                    .collect()
+            }
+        }
+    }
+
+    /// Exact nearest neighbours among the vectors waiting for the quantizer to be fitted
+    fn scan_unfitted(&self, query: &[f32], limit: usize, accept: Option<&dyn Fn(usize) -> bool>) -> Vec<(usize, f32)> {
+        let mut hits: Vec<(usize, f32)> = self
+            .unfitted
+            .read()
+            .iter()
+            .filter(|(_, idx)| accept.is_none_or(|accept| accept(*idx)))
+            .map(|(vector, idx)| (*idx, DistL2.eval(query, vector)))
+            .collect();
+        hits.sort_by(|a, b| a.1.total_cmp(&b.1));
+        hits.truncate(limit);
+        hits
+    }
 }
 
 impl super::VectorIndex for HnswRsIndex {
@@ -116,24 +729,7 @@ impl super::VectorIndex for HnswRsIndex {
             self.metadata.write().insert(id, meta);
         }
 
-        // Insert into appropriate HNSW (hnsw_rs is thread-safe)
-        match self.distance_metric {
-            DistanceMetric::Cosine => {
-                if let Some(ref hnsw) = self.inner_cosine {
-                    hnsw.insert((vector, idx));
-                }
-            },
-            DistanceMetric::Euclidean => {
-                if let Some(ref hnsw) = self.inner_euclidean {
-                    hnsw.insert((vector, idx));
-                }
-            },
-            _ => {
-                if let Some(ref hnsw) = self.inner_cosine {
-                    hnsw.insert((vector, idx));
-                }
-            }
-        }
+        self.insert_into_graph(vec![(vector.to_vec(), idx)]);
 
         Ok(())
     }
@@ -175,35 +771,7 @@ impl super::VectorIndex for HnswRsIndex {
         }
 
         // Use parallel insert for better performance (hnsw_rs is thread-safe)
-        match self.distance_metric {
-            DistanceMetric::Cosine => {
-                if let Some(ref hnsw) = self.inner_cosine {
-                    let data_refs: Vec<(&Vec<f32>, usize)> = batch_data
-                        .iter()
-                        .map(|(v, idx)| (v, *idx))
-                        .collect();
-                    hnsw.parallel_insert(&data_refs);
-                }
-            },
-            DistanceMetric::Euclidean => {
-                if let Some(ref hnsw) = self.inner_euclidean {
-                    let data_refs: Vec<(&Vec<f32>, usize)> = batch_data
-                        .iter()
-                        .map(|(v, idx)| (v, *idx))
-                        .collect();
-                    hnsw.parallel_insert(&data_refs);
-                }
-            },
-            _ => {
-                if let Some(ref hnsw) = self.inner_cosine {
-                    let data_refs: Vec<(&Vec<f32>, usize)> = batch_data
-                        .iter()
-                        .map(|(v, idx)| (v, *idx))
-                        .collect();
-                    hnsw.parallel_insert(&data_refs);
-                }
-            }
-        }
+        self.insert_into_graph(batch_data);
 
         Ok(())
     }
@@ -221,52 +789,112 @@ impl super::VectorIndex for HnswRsIndex {
             });
         }
 
-        let ef = ef_search.unwrap_or(limit.max(50));
+        // Orphaned points can occupy result slots, so ask for enough extra
+        // candidates to still fill `limit` after they are dropped
+        let fetch = limit + self.orphaned_count().min(limit * 4);
+        let ef = ef_search.unwrap_or(limit.max(50)).max(fetch);
 
         // Search in appropriate HNSW (hnsw_rs is thread-safe)
-        let internal_results = match self.distance_metric {
-            DistanceMetric::Cosine => {
-                if let Some(ref hnsw) = self.inner_cosine {
-                    hnsw.search(query, limit, ef)
-                } else {
-                    Vec::new()
-                }
-            },
-            DistanceMetric::Euclidean => {
-                if let Some(ref hnsw) = self.inner_euclidean {
-                    hnsw.search(query, limit, ef)
-                } else {
-                    Vec::new()
-                }
-            },
-            _ => {
-                if let Some(ref hnsw) = self.inner_cosine {
-                    hnsw.search(query, limit, ef)
-                } else {
-                    Vec::new()
-                }
-            }
-        };
+        let internal_results = self.search_points(query, fetch, ef, None);
 
         // Convert internal results to our SearchResult format
         let idx_to_id = self.idx_to_id.read();
         let metadata_map = self.metadata.read();
 
         let mut results = Vec::new();
-        for neighbor in internal_results {
-            let internal_idx = neighbor.d_id;
+        for (internal_idx, distance) in internal_results {
             if let Some(id) = idx_to_id.get(&internal_idx) {
                 results.push(SearchResult {
                     id: *id,
-                    distance: neighbor.distance,
+                    distance,
                     metadata: metadata_map.get(id).cloned(),
                 });
             }
         }
+        results.truncate(limit);
 
         Ok(results)
     }
 
+    fn search_counted(
+        &self,
+        query: &[f32],
+        limit: usize,
+        ef_search: Option<usize>,
+    ) -> Result<(Vec<SearchResult>, usize)> {
+        // hnsw_rs searches on the calling thread, so every distance it
+        // evaluates meanwhile is for a point this search visited
+        let (results, visited) = counting_evaluations(|| self.search(query, limit, ef_search));
+        Ok((results?, visited))
+    }
+
+    fn search_filtered(
+        &self,
+        query: &[f32],
+        limit: usize,
+        ef_search: Option<usize>,
+        filter: &SearchFilter<'_>,
+    ) -> Result<Vec<SearchResult>> {
+        if query.len() != self.dimension {
+            return Err(VectorDbError::InvalidDimension {
+                expected: self.dimension,
+                actual: query.len(),
+            });
+        }
+        if limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let idx_to_id = self.idx_to_id.read();
+        let metadata_map = self.metadata.read();
+
+        // Orphaned points have no ID and are rejected along with non-matches
+        let accept = |idx: usize| {
+            idx_to_id
+                .get(&idx)
+                .is_some_and(|id| filter(id, metadata_map.get(id)))
+        };
+
+        // Estimate selectivity on a sample; HashMap iteration order is
+        // effectively random, and exact when the collection is small
+        let live = idx_to_id.len();
+        let sampled = live.min(SELECTIVITY_SAMPLE);
+        let matched = idx_to_id
+            .values()
+            .take(sampled)
+            .filter(|id| filter(id, metadata_map.get(*id)))
+            .count();
+        let selectivity = if sampled > 0 { matched as f64 / sampled as f64 } else { 0.0 };
+
+        let scan = || self.scan_graph(query, limit, &accept);
+
+        let hits = if live <= SELECTIVITY_SAMPLE || selectivity < BRUTE_FORCE_SELECTIVITY {
+            scan()
+        } else {
+            // Widen the candidate list so roughly `limit` matches are expected in it
+            let wanted = ((limit as f64 / selectivity).ceil() as usize).min(MAX_FILTERED_EF);
+            let ef = ef_search.unwrap_or(limit.max(50)).max(wanted).max(limit);
+
+            let hits = self.search_points(query, limit, ef, Some(&accept));
+
+            // The graph walk can miss matches that sit behind non-matching
+            // neighbourhoods; an exact scan settles whether enough exist
+            if hits.len() < limit { scan() } else { hits }
+        };
+
+        Ok(hits
+            .into_iter()
+            .filter_map(|(idx, distance)| {
+                let id = *idx_to_id.get(&idx)?;
+                Some(SearchResult {
+                    id,
+                    distance,
+                    metadata: metadata_map.get(&id).cloned(),
+                })
+            })
+            .collect())
+    }
+
     fn delete(&mut self, id: &VectorId) -> Result<bool> {
         // hnsw_rs doesn't support deletion directly
         // We just remove from our mappings
@@ -274,35 +902,164 @@ impl super::VectorIndex for HnswRsIndex {
         if let Some(idx) = idx_opt {
             self.idx_to_id.write().remove(&idx);
             self.metadata.write().remove(id);
+            self.unfitted.write().retain(|(_, unfitted_idx)| *unfitted_idx != idx);
             Ok(true)
         } else {
             Ok(false)
         }
     }
 
+    fn upsert_changed(&mut self, vectors: Vec<IndexedVector>) -> Result<usize> {
+        HnswRsIndex::upsert_changed(self, vectors)
+    }
+
+    fn set_metadata(&mut self, id: &VectorId, metadata: Option<HashMap<String, serde_json::Value>>) -> bool {
+        if !self.id_to_idx.read().contains_key(id) {
+            return false;
+        }
+        let mut meta_map = self.metadata.write();
+        match metadata {
+            Some(meta) => meta_map.insert(*id, meta),
+            None => meta_map.remove(id),
+        };
+        true
+    }
+
     fn stats(&self) -> super::IndexStats {
         let vector_count = self.id_to_idx.read().len();
 
-        // Rough memory estimate
-        let memory_usage = vector_count * (self.dimension * 4 + 64); // vectors + overhead
+        let (points, links, max_layer) = match &self.graph {
+            Graph::Cosine(hnsw) => graph_shape(hnsw),
+            Graph::Euclidean(hnsw) => graph_shape(hnsw),
+            Graph::Float16Cosine(hnsw) => graph_shape(hnsw),
+            Graph::Float16Euclidean(hnsw) => graph_shape(hnsw),
+            Graph::ScalarInt8(hnsw) => graph_shape(hnsw),
+        };
+
+        // hnsw_rs keeps every point it was given, including orphans, with a
+        // neighbour list per possible layer and a separate allocation per link
+        const POINT_OVERHEAD: usize = 64 + 16 * std::mem::size_of::<Vec<usize>>();
+        const LINK_SIZE: usize = 8 + 32;
+        const MAPPING_SIZE: usize = 2 * (std::mem::size_of::<VectorId>() + std::mem::size_of::<usize>()) * 2;
+
+        let payload = self.metadata.read().values().map(crate::payload_size).sum();
+        let (vectors, quantized) = match self.graph.element() {
+            GraphElement::Float32 => (points * self.dimension * std::mem::size_of::<f32>(), 0),
+            GraphElement::Float16 => (points * self.dimension * std::mem::size_of::<f16>(), 0),
+            GraphElement::ScalarInt8 => (
+                self.unfitted.read().len() * self.dimension * std::mem::size_of::<f32>(),
+                points * self.dimension * std::mem::size_of::<i8>(),
+            ),
+        };
+        let memory = MemoryBreakdown {
+            vectors,
+            index: points * POINT_OVERHEAD + links * LINK_SIZE + vector_count * MAPPING_SIZE,
+            payload,
+            quantized,
+        };
 
         super::IndexStats {
             vector_count,
-            memory_usage,
+            memory_usage: memory.total(),
+            memory,
             dimension: self.dimension,
-            max_layer: 16,  // hnsw_rs max
-            avg_connections: 16.0,  // Approximate based on M parameter
+            max_layer,
+            avg_connections: if points > 0 { links as f32 / points as f32 } else { 0.0 },
         }
     }
 
     fn serialize(&self) -> Result<Vec<u8>> {
-        // hnsw_rs supports file_dump, but for now return empty
-        // TODO: Implement proper serialization
-        Ok(Vec::new())
+        let next_idx = *self.next_idx.read();
+
+        // hnsw_rs can only dump to files, so round-trip through a scratch directory.
+        // An empty graph has no entry point to dump and is simply recreated on load.
+        let (graph, data) = if self.graph.is_empty() {
+            (Vec::new(), Vec::new())
+        } else {
+            let scratch = ScratchDir::new()?;
+            let dumped = match &self.graph {
+                Graph::Cosine(hnsw) => hnsw.file_dump(scratch.path(), DUMP_BASENAME),
+                Graph::Euclidean(hnsw) => hnsw.file_dump(scratch.path(), DUMP_BASENAME),
+                Graph::Float16Cosine(hnsw) => hnsw.file_dump(scratch.path(), DUMP_BASENAME),
+                Graph::Float16Euclidean(hnsw) => hnsw.file_dump(scratch.path(), DUMP_BASENAME),
+                Graph::ScalarInt8(hnsw) => hnsw.file_dump(scratch.path(), DUMP_BASENAME),
+            };
+            dumped.map_err(|e| VectorDbError::IndexError {
+                message: format!("Failed to dump HNSW graph: {}", e),
+            })?;
+
+            (std::fs::read(scratch.file("graph"))?, std::fs::read(scratch.file("data"))?)
+        };
+
+        let dump = HnswRsDump {
+            version: DUMP_VERSION,
+            distance_metric: self.distance_metric,
+            dimension: self.dimension,
+            element: self.graph.element(),
+            quantizer: *self.quantizer.read(),
+            unfitted: self.unfitted.read().clone(),
+            next_idx,
+            id_to_idx: self.id_to_idx.read().iter().map(|(id, idx)| (*id, *idx)).collect(),
+            metadata_json: serde_json::to_string(&*self.metadata.read())?,
+            graph,
+            data,
+        };
+
+        bincode::serialize(&dump).map_err(|e| VectorDbError::Serialization(e.to_string()))
     }
 
-    fn deserialize(&mut self, _data: &[u8]) -> Result<()> {
-        // TODO: Implement proper deserialization
+    fn deserialize(&mut self, data: &[u8]) -> Result<()> {
+        let dump: HnswRsDump = bincode::deserialize(data)
+            .map_err(|e| VectorDbError::Serialization(e.to_string()))?;
+
+        if dump.version != DUMP_VERSION {
+            return Err(VectorDbError::IndexError {
+                message: format!("Unsupported HNSW dump version {}", dump.version),
+            });
+        }
+        if dump.distance_metric != self.distance_metric
+            || dump.dimension != self.dimension
+            || dump.element != self.graph.element()
+        {
+            return Err(VectorDbError::IndexError {
+                message: format!(
+                    "HNSW dump is for {:?}/{}/{:?} but index is {:?}/{}/{:?}",
+                    dump.distance_metric,
+                    dump.dimension,
+                    dump.element,
+                    self.distance_metric,
+                    self.dimension,
+                    self.graph.element()
+                ),
+            });
+        }
+
+        if !dump.graph.is_empty() {
+            let scratch = ScratchDir::new()?;
+            std::fs::write(scratch.file("graph"), &dump.graph)?;
+            std::fs::write(scratch.file("data"), &dump.data)?;
+
+            self.graph = match self.graph {
+                Graph::Cosine(_) => Graph::Cosine(load_graph(scratch.path())?),
+                Graph::Euclidean(_) => Graph::Euclidean(load_graph(scratch.path())?),
+                Graph::Float16Cosine(_) => Graph::Float16Cosine(load_graph(scratch.path())?),
+                Graph::Float16Euclidean(_) => Graph::Float16Euclidean(load_graph(scratch.path())?),
+                Graph::ScalarInt8(_) => Graph::ScalarInt8(load_graph(scratch.path())?),
+            };
+        }
+
+        let metadata: HashMap<VectorId, HashMap<String, serde_json::Value>> =
+            serde_json::from_str(&dump.metadata_json)?;
+
+        *self.id_to_idx.write() = dump.id_to_idx.iter().copied().collect();
+        *self.idx_to_id.write() = dump.id_to_idx.iter().map(|(id, idx)| (*idx, *id)).collect();
+        *self.metadata.write() = metadata;
+        *self.next_idx.write() = dump.next_idx;
+        if dump.quantizer.is_some() {
+            *self.quantizer.write() = dump.quantizer;
+        }
+        *self.unfitted.write() = dump.unfitted;
+
         Ok(())
     }
 }
@@ -310,6 +1067,8 @@ impl super::VectorIndex for HnswRsIndex {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::VectorIndex;
+    use uuid::Uuid;
 
     #[test]
     fn test_hnsw_rs_insert_and_search() {
@@ -348,4 +1107,261 @@ mod tests {
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].id, id1);
     }
+
+    #[test]
+    fn test_hnsw_rs_serialize_roundtrip() {
+        for metric in [DistanceMetric::Cosine, DistanceMetric::Euclidean] {
+            let mut index = HnswRsIndex::new(IndexConfig::default(), metric, 3);
+
+            let ids: Vec<Uuid> = (0..20).map(|_| Uuid::new_v4()).collect();
+            let vectors = ids
+                .iter()
+                .enumerate()
+                .map(|(i, id)| {
+                    let mut meta = HashMap::new();
+                    meta.insert("i".to_string(), serde_json::json!(i));
+                    (*id, vec![i as f32 + 1.0, 1.0, 0.5], Some(meta))
+                })
+                .collect();
+            index.batch_insert(vectors).unwrap();
+            index.delete(&ids[0]).unwrap();
+
+            let bytes = index.serialize().unwrap();
+
+            let mut restored = HnswRsIndex::new(IndexConfig::default(), metric, 3);
+            restored.deserialize(&bytes).unwrap();
+            assert_eq!(restored.stats().vector_count, 19);
+
+            let results = restored.search(&[6.0, 1.0, 0.5], 3, None).unwrap();
+            assert_eq!(results[0].id, ids[5]);
+            assert_eq!(results[0].metadata.as_ref().unwrap()["i"], serde_json::json!(5));
+            assert!(results.iter().all(|r| r.id != ids[0]));
+
+            // New inserts continue after the restored internal indices
+            let extra = Uuid::new_v4();
+            restored.insert(extra, &[100.0, 1.0, 0.5], None).unwrap();
+            let results = restored.search(&[100.0, 1.0, 0.5], 1, None).unwrap();
+            assert_eq!(results[0].id, extra);
+        }
+    }
+
+    #[test]
+    fn test_hnsw_rs_serialize_empty() {
+        let index = HnswRsIndex::new(IndexConfig::default(), DistanceMetric::Cosine, 3);
+        let bytes = index.serialize().unwrap();
+
+        let mut restored = HnswRsIndex::new(IndexConfig::default(), DistanceMetric::Cosine, 3);
+        restored.deserialize(&bytes).unwrap();
+        assert_eq!(restored.stats().vector_count, 0);
+    }
+
+    #[test]
+    fn test_hnsw_rs_scalar_int8() {
+        for metric in [DistanceMetric::Cosine, DistanceMetric::Euclidean] {
+            let mut index = HnswRsIndex::new_scalar_int8(IndexConfig::default(), metric, 3, 20);
+            assert!(index.search(&[1.0, 1.0, 0.5], 3, None).unwrap().is_empty());
+
+            let ids: Vec<Uuid> = (0..20).map(|_| Uuid::new_v4()).collect();
+            let vectors = ids
+                .iter()
+                .enumerate()
+                .map(|(i, id)| (*id, vec![i as f32 + 1.0, 1.0, 0.5], None))
+                .collect();
+            index.batch_insert(vectors).unwrap();
+
+            let results = index.search(&[6.0, 1.0, 0.5], 3, None).unwrap();
+            assert_eq!(results[0].id, ids[5]);
+            if metric == DistanceMetric::Euclidean {
+                // Approximate, but close to the exact distances of 0, 1 and 1
+                assert!(results[0].distance < 0.1);
+                assert!((results[1].distance - 1.0).abs() < 0.1);
+            }
+
+            // Only the codes are held in memory
+            let stats = index.stats();
+            assert_eq!(stats.memory.vectors, 0);
+            assert_eq!(stats.memory.quantized, 20 * 3);
+
+            let mut restored = HnswRsIndex::new_scalar_int8(IndexConfig::default(), metric, 3, 20);
+            restored.deserialize(&index.serialize().unwrap()).unwrap();
+            let results = restored.search(&[6.0, 1.0, 0.5], 1, None).unwrap();
+            assert_eq!(results[0].id, ids[5]);
+
+            // A float index refuses the quantized dump
+            let mut float = HnswRsIndex::new(IndexConfig::default(), metric, 3);
+            assert!(float.deserialize(&index.serialize().unwrap()).is_err());
+        }
+    }
+
+    #[test]
+    fn test_hnsw_rs_scalar_int8_fits_after_threshold() {
+        let mut index = HnswRsIndex::new_scalar_int8(IndexConfig::default(), DistanceMetric::Euclidean, 2, 100);
+
+        // A 20x20 grid spanning [0, 100], inserted one point at a time in a
+        // scattered order, so no single early point spans the range
+        let points: Vec<(Uuid, Vec<f32>)> = (0..400)
+            .map(|i| (i * 131) % 400)
+            .map(|i| (Uuid::new_v4(), vec![(i % 20) as f32 * 100.0 / 19.0, (i / 20) as f32 * 100.0 / 19.0]))
+            .collect();
+        let query = [42.3, 57.9];
+        let nearest = |points: &[(Uuid, Vec<f32>)]| {
+            points
+                .iter()
+                .map(|(id, v)| (*id, ((v[0] - query[0]).powi(2) + (v[1] - query[1]).powi(2)).sqrt()))
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .unwrap()
+        };
+
+        for (id, vector) in &points[..99] {
+            index.insert(*id, vector, None).unwrap();
+        }
+
+        // Below the threshold vectors are held as they are and searched exactly
+        let stats = index.stats();
+        assert_eq!(stats.memory.vectors, 99 * 2 * 4);
+        assert_eq!(stats.memory.quantized, 0);
+        let (expected_id, expected_distance) = nearest(&points[..99]);
+        let results = index.search(&query, 1, None).unwrap();
+        assert_eq!(results[0].id, expected_id);
+        assert_eq!(results[0].distance, expected_distance);
+
+        // The held vectors survive a dump
+        let mut restored = HnswRsIndex::new_scalar_int8(IndexConfig::default(), DistanceMetric::Euclidean, 2, 100);
+        restored.deserialize(&index.serialize().unwrap()).unwrap();
+        assert_eq!(restored.search(&query, 1, None).unwrap()[0].id, expected_id);
+
+        for (id, vector) in &points[99..] {
+            index.insert(*id, vector, None).unwrap();
+        }
+
+        // Past it every vector is encoded with a range fitted to the first
+        // hundred, which covers the grid
+        let stats = index.stats();
+        assert_eq!(stats.memory.vectors, 0);
+        assert_eq!(stats.memory.quantized, 400 * 2);
+        let (expected_id, expected_distance) = nearest(&points);
+        let results = index.search(&query, 1, None).unwrap();
+        assert_eq!(results[0].id, expected_id);
+        assert!((results[0].distance - expected_distance).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_hnsw_rs_float16() {
+        for metric in [DistanceMetric::Cosine, DistanceMetric::Euclidean] {
+            let mut index = HnswRsIndex::new_float16(IndexConfig::default(), metric, 3);
+
+            let ids: Vec<Uuid> = (0..20).map(|_| Uuid::new_v4()).collect();
+            let vectors: Vec<_> = ids
+                .iter()
+                .enumerate()
+                .map(|(i, id)| (*id, vec![i as f32 + 1.0, 1.0, 0.5], None))
+                .collect();
+            index.batch_insert(vectors.clone()).unwrap();
+
+            let results = index.search(&[6.0, 1.0, 0.5], 3, None).unwrap();
+            assert_eq!(results[0].id, ids[5]);
+            if metric == DistanceMetric::Euclidean {
+                assert!((results[1].distance - 1.0).abs() < 1e-2);
+            }
+
+            // Vectors are held at two bytes per component
+            let stats = index.stats();
+            assert_eq!(stats.memory.vectors, 20 * 3 * 2);
+            assert_eq!(stats.memory.quantized, 0);
+
+            let mut restored = HnswRsIndex::new_float16(IndexConfig::default(), metric, 3);
+            restored.deserialize(&index.serialize().unwrap()).unwrap();
+            let results = restored.search(&[6.0, 1.0, 0.5], 1, None).unwrap();
+            assert_eq!(results[0].id, ids[5]);
+
+            // Replaying the same vectors leaves the half precision graph alone
+            assert_eq!(restored.upsert_changed(vectors).unwrap(), 0);
+
+            // Float32 and scalar int8 indexes refuse the half precision dump
+            let mut float = HnswRsIndex::new(IndexConfig::default(), metric, 3);
+            assert!(float.deserialize(&index.serialize().unwrap()).is_err());
+            let mut int8 = HnswRsIndex::new_scalar_int8(IndexConfig::default(), metric, 3, DEFAULT_FIT_THRESHOLD);
+            assert!(int8.deserialize(&index.serialize().unwrap()).is_err());
+        }
+    }
+
+    #[test]
+    fn test_hnsw_rs_search_counted() {
+        let mut index = HnswRsIndex::new(IndexConfig::default(), DistanceMetric::Euclidean, 8);
+        let vectors: Vec<_> = (0..2000)
+            .map(|i| {
+                let vector = (0..8).map(|d| ((i * 8 + d) as f32 * 0.618).fract()).collect();
+                (Uuid::new_v4(), vector, None)
+            })
+            .collect();
+        index.batch_insert(vectors).unwrap();
+
+        let query = [0.5; 8];
+        let (results, visited) = index.search_counted(&query, 10, Some(20)).unwrap();
+        let plain: Vec<Uuid> = index.search(&query, 10, Some(20)).unwrap().iter().map(|r| r.id).collect();
+        assert_eq!(results.iter().map(|r| r.id).collect::<Vec<_>>(), plain);
+
+        // A graph search visits a fraction of the points, more with a wider beam
+        assert!(visited > 10 && visited < 2000, "visited {}", visited);
+        let (_, wider) = index.search_counted(&query, 10, Some(200)).unwrap();
+        assert!(wider > visited);
+    }
+
+    #[test]
+    fn test_hnsw_rs_filtered_search() {
+        let mut index = HnswRsIndex::new(IndexConfig::default(), DistanceMetric::Euclidean, 2);
+
+        // 3000 points on a grid; "even" matches 50%, "group" 1% and "rare" 5 points
+        let vectors: Vec<IndexedVector> = (0..3000)
+            .map(|i| {
+                let mut metadata = HashMap::new();
+                metadata.insert("even".to_string(), serde_json::json!(i % 2 == 0));
+                metadata.insert("group".to_string(), serde_json::json!(i % 100));
+                metadata.insert("rare".to_string(), serde_json::json!(i % 600 == 7));
+                (Uuid::new_v4(), vec![(i % 60) as f32, (i / 60) as f32], Some(metadata))
+            })
+            .collect();
+        index.batch_insert(vectors.clone()).unwrap();
+
+        let query = [30.37, 25.21];
+        for key in ["even", "group", "rare"] {
+            let accept = |metadata: Option<&HashMap<String, serde_json::Value>>| {
+                let value = &metadata.unwrap()[key];
+                match key {
+                    "group" => value == 42,
+                    _ => value == true,
+                }
+            };
+
+            let mut expected: Vec<(VectorId, f32)> = vectors
+                .iter()
+                .filter(|(_, _, metadata)| accept(metadata.as_ref()))
+                .map(|(id, v, _)| (*id, (v[0] - query[0]).powi(2) + (v[1] - query[1]).powi(2)))
+                .collect();
+            expected.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+            let results = index
+                .search_filtered(&query, 10, None, &|_, metadata| accept(metadata))
+                .unwrap();
+
+            assert_eq!(results.len(), expected.len().min(10), "filter on {}", key);
+            assert!(results.iter().all(|r| accept(r.metadata.as_ref())));
+            assert!(results.windows(2).all(|w| w[0].distance <= w[1].distance));
+            // The closest match is always found
+            assert_eq!(results[0].id, expected[0].0, "filter on {}", key);
+        }
+
+        // Deleted vectors never come back, even through the scan
+        let (rare_ids, _): (Vec<VectorId>, Vec<_>) = vectors
+            .iter()
+            .filter(|(_, _, metadata)| metadata.as_ref().unwrap()["rare"] == true)
+            .map(|(id, _, _)| (*id, ()))
+            .unzip();
+        index.delete(&rare_ids[0]).unwrap();
+        let results = index
+            .search_filtered(&query, 10, None, &|_, metadata| metadata.unwrap()["rare"] == true)
+            .unwrap();
+        assert_eq!(results.len(), rare_ids.len() - 1);
+        assert!(results.iter().all(|r| r.id != rare_ids[0]));
+    }
 }
@@ -0,0 +1,602 @@
+//! Inverted file (IVF) index
+//!
+//! Vectors are bucketed by their nearest centroid and a query scans only the
+//! buckets of the `nprobe` centroids closest to it. `ivf_flat` keeps each
+//! vector in the collection's element type and scores it exactly; `ivf_pq`
+//! keeps product quantization codes of each vector's offset from its
+//! centroid, scored through a distance table per bucket.
+//!
+//! Centroids are trained with k-means once the index reaches its training
+//! threshold. Until then the index holds vectors as a flat index does and
+//! scans them all.
+
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use vectordb_common::distance::euclidean_distance;
+use vectordb_common::quantization::{simple_kmeans, ProductQuantizer, TrainingStatus};
+use vectordb_common::types::*;
+use vectordb_common::{Result, VectorDbError};
+use crate::flat_index::{nearest, prepare, prepared_distance, VectorSlab};
+use crate::{SearchFilter, SearchResult};
+
+/// Most vectors the centroids and codebooks are trained on
+const TRAINING_SAMPLE: usize = 10_000;
+
+/// k-means iterations when training centroids
+const KMEANS_ITERATIONS: usize = 10;
+
+/// Serialized form of an `IvfIndex`
+#[derive(Serialize, Deserialize)]
+struct IvfDump {
+    distance_metric: DistanceMetric,
+    dimension: usize,
+    index_type: IndexType,
+    centroids: Vec<Vec<f32>>,
+    quantizer: Option<ProductQuantizer>,
+    sample_size: usize,
+    vectors: VectorSlab,
+    codes: Vec<u8>,
+    slots: Vec<Option<VectorId>>,
+    slot_lists: Vec<usize>,
+    /// Metadata map as JSON, since bincode cannot round-trip `serde_json::Value`
+    metadata_json: String,
+}
+
+pub struct IvfIndex {
+    distance_metric: DistanceMetric,
+    dimension: usize,
+    /// `IvfFlat` or `IvfPq`
+    index_type: IndexType,
+    config: IvfConfig,
+    /// Empty until trained
+    centroids: Vec<Vec<f32>>,
+    /// Residual codebooks of an `ivf_pq` index, once trained
+    quantizer: Option<ProductQuantizer>,
+    /// Vectors the centroids were trained on
+    sample_size: usize,
+    /// Prepared vectors; emptied once an `ivf_pq` index is trained
+    vectors: VectorSlab,
+    /// Residual codes of every slot back to back, for a trained `ivf_pq` index
+    codes: Vec<u8>,
+    /// Vector held in each slot; `None` for a slot freed by a delete
+    slots: Vec<Option<VectorId>>,
+    /// Bucket of each slot, once trained
+    slot_lists: Vec<usize>,
+    /// Slots in each bucket
+    lists: Vec<Vec<usize>>,
+    id_to_slot: HashMap<VectorId, usize>,
+    free_slots: Vec<usize>,
+    metadata: HashMap<VectorId, HashMap<String, serde_json::Value>>,
+}
+
+impl IvfIndex {
+    /// Untrained index; `ivf_flat` indexes hold vectors as `vector_type`
+    pub fn new(
+        distance_metric: DistanceMetric,
+        dimension: usize,
+        vector_type: VectorType,
+        index_type: IndexType,
+        config: &IvfConfig,
+    ) -> Self {
+        Self {
+            distance_metric,
+            dimension,
+            index_type,
+            config: config.clone(),
+            centroids: Vec::new(),
+            quantizer: None,
+            sample_size: 0,
+            vectors: VectorSlab::new(vector_type),
+            codes: Vec::new(),
+            slots: Vec::new(),
+            slot_lists: Vec::new(),
+            lists: Vec::new(),
+            id_to_slot: HashMap::new(),
+            free_slots: Vec::new(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    /// Reject IVF settings an index of `dimension` could not be trained with
+    pub fn validate(dimension: usize, index_type: IndexType, config: &IvfConfig) -> Result<()> {
+        if config.nlist == 0 || config.nprobe == 0 {
+            return Err(VectorDbError::Configuration {
+                message: "IVF nlist and nprobe must be at least 1".to_string(),
+            });
+        }
+        if index_type == IndexType::IvfPq {
+            ProductQuantizer::new(dimension, config.segments_for(dimension), config.pq_centroids)?;
+        }
+        Ok(())
+    }
+
+    fn is_trained(&self) -> bool {
+        !self.centroids.is_empty()
+    }
+
+    fn segments(&self) -> usize {
+        self.quantizer.as_ref().map_or(0, ProductQuantizer::num_segments)
+    }
+
+    fn check_dimension(&self, vector: &[f32]) -> Result<()> {
+        if vector.len() != self.dimension {
+            return Err(VectorDbError::InvalidDimension {
+                expected: self.dimension,
+                actual: vector.len(),
+            });
+        }
+        Ok(())
+    }
+
+    fn residual(&self, vector: &[f32], list: usize) -> Vec<f32> {
+        residual(vector, &self.centroids[list])
+    }
+
+    /// Hold a prepared vector in `slot`, in its bucket once trained
+    fn place(&mut self, slot: usize, vector: &[f32]) -> Result<()> {
+        if !self.is_trained() {
+            self.vectors.set(slot, vector);
+            return Ok(());
+        }
+
+        let list = nearest_centroid(&self.centroids, vector);
+        if self.slot_lists.len() <= slot {
+            self.slot_lists.resize(slot + 1, 0);
+        }
+        self.slot_lists[slot] = list;
+        self.lists[list].push(slot);
+
+        match &self.quantizer {
+            Some(quantizer) => {
+                let code = quantizer.encode(&self.residual(vector, list))?;
+                let segments = code.len();
+                if self.codes.len() < (slot + 1) * segments {
+                    self.codes.resize((slot + 1) * segments, 0);
+                }
+                self.codes[slot * segments..(slot + 1) * segments].copy_from_slice(&code);
+            }
+            None => self.vectors.set(slot, vector),
+        }
+        Ok(())
+    }
+
+    /// Take `slot` out of its bucket
+    fn unplace(&mut self, slot: usize) {
+        if let Some(list) = self.slot_lists.get(slot).and_then(|&list| self.lists.get_mut(list)) {
+            if let Some(position) = list.iter().position(|&s| s == slot) {
+                list.swap_remove(position);
+            }
+        }
+    }
+
+    /// Train centroids, and residual codebooks for `ivf_pq`, on the vectors
+    /// held so far, then bucket them all
+    fn train(&mut self) -> Result<()> {
+        let live: Vec<usize> = self
+            .slots
+            .iter()
+            .enumerate()
+            .filter_map(|(slot, id)| id.map(|_| slot))
+            .collect();
+        if live.is_empty() {
+            return Ok(());
+        }
+
+        let mut buffer = vec![0.0; self.dimension];
+        let held: Vec<Vec<f32>> = live
+            .iter()
+            .map(|&slot| self.vectors.get(slot, &mut buffer).to_vec())
+            .collect();
+
+        // An evenly spaced sample
+        let step = held.len().div_ceil(TRAINING_SAMPLE);
+        let sample: Vec<Vec<f32>> = held.iter().step_by(step).cloned().collect();
+        let centroids = simple_kmeans(&sample, self.config.nlist.min(sample.len()), KMEANS_ITERATIONS)?;
+
+        let quantizer = match self.index_type {
+            IndexType::IvfPq => {
+                let residuals: Vec<Vec<f32>> = sample
+                    .iter()
+                    .map(|vector| residual(vector, &centroids[nearest_centroid(&centroids, vector)]))
+                    .collect();
+                let mut quantizer = ProductQuantizer::new(
+                    self.dimension,
+                    self.config.segments_for(self.dimension),
+                    self.config.pq_centroids.min(sample.len()),
+                )?;
+                quantizer.train(&residuals)?;
+                Some(quantizer)
+            }
+            _ => None,
+        };
+
+        self.lists = vec![Vec::new(); centroids.len()];
+        self.centroids = centroids;
+        self.quantizer = quantizer;
+        self.sample_size = sample.len();
+        if self.quantizer.is_some() {
+            self.vectors = VectorSlab::new(self.vectors.vector_type());
+        }
+        for (slot, vector) in live.into_iter().zip(held) {
+            self.place(slot, &vector)?;
+        }
+        Ok(())
+    }
+
+    /// Nearest `limit` vectors accepted by `accept`, closest first, and the
+    /// number of vectors scored
+    ///
+    /// Buckets are scanned closest centroid first: `nprobe` of them, and more
+    /// while fewer than `limit` vectors have been accepted.
+    fn scan(&self, query: &[f32], limit: usize, accept: &dyn Fn(&VectorId) -> bool) -> (Vec<(VectorId, f32)>, usize) {
+        let query = prepare(self.distance_metric, query);
+        let mut buffer = vec![0.0; self.dimension];
+
+        if !self.is_trained() {
+            let hits = self
+                .slots
+                .iter()
+                .enumerate()
+                .filter_map(|(slot, id)| Some(((*id)?, slot)))
+                .filter(|(id, _)| accept(id))
+                .map(|(id, slot)| {
+                    let vector = self.vectors.get(slot, &mut buffer);
+                    (id, prepared_distance(self.distance_metric, &query, vector))
+                })
+                .collect::<Vec<_>>();
+            let scanned = hits.len();
+            return (nearest(hits, limit), scanned);
+        }
+
+        let mut order: Vec<(usize, f32)> = self
+            .centroids
+            .iter()
+            .enumerate()
+            .map(|(list, centroid)| (list, euclidean_distance(&query, centroid)))
+            .collect();
+        order.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+        let mut hits = Vec::new();
+        for (probed, (list, _)) in order.into_iter().enumerate() {
+            if probed >= self.config.nprobe && hits.len() >= limit {
+                break;
+            }
+
+            let table = self
+                .quantizer
+                .as_ref()
+                .map(|quantizer| quantizer.distance_table(&self.residual(&query, list)));
+            for &slot in &self.lists[list] {
+                let Some(id) = self.slots[slot] else { continue };
+                if !accept(&id) {
+                    continue;
+                }
+                let distance = match &table {
+                    Some(table) => {
+                        let segments = self.segments();
+                        let code = &self.codes[slot * segments..(slot + 1) * segments];
+                        self.decode_distance(ProductQuantizer::asymmetric_distance(table, code))
+                    }
+                    None => prepared_distance(self.distance_metric, &query, self.vectors.get(slot, &mut buffer)),
+                };
+                hits.push((id, distance));
+            }
+        }
+        let scanned = hits.len();
+        (nearest(hits, limit), scanned)
+    }
+
+    /// Distance in the index's metric from a squared Euclidean distance between prepared vectors
+    fn decode_distance(&self, squared: f32) -> f32 {
+        if self.distance_metric == DistanceMetric::Euclidean {
+            squared.sqrt()
+        } else {
+            // Cosine distance between unit vectors is half their squared distance
+            squared / 2.0
+        }
+    }
+
+    fn results(&self, hits: Vec<(VectorId, f32)>) -> Vec<SearchResult> {
+        hits.into_iter()
+            .map(|(id, distance)| SearchResult {
+                id,
+                distance,
+                metadata: self.metadata.get(&id).cloned(),
+            })
+            .collect()
+    }
+}
+
+/// Index of the centroid closest to a prepared vector
+fn nearest_centroid(centroids: &[Vec<f32>], vector: &[f32]) -> usize {
+    centroids
+        .iter()
+        .enumerate()
+        .map(|(list, centroid)| (list, euclidean_distance(vector, centroid)))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map_or(0, |(list, _)| list)
+}
+
+/// Offset of a prepared vector from a centroid
+fn residual(vector: &[f32], centroid: &[f32]) -> Vec<f32> {
+    vector.iter().zip(centroid).map(|(v, c)| v - c).collect()
+}
+
+impl super::VectorIndex for IvfIndex {
+    fn insert(
+        &mut self,
+        id: VectorId,
+        vector: &[f32],
+        metadata: Option<HashMap<String, serde_json::Value>>,
+    ) -> Result<()> {
+        self.check_dimension(vector)?;
+
+        let slot = match self.id_to_slot.get(&id) {
+            Some(&slot) => {
+                self.unplace(slot);
+                slot
+            }
+            None => {
+                let slot = self.free_slots.pop().unwrap_or_else(|| {
+                    self.slots.push(None);
+                    self.slots.len() - 1
+                });
+                self.slots[slot] = Some(id);
+                self.id_to_slot.insert(id, slot);
+                slot
+            }
+        };
+        self.place(slot, &prepare(self.distance_metric, vector))?;
+
+        match metadata {
+            Some(meta) => self.metadata.insert(id, meta),
+            None => self.metadata.remove(&id),
+        };
+
+        if !self.is_trained() && self.id_to_slot.len() >= self.config.training_threshold {
+            self.train()?;
+        }
+        Ok(())
+    }
+
+    fn search(&self, query: &[f32], limit: usize, _ef: Option<usize>) -> Result<Vec<SearchResult>> {
+        self.check_dimension(query)?;
+        Ok(self.results(self.scan(query, limit, &|_| true).0))
+    }
+
+    fn search_counted(&self, query: &[f32], limit: usize, _ef: Option<usize>) -> Result<(Vec<SearchResult>, usize)> {
+        self.check_dimension(query)?;
+        let (hits, scanned) = self.scan(query, limit, &|_| true);
+        Ok((self.results(hits), scanned))
+    }
+
+    fn search_filtered(
+        &self,
+        query: &[f32],
+        limit: usize,
+        _ef: Option<usize>,
+        filter: &SearchFilter<'_>,
+    ) -> Result<Vec<SearchResult>> {
+        self.check_dimension(query)?;
+        let (hits, _) = self.scan(query, limit, &|id| filter(id, self.metadata.get(id)));
+        Ok(self.results(hits))
+    }
+
+    fn delete(&mut self, id: &VectorId) -> Result<bool> {
+        self.metadata.remove(id);
+        match self.id_to_slot.remove(id) {
+            Some(slot) => {
+                self.unplace(slot);
+                self.slots[slot] = None;
+                self.free_slots.push(slot);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    fn set_metadata(&mut self, id: &VectorId, metadata: Option<HashMap<String, serde_json::Value>>) -> bool {
+        if !self.id_to_slot.contains_key(id) {
+            return false;
+        }
+        match metadata {
+            Some(meta) => self.metadata.insert(*id, meta),
+            None => self.metadata.remove(id),
+        };
+        true
+    }
+
+    fn stats(&self) -> super::IndexStats {
+        const MAPPING_SIZE: usize = 2 * (std::mem::size_of::<VectorId>() + std::mem::size_of::<usize>());
+
+        let centroids = self.centroids.len() * self.dimension * std::mem::size_of::<f32>();
+        let buckets = self.lists.iter().map(Vec::len).sum::<usize>() + self.slot_lists.len();
+        let memory = MemoryBreakdown {
+            vectors: self.vectors.memory_size(),
+            index: centroids
+                + buckets * std::mem::size_of::<usize>()
+                + self.slots.len() * std::mem::size_of::<Option<VectorId>>()
+                + self.id_to_slot.len() * MAPPING_SIZE,
+            payload: self.metadata.values().map(crate::payload_size).sum(),
+            quantized: self.codes.len() + self.quantizer.as_ref().map_or(0, ProductQuantizer::memory_size),
+        };
+
+        super::IndexStats {
+            vector_count: self.id_to_slot.len(),
+            memory_usage: memory.total(),
+            memory,
+            dimension: self.dimension,
+            max_layer: 0,
+            avg_connections: 0.0,
+        }
+    }
+
+    fn training_status(&self) -> Option<TrainingStatus> {
+        Some(match self.is_trained() {
+            true => TrainingStatus::Trained { sample_size: self.sample_size },
+            false => TrainingStatus::Untrained {
+                vectors: self.id_to_slot.len(),
+                threshold: self.config.training_threshold,
+            },
+        })
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>> {
+        let dump = IvfDump {
+            distance_metric: self.distance_metric,
+            dimension: self.dimension,
+            index_type: self.index_type,
+            centroids: self.centroids.clone(),
+            quantizer: self.quantizer.clone(),
+            sample_size: self.sample_size,
+            vectors: self.vectors.clone(),
+            codes: self.codes.clone(),
+            slots: self.slots.clone(),
+            slot_lists: self.slot_lists.clone(),
+            metadata_json: serde_json::to_string(&self.metadata)?,
+        };
+        bincode::serialize(&dump).map_err(|e| VectorDbError::Serialization(e.to_string()))
+    }
+
+    fn deserialize(&mut self, data: &[u8]) -> Result<()> {
+        let dump: IvfDump = bincode::deserialize(data)
+            .map_err(|e| VectorDbError::Serialization(e.to_string()))?;
+
+        if dump.distance_metric != self.distance_metric
+            || dump.dimension != self.dimension
+            || dump.index_type != self.index_type
+            || dump.vectors.vector_type() != self.vectors.vector_type()
+        {
+            return Err(VectorDbError::IndexError {
+                message: format!(
+                    "IVF dump is for {:?}/{}/{:?}/{:?} but index is {:?}/{}/{:?}/{:?}",
+                    dump.distance_metric,
+                    dump.dimension,
+                    dump.index_type,
+                    dump.vectors.vector_type(),
+                    self.distance_metric,
+                    self.dimension,
+                    self.index_type,
+                    self.vectors.vector_type()
+                ),
+            });
+        }
+
+        self.lists = vec![Vec::new(); dump.centroids.len()];
+        if !dump.centroids.is_empty() {
+            for (slot, id) in dump.slots.iter().enumerate() {
+                if id.is_some() {
+                    self.lists[dump.slot_lists[slot]].push(slot);
+                }
+            }
+        }
+        self.id_to_slot = dump
+            .slots
+            .iter()
+            .enumerate()
+            .filter_map(|(slot, id)| Some(((*id)?, slot)))
+            .collect();
+        self.free_slots = dump
+            .slots
+            .iter()
+            .enumerate()
+            .filter_map(|(slot, id)| id.is_none().then_some(slot))
+            .collect();
+        self.centroids = dump.centroids;
+        self.quantizer = dump.quantizer;
+        self.sample_size = dump.sample_size;
+        self.vectors = dump.vectors;
+        self.codes = dump.codes;
+        self.slots = dump.slots;
+        self.slot_lists = dump.slot_lists;
+        self.metadata = serde_json::from_str(&dump.metadata_json)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VectorIndex;
+    use uuid::Uuid;
+
+    fn config() -> IvfConfig {
+        IvfConfig {
+            nlist: 8,
+            nprobe: 2,
+            training_threshold: 200,
+            pq_segments: Some(2),
+            pq_centroids: 16,
+        }
+    }
+
+    #[test]
+    fn test_ivf_index_trains_and_probes() {
+        let vectors: Vec<(VectorId, Vec<f32>)> = (0..400)
+            .map(|i| (Uuid::new_v4(), vec![(i % 20) as f32, (i / 20) as f32, 1.0, 0.0]))
+            .collect();
+
+        for index_type in [IndexType::IvfFlat, IndexType::IvfPq] {
+            let mut index = IvfIndex::new(DistanceMetric::Euclidean, 4, VectorType::Float32, index_type, &config());
+            for (id, vector) in &vectors[..150] {
+                index.insert(*id, vector, None).unwrap();
+            }
+            assert_eq!(
+                index.training_status(),
+                Some(TrainingStatus::Untrained { vectors: 150, threshold: 200 })
+            );
+            // Exact while untrained
+            let results = index.search(&[3.0, 4.0, 1.0, 0.0], 1, None).unwrap();
+            assert_eq!(results[0].id, vectors[83].0);
+            assert_eq!(results[0].distance, 0.0);
+
+            // Trained once the threshold is reached
+            index
+                .batch_insert(vectors[150..].iter().map(|(id, v)| (*id, v.clone(), None)).collect())
+                .unwrap();
+            assert_eq!(index.training_status(), Some(TrainingStatus::Trained { sample_size: 200 }));
+            let stats = index.stats();
+            assert_eq!(stats.vector_count, 400);
+            match index_type {
+                IndexType::IvfPq => {
+                    assert_eq!(stats.memory.vectors, 0);
+                    assert!(stats.memory.quantized >= 400 * 2);
+                }
+                _ => assert_eq!(stats.memory.vectors, 400 * 4 * 4),
+            }
+
+            let results = index.search(&[3.0, 4.0, 1.0, 0.0], 10, None).unwrap();
+            assert_eq!(results.len(), 10);
+            assert!(results.iter().any(|r| r.id == vectors[83].0), "{:?}", index_type);
+            if index_type == IndexType::IvfFlat {
+                assert_eq!(results[0].id, vectors[83].0);
+                assert_eq!(results[0].distance, 0.0);
+            }
+
+            // Buckets past nprobe are scanned until enough vectors match
+            let far = vectors[399].0;
+            let results = index
+                .search_filtered(&[3.0, 4.0, 1.0, 0.0], 1, None, &|id, _| *id == far)
+                .unwrap();
+            assert_eq!(results.len(), 1);
+            assert_eq!(results[0].id, far);
+
+            // Deleted and moved vectors leave their old buckets
+            assert!(index.delete(&vectors[83].0).unwrap());
+            index.insert(vectors[84].0, &[19.0, 19.0, 1.0, 0.0], None).unwrap();
+            let results = index.search(&[3.0, 4.0, 1.0, 0.0], 400, None).unwrap();
+            assert_eq!(results.len(), 399);
+            assert!(results.iter().all(|r| r.id != vectors[83].0));
+            assert_eq!(results.iter().filter(|r| r.id == vectors[84].0).count(), 1);
+
+            let mut restored = IvfIndex::new(DistanceMetric::Euclidean, 4, VectorType::Float32, index_type, &config());
+            restored.deserialize(&index.serialize().unwrap()).unwrap();
+            assert_eq!(restored.stats().vector_count, 399);
+            assert_eq!(restored.search(&[3.0, 4.0, 1.0, 0.0], 400, None).unwrap().len(), 399);
+        }
+
+        assert!(IvfIndex::validate(4, IndexType::IvfPq, &config()).is_ok());
+        assert!(IvfIndex::validate(5, IndexType::IvfPq, &config()).is_err());
+        assert!(IvfIndex::validate(5, IndexType::IvfFlat, &config()).is_ok());
+    }
+}
@@ -1,13 +1,27 @@
+pub mod binary_index;
+pub mod flat_index;
 pub mod hnsw;
 pub mod node;
 pub mod hnsw_rs_index;  // New production-ready HNSW
+pub mod ivf_index;
+pub mod payload_index;
+pub mod pq_index;
+pub mod sparse_index;
+pub mod tokenizer;
 
 use vectordb_common::Result;
 use vectordb_common::types::*;
 
+pub use binary_index::BinaryIndex;
+pub use flat_index::FlatIndex;
 pub use hnsw::*;
 pub use node::*;
 pub use hnsw_rs_index::HnswRsIndex;  // Export the new implementation
+pub use ivf_index::IvfIndex;
+pub use payload_index::{PayloadIndex, TextMatches};
+pub use pq_index::PqIndex;
+pub use sparse_index::SparseIndex;
+pub use tokenizer::Tokenizer;
 
 /// Search result with distance and metadata
 #[derive(Debug, Clone)]
@@ -38,6 +52,13 @@ impl Ord for SearchResult {
     }
 }
 
+/// Predicate for filtered search, given a vector's ID and metadata
+pub type SearchFilter<'a> =
+    dyn Fn(&VectorId, Option<&std::collections::HashMap<String, serde_json::Value>>) -> bool + 'a;
+
+/// A vector to index: its ID, data and metadata
+pub type IndexedVector = (VectorId, Vec<f32>, Option<std::collections::HashMap<String, serde_json::Value>>);
+
 /// Trait for vector index implementations
 pub trait VectorIndex: Send + Sync {
     /// Insert a vector into the index
@@ -59,6 +80,44 @@ pub trait VectorIndex: Send + Sync {
     /// Search for nearest neighbors
     fn search(&self, query: &[f32], limit: usize, ef: Option<usize>) -> Result<Vec<SearchResult>>;
 
+    /// Search for nearest neighbors among the vectors accepted by `filter`
+    ///
+    /// Should return `limit` results whenever at least that many vectors match.
+    /// The default widens an unfiltered search until it has enough matches or
+    /// has asked for every vector in the index.
+    fn search_filtered(
+        &self,
+        query: &[f32],
+        limit: usize,
+        ef: Option<usize>,
+        filter: &SearchFilter<'_>,
+    ) -> Result<Vec<SearchResult>> {
+        let total = self.stats().vector_count;
+        let mut fetch = limit.saturating_mul(4).min(total);
+
+        loop {
+            let matched: Vec<SearchResult> = self
+                .search(query, fetch, ef.map(|ef| ef.max(fetch)))?
+                .into_iter()
+                .filter(|r| filter(&r.id, r.metadata.as_ref()))
+                .take(limit)
+                .collect();
+
+            if matched.len() == limit || fetch >= total {
+                return Ok(matched);
+            }
+            fetch = fetch.saturating_mul(4).min(total);
+        }
+    }
+
+    /// Search for nearest neighbors, also returning how many vectors the query
+    /// was compared against
+    ///
+    /// The default assumes `search` scans every vector in the index.
+    fn search_counted(&self, query: &[f32], limit: usize, ef: Option<usize>) -> Result<(Vec<SearchResult>, usize)> {
+        Ok((self.search(query, limit, ef)?, self.stats().vector_count))
+    }
+
     /// Delete a vector from the index
     fn delete(&mut self, id: &VectorId) -> Result<bool>;
 
@@ -69,9 +128,31 @@ pub trait VectorIndex: Send + Sync {
         Ok(())
     }
 
+    /// Insert or update vectors when replaying records over a reloaded index,
+    /// returning how many were added or changed
+    ///
+    /// The default updates every vector; indexes where an update is costly can
+    /// skip the ones they already hold unchanged.
+    fn upsert_changed(&mut self, vectors: Vec<IndexedVector>) -> Result<usize> {
+        let count = vectors.len();
+        for (id, vector, metadata) in vectors {
+            self.update(id, &vector, metadata)?;
+        }
+        Ok(count)
+    }
+
+    /// Replace the metadata held for a vector without touching the vector or
+    /// the index structure; returns whether the index holds the vector
+    fn set_metadata(&mut self, id: &VectorId, metadata: Option<std::collections::HashMap<String, serde_json::Value>>) -> bool;
+
     /// Get index statistics
     fn stats(&self) -> IndexStats;
 
+    /// Training state of the index's quantizer, for indexes that need one trained
+    fn training_status(&self) -> Option<vectordb_common::quantization::TrainingStatus> {
+        None
+    }
+
     /// Serialize index to bytes
     fn serialize(&self) -> Result<Vec<u8>>;
 
@@ -84,7 +165,24 @@ pub trait VectorIndex: Send + Sync {
 pub struct IndexStats {
     pub vector_count: usize,
     pub memory_usage: usize,
+    /// Split of `memory_usage` by component
+    pub memory: MemoryBreakdown,
     pub dimension: usize,
     pub max_layer: usize,
     pub avg_connections: f32,
+}
+
+/// Approximate heap size of a metadata map
+pub fn payload_size(metadata: &std::collections::HashMap<String, serde_json::Value>) -> usize {
+    fn value_size(value: &serde_json::Value) -> usize {
+        std::mem::size_of::<serde_json::Value>()
+            + match value {
+                serde_json::Value::String(s) => s.len(),
+                serde_json::Value::Array(items) => items.iter().map(value_size).sum(),
+                serde_json::Value::Object(map) => map.iter().map(|(k, v)| k.len() + value_size(v)).sum(),
+                _ => 0,
+            }
+    }
+
+    metadata.iter().map(|(k, v)| std::mem::size_of::<String>() + k.len() + value_size(v)).sum()
 }
\ No newline at end of file
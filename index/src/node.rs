@@ -97,14 +97,7 @@ impl HnswNode {
         std::mem::size_of::<Self>()
             + self.vector.len() * std::mem::size_of::<f32>()
             + self.connections.iter().map(|layer| layer.len() * std::mem::size_of::<VectorId>()).sum::<usize>()
-            + self.metadata.as_ref().map_or(0, |m| {
-                m.iter().map(|(k, v)| {
-                    k.len() + match v {
-                        serde_json::Value::String(s) => s.len(),
-                        _ => std::mem::size_of::<serde_json::Value>(),
-                    }
-                }).sum::<usize>()
-            })
+            + self.metadata.as_ref().map_or(0, crate::payload_size)
     }
 }
 
@@ -0,0 +1,1202 @@
+//! Payload field indexes
+//!
+//! Typed secondary indexes over top-level payload fields. They turn the parts
+//! of a [`Filter`] they understand into a set of candidate vectors, so callers
+//! only evaluate the full filter on those instead of walking every record.
+//! Candidate sets are always a superset of the vectors the filter matches,
+//! except for full-text conditions on text fields: those are decided by the
+//! index's tokenizer, and [`TextMatches`] carries that decision to the
+//! filter evaluator.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ops::Bound;
+use vectordb_common::filter::{
+    Condition, FieldCondition, Filter, GeoBoundingBox, GeoRadius, MatchText, MatchValue,
+    RangeCondition,
+};
+use vectordb_common::search_api::Direction;
+use vectordb_common::types::{PayloadFieldType, PayloadIndexInfo, TextIndexParams, VectorId};
+
+use crate::tokenizer::Tokenizer;
+
+type Payload = HashMap<String, Value>;
+
+/// Bits of geohash kept per point (8 base32 characters, roughly 19m x 19m cells)
+const GEO_BITS: u32 = 40;
+
+/// Upper bound on grid cells scanned for one geo condition
+const MAX_GEO_CELLS: u64 = 64;
+
+/// Mean earth radius in meters, as used by the filter evaluator
+const EARTH_RADIUS: f64 = 6371000.0;
+
+/// Integers beyond this lose precision as `f64`, so range bounds past it are dropped
+const MAX_EXACT_INTEGER: f64 = 9007199254740992.0;
+
+/// Sorted postings lists for one field, plus the keys each vector is filed under
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Postings<K: Ord> {
+    map: BTreeMap<K, HashSet<VectorId>>,
+    keys: HashMap<VectorId, Vec<K>>,
+}
+
+impl<K: Ord> Default for Postings<K> {
+    fn default() -> Self {
+        Self {
+            map: BTreeMap::new(),
+            keys: HashMap::new(),
+        }
+    }
+}
+
+impl<K: Ord + Clone> Postings<K> {
+    /// File `id` under `keys`; with no keys it still counts as having a value
+    fn insert(&mut self, id: VectorId, keys: Vec<K>) {
+        for key in &keys {
+            self.map.entry(key.clone()).or_default().insert(id);
+        }
+        self.keys.insert(id, keys);
+    }
+
+    fn remove(&mut self, id: &VectorId) {
+        let Some(keys) = self.keys.remove(id) else {
+            return;
+        };
+        for key in keys {
+            if let Some(ids) = self.map.get_mut(&key) {
+                ids.remove(id);
+                if ids.is_empty() {
+                    self.map.remove(&key);
+                }
+            }
+        }
+    }
+
+    fn get(&self, key: &K, out: &mut HashSet<VectorId>) {
+        if let Some(ids) = self.map.get(key) {
+            out.extend(ids.iter().copied());
+        }
+    }
+
+    fn range(&self, lower: Bound<K>, upper: Bound<K>, out: &mut HashSet<VectorId>) {
+        if range_is_empty(&lower, &upper) {
+            return;
+        }
+        for ids in self.map.range((lower, upper)).map(|(_, ids)| ids) {
+            out.extend(ids.iter().copied());
+        }
+    }
+
+    /// Up to `limit` `(key, id)` pairs in key order, then ID order within a
+    /// key, strictly after the pair `after`
+    ///
+    /// A vector filed under several keys is listed once, under its smallest
+    /// key ascending and its largest descending.
+    fn ordered(&self, direction: Direction, after: Option<(&K, &VectorId)>, limit: usize) -> Vec<(K, VectorId)> {
+        let keys: Box<dyn Iterator<Item = (&K, &HashSet<VectorId>)>> = match (direction, after) {
+            (Direction::Asc, None) => Box::new(self.map.iter()),
+            (Direction::Asc, Some((key, _))) => Box::new(self.map.range((Bound::Included(key), Bound::Unbounded))),
+            (Direction::Desc, None) => Box::new(self.map.iter().rev()),
+            (Direction::Desc, Some((key, _))) => {
+                Box::new(self.map.range((Bound::Unbounded, Bound::Included(key))).rev())
+            }
+        };
+
+        let mut out = Vec::with_capacity(limit);
+        for (key, ids) in keys {
+            let mut ids: Vec<VectorId> = match after {
+                Some((after_key, after_id)) if after_key == key => ids
+                    .iter()
+                    .filter(|id| match direction {
+                        Direction::Asc => *id > after_id,
+                        Direction::Desc => *id < after_id,
+                    })
+                    .copied()
+                    .collect(),
+                _ => ids.iter().copied().collect(),
+            };
+            ids.retain(|id| self.ordering_key(id, direction) == Some(key));
+            ids.sort_unstable();
+            if direction == Direction::Desc {
+                ids.reverse();
+            }
+
+            for id in ids {
+                if out.len() == limit {
+                    return out;
+                }
+                out.push((key.clone(), id));
+            }
+        }
+        out
+    }
+
+    /// The one key `id` is ordered by
+    fn ordering_key(&self, id: &VectorId, direction: Direction) -> Option<&K> {
+        let keys = self.keys.get(id)?;
+        match direction {
+            Direction::Asc => keys.iter().min(),
+            Direction::Desc => keys.iter().max(),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    fn ids(&self) -> impl Iterator<Item = &VectorId> {
+        self.keys.keys()
+    }
+}
+
+/// The distinct keys `key` reads from `value`, or from each element if it is an array
+fn elements<K: Ord>(value: &Value, key: impl Fn(&Value) -> Option<K>) -> Vec<K> {
+    let mut keys: Vec<K> = match value {
+        Value::Array(items) => items.iter().filter_map(&key).collect(),
+        value => key(value).into_iter().collect(),
+    };
+    keys.sort();
+    keys.dedup();
+    keys
+}
+
+/// Whether `(lower, upper)` selects nothing; `BTreeMap::range` panics on these
+fn range_is_empty<K: Ord>(lower: &Bound<K>, upper: &Bound<K>) -> bool {
+    match (lower, upper) {
+        (Bound::Included(lo), Bound::Included(hi)) => lo > hi,
+        (Bound::Included(lo), Bound::Excluded(hi))
+        | (Bound::Excluded(lo), Bound::Included(hi))
+        | (Bound::Excluded(lo), Bound::Excluded(hi)) => lo >= hi,
+        _ => false,
+    }
+}
+
+/// Index for a single field, by value type
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum FieldIndex {
+    Keyword(Postings<String>),
+    Integer {
+        values: Postings<i64>,
+        /// Numbers that are not `i64`; a range can still match them
+        other_numbers: HashSet<VectorId>,
+    },
+    /// Keyed by [`float_key`] so the tree orders like the numbers do
+    Float(Postings<u64>),
+    Bool(Postings<bool>),
+    Geo {
+        /// Keyed by [`GEO_BITS`]-bit geohash
+        cells: Postings<u64>,
+        /// Coordinates outside the valid lat/lon range
+        out_of_range: HashSet<VectorId>,
+    },
+    Text {
+        tokenizer: Tokenizer,
+        tokens: Postings<String>,
+    },
+}
+
+impl FieldIndex {
+    fn new(field_type: PayloadFieldType) -> Self {
+        match field_type {
+            PayloadFieldType::Keyword => Self::Keyword(Postings::default()),
+            PayloadFieldType::Integer => Self::Integer {
+                values: Postings::default(),
+                other_numbers: HashSet::new(),
+            },
+            PayloadFieldType::Float => Self::Float(Postings::default()),
+            PayloadFieldType::Bool => Self::Bool(Postings::default()),
+            PayloadFieldType::Geo => Self::Geo {
+                cells: Postings::default(),
+                out_of_range: HashSet::new(),
+            },
+            PayloadFieldType::Text => Self::text(TextIndexParams::default()),
+        }
+    }
+
+    fn text(params: TextIndexParams) -> Self {
+        Self::Text {
+            tokenizer: Tokenizer::from(params),
+            tokens: Postings::default(),
+        }
+    }
+
+    /// An empty index with the same type and settings
+    fn cleared(&self) -> Self {
+        match self {
+            Self::Text { tokenizer, .. } => Self::text(tokenizer.params().clone()),
+            other => Self::new(other.field_type()),
+        }
+    }
+
+    fn text_params(&self) -> Option<&TextIndexParams> {
+        match self {
+            Self::Text { tokenizer, .. } => Some(tokenizer.params()),
+            _ => None,
+        }
+    }
+
+    fn field_type(&self) -> PayloadFieldType {
+        match self {
+            Self::Keyword(_) => PayloadFieldType::Keyword,
+            Self::Integer { .. } => PayloadFieldType::Integer,
+            Self::Float(_) => PayloadFieldType::Float,
+            Self::Bool(_) => PayloadFieldType::Bool,
+            Self::Geo { .. } => PayloadFieldType::Geo,
+            Self::Text { .. } => PayloadFieldType::Text,
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Self::Keyword(postings) => postings.len(),
+            Self::Integer { values, other_numbers } => values.len() + other_numbers.len(),
+            Self::Float(postings) => postings.len(),
+            Self::Bool(postings) => postings.len(),
+            Self::Geo { cells, out_of_range } => cells.len() + out_of_range.len(),
+            Self::Text { tokens, .. } => tokens.len(),
+        }
+    }
+
+    /// File a vector under its value, mirroring what the filter evaluator reads
+    ///
+    /// Keyword, integer and bool fields also file a vector under each element
+    /// of an array value, as facets count them; that only adds candidates.
+    fn insert(&mut self, id: VectorId, value: &Value) {
+        match self {
+            Self::Keyword(postings) => {
+                let keys = elements(value, |v| v.as_str().map(str::to_string));
+                if !keys.is_empty() {
+                    postings.insert(id, keys);
+                }
+            }
+            Self::Integer { values, other_numbers } => {
+                let keys = elements(value, Value::as_i64);
+                if !keys.is_empty() {
+                    values.insert(id, keys);
+                } else if value.is_number() {
+                    other_numbers.insert(id);
+                }
+            }
+            Self::Float(postings) => {
+                if let Some(f) = value.as_f64().filter(|f| !f.is_nan()) {
+                    postings.insert(id, vec![float_key(f)]);
+                }
+            }
+            Self::Bool(postings) => {
+                let keys = elements(value, Value::as_bool);
+                if !keys.is_empty() {
+                    postings.insert(id, keys);
+                }
+            }
+            Self::Geo { cells, out_of_range } => {
+                let Some(obj) = value.as_object() else {
+                    return;
+                };
+                let lat = obj.get("lat").and_then(|v| v.as_f64());
+                let lon = obj.get("lon").and_then(|v| v.as_f64());
+                if let (Some(lat), Some(lon)) = (lat, lon) {
+                    if (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon) {
+                        cells.insert(id, vec![geohash(lat, lon)]);
+                    } else {
+                        out_of_range.insert(id);
+                    }
+                }
+            }
+            Self::Text { tokenizer, tokens } => {
+                if let Some(s) = value.as_str() {
+                    tokens.insert(id, tokenizer.index_tokens(s));
+                }
+            }
+        }
+    }
+
+    fn remove(&mut self, id: &VectorId) {
+        match self {
+            Self::Keyword(postings) => postings.remove(id),
+            Self::Integer { values, other_numbers } => {
+                values.remove(id);
+                other_numbers.remove(id);
+            }
+            Self::Float(postings) => postings.remove(id),
+            Self::Bool(postings) => postings.remove(id),
+            Self::Geo { cells, out_of_range } => {
+                cells.remove(id);
+                out_of_range.remove(id);
+            }
+            Self::Text { tokens, .. } => tokens.remove(id),
+        }
+    }
+
+    /// Vectors whose text contains every query token
+    fn match_text(&self, text: &str) -> Option<HashSet<VectorId>> {
+        let Self::Text { tokenizer, tokens } = self else {
+            return None;
+        };
+
+        let query = tokenizer.query_tokens(text);
+        if query.is_empty() {
+            // Like an empty substring, an empty query matches any text
+            return Some(tokens.ids().copied().collect());
+        }
+
+        let mut sets: Vec<&HashSet<VectorId>> = Vec::with_capacity(query.len());
+        for token in &query {
+            match tokens.map.get(token) {
+                Some(ids) => sets.push(ids),
+                None => return Some(HashSet::new()),
+            }
+        }
+        sets.sort_by_key(|ids| ids.len());
+        let (smallest, rest) = sets.split_first()?;
+        Some(
+            smallest
+                .iter()
+                .filter(|id| rest.iter().all(|ids| ids.contains(*id)))
+                .copied()
+                .collect(),
+        )
+    }
+
+    /// Look up one match value; `false` if this index cannot answer it
+    fn match_value(&self, value: &MatchValue, out: &mut HashSet<VectorId>) -> bool {
+        match (self, value) {
+            (Self::Keyword(postings), MatchValue::Keyword(s)) => postings.get(s, out),
+            (Self::Integer { values, .. }, MatchValue::Integer(i)) => values.get(i, out),
+            (Self::Bool(postings), MatchValue::Bool(b)) => postings.get(b, out),
+            _ => return false,
+        }
+        true
+    }
+
+    fn range(&self, range: &RangeCondition) -> Option<HashSet<VectorId>> {
+        let lower = lower_bound(range.gte, range.gt);
+        let upper = upper_bound(range.lte, range.lt);
+        let mut out = HashSet::new();
+
+        match self {
+            Self::Integer { values, other_numbers } => {
+                values.range(integer_lower(lower), integer_upper(upper), &mut out);
+                out.extend(other_numbers.iter().copied());
+            }
+            Self::Float(postings) => {
+                postings.range(lower.map(float_key), upper.map(float_key), &mut out);
+            }
+            _ => return None,
+        }
+        Some(out)
+    }
+
+    fn geo_radius(&self, geo: &GeoRadius) -> Option<HashSet<VectorId>> {
+        let Self::Geo { cells, out_of_range } = self else {
+            return None;
+        };
+        if !(-90.0..=90.0).contains(&geo.latitude) || !(-180.0..=180.0).contains(&geo.longitude) {
+            return None;
+        }
+
+        let mut out = out_of_range.clone();
+        if geo.radius_meters.is_nan() || geo.radius_meters < 0.0 {
+            return Some(out);
+        }
+
+        // Bounding box of the circle (Matuschek), padded slightly against rounding
+        let d = geo.radius_meters / EARTH_RADIUS * 1.0001 + 1e-9;
+        let lat = geo.latitude.to_radians();
+        let lon = geo.longitude.to_radians();
+        let (lat_min, lat_max) = (lat - d, lat + d);
+        let half_pi = std::f64::consts::FRAC_PI_2;
+        let pi = std::f64::consts::PI;
+
+        if lat_min <= -half_pi || lat_max >= half_pi {
+            // The circle covers a pole, so every longitude is in range
+            scan_geo_box(
+                cells,
+                lat_min.max(-half_pi).to_degrees(),
+                lat_max.min(half_pi).to_degrees(),
+                -180.0,
+                180.0,
+                &mut out,
+            );
+            return Some(out);
+        }
+
+        let dlon = (d.sin() / lat.cos()).min(1.0).asin();
+        let (lon_min, lon_max) = (lon - dlon, lon + dlon);
+        let (lat_min, lat_max) = (lat_min.to_degrees(), lat_max.to_degrees());
+
+        if lon_min < -pi {
+            scan_geo_box(cells, lat_min, lat_max, (lon_min + 2.0 * pi).to_degrees(), 180.0, &mut out);
+            scan_geo_box(cells, lat_min, lat_max, -180.0, lon_max.to_degrees(), &mut out);
+        } else if lon_max > pi {
+            scan_geo_box(cells, lat_min, lat_max, lon_min.to_degrees(), 180.0, &mut out);
+            scan_geo_box(cells, lat_min, lat_max, -180.0, (lon_max - 2.0 * pi).to_degrees(), &mut out);
+        } else {
+            scan_geo_box(cells, lat_min, lat_max, lon_min.to_degrees(), lon_max.to_degrees(), &mut out);
+        }
+        Some(out)
+    }
+
+    fn geo_bounding_box(&self, geo: &GeoBoundingBox) -> Option<HashSet<VectorId>> {
+        let Self::Geo { cells, out_of_range } = self else {
+            return None;
+        };
+
+        let mut out = out_of_range.clone();
+        let (lat_min, lat_max) = (geo.bottom_right.lat, geo.top_left.lat);
+        let (lon_min, lon_max) = (geo.top_left.lon, geo.bottom_right.lon);
+        // Inverted or NaN boxes match nothing in the evaluator either
+        if lat_min <= lat_max && lon_min <= lon_max {
+            scan_geo_box(cells, lat_min, lat_max, lon_min, lon_max, &mut out);
+        }
+        Some(out)
+    }
+}
+
+/// Map an `f64` to a `u64` with the same ordering (NaN excluded by callers)
+fn float_key(value: f64) -> u64 {
+    // -0.0 compares equal to 0.0, so they must share a key
+    let value = if value == 0.0 { 0.0 } else { value };
+    let bits = value.to_bits();
+    if bits >> 63 == 0 {
+        bits | (1 << 63)
+    } else {
+        !bits
+    }
+}
+
+/// Inverse of [`float_key`]
+fn key_float(key: u64) -> f64 {
+    if key >> 63 == 1 {
+        f64::from_bits(key & !(1 << 63))
+    } else {
+        f64::from_bits(!key)
+    }
+}
+
+/// Tightest lower bound implied by `gte` and `gt`; NaN bounds never exclude anything
+fn lower_bound(gte: Option<f64>, gt: Option<f64>) -> Bound<f64> {
+    match (gte.filter(|v| !v.is_nan()), gt.filter(|v| !v.is_nan())) {
+        (None, None) => Bound::Unbounded,
+        (Some(gte), None) => Bound::Included(gte),
+        (None, Some(gt)) => Bound::Excluded(gt),
+        (Some(gte), Some(gt)) if gt >= gte => Bound::Excluded(gt),
+        (Some(gte), Some(_)) => Bound::Included(gte),
+    }
+}
+
+/// Tightest upper bound implied by `lte` and `lt`
+fn upper_bound(lte: Option<f64>, lt: Option<f64>) -> Bound<f64> {
+    match (lte.filter(|v| !v.is_nan()), lt.filter(|v| !v.is_nan())) {
+        (None, None) => Bound::Unbounded,
+        (Some(lte), None) => Bound::Included(lte),
+        (None, Some(lt)) => Bound::Excluded(lt),
+        (Some(lte), Some(lt)) if lt <= lte => Bound::Excluded(lt),
+        (Some(lte), Some(_)) => Bound::Included(lte),
+    }
+}
+
+/// Smallest integers satisfying a float lower bound
+fn integer_lower(bound: Bound<f64>) -> Bound<i64> {
+    match bound {
+        Bound::Included(v) if v.abs() < MAX_EXACT_INTEGER => Bound::Included(v.ceil() as i64),
+        Bound::Excluded(v) if v.abs() < MAX_EXACT_INTEGER => Bound::Included(v.floor() as i64 + 1),
+        _ => Bound::Unbounded,
+    }
+}
+
+/// Largest integers satisfying a float upper bound
+fn integer_upper(bound: Bound<f64>) -> Bound<i64> {
+    match bound {
+        Bound::Included(v) if v.abs() < MAX_EXACT_INTEGER => Bound::Included(v.floor() as i64),
+        Bound::Excluded(v) if v.abs() < MAX_EXACT_INTEGER => Bound::Included(v.ceil() as i64 - 1),
+        _ => Bound::Unbounded,
+    }
+}
+
+/// Grid coordinate of `value` along one axis at [`GEO_BITS`] precision
+///
+/// Monotone in `value`, so a point inside a box always lands between the
+/// box corners' coordinates.
+fn grid_coord(value: f64, min: f64, max: f64) -> u64 {
+    let cells = 1u64 << (GEO_BITS / 2);
+    let coord = ((value - min) / (max - min) * cells as f64).floor();
+    (coord.max(0.0) as u64).min(cells - 1)
+}
+
+/// Interleave grid coordinates into a geohash, longitude bit first
+fn interleave(lon: u64, lat: u64, bits_per_axis: u32) -> u64 {
+    let mut hash = 0;
+    for bit in (0..bits_per_axis).rev() {
+        hash = (hash << 1) | ((lon >> bit) & 1);
+        hash = (hash << 1) | ((lat >> bit) & 1);
+    }
+    hash
+}
+
+fn geohash(lat: f64, lon: f64) -> u64 {
+    interleave(
+        grid_coord(lon, -180.0, 180.0),
+        grid_coord(lat, -90.0, 90.0),
+        GEO_BITS / 2,
+    )
+}
+
+/// Collect vectors in every geohash cell overlapping a lat/lon box
+///
+/// Uses the finest grid level that covers the box with at most
+/// [`MAX_GEO_CELLS`] cells; each cell is a contiguous run of full-precision keys.
+fn scan_geo_box(
+    cells: &Postings<u64>,
+    lat_min: f64,
+    lat_max: f64,
+    lon_min: f64,
+    lon_max: f64,
+    out: &mut HashSet<VectorId>,
+) {
+    let (x_min, x_max) = (grid_coord(lon_min, -180.0, 180.0), grid_coord(lon_max, -180.0, 180.0));
+    let (y_min, y_max) = (grid_coord(lat_min, -90.0, 90.0), grid_coord(lat_max, -90.0, 90.0));
+
+    let mut shift = 0;
+    while shift < GEO_BITS / 2
+        && ((x_max >> shift) - (x_min >> shift) + 1) * ((y_max >> shift) - (y_min >> shift) + 1)
+            > MAX_GEO_CELLS
+    {
+        shift += 1;
+    }
+
+    let key_shift = 2 * shift;
+    for x in (x_min >> shift)..=(x_max >> shift) {
+        for y in (y_min >> shift)..=(y_max >> shift) {
+            let prefix = interleave(x, y, GEO_BITS / 2 - shift);
+            let start = prefix << key_shift;
+            let end = (prefix + 1) << key_shift;
+            cells.range(Bound::Included(start), Bound::Excluded(end), out);
+        }
+    }
+}
+
+/// Results of the full-text conditions in a filter, as decided by text indexes
+///
+/// Pass [`TextMatches::get`] to `filter_matches_with` so the evaluator uses
+/// token matching for indexed text fields instead of a substring check.
+#[derive(Debug, Default)]
+pub struct TextMatches {
+    matches: HashMap<(String, String), HashSet<VectorId>>,
+}
+
+impl TextMatches {
+    /// Whether `id` satisfies `condition`, or `None` if its field has no text index
+    pub fn get(&self, id: &VectorId, condition: &MatchText) -> Option<bool> {
+        self.matches
+            .get(&(condition.key.clone(), condition.text.clone()))
+            .map(|ids| ids.contains(id))
+    }
+}
+
+fn intersect(a: HashSet<VectorId>, b: HashSet<VectorId>) -> HashSet<VectorId> {
+    let (small, large) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    small.into_iter().filter(|id| large.contains(id)).collect()
+}
+
+/// Payload field indexes for one collection
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PayloadIndex {
+    fields: BTreeMap<String, FieldIndex>,
+}
+
+impl PayloadIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declare an index on `field`, filling it from `payloads`
+    ///
+    /// Replaces any existing index on the field.
+    pub fn create_field<'a>(
+        &mut self,
+        field: &str,
+        field_type: PayloadFieldType,
+        payloads: impl IntoIterator<Item = (VectorId, Option<&'a Payload>)>,
+    ) {
+        self.add_field(field, FieldIndex::new(field_type), payloads);
+    }
+
+    /// Declare a full-text index on `field` with the given tokenizer settings
+    ///
+    /// Settings should be checked with [`Tokenizer::validate`] first.
+    pub fn create_text_field<'a>(
+        &mut self,
+        field: &str,
+        params: TextIndexParams,
+        payloads: impl IntoIterator<Item = (VectorId, Option<&'a Payload>)>,
+    ) {
+        self.add_field(field, FieldIndex::text(params), payloads);
+    }
+
+    fn add_field<'a>(
+        &mut self,
+        field: &str,
+        mut index: FieldIndex,
+        payloads: impl IntoIterator<Item = (VectorId, Option<&'a Payload>)>,
+    ) {
+        for (id, payload) in payloads {
+            if let Some(value) = payload.and_then(|p| p.get(field)) {
+                index.insert(id, value);
+            }
+        }
+        self.fields.insert(field.to_string(), index);
+    }
+
+    /// Remove the index on `field`, returning whether there was one
+    pub fn drop_field(&mut self, field: &str) -> bool {
+        self.fields.remove(field).is_some()
+    }
+
+    /// The same field declarations with no vectors indexed
+    pub fn cleared(&self) -> Self {
+        Self {
+            fields: self
+                .fields
+                .iter()
+                .map(|(name, index)| (name.clone(), index.cleared()))
+                .collect(),
+        }
+    }
+
+    pub fn info(&self) -> Vec<PayloadIndexInfo> {
+        self.fields
+            .iter()
+            .map(|(name, index)| PayloadIndexInfo {
+                field_name: name.clone(),
+                field_type: index.field_type(),
+                indexed_vectors: index.len(),
+                text_params: index.text_params().cloned(),
+            })
+            .collect()
+    }
+
+    /// Whether no fields are indexed
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// Index (or re-index) a vector's payload
+    pub fn insert(&mut self, id: VectorId, payload: Option<&Payload>) {
+        for (field, index) in self.fields.iter_mut() {
+            index.remove(&id);
+            if let Some(value) = payload.and_then(|p| p.get(field)) {
+                index.insert(id, value);
+            }
+        }
+    }
+
+    /// Drop a vector from every field
+    pub fn remove(&mut self, id: &VectorId) {
+        for index in self.fields.values_mut() {
+            index.remove(id);
+        }
+    }
+
+    /// Value counts for `field`, optionally restricted to `among`
+    ///
+    /// Only keyword, integer and bool indexes can answer this; `None` means
+    /// the field has no such index.
+    pub fn facet(&self, field: &str, among: Option<&HashSet<VectorId>>) -> Option<Vec<(Value, usize)>> {
+        fn counts<K: Ord>(
+            postings: &Postings<K>,
+            among: Option<&HashSet<VectorId>>,
+            value: impl Fn(&K) -> Value,
+        ) -> Vec<(Value, usize)> {
+            postings
+                .map
+                .iter()
+                .map(|(key, ids)| {
+                    let count = match among {
+                        Some(among) => ids.iter().filter(|id| among.contains(id)).count(),
+                        None => ids.len(),
+                    };
+                    (value(key), count)
+                })
+                .filter(|(_, count)| *count > 0)
+                .collect()
+        }
+
+        match self.fields.get(field)? {
+            FieldIndex::Keyword(postings) => Some(counts(postings, among, |s| Value::from(s.as_str()))),
+            FieldIndex::Integer { values, .. } => Some(counts(values, among, |i| Value::from(*i))),
+            FieldIndex::Bool(postings) => Some(counts(postings, among, |b| Value::from(*b))),
+            _ => None,
+        }
+    }
+
+    /// Up to `limit` vectors filed under `field`, ordered by value and then by
+    /// ID, strictly after the `(value, id)` position `after`
+    ///
+    /// A vector with an array value is listed once, by its smallest element
+    /// ascending and its largest descending. Only keyword, integer, float and
+    /// bool indexes are ordered; `None` means the field has no such index or
+    /// `after` holds a value of another type.
+    pub fn ordered(
+        &self,
+        field: &str,
+        direction: Direction,
+        after: Option<(&Value, &VectorId)>,
+        limit: usize,
+    ) -> Option<Vec<(Value, VectorId)>> {
+        fn page<K: Ord + Clone>(
+            postings: &Postings<K>,
+            direction: Direction,
+            after: Option<(&Value, &VectorId)>,
+            limit: usize,
+            key: impl Fn(&Value) -> Option<K>,
+            value: impl Fn(&K) -> Value,
+        ) -> Option<Vec<(Value, VectorId)>> {
+            let after = match after {
+                Some((after_value, id)) => Some((key(after_value)?, *id)),
+                None => None,
+            };
+            let page = postings.ordered(direction, after.as_ref().map(|(k, id)| (k, id)), limit);
+            Some(page.into_iter().map(|(k, id)| (value(&k), id)).collect())
+        }
+
+        match self.fields.get(field)? {
+            FieldIndex::Keyword(postings) => page(
+                postings,
+                direction,
+                after,
+                limit,
+                |v| v.as_str().map(str::to_string),
+                |s| Value::from(s.as_str()),
+            ),
+            FieldIndex::Integer { values, .. } => {
+                page(values, direction, after, limit, Value::as_i64, |i| Value::from(*i))
+            }
+            FieldIndex::Float(postings) => page(
+                postings,
+                direction,
+                after,
+                limit,
+                |v| v.as_f64().filter(|f| !f.is_nan()).map(float_key),
+                |k| Value::from(key_float(*k)),
+            ),
+            FieldIndex::Bool(postings) => {
+                page(postings, direction, after, limit, Value::as_bool, |b| Value::from(*b))
+            }
+            _ => None,
+        }
+    }
+
+    /// Vectors that may match `filter`, or `None` if the indexes cannot narrow it
+    ///
+    /// The result is a superset of the matches; callers still evaluate the
+    /// filter on each candidate, with [`Self::text_matches`] deciding
+    /// full-text conditions.
+    pub fn candidates(&self, filter: &Filter) -> Option<HashSet<VectorId>> {
+        match filter {
+            Filter::Must(conditions) => conditions
+                .iter()
+                .filter_map(|c| self.condition_candidates(c))
+                .reduce(intersect),
+            Filter::Should(conditions) => self.at_least_candidates(conditions, 1),
+            Filter::MustNot(_) => None,
+            Filter::MinShould { conditions, min_count } => {
+                self.at_least_candidates(conditions, *min_count)
+            }
+        }
+    }
+
+    /// Candidates for "at least `min_count` of `conditions` hold"
+    ///
+    /// A match satisfies more conditions than there are unindexable ones, so
+    /// it must satisfy an indexable one: the union of those is a superset.
+    fn at_least_candidates(&self, conditions: &[Condition], min_count: usize) -> Option<HashSet<VectorId>> {
+        let mut unindexed = 0;
+        let mut out = HashSet::new();
+        for condition in conditions {
+            match self.condition_candidates(condition) {
+                Some(ids) => out.extend(ids),
+                None => unindexed += 1,
+            }
+        }
+        (unindexed < min_count).then_some(out)
+    }
+
+    /// Resolve every full-text condition in `filter` that has a text index
+    pub fn text_matches(&self, filter: &Filter) -> TextMatches {
+        let mut matches = TextMatches::default();
+        self.collect_text_matches(filter, &mut matches);
+        matches
+    }
+
+    fn collect_text_matches(&self, filter: &Filter, out: &mut TextMatches) {
+        let conditions = match filter {
+            Filter::Must(conditions)
+            | Filter::Should(conditions)
+            | Filter::MustNot(conditions)
+            | Filter::MinShould { conditions, .. } => conditions,
+        };
+        for condition in conditions {
+            match condition {
+                Condition::Filter(filter) => self.collect_text_matches(filter, out),
+                Condition::Match(FieldCondition::MatchText(m)) => {
+                    let key = (m.key.clone(), m.text.clone());
+                    if out.matches.contains_key(&key) {
+                        continue;
+                    }
+                    if let Some(ids) = self.fields.get(&m.key).and_then(|f| f.match_text(&m.text)) {
+                        out.matches.insert(key, ids);
+                    }
+                }
+                Condition::Match(_) => {}
+            }
+        }
+    }
+
+    fn condition_candidates(&self, condition: &Condition) -> Option<HashSet<VectorId>> {
+        match condition {
+            Condition::Filter(filter) => self.candidates(filter),
+            Condition::Match(field_condition) => self.field_candidates(field_condition),
+        }
+    }
+
+    fn field_candidates(&self, condition: &FieldCondition) -> Option<HashSet<VectorId>> {
+        match condition {
+            FieldCondition::MatchKeyword(m) => {
+                let mut out = HashSet::new();
+                self.fields.get(&m.key)?.match_value(&m.value, &mut out).then_some(out)
+            }
+            FieldCondition::MatchAny(m) => {
+                let index = self.fields.get(&m.key)?;
+                let mut out = HashSet::new();
+                for value in &m.any {
+                    if !index.match_value(value, &mut out) {
+                        return None;
+                    }
+                }
+                Some(out)
+            }
+            FieldCondition::Range(range) => self.fields.get(&range.key)?.range(range),
+            FieldCondition::GeoRadius(geo) => self.fields.get(&geo.key)?.geo_radius(geo),
+            FieldCondition::GeoBoundingBox(geo) => self.fields.get(&geo.key)?.geo_bounding_box(geo),
+            FieldCondition::MatchText(m) => self.fields.get(&m.key)?.match_text(&m.text),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use vectordb_common::filter::{filter_matches, filter_matches_with, GeoPoint, MatchAny, MatchKeyword};
+
+    fn payload(value: Value) -> Payload {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn build(fields: &[(&str, PayloadFieldType)], points: &[(VectorId, Payload)]) -> PayloadIndex {
+        let mut index = PayloadIndex::new();
+        for (field, field_type) in fields {
+            index.create_field(field, *field_type, points.iter().map(|(id, p)| (*id, Some(p))));
+        }
+        index
+    }
+
+    /// Candidates must contain every point the evaluator accepts
+    fn assert_superset(index: &PayloadIndex, filter: &Filter, points: &[(VectorId, Payload)]) -> HashSet<VectorId> {
+        let candidates = index.candidates(filter).expect("filter should be indexable");
+        for (id, p) in points {
+            if filter_matches(filter, Some(p)) {
+                assert!(candidates.contains(id), "missing match {:?} for {:?}", p, filter);
+            }
+        }
+        candidates
+    }
+
+    fn keyword(key: &str, value: MatchValue) -> Condition {
+        Condition::Match(FieldCondition::MatchKeyword(MatchKeyword { key: key.to_string(), value }))
+    }
+
+    fn range(key: &str, gte: Option<f64>, gt: Option<f64>, lte: Option<f64>, lt: Option<f64>) -> Condition {
+        Condition::Match(FieldCondition::Range(RangeCondition { key: key.to_string(), gte, gt, lte, lt }))
+    }
+
+    #[test]
+    fn test_keyword_and_bool_match() {
+        let points: Vec<_> = (0..20)
+            .map(|i| {
+                let color = ["red", "green", "blue"][i % 3];
+                (VectorId::new_v4(), payload(json!({"color": color, "in_stock": i % 2 == 0})))
+            })
+            .collect();
+        let index = build(
+            &[("color", PayloadFieldType::Keyword), ("in_stock", PayloadFieldType::Bool)],
+            &points,
+        );
+
+        let filter = Filter::Must(vec![
+            keyword("color", MatchValue::Keyword("red".to_string())),
+            keyword("in_stock", MatchValue::Bool(true)),
+        ]);
+        let candidates = assert_superset(&index, &filter, &points);
+        assert_eq!(candidates.len(), points.iter().filter(|(_, p)| filter_matches(&filter, Some(p))).count());
+
+        let any = Filter::Must(vec![Condition::Match(FieldCondition::MatchAny(MatchAny {
+            key: "color".to_string(),
+            any: vec![MatchValue::Keyword("red".to_string()), MatchValue::Keyword("blue".to_string())],
+        }))]);
+        assert_eq!(assert_superset(&index, &any, &points).len(), 13);
+
+        // A keyword index cannot answer an integer match
+        let wrong_type = Filter::Must(vec![keyword("color", MatchValue::Integer(1))]);
+        assert!(index.candidates(&wrong_type).is_none());
+    }
+
+    #[test]
+    fn test_numeric_ranges() {
+        let values = [json!(-3), json!(0), json!(1), json!(2), json!(2.5), json!(7), json!(-0.0), json!(u64::MAX)];
+        let points: Vec<_> = values
+            .iter()
+            .map(|v| (VectorId::new_v4(), payload(json!({"n": v}))))
+            .collect();
+
+        for field_type in [PayloadFieldType::Integer, PayloadFieldType::Float] {
+            let index = build(&[("n", field_type)], &points);
+            let bounds = [
+                (Some(0.0), None, Some(2.0), None),
+                (None, Some(0.0), None, Some(2.0)),
+                (Some(1.5), Some(1.0), None, None),
+                (None, None, Some(-0.0), None),
+                (Some(2.5), None, Some(2.5), None),
+                (None, Some(2.0), None, Some(2.0)),
+                (Some(1e19), None, None, None),
+            ];
+            for (gte, gt, lte, lt) in bounds {
+                let filter = Filter::Must(vec![range("n", gte, gt, lte, lt)]);
+                assert_superset(&index, &filter, &points);
+            }
+        }
+
+        let index = build(&[("n", PayloadFieldType::Float)], &points);
+        let filter = Filter::Must(vec![range("n", Some(0.0), None, Some(2.0), None)]);
+        assert_eq!(index.candidates(&filter).unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_geo_conditions() {
+        let mut points = Vec::new();
+        for lat in (-89..=89).step_by(7) {
+            for lon in (-179..=179).step_by(11) {
+                let (lat, lon) = (lat as f64 + 0.37, lon as f64 + 0.61);
+                points.push((VectorId::new_v4(), payload(json!({"loc": {"lat": lat, "lon": lon}}))));
+            }
+        }
+        let index = build(&[("loc", PayloadFieldType::Geo)], &points);
+
+        let radius = |lat: f64, lon: f64, radius_meters: f64| {
+            Filter::Must(vec![Condition::Match(FieldCondition::GeoRadius(GeoRadius {
+                key: "loc".to_string(),
+                latitude: lat,
+                longitude: lon,
+                radius_meters,
+            }))])
+        };
+        for filter in [
+            radius(40.0, -74.0, 900_000.0),
+            radius(0.0, 179.5, 1_500_000.0),
+            radius(85.0, 10.0, 800_000.0),
+            radius(-20.0, 30.0, 0.0),
+        ] {
+            let candidates = assert_superset(&index, &filter, &points);
+            assert!(candidates.len() < points.len());
+        }
+
+        let bbox = Filter::Must(vec![Condition::Match(FieldCondition::GeoBoundingBox(GeoBoundingBox {
+            key: "loc".to_string(),
+            top_left: GeoPoint { lat: 50.0, lon: -10.0 },
+            bottom_right: GeoPoint { lat: 30.0, lon: 40.0 },
+        }))]);
+        let candidates = assert_superset(&index, &bbox, &points);
+        assert!(candidates.len() < points.len() / 4);
+    }
+
+    #[test]
+    fn test_boolean_composition() {
+        let points: Vec<_> = (0..30)
+            .map(|i| (VectorId::new_v4(), payload(json!({"kind": format!("k{}", i % 5), "n": i, "text": "x"}))))
+            .collect();
+        let index = build(
+            &[("kind", PayloadFieldType::Keyword), ("n", PayloadFieldType::Integer)],
+            &points,
+        );
+
+        let kind = |k: &str| keyword("kind", MatchValue::Keyword(k.to_string()));
+        let unindexed = keyword("text", MatchValue::Keyword("x".to_string()));
+
+        // Unindexable conditions inside Must are skipped, not fatal
+        let must = Filter::Must(vec![kind("k1"), unindexed.clone(), range("n", Some(10.0), None, None, None)]);
+        assert_eq!(assert_superset(&index, &must, &points).len(), 4);
+
+        let should = Filter::Should(vec![kind("k1"), Condition::Filter(Box::new(Filter::Must(vec![kind("k2")])))]);
+        assert_eq!(assert_superset(&index, &should, &points).len(), 12);
+
+        assert!(index.candidates(&Filter::Should(vec![kind("k1"), unindexed.clone()])).is_none());
+        let min_should = Filter::MinShould { conditions: vec![kind("k1"), kind("k2"), unindexed], min_count: 2 };
+        assert_superset(&index, &min_should, &points);
+        assert!(index.candidates(&Filter::MustNot(vec![kind("k1")])).is_none());
+    }
+
+    #[test]
+    fn test_text_field_matches_tokens() {
+        let docs = [
+            "The quick brown fox",
+            "Quick thinking, brown-nosing",
+            "a slow green turtle",
+            "",
+        ];
+        let points: Vec<_> = docs
+            .iter()
+            .map(|d| (VectorId::new_v4(), payload(json!({"body": d}))))
+            .collect();
+        let mut index = PayloadIndex::new();
+        index.create_text_field(
+            "body",
+            TextIndexParams {
+                stop_words_language: Some(vectordb_common::types::StopWordsLanguage::English),
+                ..Default::default()
+            },
+            points.iter().map(|(id, p)| (*id, Some(p))),
+        );
+
+        let text = |t: &str| {
+            Filter::Must(vec![Condition::Match(FieldCondition::MatchText(MatchText {
+                key: "body".to_string(),
+                text: t.to_string(),
+            }))])
+        };
+
+        // Word order and punctuation do not matter, stop words are ignored
+        let filter = text("brown, the QUICK");
+        let candidates = index.candidates(&filter).unwrap();
+        assert_eq!(candidates, [points[0].0, points[1].0].into_iter().collect());
+
+        // The substring fallback would reject this; the text index decides instead
+        let matches = index.text_matches(&filter);
+        let (id, p) = &points[1];
+        assert!(!filter_matches(&filter, Some(p)));
+        assert!(filter_matches_with(&filter, Some(p), &|m| matches.get(id, m)));
+        assert_eq!(matches.get(&points[2].0, &MatchText { key: "body".to_string(), text: "brown, the QUICK".to_string() }), Some(false));
+
+        assert!(index.candidates(&text("fox turtle")).unwrap().is_empty());
+        assert_eq!(index.candidates(&text("the")).unwrap().len(), 4);
+
+        let info = &index.info()[0];
+        assert_eq!(info.field_type, PayloadFieldType::Text);
+        assert!(info.text_params.as_ref().unwrap().stop_words_language.is_some());
+        let cleared = index.cleared();
+        assert_eq!(cleared.info()[0].text_params, info.text_params);
+        assert_eq!(cleared.info()[0].indexed_vectors, 0);
+    }
+
+    #[test]
+    fn test_updates_and_serialization() {
+        let id = VectorId::new_v4();
+        let mut index = PayloadIndex::new();
+        index.create_field("kind", PayloadFieldType::Keyword, std::iter::empty());
+
+        let filter = |k: &str| Filter::Must(vec![keyword("kind", MatchValue::Keyword(k.to_string()))]);
+
+        index.insert(id, Some(&payload(json!({"kind": "a"}))));
+        index.insert(id, Some(&payload(json!({"kind": "b"}))));
+        assert!(index.candidates(&filter("a")).unwrap().is_empty());
+        assert!(index.candidates(&filter("b")).unwrap().contains(&id));
+
+        let restored: PayloadIndex = bincode::deserialize(&bincode::serialize(&index).unwrap()).unwrap();
+        assert!(restored.candidates(&filter("b")).unwrap().contains(&id));
+        assert_eq!(restored.info()[0].indexed_vectors, 1);
+
+        index.remove(&id);
+        assert!(index.candidates(&filter("b")).unwrap().is_empty());
+        assert_eq!(index.info()[0].indexed_vectors, 0);
+        assert!(index.drop_field("kind"));
+        assert!(index.is_empty());
+    }
+
+    #[test]
+    fn test_facet_counts() {
+        let points: Vec<_> = (0..10)
+            .map(|i| {
+                let color = ["red", "blue"][i % 2];
+                let sizes = json!([i % 2, 7, 7]);
+                (VectorId::new_v4(), payload(json!({"color": color, "size": i % 3, "sizes": sizes, "score": 0.5})))
+            })
+            .collect();
+        let mut index = build(
+            &[
+                ("color", PayloadFieldType::Keyword),
+                ("size", PayloadFieldType::Integer),
+                ("sizes", PayloadFieldType::Integer),
+                ("score", PayloadFieldType::Float),
+            ],
+            &points,
+        );
+
+        assert_eq!(index.facet("color", None).unwrap(), vec![(json!("blue"), 5), (json!("red"), 5)]);
+        assert_eq!(index.facet("size", None).unwrap(), vec![(json!(0), 4), (json!(1), 3), (json!(2), 3)]);
+
+        let among: HashSet<_> = points[..3].iter().map(|(id, _)| *id).collect();
+        assert_eq!(index.facet("size", Some(&among)).unwrap(), vec![(json!(0), 1), (json!(1), 1), (json!(2), 1)]);
+
+        // Array elements count once per point, and are dropped with it
+        assert_eq!(index.facet("sizes", None).unwrap(), vec![(json!(0), 5), (json!(1), 5), (json!(7), 10)]);
+        index.insert(points[0].0, Some(&payload(json!({"sizes": 1}))));
+        assert_eq!(index.facet("sizes", None).unwrap(), vec![(json!(0), 4), (json!(1), 6), (json!(7), 9)]);
+
+        assert!(index.facet("score", None).is_none());
+        assert!(index.facet("missing", None).is_none());
+    }
+
+    #[test]
+    fn test_ordered_pages() {
+        let points: Vec<_> = [3.5, -1.0, 3.5, 0.0, 7.25, 3.5]
+            .iter()
+            .map(|score| (VectorId::new_v4(), payload(json!({"score": score, "rank": 1}))))
+            .chain([(VectorId::new_v4(), payload(json!({"rank": 2})))])
+            .collect();
+        let index = build(&[("score", PayloadFieldType::Float), ("rank", PayloadFieldType::Text)], &points);
+
+        // Paging two at a time visits every valued point once, in value then ID order
+        for direction in [Direction::Asc, Direction::Desc] {
+            let mut seen = Vec::new();
+            let mut after: Option<(Value, VectorId)> = None;
+            loop {
+                let page = index
+                    .ordered("score", direction, after.as_ref().map(|(v, id)| (v, id)), 2)
+                    .unwrap();
+                if page.is_empty() {
+                    break;
+                }
+                after = page.last().cloned();
+                seen.extend(page);
+            }
+
+            let mut expected: Vec<(f64, VectorId)> = points[..6]
+                .iter()
+                .map(|(id, p)| (p["score"].as_f64().unwrap(), *id))
+                .collect();
+            expected.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+            if direction == Direction::Desc {
+                expected.reverse();
+            }
+            let seen: Vec<(f64, VectorId)> = seen.iter().map(|(v, id)| (v.as_f64().unwrap(), *id)).collect();
+            assert_eq!(seen, expected);
+        }
+
+        let first = index.ordered("score", Direction::Asc, None, 1).unwrap();
+        assert_eq!(first[0].0, json!(-1.0));
+        assert!(index.ordered("rank", Direction::Asc, None, 1).is_none());
+        assert!(index.ordered("score", Direction::Asc, Some((&json!("x"), &first[0].1)), 1).is_none());
+    }
+}
@@ -0,0 +1,405 @@
+//! Product quantization index
+//!
+//! A flat index holding each vector as one product quantization code per
+//! segment. Queries build a distance table once and score every code with a
+//! lookup per segment. Until its quantizer is trained the index holds vectors
+//! at full precision and scans them exactly instead.
+
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use vectordb_common::distance::{cosine_similarity, euclidean_distance, normalize};
+use vectordb_common::quantization::{ProductQuantizationConfig, ProductQuantizer, TrainingStatus};
+use vectordb_common::types::*;
+use vectordb_common::{Result, VectorDbError};
+use crate::{SearchFilter, SearchResult};
+
+/// Serialized form of a `PqIndex`
+#[derive(Serialize, Deserialize)]
+struct PqDump {
+    distance_metric: DistanceMetric,
+    dimension: usize,
+    quantizer: Option<ProductQuantizer>,
+    sample_size: usize,
+    vectors: Vec<(VectorId, Vec<f32>)>,
+    codes: Vec<(VectorId, Vec<u8>)>,
+    /// Metadata map as JSON, since bincode cannot round-trip `serde_json::Value`
+    metadata_json: String,
+}
+
+pub struct PqIndex {
+    distance_metric: DistanceMetric,
+    dimension: usize,
+    training_threshold: usize,
+    quantizer: Option<ProductQuantizer>,
+    /// Vectors the quantizer was trained on
+    sample_size: usize,
+    /// Full-precision vectors, only held while the quantizer is untrained
+    vectors: HashMap<VectorId, Vec<f32>>,
+    /// Codes of every slot back to back, one byte per segment
+    codes: Vec<u8>,
+    /// Vector held in each slot; `None` for a slot freed by a delete
+    slots: Vec<Option<VectorId>>,
+    id_to_slot: HashMap<VectorId, usize>,
+    free_slots: Vec<usize>,
+    metadata: HashMap<VectorId, HashMap<String, serde_json::Value>>,
+}
+
+impl PqIndex {
+    /// Untrained index, holding vectors at full precision
+    pub fn new(distance_metric: DistanceMetric, dimension: usize, config: &ProductQuantizationConfig) -> Self {
+        Self {
+            distance_metric,
+            dimension,
+            training_threshold: config.training_threshold,
+            quantizer: None,
+            sample_size: 0,
+            vectors: HashMap::new(),
+            codes: Vec::new(),
+            slots: Vec::new(),
+            id_to_slot: HashMap::new(),
+            free_slots: Vec::new(),
+            metadata: HashMap::new(),
+        }
+    }
+
+    /// Index encoding every vector with `quantizer`, trained on `sample_size` vectors
+    pub fn trained(
+        distance_metric: DistanceMetric,
+        dimension: usize,
+        config: &ProductQuantizationConfig,
+        quantizer: ProductQuantizer,
+        sample_size: usize,
+    ) -> Self {
+        Self {
+            quantizer: Some(quantizer),
+            sample_size,
+            ..Self::new(distance_metric, dimension, config)
+        }
+    }
+
+    /// Train a quantizer for this kind of index on `sample`
+    ///
+    /// Vectors are normalized first for every metric but Euclidean, so those
+    /// are searched by cosine distance, as in the HNSW index.
+    pub fn train_quantizer(
+        distance_metric: DistanceMetric,
+        dimension: usize,
+        config: &ProductQuantizationConfig,
+        sample: &[Vec<f32>],
+    ) -> Result<ProductQuantizer> {
+        let mut quantizer = ProductQuantizer::new(dimension, config.num_segments, config.num_centroids)?;
+        let prepared: Vec<Vec<f32>> = sample.iter().map(|v| prepare(distance_metric, v)).collect();
+        quantizer.train(&prepared)?;
+        Ok(quantizer)
+    }
+
+    fn check_dimension(&self, vector: &[f32]) -> Result<()> {
+        if vector.len() != self.dimension {
+            return Err(VectorDbError::InvalidDimension {
+                expected: self.dimension,
+                actual: vector.len(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Hold `code` under `id`, replacing whatever was held for it
+    fn store_code(&mut self, id: VectorId, code: Vec<u8>) {
+        let slot = match self.id_to_slot.get(&id) {
+            Some(&slot) => slot,
+            None => {
+                let slot = self.free_slots.pop().unwrap_or_else(|| {
+                    self.slots.push(None);
+                    self.codes.resize(self.codes.len() + code.len(), 0);
+                    self.slots.len() - 1
+                });
+                self.slots[slot] = Some(id);
+                self.id_to_slot.insert(id, slot);
+                slot
+            }
+        };
+        self.codes[slot * code.len()..(slot + 1) * code.len()].copy_from_slice(&code);
+    }
+
+    /// Nearest `limit` vectors accepted by `accept`, closest first
+    fn scan(&self, query: &[f32], limit: usize, accept: &dyn Fn(&VectorId) -> bool) -> Vec<(VectorId, f32)> {
+        let mut hits: Vec<(VectorId, f32)> = match &self.quantizer {
+            Some(quantizer) => {
+                let table = quantizer.distance_table(&prepare(self.distance_metric, query));
+                let segments = quantizer.num_segments();
+                self.slots
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(slot, id)| Some(((*id)?, slot)))
+                    .filter(|(id, _)| accept(id))
+                    .map(|(id, slot)| {
+                        let code = &self.codes[slot * segments..(slot + 1) * segments];
+                        let squared = ProductQuantizer::asymmetric_distance(&table, code);
+                        (id, self.decode_distance(squared))
+                    })
+                    .collect()
+            }
+            None => self
+                .vectors
+                .iter()
+                .filter(|(id, _)| accept(id))
+                .map(|(id, vector)| (*id, self.exact_distance(query, vector)))
+                .collect(),
+        };
+
+        if hits.len() > limit {
+            hits.select_nth_unstable_by(limit, |a, b| a.1.total_cmp(&b.1));
+            hits.truncate(limit);
+        }
+        hits.sort_by(|a, b| a.1.total_cmp(&b.1));
+        hits
+    }
+
+    /// Distance in the index's metric from a squared Euclidean distance between prepared vectors
+    fn decode_distance(&self, squared: f32) -> f32 {
+        if self.distance_metric == DistanceMetric::Euclidean {
+            squared.sqrt()
+        } else {
+            // Cosine distance between unit vectors is half their squared distance
+            squared / 2.0
+        }
+    }
+
+    fn exact_distance(&self, a: &[f32], b: &[f32]) -> f32 {
+        if self.distance_metric == DistanceMetric::Euclidean {
+            euclidean_distance(a, b)
+        } else {
+            1.0 - cosine_similarity(a, b)
+        }
+    }
+
+    fn results(&self, hits: Vec<(VectorId, f32)>) -> Vec<SearchResult> {
+        hits.into_iter()
+            .map(|(id, distance)| SearchResult {
+                id,
+                distance,
+                metadata: self.metadata.get(&id).cloned(),
+            })
+            .collect()
+    }
+}
+
+/// Vector as it is trained on and encoded: normalized unless the metric is Euclidean
+fn prepare(distance_metric: DistanceMetric, vector: &[f32]) -> Vec<f32> {
+    let mut prepared = vector.to_vec();
+    if distance_metric != DistanceMetric::Euclidean {
+        normalize(&mut prepared);
+    }
+    prepared
+}
+
+impl super::VectorIndex for PqIndex {
+    fn insert(
+        &mut self,
+        id: VectorId,
+        vector: &[f32],
+        metadata: Option<HashMap<String, serde_json::Value>>,
+    ) -> Result<()> {
+        self.check_dimension(vector)?;
+
+        match &self.quantizer {
+            Some(quantizer) => {
+                let code = quantizer.encode(&prepare(self.distance_metric, vector))?;
+                self.store_code(id, code);
+            }
+            None => {
+                self.vectors.insert(id, vector.to_vec());
+            }
+        }
+
+        match metadata {
+            Some(meta) => self.metadata.insert(id, meta),
+            None => self.metadata.remove(&id),
+        };
+        Ok(())
+    }
+
+    fn search(&self, query: &[f32], limit: usize, _ef: Option<usize>) -> Result<Vec<SearchResult>> {
+        self.check_dimension(query)?;
+        Ok(self.results(self.scan(query, limit, &|_| true)))
+    }
+
+    fn search_filtered(
+        &self,
+        query: &[f32],
+        limit: usize,
+        _ef: Option<usize>,
+        filter: &SearchFilter<'_>,
+    ) -> Result<Vec<SearchResult>> {
+        self.check_dimension(query)?;
+        let hits = self.scan(query, limit, &|id| filter(id, self.metadata.get(id)));
+        Ok(self.results(hits))
+    }
+
+    fn delete(&mut self, id: &VectorId) -> Result<bool> {
+        self.metadata.remove(id);
+        if let Some(slot) = self.id_to_slot.remove(id) {
+            self.slots[slot] = None;
+            self.free_slots.push(slot);
+            return Ok(true);
+        }
+        Ok(self.vectors.remove(id).is_some())
+    }
+
+    fn set_metadata(&mut self, id: &VectorId, metadata: Option<HashMap<String, serde_json::Value>>) -> bool {
+        // Untrained vectors are only held at full precision
+        if !self.id_to_slot.contains_key(id) && !self.vectors.contains_key(id) {
+            return false;
+        }
+        match metadata {
+            Some(meta) => self.metadata.insert(*id, meta),
+            None => self.metadata.remove(id),
+        };
+        true
+    }
+
+    fn stats(&self) -> super::IndexStats {
+        const MAPPING_SIZE: usize = 2 * (std::mem::size_of::<VectorId>() + std::mem::size_of::<usize>());
+
+        let vector_count = self.id_to_slot.len() + self.vectors.len();
+        let memory = MemoryBreakdown {
+            vectors: self.vectors.len() * (self.dimension * std::mem::size_of::<f32>() + MAPPING_SIZE),
+            index: self.slots.len() * std::mem::size_of::<Option<VectorId>>() + self.id_to_slot.len() * MAPPING_SIZE,
+            payload: self.metadata.values().map(crate::payload_size).sum(),
+            quantized: self.codes.len() + self.quantizer.as_ref().map_or(0, ProductQuantizer::memory_size),
+        };
+
+        super::IndexStats {
+            vector_count,
+            memory_usage: memory.total(),
+            memory,
+            dimension: self.dimension,
+            max_layer: 0,
+            avg_connections: 0.0,
+        }
+    }
+
+    fn training_status(&self) -> Option<TrainingStatus> {
+        Some(match self.quantizer {
+            Some(_) => TrainingStatus::Trained { sample_size: self.sample_size },
+            None => TrainingStatus::Untrained {
+                vectors: self.vectors.len(),
+                threshold: self.training_threshold,
+            },
+        })
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>> {
+        let segments = self.quantizer.as_ref().map_or(0, ProductQuantizer::num_segments);
+        let dump = PqDump {
+            distance_metric: self.distance_metric,
+            dimension: self.dimension,
+            quantizer: self.quantizer.clone(),
+            sample_size: self.sample_size,
+            vectors: self.vectors.iter().map(|(id, v)| (*id, v.clone())).collect(),
+            codes: self
+                .id_to_slot
+                .iter()
+                .map(|(id, &slot)| (*id, self.codes[slot * segments..(slot + 1) * segments].to_vec()))
+                .collect(),
+            metadata_json: serde_json::to_string(&self.metadata)?,
+        };
+        bincode::serialize(&dump).map_err(|e| VectorDbError::Serialization(e.to_string()))
+    }
+
+    fn deserialize(&mut self, data: &[u8]) -> Result<()> {
+        let dump: PqDump = bincode::deserialize(data)
+            .map_err(|e| VectorDbError::Serialization(e.to_string()))?;
+
+        if dump.distance_metric != self.distance_metric || dump.dimension != self.dimension {
+            return Err(VectorDbError::IndexError {
+                message: format!(
+                    "PQ dump is for {:?}/{} but index is {:?}/{}",
+                    dump.distance_metric, dump.dimension, self.distance_metric, self.dimension
+                ),
+            });
+        }
+
+        self.quantizer = dump.quantizer;
+        self.sample_size = dump.sample_size;
+        self.vectors = dump.vectors.into_iter().collect();
+        self.slots = dump.codes.iter().map(|(id, _)| Some(*id)).collect();
+        self.id_to_slot = dump.codes.iter().enumerate().map(|(slot, (id, _))| (*id, slot)).collect();
+        self.codes = dump.codes.into_iter().flat_map(|(_, code)| code).collect();
+        self.free_slots.clear();
+        self.metadata = serde_json::from_str(&dump.metadata_json)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::VectorIndex;
+    use uuid::Uuid;
+
+    fn config() -> ProductQuantizationConfig {
+        serde_json::from_value(serde_json::json!({
+            "num_segments": 2,
+            "num_centroids": 16,
+            "training_threshold": 100,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_pq_index_lifecycle() {
+        let vectors: Vec<(VectorId, Vec<f32>)> = (0..200)
+            .map(|i| (Uuid::new_v4(), vec![(i % 20) as f32, (i / 20) as f32, 1.0, 0.0]))
+            .collect();
+
+        let mut index = PqIndex::new(DistanceMetric::Euclidean, 4, &config());
+        for (id, vector) in &vectors[..150] {
+            index.insert(*id, vector, None).unwrap();
+        }
+        assert_eq!(
+            index.training_status(),
+            Some(TrainingStatus::Untrained { vectors: 150, threshold: 100 })
+        );
+        // Exact while untrained
+        let results = index.search(&[3.0, 4.0, 1.0, 0.0], 1, None).unwrap();
+        assert_eq!(results[0].id, vectors[83].0);
+        assert_eq!(results[0].distance, 0.0);
+
+        let sample: Vec<Vec<f32>> = vectors.iter().map(|(_, v)| v.clone()).collect();
+        let quantizer = PqIndex::train_quantizer(DistanceMetric::Euclidean, 4, &config(), &sample).unwrap();
+        let mut index = PqIndex::trained(DistanceMetric::Euclidean, 4, &config(), quantizer, sample.len());
+        index
+            .batch_insert(vectors.iter().map(|(id, v)| (*id, v.clone(), None)).collect())
+            .unwrap();
+        assert_eq!(index.training_status(), Some(TrainingStatus::Trained { sample_size: 200 }));
+
+        let stats = index.stats();
+        assert_eq!(stats.vector_count, 200);
+        assert_eq!(stats.memory.vectors, 0);
+        assert!(stats.memory.quantized >= 200 * 2);
+
+        // Approximate once trained, but the neighbourhood is right
+        let results = index.search(&[3.0, 4.0, 1.0, 0.0], 10, None).unwrap();
+        assert!(results[0].distance < 2.0);
+        assert!(results.iter().any(|r| r.id == vectors[83].0));
+
+        // Deleted slots are reused and never returned
+        assert!(index.delete(&vectors[83].0).unwrap());
+        let replacement = Uuid::new_v4();
+        index.insert(replacement, &[3.0, 4.0, 1.0, 0.0], None).unwrap();
+        let results = index
+            .search_filtered(&[3.0, 4.0, 1.0, 0.0], 200, None, &|id, _| *id != replacement)
+            .unwrap();
+        assert_eq!(results.len(), 199);
+        assert!(results.iter().all(|r| r.id != vectors[83].0));
+
+        let mut restored = PqIndex::new(DistanceMetric::Euclidean, 4, &config());
+        restored.deserialize(&index.serialize().unwrap()).unwrap();
+        assert_eq!(restored.stats().vector_count, 200);
+        assert_eq!(
+            restored.search(&[3.0, 4.0, 1.0, 0.0], 5, None).unwrap().len(),
+            5
+        );
+    }
+}
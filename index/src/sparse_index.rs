@@ -0,0 +1,137 @@
+//! Sparse vector index
+//!
+//! An inverted index over one named sparse vector space: each term (sparse
+//! index) maps to the vectors with a non-zero weight for it, so a query only
+//! touches the postings of its own terms.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use vectordb_common::sparse::{SparseModifier, SparseVector, SparseVectorParams, BM25};
+use vectordb_common::types::VectorId;
+
+/// Inverted index for one sparse vector space
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SparseIndex {
+    modifier: SparseModifier,
+    postings: HashMap<u32, HashMap<VectorId, f32>>,
+    /// Indexed vectors, so a replaced or removed vector can be taken out of its postings
+    vectors: HashMap<VectorId, SparseVector>,
+    /// Corpus statistics, kept up to date for the BM25 modifier
+    bm25: BM25,
+}
+
+impl SparseIndex {
+    pub fn new(params: &SparseVectorParams) -> Self {
+        Self {
+            modifier: params.modifier,
+            postings: HashMap::new(),
+            vectors: HashMap::new(),
+            bm25: BM25::default(),
+        }
+    }
+
+    /// Index `vector` under `id`, replacing whatever was indexed for it before
+    pub fn insert(&mut self, id: VectorId, vector: &SparseVector) {
+        self.remove(&id);
+
+        for (&term, &weight) in vector.indices.iter().zip(vector.values.iter()) {
+            self.postings.entry(term).or_default().insert(id, weight);
+        }
+        self.bm25.add_document(vector);
+        self.vectors.insert(id, vector.clone());
+    }
+
+    pub fn remove(&mut self, id: &VectorId) {
+        let Some(vector) = self.vectors.remove(id) else {
+            return;
+        };
+        for term in &vector.indices {
+            if let Some(ids) = self.postings.get_mut(term) {
+                ids.remove(id);
+                if ids.is_empty() {
+                    self.postings.remove(term);
+                }
+            }
+        }
+        self.bm25.remove_document(&vector);
+    }
+
+    /// Number of indexed vectors
+    pub fn len(&self) -> usize {
+        self.vectors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vectors.is_empty()
+    }
+
+    /// Score every vector sharing a term with `query`, best first
+    pub fn search(&self, query: &SparseVector) -> Vec<(VectorId, f32)> {
+        let mut scores: HashMap<VectorId, f32> = HashMap::new();
+
+        for (&term, &query_weight) in query.indices.iter().zip(query.values.iter()) {
+            let Some(ids) = self.postings.get(&term) else {
+                continue;
+            };
+            for (id, &weight) in ids {
+                let score = match self.modifier {
+                    SparseModifier::None => weight,
+                    SparseModifier::Bm25 => {
+                        let doc_length = self.vectors.get(id).map_or(0, SparseVector::nnz);
+                        self.bm25.term_score(term, weight, doc_length)
+                    }
+                };
+                *scores.entry(*id).or_insert(0.0) += score * query_weight;
+            }
+        }
+
+        let mut results: Vec<(VectorId, f32)> = scores.into_iter().collect();
+        results.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_sparse_index_dot_product() {
+        let mut index = SparseIndex::new(&SparseVectorParams::default());
+        let a = Uuid::new_v4();
+        let b = Uuid::new_v4();
+        index.insert(a, &SparseVector::new(vec![1, 5], vec![1.0, 2.0]));
+        index.insert(b, &SparseVector::new(vec![5, 9], vec![0.5, 4.0]));
+
+        let results = index.search(&SparseVector::new(vec![5], vec![2.0]));
+        assert_eq!(results, vec![(a, 4.0), (b, 1.0)]);
+
+        // Replacing a vector drops its old terms
+        index.insert(a, &SparseVector::new(vec![9], vec![1.0]));
+        let results = index.search(&SparseVector::new(vec![5], vec![2.0]));
+        assert_eq!(results, vec![(b, 1.0)]);
+
+        index.remove(&b);
+        assert!(index.search(&SparseVector::new(vec![5], vec![2.0])).is_empty());
+        assert_eq!(index.len(), 1);
+    }
+
+    #[test]
+    fn test_sparse_index_bm25_prefers_rare_terms() {
+        let mut index = SparseIndex::new(&SparseVectorParams {
+            modifier: SparseModifier::Bm25,
+        });
+        let common_only = Uuid::new_v4();
+        let rare = Uuid::new_v4();
+        index.insert(common_only, &SparseVector::new(vec![1, 2], vec![1.0, 1.0]));
+        index.insert(rare, &SparseVector::new(vec![1, 3], vec![1.0, 1.0]));
+        for _ in 0..5 {
+            index.insert(Uuid::new_v4(), &SparseVector::new(vec![1, 2], vec![1.0, 1.0]));
+        }
+
+        let results = index.search(&SparseVector::new(vec![2, 3], vec![1.0, 1.0]));
+        assert_eq!(results[0].0, rare);
+        assert_eq!(results.len(), 7);
+    }
+}
@@ -0,0 +1,261 @@
+//! Tokenizer for full-text payload indexes
+//!
+//! Documents and queries go through the same pipeline (split, lowercase,
+//! stop words, length limits); they differ only in how partial-word
+//! tokenizers expand words, so that query tokens are always a subset of the
+//! tokens of any document that contains the query words.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use vectordb_common::types::{StopWordsLanguage, TextIndexParams, TokenizerType};
+use vectordb_common::{Result, VectorDbError};
+
+/// N-gram size used when the params leave it open
+const DEFAULT_NGRAM: usize = 3;
+
+const ENGLISH_STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "but", "by", "for", "if", "in", "into", "is",
+    "it", "no", "not", "of", "on", "or", "such", "that", "the", "their", "then", "there",
+    "these", "they", "this", "to", "was", "will", "with",
+];
+
+/// Splits text into index and query tokens according to [`TextIndexParams`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "TextIndexParams", into = "TextIndexParams")]
+pub struct Tokenizer {
+    params: TextIndexParams,
+    stop_words: HashSet<String>,
+}
+
+impl From<TextIndexParams> for Tokenizer {
+    fn from(params: TextIndexParams) -> Self {
+        let builtin = match params.stop_words_language {
+            Some(StopWordsLanguage::English) => ENGLISH_STOP_WORDS,
+            None => &[],
+        };
+        let stop_words = builtin
+            .iter()
+            .map(|w| w.to_string())
+            .chain(params.stop_words.iter().cloned())
+            .map(|w| if params.lowercase { w.to_lowercase() } else { w })
+            .collect();
+
+        Self { params, stop_words }
+    }
+}
+
+impl From<Tokenizer> for TextIndexParams {
+    fn from(tokenizer: Tokenizer) -> Self {
+        tokenizer.params
+    }
+}
+
+impl Tokenizer {
+    /// Reject token length settings that could never produce a token
+    pub fn validate(params: &TextIndexParams) -> Result<()> {
+        let invalid = |message: &str| VectorDbError::InvalidInput {
+            message: message.to_string(),
+        };
+
+        if params.min_token_len == Some(0) || params.max_token_len == Some(0) {
+            return Err(invalid("Token lengths must be at least 1"));
+        }
+        if let (Some(min), Some(max)) = (params.min_token_len, params.max_token_len) {
+            if min > max {
+                return Err(invalid("min_token_len must not exceed max_token_len"));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn params(&self) -> &TextIndexParams {
+        &self.params
+    }
+
+    /// Tokens to index for a document, sorted and deduplicated
+    pub fn index_tokens(&self, text: &str) -> Vec<String> {
+        let mut tokens = Vec::new();
+        for word in self.words(text) {
+            match self.params.tokenizer {
+                TokenizerType::Whitespace | TokenizerType::Word => {
+                    if self.within_length(&word) {
+                        tokens.push(word);
+                    }
+                }
+                TokenizerType::Prefix => {
+                    let chars: Vec<char> = word.chars().collect();
+                    let min = self.params.min_token_len.unwrap_or(1);
+                    let max = self.params.max_token_len.unwrap_or(chars.len()).min(chars.len());
+                    for len in min..=max {
+                        tokens.push(chars[..len].iter().collect());
+                    }
+                }
+                TokenizerType::Ngram => {
+                    let chars: Vec<char> = word.chars().collect();
+                    let (min, max) = self.ngram_sizes();
+                    if chars.len() < min {
+                        // Too short for any n-gram; keep it whole so it can still be found
+                        tokens.push(word);
+                        continue;
+                    }
+                    for n in min..=max.min(chars.len()) {
+                        tokens.extend(chars.windows(n).map(|w| w.iter().collect::<String>()));
+                    }
+                }
+            }
+        }
+        tokens.sort_unstable();
+        tokens.dedup();
+        tokens
+    }
+
+    /// Tokens a document must contain to match `text`, sorted and deduplicated
+    pub fn query_tokens(&self, text: &str) -> Vec<String> {
+        let mut tokens = Vec::new();
+        for word in self.words(text) {
+            match self.params.tokenizer {
+                TokenizerType::Whitespace | TokenizerType::Word => {
+                    if self.within_length(&word) {
+                        tokens.push(word);
+                    }
+                }
+                TokenizerType::Prefix => {
+                    // Query words match as prefixes; past the longest indexed
+                    // prefix only the indexed part can be checked
+                    let len = word.chars().count();
+                    if len >= self.params.min_token_len.unwrap_or(1) {
+                        match self.params.max_token_len {
+                            Some(max) if len > max => tokens.push(word.chars().take(max).collect()),
+                            _ => tokens.push(word),
+                        }
+                    }
+                }
+                TokenizerType::Ngram => {
+                    let chars: Vec<char> = word.chars().collect();
+                    let (_, max) = self.ngram_sizes();
+                    if chars.len() <= max {
+                        tokens.push(word);
+                    } else {
+                        tokens.extend(chars.windows(max).map(|w| w.iter().collect::<String>()));
+                    }
+                }
+            }
+        }
+        tokens.sort_unstable();
+        tokens.dedup();
+        tokens
+    }
+
+    /// Split, lowercase and drop stop words
+    fn words<'a>(&'a self, text: &'a str) -> impl Iterator<Item = String> + 'a {
+        let split: Box<dyn Iterator<Item = &str>> = match self.params.tokenizer {
+            TokenizerType::Whitespace => Box::new(text.split_whitespace()),
+            _ => Box::new(text.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty())),
+        };
+        split
+            .map(|w| if self.params.lowercase { w.to_lowercase() } else { w.to_string() })
+            .filter(|w| !self.stop_words.contains(w))
+    }
+
+    fn within_length(&self, word: &str) -> bool {
+        let len = word.chars().count();
+        self.params.min_token_len.is_none_or(|min| len >= min)
+            && self.params.max_token_len.is_none_or(|max| len <= max)
+    }
+
+    fn ngram_sizes(&self) -> (usize, usize) {
+        let min = self
+            .params
+            .min_token_len
+            .unwrap_or_else(|| DEFAULT_NGRAM.min(self.params.max_token_len.unwrap_or(DEFAULT_NGRAM)));
+        let max = self.params.max_token_len.unwrap_or_else(|| min.max(DEFAULT_NGRAM));
+        (min, max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokenizer(tokenizer: TokenizerType) -> Tokenizer {
+        Tokenizer::from(TextIndexParams {
+            tokenizer,
+            ..Default::default()
+        })
+    }
+
+    fn is_subset(query: &[String], doc: &[String]) -> bool {
+        query.iter().all(|t| doc.contains(t))
+    }
+
+    #[test]
+    fn test_word_and_whitespace() {
+        let word = tokenizer(TokenizerType::Word);
+        assert_eq!(word.index_tokens("Hello, World! hello-world"), vec!["hello", "world"]);
+
+        let whitespace = tokenizer(TokenizerType::Whitespace);
+        assert_eq!(whitespace.index_tokens("Hello, World!"), vec!["hello,", "world!"]);
+
+        let case_sensitive = Tokenizer::from(TextIndexParams {
+            lowercase: false,
+            ..Default::default()
+        });
+        assert_eq!(case_sensitive.index_tokens("Rust rust"), vec!["Rust", "rust"]);
+    }
+
+    #[test]
+    fn test_stop_words_and_lengths() {
+        let tokenizer = Tokenizer::from(TextIndexParams {
+            stop_words_language: Some(StopWordsLanguage::English),
+            stop_words: vec!["Vector".to_string()],
+            min_token_len: Some(2),
+            ..Default::default()
+        });
+        assert_eq!(
+            tokenizer.index_tokens("The vector database is a store of embeddings x"),
+            vec!["database", "embeddings", "store"]
+        );
+    }
+
+    #[test]
+    fn test_prefix_queries_match_partial_words() {
+        let tokenizer = Tokenizer::from(TextIndexParams {
+            tokenizer: TokenizerType::Prefix,
+            max_token_len: Some(5),
+            ..Default::default()
+        });
+        let doc = tokenizer.index_tokens("Quantization of embeddings");
+        assert!(is_subset(&tokenizer.query_tokens("quant emb"), &doc));
+        assert!(is_subset(&tokenizer.query_tokens("quantum"), &doc));
+        assert!(!is_subset(&tokenizer.query_tokens("queue"), &doc));
+    }
+
+    #[test]
+    fn test_ngram_queries_match_infixes() {
+        let tokenizer = tokenizer(TokenizerType::Ngram);
+        let doc = tokenizer.index_tokens("approximate search, ai");
+        assert!(is_subset(&tokenizer.query_tokens("proxim"), &doc));
+        assert!(is_subset(&tokenizer.query_tokens("arc"), &doc));
+        assert!(is_subset(&tokenizer.query_tokens("AI"), &doc));
+        assert!(!is_subset(&tokenizer.query_tokens("proxy"), &doc));
+    }
+
+    #[test]
+    fn test_validate_and_round_trip() {
+        assert!(Tokenizer::validate(&TextIndexParams {
+            min_token_len: Some(4),
+            max_token_len: Some(2),
+            ..Default::default()
+        })
+        .is_err());
+
+        let params = TextIndexParams {
+            stop_words_language: Some(StopWordsLanguage::English),
+            ..Default::default()
+        };
+        let bytes = bincode::serialize(&Tokenizer::from(params.clone())).unwrap();
+        let restored: Tokenizer = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(restored.params(), &params);
+        assert_eq!(restored.index_tokens("the cat"), vec!["cat"]);
+    }
+}
@@ -4,7 +4,9 @@ pub mod vectordb {
 
 pub use vectordb::*;
 
-use vectordb_common::types;
+use std::collections::HashMap;
+use vectordb_common::filter as filters;
+use vectordb_common::{quantization, search_api, sparse, types};
 
 // Conversion functions between protobuf and common types
 impl From<types::DistanceMetric> for DistanceMetric {
@@ -30,6 +32,266 @@ impl From<DistanceMetric> for types::DistanceMetric {
     }
 }
 
+impl From<types::IndexConfig> for IndexConfig {
+    fn from(config: types::IndexConfig) -> Self {
+        IndexConfig {
+            max_connections: config.max_connections as u32,
+            ef_construction: config.ef_construction as u32,
+            ef_search: config.ef_search as u32,
+            max_layer: config.max_layer as u32,
+            index_type: IndexType::from(config.index_type) as i32,
+            ivf: Some(config.ivf.into()),
+        }
+    }
+}
+
+impl From<IndexConfig> for types::IndexConfig {
+    fn from(config: IndexConfig) -> Self {
+        types::IndexConfig {
+            max_connections: config.max_connections as usize,
+            ef_construction: config.ef_construction as usize,
+            ef_search: config.ef_search as usize,
+            max_layer: config.max_layer as usize,
+            index_type: config.index_type().into(),
+            ivf: config.ivf.map(Into::into).unwrap_or_default(),
+        }
+    }
+}
+
+impl From<types::IndexType> for IndexType {
+    fn from(index_type: types::IndexType) -> Self {
+        match index_type {
+            types::IndexType::Hnsw => IndexType::Hnsw,
+            types::IndexType::Flat => IndexType::Flat,
+            types::IndexType::IvfFlat => IndexType::IvfFlat,
+            types::IndexType::IvfPq => IndexType::IvfPq,
+        }
+    }
+}
+
+impl From<IndexType> for types::IndexType {
+    fn from(index_type: IndexType) -> Self {
+        match index_type {
+            IndexType::Hnsw => types::IndexType::Hnsw,
+            IndexType::Flat => types::IndexType::Flat,
+            IndexType::IvfFlat => types::IndexType::IvfFlat,
+            IndexType::IvfPq => types::IndexType::IvfPq,
+        }
+    }
+}
+
+impl From<types::IvfConfig> for IvfConfig {
+    fn from(config: types::IvfConfig) -> Self {
+        IvfConfig {
+            nlist: config.nlist as u32,
+            nprobe: config.nprobe as u32,
+            training_threshold: config.training_threshold as u64,
+            pq_segments: config.pq_segments.unwrap_or(0) as u32,
+            pq_centroids: config.pq_centroids as u32,
+        }
+    }
+}
+
+impl From<IvfConfig> for types::IvfConfig {
+    fn from(config: IvfConfig) -> Self {
+        let defaults = types::IvfConfig::default();
+        let or_default = |value: u32, default: usize| if value == 0 { default } else { value as usize };
+        types::IvfConfig {
+            nlist: or_default(config.nlist, defaults.nlist),
+            nprobe: or_default(config.nprobe, defaults.nprobe),
+            training_threshold: match config.training_threshold {
+                0 => defaults.training_threshold,
+                threshold => threshold as usize,
+            },
+            pq_segments: (config.pq_segments > 0).then_some(config.pq_segments as usize),
+            pq_centroids: or_default(config.pq_centroids, defaults.pq_centroids),
+        }
+    }
+}
+
+impl From<types::VectorParams> for VectorParams {
+    fn from(params: types::VectorParams) -> Self {
+        VectorParams {
+            dimension: params.dimension as u32,
+            distance_metric: DistanceMetric::from(params.distance_metric) as i32,
+            index_config: params.index_config.map(Into::into),
+        }
+    }
+}
+
+impl From<VectorParams> for types::VectorParams {
+    fn from(params: VectorParams) -> Self {
+        types::VectorParams {
+            dimension: params.dimension as usize,
+            distance_metric: params.distance_metric().into(),
+            index_config: params.index_config.map(Into::into),
+        }
+    }
+}
+
+impl From<types::MultiVectorParams> for MultiVectorParams {
+    fn from(params: types::MultiVectorParams) -> Self {
+        MultiVectorParams {
+            dimension: params.dimension as u32,
+            distance_metric: DistanceMetric::from(params.distance_metric) as i32,
+        }
+    }
+}
+
+impl From<MultiVectorParams> for types::MultiVectorParams {
+    fn from(params: MultiVectorParams) -> Self {
+        types::MultiVectorParams {
+            dimension: params.dimension as usize,
+            distance_metric: params.distance_metric().into(),
+        }
+    }
+}
+
+impl From<Vec<Vec<f32>>> for MultiVector {
+    fn from(rows: Vec<Vec<f32>>) -> Self {
+        MultiVector {
+            rows: rows.into_iter().map(|data| DenseVector { data }).collect(),
+        }
+    }
+}
+
+impl From<MultiVector> for Vec<Vec<f32>> {
+    fn from(multivector: MultiVector) -> Self {
+        multivector.rows.into_iter().map(|row| row.data).collect()
+    }
+}
+
+impl From<types::MultiVectorQuery> for MultiVectorQuery {
+    fn from(query: types::MultiVectorQuery) -> Self {
+        let mode = match query.mode {
+            types::MultiVectorMode::Rescore => MultiVectorMode::Rescore,
+            types::MultiVectorMode::BruteForce => MultiVectorMode::BruteForce,
+        };
+        MultiVectorQuery {
+            using: query.using,
+            vectors: query.vectors.into_iter().map(|data| DenseVector { data }).collect(),
+            mode: mode as i32,
+        }
+    }
+}
+
+impl From<MultiVectorQuery> for types::MultiVectorQuery {
+    fn from(query: MultiVectorQuery) -> Self {
+        let mode = match query.mode() {
+            MultiVectorMode::Rescore => types::MultiVectorMode::Rescore,
+            MultiVectorMode::BruteForce => types::MultiVectorMode::BruteForce,
+        };
+        types::MultiVectorQuery {
+            using: query.using,
+            vectors: query.vectors.into_iter().map(|row| row.data).collect(),
+            mode,
+        }
+    }
+}
+
+impl From<types::MemoryBreakdown> for MemoryBreakdown {
+    fn from(memory: types::MemoryBreakdown) -> Self {
+        MemoryBreakdown {
+            vectors: memory.vectors as u64,
+            index: memory.index as u64,
+            payload: memory.payload as u64,
+            quantized: memory.quantized as u64,
+        }
+    }
+}
+
+impl From<MemoryBreakdown> for types::MemoryBreakdown {
+    fn from(memory: MemoryBreakdown) -> Self {
+        types::MemoryBreakdown {
+            vectors: memory.vectors as usize,
+            index: memory.index as usize,
+            payload: memory.payload as usize,
+            quantized: memory.quantized as usize,
+        }
+    }
+}
+
+impl From<quantization::TrainingStatus> for QuantizationStatus {
+    fn from(status: quantization::TrainingStatus) -> Self {
+        match status {
+            quantization::TrainingStatus::Untrained { vectors, threshold } => QuantizationStatus {
+                trained: false,
+                vectors: vectors as u64,
+                threshold: threshold as u64,
+                sample_size: 0,
+            },
+            quantization::TrainingStatus::Trained { sample_size } => QuantizationStatus {
+                trained: true,
+                sample_size: sample_size as u64,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl From<QuantizationStatus> for quantization::TrainingStatus {
+    fn from(status: QuantizationStatus) -> Self {
+        if status.trained {
+            quantization::TrainingStatus::Trained {
+                sample_size: status.sample_size as usize,
+            }
+        } else {
+            quantization::TrainingStatus::Untrained {
+                vectors: status.vectors as usize,
+                threshold: status.threshold as usize,
+            }
+        }
+    }
+}
+
+impl From<search_api::LatencySummary> for LatencySummary {
+    fn from(latency: search_api::LatencySummary) -> Self {
+        LatencySummary {
+            mean_ms: latency.mean_ms,
+            p50_ms: latency.p50_ms,
+            p95_ms: latency.p95_ms,
+            p99_ms: latency.p99_ms,
+            max_ms: latency.max_ms,
+        }
+    }
+}
+
+impl From<LatencySummary> for search_api::LatencySummary {
+    fn from(latency: LatencySummary) -> Self {
+        search_api::LatencySummary {
+            mean_ms: latency.mean_ms,
+            p50_ms: latency.p50_ms,
+            p95_ms: latency.p95_ms,
+            p99_ms: latency.p99_ms,
+            max_ms: latency.max_ms,
+        }
+    }
+}
+
+impl From<search_api::EvaluationRun> for EvaluationRun {
+    fn from(run: search_api::EvaluationRun) -> Self {
+        EvaluationRun {
+            ef_search: run.ef_search as u32,
+            recall: run.recall,
+            latency: Some(run.latency.into()),
+            visited_mean: run.visited_mean,
+            visited_max: run.visited_max as u64,
+        }
+    }
+}
+
+impl From<EvaluationRun> for search_api::EvaluationRun {
+    fn from(run: EvaluationRun) -> Self {
+        search_api::EvaluationRun {
+            ef_search: run.ef_search as usize,
+            recall: run.recall,
+            latency: run.latency.map(Into::into).unwrap_or_default(),
+            visited_mean: run.visited_mean,
+            visited_max: run.visited_max as usize,
+        }
+    }
+}
+
 impl From<types::VectorType> for VectorType {
     fn from(vector_type: types::VectorType) -> Self {
         match vector_type {
@@ -49,4 +311,545 @@ impl From<VectorType> for types::VectorType {
             _ => types::VectorType::Float32, // Default fallback
         }
     }
-}
\ No newline at end of file
+}
+
+impl From<types::PayloadFieldType> for PayloadFieldType {
+    fn from(field_type: types::PayloadFieldType) -> Self {
+        match field_type {
+            types::PayloadFieldType::Keyword => PayloadFieldType::Keyword,
+            types::PayloadFieldType::Integer => PayloadFieldType::Integer,
+            types::PayloadFieldType::Float => PayloadFieldType::Float,
+            types::PayloadFieldType::Bool => PayloadFieldType::Bool,
+            types::PayloadFieldType::Geo => PayloadFieldType::Geo,
+            types::PayloadFieldType::Text => PayloadFieldType::Text,
+        }
+    }
+}
+
+impl TryFrom<PayloadFieldType> for types::PayloadFieldType {
+    type Error = &'static str;
+
+    /// There is no sensible default index type, so unspecified is rejected
+    fn try_from(field_type: PayloadFieldType) -> Result<Self, Self::Error> {
+        match field_type {
+            PayloadFieldType::Keyword => Ok(types::PayloadFieldType::Keyword),
+            PayloadFieldType::Integer => Ok(types::PayloadFieldType::Integer),
+            PayloadFieldType::Float => Ok(types::PayloadFieldType::Float),
+            PayloadFieldType::Bool => Ok(types::PayloadFieldType::Bool),
+            PayloadFieldType::Geo => Ok(types::PayloadFieldType::Geo),
+            PayloadFieldType::Text => Ok(types::PayloadFieldType::Text),
+            PayloadFieldType::Unspecified => Err("Payload field type is required"),
+        }
+    }
+}
+
+impl From<types::PayloadIndexInfo> for PayloadIndexInfo {
+    fn from(info: types::PayloadIndexInfo) -> Self {
+        PayloadIndexInfo {
+            field_name: info.field_name,
+            field_type: PayloadFieldType::from(info.field_type) as i32,
+            indexed_vectors: info.indexed_vectors as u64,
+            text_params: info.text_params.map(Into::into),
+        }
+    }
+}
+
+impl From<types::TextIndexParams> for TextIndexParams {
+    fn from(params: types::TextIndexParams) -> Self {
+        let tokenizer = match params.tokenizer {
+            types::TokenizerType::Word => TokenizerType::Word,
+            types::TokenizerType::Whitespace => TokenizerType::Whitespace,
+            types::TokenizerType::Prefix => TokenizerType::Prefix,
+            types::TokenizerType::Ngram => TokenizerType::Ngram,
+        };
+        let stop_words_language = match params.stop_words_language {
+            Some(types::StopWordsLanguage::English) => StopWordsLanguage::English,
+            None => StopWordsLanguage::None,
+        };
+        TextIndexParams {
+            tokenizer: tokenizer as i32,
+            lowercase: Some(params.lowercase),
+            min_token_len: params.min_token_len.map(|len| len as u32),
+            max_token_len: params.max_token_len.map(|len| len as u32),
+            stop_words_language: stop_words_language as i32,
+            stop_words: params.stop_words,
+        }
+    }
+}
+
+impl From<TextIndexParams> for types::TextIndexParams {
+    fn from(params: TextIndexParams) -> Self {
+        let tokenizer = match params.tokenizer() {
+            TokenizerType::Whitespace => types::TokenizerType::Whitespace,
+            TokenizerType::Prefix => types::TokenizerType::Prefix,
+            TokenizerType::Ngram => types::TokenizerType::Ngram,
+            TokenizerType::Word | TokenizerType::Unspecified => types::TokenizerType::Word,
+        };
+        let stop_words_language = match params.stop_words_language() {
+            StopWordsLanguage::English => Some(types::StopWordsLanguage::English),
+            StopWordsLanguage::None => None,
+        };
+        types::TextIndexParams {
+            tokenizer,
+            lowercase: params.lowercase.unwrap_or(true),
+            min_token_len: params.min_token_len.map(|len| len as usize),
+            max_token_len: params.max_token_len.map(|len| len as usize),
+            stop_words_language,
+            stop_words: params.stop_words,
+        }
+    }
+}
+
+impl From<sparse::SparseVector> for SparseVector {
+    fn from(vector: sparse::SparseVector) -> Self {
+        SparseVector {
+            indices: vector.indices,
+            values: vector.values,
+        }
+    }
+}
+
+impl From<SparseVector> for sparse::SparseVector {
+    /// Mismatched lengths are left for the store to reject
+    fn from(vector: SparseVector) -> Self {
+        sparse::SparseVector {
+            indices: vector.indices,
+            values: vector.values,
+        }
+    }
+}
+
+impl From<sparse::SparseVectorParams> for SparseVectorParams {
+    fn from(params: sparse::SparseVectorParams) -> Self {
+        let modifier = match params.modifier {
+            sparse::SparseModifier::None => SparseModifier::None,
+            sparse::SparseModifier::Bm25 => SparseModifier::Bm25,
+        };
+        SparseVectorParams {
+            modifier: modifier as i32,
+        }
+    }
+}
+
+impl From<SparseVectorParams> for sparse::SparseVectorParams {
+    fn from(params: SparseVectorParams) -> Self {
+        let modifier = match params.modifier() {
+            SparseModifier::None => sparse::SparseModifier::None,
+            SparseModifier::Bm25 => sparse::SparseModifier::Bm25,
+        };
+        sparse::SparseVectorParams { modifier }
+    }
+}
+
+impl From<sparse::FusionMethod> for FusionMethod {
+    fn from(method: sparse::FusionMethod) -> Self {
+        match method {
+            sparse::FusionMethod::RelativeScoreFusion => FusionMethod::RelativeScore,
+            sparse::FusionMethod::ReciprocalRankFusion => FusionMethod::ReciprocalRank,
+            sparse::FusionMethod::DistributionBasedScoreFusion => FusionMethod::DistributionBased,
+        }
+    }
+}
+
+impl From<FusionMethod> for sparse::FusionMethod {
+    fn from(method: FusionMethod) -> Self {
+        match method {
+            FusionMethod::ReciprocalRank => sparse::FusionMethod::ReciprocalRankFusion,
+            FusionMethod::DistributionBased => sparse::FusionMethod::DistributionBasedScoreFusion,
+            FusionMethod::RelativeScore | FusionMethod::Unspecified => {
+                sparse::FusionMethod::RelativeScoreFusion
+            }
+        }
+    }
+}
+
+impl From<search_api::OrderBy> for OrderBy {
+    fn from(order_by: search_api::OrderBy) -> Self {
+        let direction = match order_by.direction {
+            search_api::Direction::Asc => Direction::Asc,
+            search_api::Direction::Desc => Direction::Desc,
+        };
+        Self {
+            key: order_by.key,
+            direction: direction.into(),
+        }
+    }
+}
+
+impl From<OrderBy> for search_api::OrderBy {
+    fn from(order_by: OrderBy) -> Self {
+        let direction = match Direction::try_from(order_by.direction).unwrap_or(Direction::Asc) {
+            Direction::Asc => search_api::Direction::Asc,
+            Direction::Desc => search_api::Direction::Desc,
+        };
+        Self {
+            key: order_by.key,
+            direction,
+        }
+    }
+}
+
+impl From<serde_json::Value> for PayloadValue {
+    fn from(value: serde_json::Value) -> Self {
+        use payload_value::Kind;
+
+        let kind = match value {
+            serde_json::Value::Null => Kind::NullValue(NullValue::NullValue.into()),
+            serde_json::Value::Bool(b) => Kind::BoolValue(b),
+            serde_json::Value::Number(n) => match (n.as_i64(), n.as_u64()) {
+                (Some(i), _) => Kind::IntegerValue(i),
+                (None, Some(u)) => Kind::UnsignedValue(u),
+                (None, None) => Kind::DoubleValue(n.as_f64().unwrap_or_default()),
+            },
+            serde_json::Value::String(s) => Kind::StringValue(s),
+            serde_json::Value::Array(values) => Kind::ListValue(PayloadList {
+                values: values.into_iter().map(Into::into).collect(),
+            }),
+            serde_json::Value::Object(fields) => Kind::StructValue(PayloadStruct {
+                fields: fields.into_iter().map(|(key, value)| (key, value.into())).collect(),
+            }),
+        };
+        PayloadValue { kind: Some(kind) }
+    }
+}
+
+impl From<PayloadValue> for serde_json::Value {
+    fn from(value: PayloadValue) -> Self {
+        use payload_value::Kind;
+
+        match value.kind {
+            None | Some(Kind::NullValue(_)) => serde_json::Value::Null,
+            Some(Kind::BoolValue(b)) => serde_json::Value::Bool(b),
+            Some(Kind::IntegerValue(i)) => i.into(),
+            Some(Kind::UnsignedValue(u)) => u.into(),
+            Some(Kind::DoubleValue(d)) => {
+                serde_json::Number::from_f64(d).map_or(serde_json::Value::Null, serde_json::Value::Number)
+            }
+            Some(Kind::StringValue(s)) => serde_json::Value::String(s),
+            Some(Kind::ListValue(list)) => {
+                serde_json::Value::Array(list.values.into_iter().map(Into::into).collect())
+            }
+            Some(Kind::StructValue(object)) => serde_json::Value::Object(
+                object.fields.into_iter().map(|(key, value)| (key, value.into())).collect(),
+            ),
+        }
+    }
+}
+
+/// Payload of a point as a proto map, empty when it has none
+pub fn payload_to_proto(payload: Option<HashMap<String, serde_json::Value>>) -> HashMap<String, PayloadValue> {
+    payload
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(key, value)| (key, value.into()))
+        .collect()
+}
+
+/// Payload of a proto map, `None` when it is empty
+pub fn payload_from_proto(payload: HashMap<String, PayloadValue>) -> Option<HashMap<String, serde_json::Value>> {
+    if payload.is_empty() {
+        None
+    } else {
+        Some(payload.into_iter().map(|(key, value)| (key, value.into())).collect())
+    }
+}
+
+impl From<filters::Filter> for Filter {
+    fn from(filter: filters::Filter) -> Self {
+        let kind = match filter {
+            filters::Filter::Must(conditions) => filter::Kind::Must(conditions.into()),
+            filters::Filter::Should(conditions) => filter::Kind::Should(conditions.into()),
+            filters::Filter::MustNot(conditions) => filter::Kind::MustNot(conditions.into()),
+            filters::Filter::MinShould { conditions, min_count } => filter::Kind::MinShould(MinShould {
+                conditions: conditions.into_iter().map(Into::into).collect(),
+                min_count: min_count as u64,
+            }),
+        };
+        Filter { kind: Some(kind) }
+    }
+}
+
+impl TryFrom<Filter> for filters::Filter {
+    type Error = &'static str;
+
+    fn try_from(filter: Filter) -> Result<Self, Self::Error> {
+        Ok(match filter.kind.ok_or("Filter clause is required")? {
+            filter::Kind::Must(conditions) => filters::Filter::Must(conditions.try_into()?),
+            filter::Kind::Should(conditions) => filters::Filter::Should(conditions.try_into()?),
+            filter::Kind::MustNot(conditions) => filters::Filter::MustNot(conditions.try_into()?),
+            filter::Kind::MinShould(min_should) => filters::Filter::MinShould {
+                conditions: Conditions { conditions: min_should.conditions }.try_into()?,
+                min_count: min_should.min_count as usize,
+            },
+        })
+    }
+}
+
+impl From<search_api::PointSelector> for PointSelector {
+    fn from(points: search_api::PointSelector) -> Self {
+        let selector = match points {
+            search_api::PointSelector::Ids(ids) => point_selector::Selector::Ids(PointIds {
+                ids: ids.iter().map(ToString::to_string).collect(),
+            }),
+            search_api::PointSelector::Filter(filter) => point_selector::Selector::Filter(filter.into()),
+        };
+        PointSelector { selector: Some(selector) }
+    }
+}
+
+impl TryFrom<PointSelector> for search_api::PointSelector {
+    type Error = &'static str;
+
+    fn try_from(points: PointSelector) -> Result<Self, Self::Error> {
+        Ok(match points.selector.ok_or("Point IDs or a filter are required")? {
+            point_selector::Selector::Ids(ids) => search_api::PointSelector::Ids(
+                ids.ids
+                    .iter()
+                    .map(|id| id.parse().map_err(|_| "Invalid point ID"))
+                    .collect::<Result<_, _>>()?,
+            ),
+            point_selector::Selector::Filter(filter) => search_api::PointSelector::Filter(filter.try_into()?),
+        })
+    }
+}
+
+impl From<Vec<filters::Condition>> for Conditions {
+    fn from(conditions: Vec<filters::Condition>) -> Self {
+        Conditions {
+            conditions: conditions.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl TryFrom<Conditions> for Vec<filters::Condition> {
+    type Error = &'static str;
+
+    fn try_from(conditions: Conditions) -> Result<Self, Self::Error> {
+        conditions.conditions.into_iter().map(TryInto::try_into).collect()
+    }
+}
+
+impl From<filters::Condition> for Condition {
+    fn from(condition: filters::Condition) -> Self {
+        let kind = match condition {
+            filters::Condition::Match(field) => condition::Kind::Field(field.into()),
+            filters::Condition::Filter(filter) => condition::Kind::Filter((*filter).into()),
+        };
+        Condition { kind: Some(kind) }
+    }
+}
+
+impl TryFrom<Condition> for filters::Condition {
+    type Error = &'static str;
+
+    fn try_from(condition: Condition) -> Result<Self, Self::Error> {
+        Ok(match condition.kind.ok_or("Filter condition is required")? {
+            condition::Kind::Field(field) => filters::Condition::Match(field.try_into()?),
+            condition::Kind::Filter(filter) => filters::Condition::Filter(Box::new(filter.try_into()?)),
+        })
+    }
+}
+
+impl From<filters::FieldCondition> for FieldCondition {
+    fn from(condition: filters::FieldCondition) -> Self {
+        use field_condition::Kind;
+
+        let kind = match condition {
+            filters::FieldCondition::MatchKeyword(m) => Kind::MatchKeyword(MatchKeyword {
+                key: m.key,
+                value: Some(m.value.into()),
+            }),
+            filters::FieldCondition::MatchAny(m) => Kind::MatchAny(MatchAny {
+                key: m.key,
+                any: m.any.into_iter().map(Into::into).collect(),
+            }),
+            filters::FieldCondition::MatchText(m) => Kind::MatchText(MatchText { key: m.key, text: m.text }),
+            filters::FieldCondition::Range(r) => Kind::Range(RangeCondition {
+                key: r.key,
+                gte: r.gte,
+                gt: r.gt,
+                lte: r.lte,
+                lt: r.lt,
+            }),
+            filters::FieldCondition::GeoRadius(g) => Kind::GeoRadius(GeoRadius {
+                key: g.key,
+                latitude: g.latitude,
+                longitude: g.longitude,
+                radius_meters: g.radius_meters,
+            }),
+            filters::FieldCondition::GeoBoundingBox(g) => Kind::GeoBoundingBox(GeoBoundingBox {
+                key: g.key,
+                top_left: Some(GeoPoint { lat: g.top_left.lat, lon: g.top_left.lon }),
+                bottom_right: Some(GeoPoint { lat: g.bottom_right.lat, lon: g.bottom_right.lon }),
+            }),
+            filters::FieldCondition::ValuesCount(v) => Kind::ValuesCount(ValuesCount {
+                key: v.key,
+                gte: v.gte.map(|n| n as u64),
+                gt: v.gt.map(|n| n as u64),
+                lte: v.lte.map(|n| n as u64),
+                lt: v.lt.map(|n| n as u64),
+            }),
+            filters::FieldCondition::IsEmpty(c) => Kind::IsEmpty(IsEmpty { key: c.key }),
+            filters::FieldCondition::IsNull(c) => Kind::IsNull(IsNull { key: c.key }),
+        };
+        FieldCondition { kind: Some(kind) }
+    }
+}
+
+impl TryFrom<FieldCondition> for filters::FieldCondition {
+    type Error = &'static str;
+
+    fn try_from(condition: FieldCondition) -> Result<Self, Self::Error> {
+        use field_condition::Kind;
+
+        let point = |point: Option<GeoPoint>| {
+            point
+                .map(|p| filters::GeoPoint { lat: p.lat, lon: p.lon })
+                .ok_or("Bounding box corners are required")
+        };
+
+        Ok(match condition.kind.ok_or("Field condition is required")? {
+            Kind::MatchKeyword(m) => filters::FieldCondition::MatchKeyword(filters::MatchKeyword {
+                key: m.key,
+                value: m.value.ok_or("Match value is required")?.try_into()?,
+            }),
+            Kind::MatchAny(m) => filters::FieldCondition::MatchAny(filters::MatchAny {
+                key: m.key,
+                any: m.any.into_iter().map(TryInto::try_into).collect::<Result<_, _>>()?,
+            }),
+            Kind::MatchText(m) => filters::FieldCondition::MatchText(filters::MatchText { key: m.key, text: m.text }),
+            Kind::Range(r) => filters::FieldCondition::Range(filters::RangeCondition {
+                key: r.key,
+                gte: r.gte,
+                gt: r.gt,
+                lte: r.lte,
+                lt: r.lt,
+            }),
+            Kind::GeoRadius(g) => filters::FieldCondition::GeoRadius(filters::GeoRadius {
+                key: g.key,
+                latitude: g.latitude,
+                longitude: g.longitude,
+                radius_meters: g.radius_meters,
+            }),
+            Kind::GeoBoundingBox(g) => filters::FieldCondition::GeoBoundingBox(filters::GeoBoundingBox {
+                key: g.key,
+                top_left: point(g.top_left)?,
+                bottom_right: point(g.bottom_right)?,
+            }),
+            Kind::ValuesCount(v) => filters::FieldCondition::ValuesCount(filters::ValuesCount {
+                key: v.key,
+                gte: v.gte.map(|n| n as usize),
+                gt: v.gt.map(|n| n as usize),
+                lte: v.lte.map(|n| n as usize),
+                lt: v.lt.map(|n| n as usize),
+            }),
+            Kind::IsEmpty(c) => filters::FieldCondition::IsEmpty(filters::IsEmpty { key: c.key }),
+            Kind::IsNull(c) => filters::FieldCondition::IsNull(filters::IsNull { key: c.key }),
+        })
+    }
+}
+
+impl From<filters::MatchValue> for MatchValue {
+    fn from(value: filters::MatchValue) -> Self {
+        let kind = match value {
+            filters::MatchValue::Keyword(s) => match_value::Kind::Keyword(s),
+            filters::MatchValue::Integer(i) => match_value::Kind::Integer(i),
+            filters::MatchValue::Bool(b) => match_value::Kind::Boolean(b),
+        };
+        MatchValue { kind: Some(kind) }
+    }
+}
+
+impl TryFrom<MatchValue> for filters::MatchValue {
+    type Error = &'static str;
+
+    fn try_from(value: MatchValue) -> Result<Self, Self::Error> {
+        Ok(match value.kind.ok_or("Match value is required")? {
+            match_value::Kind::Keyword(s) => filters::MatchValue::Keyword(s),
+            match_value::Kind::Integer(i) => filters::MatchValue::Integer(i),
+            match_value::Kind::Boolean(b) => filters::MatchValue::Bool(b),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_payload_roundtrip() {
+        let payload = serde_json::json!({
+            "name": "widget",
+            "count": -3,
+            "big": u64::MAX,
+            "price": 9.5,
+            "whole": 2.0,
+            "in_stock": true,
+            "missing": null,
+            "tags": ["a", 1, [false]],
+            "dims": {"w": 1.25, "h": {"unit": "cm"}},
+        });
+        let map: HashMap<String, serde_json::Value> = serde_json::from_value(payload.clone()).unwrap();
+
+        let restored = payload_from_proto(payload_to_proto(Some(map))).unwrap();
+        assert_eq!(serde_json::to_value(&restored).unwrap(), payload);
+        assert!(restored["whole"].is_f64());
+        assert_eq!(payload_from_proto(payload_to_proto(None)), None);
+    }
+
+    #[test]
+    fn test_filter_roundtrip() {
+        let filter: filters::Filter = serde_json::from_value(serde_json::json!({
+            "must": [
+                {"match": {"key": "size", "any": [1, "xl", true]}},
+                {"match": {"key": "body", "text": "hello"}},
+                {"match": {"key": "price", "gte": 1.5, "lt": 10.0}},
+                {"match": {"key": "loc", "latitude": 52.5, "longitude": 13.4, "radius_meters": 1000.0}},
+                {"match": {"key": "area", "top_left": {"lat": 1.0, "lon": 2.0}, "bottom_right": {"lat": 0.0, "lon": 3.0}}},
+                {"filter": {"min_should": {"conditions": [{"match": {"key": "n", "text": "x"}}], "min_count": 1}}},
+                {"filter": {"must_not": [{"match": {"key": "flag", "any": [false]}}]}},
+                {"filter": {"should": []}}
+            ]
+        }))
+        .unwrap();
+
+        let restored: filters::Filter = Filter::from(filter.clone()).try_into().unwrap();
+        assert_eq!(serde_json::to_value(&restored).unwrap(), serde_json::to_value(&filter).unwrap());
+
+        // Untagged conditions that share a JSON shape still cross as the variant they are
+        let keyword = |key: &str, value| {
+            filters::Condition::Match(filters::FieldCondition::MatchKeyword(filters::MatchKeyword {
+                key: key.to_string(),
+                value,
+            }))
+        };
+        let conditions = vec![
+            keyword("color", filters::MatchValue::Keyword("red".to_string())),
+            keyword("n", filters::MatchValue::Integer(-3)),
+            keyword("flag", filters::MatchValue::Bool(false)),
+            filters::Condition::Match(filters::FieldCondition::ValuesCount(filters::ValuesCount {
+                key: "tags".to_string(),
+                gte: Some(2),
+                gt: None,
+                lte: None,
+                lt: None,
+            })),
+            filters::Condition::Match(filters::FieldCondition::IsEmpty(filters::IsEmpty { key: "a".to_string() })),
+            filters::Condition::Match(filters::FieldCondition::IsNull(filters::IsNull { key: "b".to_string() })),
+        ];
+        let restored: filters::Filter = Filter::from(filters::Filter::Should(conditions)).try_into().unwrap();
+        let filters::Filter::Should(restored) = restored else { panic!("expected should") };
+        let keyword_value = |condition: &filters::Condition| match condition {
+            filters::Condition::Match(filters::FieldCondition::MatchKeyword(m)) => m.value.clone(),
+            other => panic!("expected keyword match, got {:?}", other),
+        };
+        assert!(matches!(keyword_value(&restored[0]), filters::MatchValue::Keyword(v) if v == "red"));
+        assert!(matches!(keyword_value(&restored[1]), filters::MatchValue::Integer(-3)));
+        assert!(matches!(keyword_value(&restored[2]), filters::MatchValue::Bool(false)));
+        assert!(matches!(restored[3], filters::Condition::Match(filters::FieldCondition::ValuesCount(_))));
+        assert!(matches!(restored[4], filters::Condition::Match(filters::FieldCondition::IsEmpty(_))));
+        assert!(matches!(restored[5], filters::Condition::Match(filters::FieldCondition::IsNull(_))));
+
+        assert!(filters::Filter::try_from(Filter { kind: None }).is_err());
+    }
+}
@@ -33,6 +33,31 @@ pub struct ServerConfig {
     
     /// Enable CORS for REST API
     pub enable_cors: bool,
+
+    /// Fraction of dead records in a collection's data file that triggers compaction
+    #[serde(default = "default_compaction_dead_ratio")]
+    pub compaction_dead_ratio: f64,
+
+    /// Seconds between background compaction checks (0 disables them)
+    #[serde(default = "default_compaction_interval")]
+    pub compaction_interval_secs: u64,
+
+    /// Seconds between writing search indexes to disk (0 disables it; indexes
+    /// are still written on shutdown)
+    #[serde(default = "default_index_persist_interval")]
+    pub index_persist_interval_secs: u64,
+}
+
+fn default_compaction_dead_ratio() -> f64 {
+    0.5
+}
+
+fn default_compaction_interval() -> u64 {
+    300
+}
+
+fn default_index_persist_interval() -> u64 {
+    600
 }
 
 impl Default for ServerConfig {
@@ -48,6 +73,9 @@ impl Default for ServerConfig {
             enable_logging: true,
             log_level: "info".to_string(),
             enable_cors: true,
+            compaction_dead_ratio: default_compaction_dead_ratio(),
+            compaction_interval_secs: default_compaction_interval(),
+            index_persist_interval_secs: default_index_persist_interval(),
         }
     }
 }
@@ -81,6 +109,10 @@ impl ServerConfig {
             return Err(anyhow::anyhow!("request_timeout must be greater than 0"));
         }
         
+        if self.compaction_dead_ratio <= 0.0 || self.compaction_dead_ratio > 1.0 {
+            return Err(anyhow::anyhow!("compaction_dead_ratio must be in (0, 1]"));
+        }
+        
         // Validate log level
         match self.log_level.to_lowercase().as_str() {
             "trace" | "debug" | "info" | "warn" | "error" => {}
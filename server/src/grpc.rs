@@ -3,18 +3,153 @@ use vectordb_proto::{
     DeleteCollectionRequest, DeleteCollectionResponse, ListCollectionsRequest,
     ListCollectionsResponse, GetCollectionInfoRequest, GetCollectionInfoResponse,
     InsertRequest, InsertResponse, BatchInsertRequest, BatchInsertResponse,
-    DeleteRequest, DeleteResponse, QueryRequest, QueryResponse, QueryResult,
+    DeleteRequest, DeleteResponse, GetRequest, GetResponse, QueryRequest, QueryResponse, QueryResult,
     UpdateRequest, UpdateResponse, GetStatsRequest, GetStatsResponse,
-    HealthRequest, HealthResponse
+    HealthRequest, HealthResponse, CompactCollectionRequest, CompactCollectionResponse,
+    CreatePayloadIndexRequest, CreatePayloadIndexResponse, DeletePayloadIndexRequest,
+    DeletePayloadIndexResponse, ListPayloadIndexesRequest, ListPayloadIndexesResponse,
+    StreamInsertRequest, StreamInsertResponse, Vector as ProtoVector, payload_from_proto,
+    payload_to_proto,
 };
+use vectordb_common::VectorDbError;
 use vectordb_vectorstore::VectorStore;
 use std::sync::Arc;
-use std::collections::HashMap;
-use tonic::{Request, Response, Status};
-use tracing::{info, error, instrument};
+use std::collections::{BTreeMap, HashMap};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::{Request, Response, Status, Streaming};
+use tracing::{info, warn, error, instrument};
 use uuid::Uuid;
 use std::net::SocketAddr;
 
+/// Named sparse vectors of a proto `Vector`, `None` when it has none
+fn sparse_from_proto(
+    sparse: HashMap<String, vectordb_proto::SparseVector>,
+) -> Option<BTreeMap<String, vectordb_common::SparseVector>> {
+    if sparse.is_empty() {
+        None
+    } else {
+        Some(sparse.into_iter().map(|(name, vector)| (name, vector.into())).collect())
+    }
+}
+
+/// Named dense vectors of a proto `Vector`, `None` when it has none
+fn named_vectors_from_proto(
+    vectors: HashMap<String, vectordb_proto::DenseVector>,
+) -> Option<BTreeMap<String, Vec<f32>>> {
+    if vectors.is_empty() {
+        None
+    } else {
+        Some(vectors.into_iter().map(|(name, vector)| (name, vector.data)).collect())
+    }
+}
+
+/// Named multi-vectors of a proto `Vector`, `None` when it has none
+fn multivectors_from_proto(
+    multivectors: HashMap<String, vectordb_proto::MultiVector>,
+) -> Option<BTreeMap<String, Vec<Vec<f32>>>> {
+    if multivectors.is_empty() {
+        None
+    } else {
+        Some(multivectors.into_iter().map(|(name, rows)| (name, rows.into())).collect())
+    }
+}
+
+/// Common form of a proto `Vector`
+fn vector_from_proto(vector: ProtoVector) -> Result<vectordb_common::types::Vector, &'static str> {
+    let id = Uuid::parse_str(&vector.id).map_err(|_| "Invalid vector ID format")?;
+
+    Ok(vectordb_common::types::Vector {
+        id,
+        data: vector.data,
+        metadata: payload_from_proto(vector.metadata),
+        sparse: sparse_from_proto(vector.sparse),
+        vectors: named_vectors_from_proto(vector.vectors),
+        multivectors: multivectors_from_proto(vector.multivectors),
+    })
+}
+
+/// Proto form of a common `Vector`
+fn vector_to_proto(vector: vectordb_common::types::Vector) -> ProtoVector {
+    ProtoVector {
+        id: vector.id.to_string(),
+        data: vector.data,
+        metadata: payload_to_proto(vector.metadata),
+        sparse: vector.sparse
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(name, sparse)| (name, sparse.into()))
+            .collect(),
+        vectors: vector.vectors
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(name, data)| (name, vectordb_proto::DenseVector { data }))
+            .collect(),
+        multivectors: vector.multivectors
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(name, rows)| (name, rows.into()))
+            .collect(),
+    }
+}
+
+/// Common form of a payload operation's required point selector
+fn points_from_proto(
+    points: Option<vectordb_proto::PointSelector>,
+) -> Result<vectordb_common::PointSelector, &'static str> {
+    points.ok_or("Point IDs or a filter are required")?.try_into()
+}
+
+/// Status for a failed payload operation
+fn update_payload_status(e: VectorDbError) -> Status {
+    match e {
+        VectorDbError::CollectionNotFound { name } => {
+            Status::not_found(format!("Collection not found: {}", name))
+        }
+        e => {
+            error!("Failed to update payload: {}", e);
+            Status::internal(e.to_string())
+        }
+    }
+}
+
+/// Acknowledgements a `StreamInsert` call buffers before it stops reading
+/// batches from a client that is not reading them
+const STREAM_INSERT_ACK_BUFFER: usize = 16;
+
+/// Write one `StreamInsert` batch through the store and acknowledge it
+async fn insert_stream_batch(store: &VectorStore, batch: StreamInsertRequest) -> StreamInsertResponse {
+    let batch_id = batch.batch_id;
+    let rejected = |message: String| StreamInsertResponse {
+        batch_id,
+        success: false,
+        message,
+        inserted_count: 0,
+    };
+
+    let vectors = match batch.vectors
+        .into_iter()
+        .map(vector_from_proto)
+        .collect::<Result<Vec<_>, _>>()
+    {
+        Ok(vectors) => vectors,
+        Err(e) => return rejected(e.to_string()),
+    };
+
+    match store.batch_insert(&batch.collection_name, &vectors).await {
+        Ok(()) => StreamInsertResponse {
+            batch_id,
+            success: true,
+            message: String::new(),
+            inserted_count: vectors.len() as u32,
+        },
+        Err(e) => {
+            error!("Failed to insert streamed batch {}: {}", batch_id, e);
+            rejected(e.to_string())
+        }
+    }
+}
+
 /// gRPC service implementation
 pub struct VectorDbService {
     store: Arc<VectorStore>,
@@ -44,15 +179,23 @@ impl VectorDb for VectorDbService {
             dimension: config.dimension as usize,
             distance_metric: config.distance_metric().into(),
             vector_type: config.vector_type().into(),
-            index_config: config.index_config.map_or(vectordb_common::types::IndexConfig::default(), |ic| {
-                vectordb_common::types::IndexConfig {
-                    max_connections: ic.max_connections as usize,
-                    ef_construction: ic.ef_construction as usize,
-                    ef_search: ic.ef_search as usize,
-                    max_layer: ic.max_layer as usize,
-                }
-            }),
+            index_config: config.index_config.map(Into::into).unwrap_or_default(),
             quantization: None,
+            sparse_vectors: if config.sparse_vectors.is_empty() {
+                None
+            } else {
+                Some(config.sparse_vectors.into_iter().map(|(name, params)| (name, params.into())).collect())
+            },
+            vectors: if config.vectors.is_empty() {
+                None
+            } else {
+                Some(config.vectors.into_iter().map(|(name, params)| (name, params.into())).collect())
+            },
+            multivectors: if config.multivectors.is_empty() {
+                None
+            } else {
+                Some(config.multivectors.into_iter().map(|(name, params)| (name, params.into())).collect())
+            },
         };
         
         match self.store.create_collection(&collection_config).await {
@@ -134,12 +277,22 @@ impl VectorDb for VectorDbService {
             dimension: config.dimension as u32,
             distance_metric: config.distance_metric.into(),
             vector_type: config.vector_type.into(),
-            index_config: Some(vectordb_proto::IndexConfig {
-                max_connections: config.index_config.max_connections as u32,
-                ef_construction: config.index_config.ef_construction as u32,
-                ef_search: config.index_config.ef_search as u32,
-                max_layer: config.index_config.max_layer as u32,
-            }),
+            index_config: Some(config.index_config.into()),
+            sparse_vectors: config.sparse_vectors
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(name, params)| (name, params.into()))
+                .collect(),
+            vectors: config.vectors
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(name, params)| (name, params.into()))
+                .collect(),
+            multivectors: config.multivectors
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(name, params)| (name, params.into()))
+                .collect(),
         };
         
         let proto_stats = vectordb_proto::CollectionStats {
@@ -148,6 +301,8 @@ impl VectorDb for VectorDbService {
             dimension: stats.dimension as u32,
             index_size: stats.index_size as u64,
             memory_usage: stats.memory_usage as u64,
+            memory: Some(stats.memory.into()),
+            quantization: stats.quantization.map(Into::into),
         };
         
         Ok(Response::new(GetCollectionInfoResponse {
@@ -170,22 +325,15 @@ impl VectorDb for VectorDbService {
         let vector_id = Uuid::parse_str(&vector_proto.id)
             .map_err(|_| Status::invalid_argument("Invalid vector ID format"))?;
         
-        let metadata = if vector_proto.metadata.is_empty() {
-            None
-        } else {
-            Some(
-                vector_proto
-                    .metadata
-                    .into_iter()
-                    .map(|(k, v)| (k, serde_json::Value::String(v)))
-                    .collect::<HashMap<String, serde_json::Value>>(),
-            )
-        };
+        let metadata = payload_from_proto(vector_proto.metadata);
         
         let vector = vectordb_common::types::Vector {
             id: vector_id,
             data: vector_proto.data,
             metadata,
+            sparse: sparse_from_proto(vector_proto.sparse),
+            vectors: named_vectors_from_proto(vector_proto.vectors),
+            multivectors: multivectors_from_proto(vector_proto.multivectors),
         };
         
         match self.store.insert(&req.collection_name, &vector).await {
@@ -212,30 +360,11 @@ impl VectorDb for VectorDbService {
     ) -> Result<Response<BatchInsertResponse>, Status> {
         let req = request.into_inner();
         
-        let mut vectors = Vec::new();
-        
-        for vector_proto in req.vectors {
-            let vector_id = Uuid::parse_str(&vector_proto.id)
-                .map_err(|_| Status::invalid_argument("Invalid vector ID format"))?;
-            
-            let metadata = if vector_proto.metadata.is_empty() {
-                None
-            } else {
-                Some(
-                    vector_proto
-                        .metadata
-                        .into_iter()
-                        .map(|(k, v)| (k, serde_json::Value::String(v)))
-                        .collect::<HashMap<String, serde_json::Value>>(),
-                )
-            };
-            
-            vectors.push(vectordb_common::types::Vector {
-                id: vector_id,
-                data: vector_proto.data,
-                metadata,
-            });
-        }
+        let vectors = req.vectors
+            .into_iter()
+            .map(vector_from_proto)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(Status::invalid_argument)?;
         
         match self.store.batch_insert(&req.collection_name, &vectors).await {
             Ok(()) => {
@@ -256,6 +385,45 @@ impl VectorDb for VectorDbService {
         }
     }
     
+    type StreamInsertStream = ReceiverStream<Result<StreamInsertResponse, Status>>;
+
+    #[instrument(skip(self, request))]
+    async fn stream_insert(
+        &self,
+        request: Request<Streaming<StreamInsertRequest>>,
+    ) -> Result<Response<Self::StreamInsertStream>, Status> {
+        let mut batches = request.into_inner();
+        let store = self.store.clone();
+        let (acks, rx) = mpsc::channel(STREAM_INSERT_ACK_BUFFER);
+
+        // Batches are read one at a time and only once the previous one is
+        // written, so a slow store or a client that stops reading acks pushes
+        // back on the sender through HTTP/2 flow control
+        tokio::spawn(async move {
+            let mut received = 0u64;
+            loop {
+                let batch = match batches.message().await {
+                    Ok(Some(batch)) => batch,
+                    Ok(None) => break,
+                    Err(status) => {
+                        warn!("Bulk ingestion stream failed after {} batches: {}", received, status);
+                        break;
+                    }
+                };
+                received += 1;
+
+                let ack = insert_stream_batch(&store, batch).await;
+                if acks.send(Ok(ack)).await.is_err() {
+                    // Client hung up
+                    break;
+                }
+            }
+            info!("Bulk ingestion stream closed after {} batches", received);
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+    
     #[instrument(skip(self))]
     async fn delete(
         &self,
@@ -290,6 +458,32 @@ impl VectorDb for VectorDbService {
         }
     }
     
+    #[instrument(skip(self))]
+    async fn get(
+        &self,
+        request: Request<GetRequest>,
+    ) -> Result<Response<GetResponse>, Status> {
+        let req = request.into_inner();
+
+        let vector_id = Uuid::parse_str(&req.vector_id)
+            .map_err(|_| Status::invalid_argument("Invalid vector ID format"))?;
+
+        match self.store.get(&req.collection_name, &vector_id).await {
+            Ok(vector) => {
+                let vector = vector.map(vector_to_proto);
+
+                Ok(Response::new(GetResponse { vector }))
+            }
+            Err(VectorDbError::CollectionNotFound { name }) => {
+                Err(Status::not_found(format!("Collection not found: {}", name)))
+            }
+            Err(e) => {
+                error!("Failed to get vector: {}", e);
+                Err(Status::internal(e.to_string()))
+            }
+        }
+    }
+
     #[instrument(skip(self))]
     async fn query(
         &self,
@@ -298,9 +492,10 @@ impl VectorDb for VectorDbService {
         let start_time = std::time::Instant::now();
         let req = request.into_inner();
         
-        // TODO: Parse proto filter map into Filter type
-        // For now, simple filters are not supported via gRPC
-        let filter = None;
+        let filter = req.filter
+            .map(TryInto::try_into)
+            .transpose()
+            .map_err(|e| Status::invalid_argument(format!("Invalid filter: {}", e)))?;
 
         let query_request = vectordb_common::types::QueryRequest {
             collection: req.collection_name,
@@ -308,6 +503,11 @@ impl VectorDb for VectorDbService {
             limit: req.limit as usize,
             ef_search: req.ef_search.map(|ef| ef as usize),
             filter,
+            using: req.using,
+            multivector: req.multivector.map(Into::into),
+            rescore: req.rescore,
+            oversampling: req.oversampling,
+            exact: req.exact,
         };
         
         match self.store.query(&query_request).await {
@@ -319,11 +519,7 @@ impl VectorDb for VectorDbService {
                     .map(|r| QueryResult {
                         id: r.id.to_string(),
                         distance: r.distance,
-                        metadata: r.metadata.map_or(HashMap::new(), |meta| {
-                            meta.into_iter()
-                                .map(|(k, v)| (k, v.to_string()))
-                                .collect()
-                        }),
+                        metadata: payload_to_proto(r.metadata),
                     })
                     .collect();
                 
@@ -353,22 +549,15 @@ impl VectorDb for VectorDbService {
         let vector_id = Uuid::parse_str(&vector_proto.id)
             .map_err(|_| Status::invalid_argument("Invalid vector ID format"))?;
         
-        let metadata = if vector_proto.metadata.is_empty() {
-            None
-        } else {
-            Some(
-                vector_proto
-                    .metadata
-                    .into_iter()
-                    .map(|(k, v)| (k, serde_json::Value::String(v)))
-                    .collect::<HashMap<String, serde_json::Value>>(),
-            )
-        };
+        let metadata = payload_from_proto(vector_proto.metadata);
         
         let vector = vectordb_common::types::Vector {
             id: vector_id,
             data: vector_proto.data,
             metadata,
+            sparse: sparse_from_proto(vector_proto.sparse),
+            vectors: named_vectors_from_proto(vector_proto.vectors),
+            multivectors: multivectors_from_proto(vector_proto.multivectors),
         };
         
         match self.store.update(&req.collection_name, &vector).await {
@@ -387,6 +576,81 @@ impl VectorDb for VectorDbService {
             }
         }
     }
+
+    #[instrument(skip(self))]
+    async fn set_payload(
+        &self,
+        request: Request<vectordb_proto::SetPayloadRequest>,
+    ) -> Result<Response<vectordb_proto::UpdatePayloadResponse>, Status> {
+        let req = request.into_inner();
+
+        let set_request = vectordb_common::SetPayloadRequest {
+            collection: req.collection_name,
+            points: points_from_proto(req.points).map_err(Status::invalid_argument)?,
+            payload: payload_from_proto(req.payload).unwrap_or_default(),
+        };
+
+        let response = self.store.set_payload(&set_request).await.map_err(update_payload_status)?;
+        Ok(Response::new(vectordb_proto::UpdatePayloadResponse {
+            updated: response.updated as u64,
+        }))
+    }
+
+    #[instrument(skip(self))]
+    async fn overwrite_payload(
+        &self,
+        request: Request<vectordb_proto::SetPayloadRequest>,
+    ) -> Result<Response<vectordb_proto::UpdatePayloadResponse>, Status> {
+        let req = request.into_inner();
+
+        let set_request = vectordb_common::SetPayloadRequest {
+            collection: req.collection_name,
+            points: points_from_proto(req.points).map_err(Status::invalid_argument)?,
+            payload: payload_from_proto(req.payload).unwrap_or_default(),
+        };
+
+        let response = self.store.overwrite_payload(&set_request).await.map_err(update_payload_status)?;
+        Ok(Response::new(vectordb_proto::UpdatePayloadResponse {
+            updated: response.updated as u64,
+        }))
+    }
+
+    #[instrument(skip(self))]
+    async fn delete_payload_keys(
+        &self,
+        request: Request<vectordb_proto::DeletePayloadKeysRequest>,
+    ) -> Result<Response<vectordb_proto::UpdatePayloadResponse>, Status> {
+        let req = request.into_inner();
+
+        let delete_request = vectordb_common::DeletePayloadKeysRequest {
+            collection: req.collection_name,
+            points: points_from_proto(req.points).map_err(Status::invalid_argument)?,
+            keys: req.keys,
+        };
+
+        let response = self.store.delete_payload_keys(&delete_request).await.map_err(update_payload_status)?;
+        Ok(Response::new(vectordb_proto::UpdatePayloadResponse {
+            updated: response.updated as u64,
+        }))
+    }
+
+    #[instrument(skip(self))]
+    async fn clear_payload(
+        &self,
+        request: Request<vectordb_proto::ClearPayloadRequest>,
+    ) -> Result<Response<vectordb_proto::UpdatePayloadResponse>, Status> {
+        let req = request.into_inner();
+
+        let clear_request = vectordb_common::ClearPayloadRequest {
+            collection: req.collection_name,
+            points: points_from_proto(req.points).map_err(Status::invalid_argument)?,
+        };
+
+        let response = self.store.clear_payload(&clear_request).await.map_err(update_payload_status)?;
+        Ok(Response::new(vectordb_proto::UpdatePayloadResponse {
+            updated: response.updated as u64,
+        }))
+    }
     
     #[instrument(skip(self))]
     async fn get_stats(
@@ -401,6 +665,7 @@ impl VectorDb for VectorDbService {
                     memory_usage: stats.memory_usage,
                     disk_usage: stats.disk_usage,
                     uptime_seconds: stats.uptime_seconds,
+                    memory: Some(stats.memory.into()),
                 };
                 
                 Ok(Response::new(GetStatsResponse {
@@ -425,6 +690,172 @@ impl VectorDb for VectorDbService {
         }))
     }
 
+    #[instrument(skip(self))]
+    async fn compact_collection(
+        &self,
+        request: Request<CompactCollectionRequest>,
+    ) -> Result<Response<CompactCollectionResponse>, Status> {
+        let req = request.into_inner();
+
+        match self.store.compact_collection(&req.collection_name).await {
+            Ok(report) => Ok(Response::new(CompactCollectionResponse {
+                success: true,
+                message: "Collection compacted successfully".to_string(),
+                records_before: report.records_before as u64,
+                records_after: report.records_after as u64,
+                bytes_before: report.bytes_before,
+                bytes_after: report.bytes_after,
+                duration_ms: report.duration_ms,
+            })),
+            Err(e) => {
+                error!("Failed to compact collection: {}", e);
+                Ok(Response::new(CompactCollectionResponse {
+                    success: false,
+                    message: e.to_string(),
+                    ..Default::default()
+                }))
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn train_quantizer(
+        &self,
+        request: Request<vectordb_proto::TrainQuantizerRequest>,
+    ) -> Result<Response<vectordb_proto::TrainQuantizerResponse>, Status> {
+        let req = request.into_inner();
+
+        match self.store.train_quantizer(&req.collection_name).await {
+            Ok(status) => Ok(Response::new(vectordb_proto::TrainQuantizerResponse {
+                status: Some(status.into()),
+            })),
+            Err(VectorDbError::CollectionNotFound { name }) => {
+                Err(Status::not_found(format!("Collection not found: {}", name)))
+            }
+            Err(VectorDbError::InvalidInput { message }) => Err(Status::invalid_argument(message)),
+            Err(e) => {
+                error!("Failed to train quantizer: {}", e);
+                Err(Status::internal(e.to_string()))
+            }
+        }
+    }
+
+    #[instrument(skip(self, request))]
+    async fn evaluate(
+        &self,
+        request: Request<vectordb_proto::EvaluateRequest>,
+    ) -> Result<Response<vectordb_proto::EvaluateResponse>, Status> {
+        let req = request.into_inner();
+
+        let evaluate_request = vectordb_common::EvaluateRequest {
+            collection: req.collection_name,
+            queries: req.queries.into_iter().map(|query| query.data).collect(),
+            sample: req.sample.map_or(vectordb_common::EvaluateRequest::DEFAULT_SAMPLE, |sample| sample as usize),
+            limit: req.limit.map_or(vectordb_common::EvaluateRequest::DEFAULT_LIMIT, |limit| limit as usize),
+            ef_search: req.ef_search.into_iter().map(|ef| ef as usize).collect(),
+            using: req.using,
+        };
+
+        match self.store.evaluate(&evaluate_request).await {
+            Ok(response) => Ok(Response::new(vectordb_proto::EvaluateResponse {
+                queries: response.queries as u32,
+                limit: response.limit as u32,
+                exact_latency: Some(response.exact_latency.into()),
+                runs: response.runs.into_iter().map(Into::into).collect(),
+            })),
+            Err(VectorDbError::CollectionNotFound { name }) => {
+                Err(Status::not_found(format!("Collection not found: {}", name)))
+            }
+            Err(e @ (VectorDbError::InvalidInput { .. } | VectorDbError::InvalidDimension { .. })) => {
+                Err(Status::invalid_argument(e.to_string()))
+            }
+            Err(e) => {
+                error!("Failed to evaluate collection: {}", e);
+                Err(Status::internal(e.to_string()))
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn create_payload_index(
+        &self,
+        request: Request<CreatePayloadIndexRequest>,
+    ) -> Result<Response<CreatePayloadIndexResponse>, Status> {
+        let req = request.into_inner();
+        let field_type = req.field_type().try_into().map_err(Status::invalid_argument)?;
+
+        let result = match (field_type, req.text_params) {
+            (vectordb_common::types::PayloadFieldType::Text, params) => {
+                let params = params.map(Into::into).unwrap_or_default();
+                self.store.create_text_index(&req.collection_name, &req.field_name, params).await
+            }
+            (_, Some(_)) => {
+                return Err(Status::invalid_argument("Text index params are only valid for text fields"));
+            }
+            (field_type, None) => {
+                self.store.create_payload_index(&req.collection_name, &req.field_name, field_type).await
+            }
+        };
+
+        match result {
+            Ok(()) => Ok(Response::new(CreatePayloadIndexResponse {
+                success: true,
+                message: "Payload index created successfully".to_string(),
+            })),
+            Err(e) => {
+                error!("Failed to create payload index: {}", e);
+                Ok(Response::new(CreatePayloadIndexResponse {
+                    success: false,
+                    message: e.to_string(),
+                }))
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn delete_payload_index(
+        &self,
+        request: Request<DeletePayloadIndexRequest>,
+    ) -> Result<Response<DeletePayloadIndexResponse>, Status> {
+        let req = request.into_inner();
+
+        match self.store.delete_payload_index(&req.collection_name, &req.field_name).await {
+            Ok(true) => Ok(Response::new(DeletePayloadIndexResponse {
+                success: true,
+                message: "Payload index deleted successfully".to_string(),
+            })),
+            Ok(false) => Ok(Response::new(DeletePayloadIndexResponse {
+                success: false,
+                message: format!("No payload index on field: {}", req.field_name),
+            })),
+            Err(e) => {
+                error!("Failed to delete payload index: {}", e);
+                Ok(Response::new(DeletePayloadIndexResponse {
+                    success: false,
+                    message: e.to_string(),
+                }))
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn list_payload_indexes(
+        &self,
+        request: Request<ListPayloadIndexesRequest>,
+    ) -> Result<Response<ListPayloadIndexesResponse>, Status> {
+        let req = request.into_inner();
+
+        match self.store.list_payload_indexes(&req.collection_name) {
+            Ok(indexes) => Ok(Response::new(ListPayloadIndexesResponse {
+                indexes: indexes.into_iter().map(Into::into).collect(),
+            })),
+            Err(VectorDbError::CollectionNotFound { name }) => {
+                Err(Status::not_found(format!("Collection not found: {}", name)))
+            }
+            Err(e) => Err(Status::internal(e.to_string())),
+        }
+    }
+
     // Advanced search operations (TODO: implement)
     async fn recommend(
         &self,
@@ -440,11 +871,100 @@ impl VectorDb for VectorDbService {
         Err(Status::unimplemented("Discover API not yet implemented"))
     }
 
+    #[instrument(skip(self))]
     async fn scroll(
         &self,
-        _request: Request<vectordb_proto::ScrollRequest>,
+        request: Request<vectordb_proto::ScrollRequest>,
     ) -> Result<Response<vectordb_proto::ScrollResponse>, Status> {
-        Err(Status::unimplemented("Scroll API not yet implemented"))
+        let req = request.into_inner();
+
+        let filter = req.filter
+            .map(TryInto::try_into)
+            .transpose()
+            .map_err(|e| Status::invalid_argument(format!("Invalid filter: {}", e)))?;
+
+        let scroll_request = vectordb_common::ScrollRequest {
+            collection: req.collection_name,
+            filter,
+            limit: req.limit as usize,
+            offset: req.offset,
+            with_vectors: req.with_vectors,
+            with_payload: req.with_payload,
+            order_by: req.order_by.map(Into::into),
+        };
+
+        match self.store.scroll(&scroll_request).await {
+            Ok(response) => Ok(Response::new(vectordb_proto::ScrollResponse {
+                points: response.points
+                    .into_iter()
+                    .map(|p| vectordb_proto::ScoredPoint {
+                        id: p.id.to_string(),
+                        score: p.score,
+                        vector: p.vector.unwrap_or_default(),
+                        payload: payload_to_proto(p.payload),
+                    })
+                    .collect(),
+                next_offset: response.next_offset,
+            })),
+            Err(VectorDbError::CollectionNotFound { name }) => {
+                Err(Status::not_found(format!("Collection not found: {}", name)))
+            }
+            Err(e @ VectorDbError::InvalidInput { .. }) => {
+                Err(Status::invalid_argument(e.to_string()))
+            }
+            Err(e) => {
+                error!("Failed to execute scroll: {}", e);
+                Err(Status::internal(e.to_string()))
+            }
+        }
+    }
+
+    type ExportPointsStream = ReceiverStream<Result<vectordb_proto::ExportPointsResponse, Status>>;
+
+    #[instrument(skip(self))]
+    async fn export_points(
+        &self,
+        request: Request<vectordb_proto::ExportPointsRequest>,
+    ) -> Result<Response<Self::ExportPointsStream>, Status> {
+        let req = request.into_inner();
+
+        if self.store.get_collection_config(&req.collection_name)
+            .map_err(|e| Status::internal(e.to_string()))?
+            .is_none()
+        {
+            return Err(Status::not_found(format!("Collection not found: {}", req.collection_name)));
+        }
+
+        let filter = req.filter
+            .map(TryInto::try_into)
+            .transpose()
+            .map_err(|e| Status::invalid_argument(format!("Invalid filter: {}", e)))?;
+
+        let export_request = vectordb_common::ExportRequest {
+            collection: req.collection_name,
+            filter,
+            with_vectors: req.with_vectors,
+            with_payload: req.with_payload,
+            batch_size: req.batch_size.map_or(vectordb_common::ExportRequest::DEFAULT_BATCH_SIZE, |size| size as usize),
+        };
+
+        let mut batches = self.store.export(export_request);
+        let (responses, rx) = mpsc::channel(1);
+        tokio::spawn(async move {
+            while let Some(batch) = batches.recv().await {
+                let response = batch
+                    .map(|points| vectordb_proto::ExportPointsResponse {
+                        points: points.into_iter().map(vector_to_proto).collect(),
+                    })
+                    .map_err(|e| Status::internal(e.to_string()));
+                if responses.send(response).await.is_err() {
+                    // Client hung up, dropping `batches` stops the export
+                    break;
+                }
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
     }
 
     async fn count(
@@ -454,6 +974,47 @@ impl VectorDb for VectorDbService {
         Err(Status::unimplemented("Count API not yet implemented"))
     }
 
+    #[instrument(skip(self))]
+    async fn facet(
+        &self,
+        request: Request<vectordb_proto::FacetRequest>,
+    ) -> Result<Response<vectordb_proto::FacetResponse>, Status> {
+        let req = request.into_inner();
+
+        let filter = req.filter
+            .map(TryInto::try_into)
+            .transpose()
+            .map_err(|e| Status::invalid_argument(format!("Invalid filter: {}", e)))?;
+
+        let facet_request = vectordb_common::FacetRequest {
+            collection: req.collection_name,
+            field: req.field,
+            filter,
+            limit: req.limit.map_or(vectordb_common::FacetRequest::DEFAULT_LIMIT, |limit| limit as usize),
+            exact: req.exact,
+        };
+
+        match self.store.facet(&facet_request).await {
+            Ok(response) => Ok(Response::new(vectordb_proto::FacetResponse {
+                field: response.field,
+                values: response.values
+                    .into_iter()
+                    .map(|v| vectordb_proto::FacetValue {
+                        value: Some(v.value.into()),
+                        count: v.count as u64,
+                    })
+                    .collect(),
+            })),
+            Err(VectorDbError::CollectionNotFound { name }) => {
+                Err(Status::not_found(format!("Collection not found: {}", name)))
+            }
+            Err(e) => {
+                error!("Failed to execute facet: {}", e);
+                Err(Status::internal(e.to_string()))
+            }
+        }
+    }
+
     async fn batch_search(
         &self,
         _request: Request<vectordb_proto::BatchSearchRequest>,
@@ -461,6 +1022,121 @@ impl VectorDb for VectorDbService {
         Err(Status::unimplemented("Batch search API not yet implemented"))
     }
 
+    #[instrument(skip(self))]
+    async fn search_groups(
+        &self,
+        request: Request<vectordb_proto::SearchGroupsRequest>,
+    ) -> Result<Response<vectordb_proto::SearchGroupsResponse>, Status> {
+        let req = request.into_inner();
+
+        let filter = req.filter
+            .map(TryInto::try_into)
+            .transpose()
+            .map_err(|e| Status::invalid_argument(format!("Invalid filter: {}", e)))?;
+
+        let group_request = vectordb_common::GroupByRequest {
+            collection: req.collection_name,
+            vector: req.vector,
+            group_by: req.group_by,
+            group_size: req.group_size as usize,
+            limit: req.limit as usize,
+            filter,
+            with_lookup: req.with_lookup.map(|lookup| vectordb_common::WithLookup {
+                collection: lookup.collection,
+                with_payload: lookup.with_payload,
+                with_vectors: lookup.with_vectors,
+            }),
+        };
+
+        match self.store.search_groups(&group_request).await {
+            Ok(response) => {
+                let groups = response.groups
+                    .into_iter()
+                    .map(|group| vectordb_proto::PointGroup {
+                        id: Some(group.id.into()),
+                        hits: group.hits
+                            .into_iter()
+                            .map(|r| QueryResult {
+                                id: r.id.to_string(),
+                                distance: r.distance,
+                                metadata: payload_to_proto(r.metadata),
+                            })
+                            .collect(),
+                        lookup: group.lookup.map(|p| vectordb_proto::ScoredPoint {
+                            id: p.id.to_string(),
+                            score: p.score,
+                            vector: p.vector.unwrap_or_default(),
+                            payload: payload_to_proto(p.payload),
+                        }),
+                    })
+                    .collect();
+
+                Ok(Response::new(vectordb_proto::SearchGroupsResponse { groups }))
+            }
+            Err(VectorDbError::CollectionNotFound { name }) => {
+                Err(Status::not_found(format!("Collection not found: {}", name)))
+            }
+            Err(e @ (VectorDbError::InvalidInput { .. } | VectorDbError::InvalidDimension { .. })) => {
+                Err(Status::invalid_argument(e.to_string()))
+            }
+            Err(e) => {
+                error!("Failed to execute grouped search: {}", e);
+                Err(Status::internal(e.to_string()))
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn hybrid_search(
+        &self,
+        request: Request<vectordb_proto::HybridSearchRequest>,
+    ) -> Result<Response<vectordb_proto::HybridSearchResponse>, Status> {
+        let req = request.into_inner();
+
+        let filter = req.filter
+            .map(TryInto::try_into)
+            .transpose()
+            .map_err(|e| Status::invalid_argument(format!("Invalid filter: {}", e)))?;
+
+        let hybrid_request = vectordb_common::HybridSearchRequest {
+            collection: req.collection_name,
+            dense: if req.dense.is_empty() { None } else { Some(req.dense) },
+            sparse: req.sparse.map(Into::into),
+            sparse_name: req.sparse_name,
+            fusion: vectordb_proto::FusionMethod::try_from(req.fusion)
+                .unwrap_or(vectordb_proto::FusionMethod::Unspecified)
+                .into(),
+            limit: req.limit as usize,
+            filter,
+        };
+
+        match self.store.hybrid_search(&hybrid_request).await {
+            Ok(points) => {
+                let points = points
+                    .into_iter()
+                    .map(|p| vectordb_proto::ScoredPoint {
+                        id: p.id.to_string(),
+                        score: p.score,
+                        vector: p.vector.unwrap_or_default(),
+                        payload: payload_to_proto(p.payload),
+                    })
+                    .collect();
+
+                Ok(Response::new(vectordb_proto::HybridSearchResponse { points }))
+            }
+            Err(VectorDbError::CollectionNotFound { name }) => {
+                Err(Status::not_found(format!("Collection not found: {}", name)))
+            }
+            Err(e @ (VectorDbError::InvalidInput { .. } | VectorDbError::InvalidDimension { .. })) => {
+                Err(Status::invalid_argument(e.to_string()))
+            }
+            Err(e) => {
+                error!("Failed to execute hybrid search: {}", e);
+                Err(Status::internal(e.to_string()))
+            }
+        }
+    }
+
     // Snapshot operations (TODO: implement)
     async fn create_snapshot(
         &self,
@@ -17,6 +17,9 @@ pub use metrics::*;
 pub use health::*;
 pub use auth::*;
 
+/// How often collection stats are recomputed for the metrics exporter
+const STATS_REFRESH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(15);
+
 /// Main server application
 pub struct VectorDbServer {
     config: ServerConfig,
@@ -71,6 +74,56 @@ impl VectorDbServer {
             })
         };
         
+        // Start background compaction
+        if self.config.compaction_interval_secs > 0 {
+            let store = Arc::clone(&self.store);
+            let interval = std::time::Duration::from_secs(self.config.compaction_interval_secs);
+            let dead_ratio = self.config.compaction_dead_ratio;
+
+            tokio::spawn(async move {
+                let mut ticker = tokio::time::interval(interval);
+                ticker.tick().await; // first tick fires immediately
+                loop {
+                    ticker.tick().await;
+                    if let Err(e) = store.compact_if_needed(dead_ratio).await {
+                        error!("Background compaction failed: {}", e);
+                    }
+                }
+            });
+        }
+
+        // Refresh collection stats so the Prometheus gauges stay current
+        {
+            let store = Arc::clone(&self.store);
+
+            tokio::spawn(async move {
+                let mut ticker = tokio::time::interval(STATS_REFRESH_INTERVAL);
+                loop {
+                    ticker.tick().await;
+                    if let Err(e) = store.get_server_stats().await {
+                        error!("Failed to refresh stats: {}", e);
+                    }
+                }
+            });
+        }
+
+        // Periodically persist search indexes so restarts don't rebuild them
+        if self.config.index_persist_interval_secs > 0 {
+            let store = Arc::clone(&self.store);
+            let interval = std::time::Duration::from_secs(self.config.index_persist_interval_secs);
+
+            tokio::spawn(async move {
+                let mut ticker = tokio::time::interval(interval);
+                ticker.tick().await; // first tick fires immediately
+                loop {
+                    ticker.tick().await;
+                    if let Err(e) = store.persist_indexes().await {
+                        error!("Background index persistence failed: {}", e);
+                    }
+                }
+            });
+        }
+        
         info!("VectorDB servers started");
         info!("gRPC API: {}:{}", self.config.host, self.config.grpc_port);
         info!("REST API: {}:{}", self.config.host, self.config.rest_port);
@@ -93,6 +146,12 @@ impl VectorDbServer {
                     error!("Metrics server task failed: {}", e);
                 }
             }
+            _ = tokio::signal::ctrl_c() => {
+                info!("Shutdown signal received, flushing data and indexes");
+                if let Err(e) = self.store.sync().await {
+                    error!("Failed to sync on shutdown: {}", e);
+                }
+            }
         }
         
         Ok(())
@@ -70,6 +70,18 @@ async fn metrics_index() -> Html<&'static str> {
             <li><strong>vectorstore_collections_total</strong> - Total number of collections</li>
             <li><strong>vectorstore_vectors_total</strong> - Total number of vectors</li>
             <li><strong>vectorstore_memory_usage</strong> - Memory usage in bytes</li>
+            <li><strong>vectorstore_memory_usage_component</strong> - Memory usage in bytes by component (vectors, index, payload, quantized)</li>
+            <li><strong>vectorstore_collection_vectors</strong> - Live vectors per collection</li>
+            <li><strong>vectorstore_collection_memory</strong> - Memory usage in bytes per collection and component</li>
+            <li><strong>storage_compaction_runs_total</strong> - Completed compaction runs</li>
+            <li><strong>storage_compaction_bytes_reclaimed_total</strong> - Bytes reclaimed by compaction</li>
+            <li><strong>storage_compaction_duration_seconds</strong> - Compaction duration</li>
+            <li><strong>storage_compaction_running</strong> - Whether a collection is being compacted</li>
+            <li><strong>storage_compaction_progress</strong> - Fraction of live records copied by the current compaction</li>
+            <li><strong>storage_compaction_dead_ratio</strong> - Fraction of dead records per collection</li>
+            <li><strong>vectorstore_index_loads_total</strong> - Indexes loaded from disk at startup</li>
+            <li><strong>vectorstore_index_rebuilds_total</strong> - Indexes rebuilt from the data file at startup</li>
+            <li><strong>vectorstore_index_persist_duration_seconds</strong> - Index persistence duration</li>
         </ul>
         
         <h2>Usage</h2>
@@ -146,4 +158,54 @@ pub fn init_metrics() {
         "vectorstore.memory.usage",
         "Memory usage in bytes"
     );
+    metrics::describe_gauge!(
+        "vectorstore.memory.usage.component",
+        "Memory usage in bytes by component"
+    );
+    metrics::describe_gauge!(
+        "vectorstore.collection.vectors",
+        "Live vectors in a collection"
+    );
+    metrics::describe_gauge!(
+        "vectorstore.collection.memory",
+        "Memory usage of a collection in bytes by component"
+    );
+
+    metrics::describe_counter!(
+        "storage.compaction.runs",
+        "Number of completed compaction runs"
+    );
+    metrics::describe_counter!(
+        "storage.compaction.bytes_reclaimed",
+        "Bytes reclaimed from data files by compaction"
+    );
+    metrics::describe_histogram!(
+        "storage.compaction.duration",
+        "Compaction duration"
+    );
+    metrics::describe_gauge!(
+        "storage.compaction.running",
+        "Whether a compaction is running for a collection"
+    );
+    metrics::describe_gauge!(
+        "storage.compaction.progress",
+        "Fraction of live records copied by the current compaction"
+    );
+    metrics::describe_gauge!(
+        "storage.compaction.dead_ratio",
+        "Fraction of records in a collection's data file that are dead"
+    );
+
+    metrics::describe_counter!(
+        "vectorstore.index.loads",
+        "Indexes loaded from a persisted dump at startup"
+    );
+    metrics::describe_counter!(
+        "vectorstore.index.rebuilds",
+        "Indexes rebuilt from the data file at startup"
+    );
+    metrics::describe_histogram!(
+        "vectorstore.index.persist.duration",
+        "Time taken to write a search index to disk"
+    );
 }
\ No newline at end of file
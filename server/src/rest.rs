@@ -1,12 +1,14 @@
 use vectordb_vectorstore::VectorStore;
 use vectordb_common::types::*;
 use std::sync::Arc;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use vectordb_common::sparse::{SparseVector, SparseVectorParams};
 use std::time::Duration;
 use axum::{
+    body::Body,
     extract::{Path, Query, State},
-    http::StatusCode,
-    response::Json,
+    http::{header, StatusCode},
+    response::{Json, Response},
     routing::{get, post, delete, put},
     Router,
 };
@@ -15,6 +17,7 @@ use tracing::{info, error, instrument};
 use uuid::Uuid;
 use std::net::SocketAddr;
 use tokio::time::timeout;
+use tokio_stream::{wrappers::ReceiverStream, StreamExt};
 
 /// REST API response wrapper
 #[derive(Serialize)]
@@ -52,6 +55,12 @@ struct CreateCollectionRequest {
     vector_type: VectorType,
     index_config: Option<IndexConfig>,
     quantization: Option<vectordb_common::quantization::QuantizationConfig>,
+    #[serde(default)]
+    sparse_vectors: Option<BTreeMap<String, SparseVectorParams>>,
+    #[serde(default)]
+    vectors: Option<BTreeMap<String, VectorParams>>,
+    #[serde(default)]
+    multivectors: Option<BTreeMap<String, MultiVectorParams>>,
 }
 
 /// Collection creation response
@@ -79,8 +88,16 @@ struct UpdateVectorResponse {
 #[derive(Deserialize, Debug)]
 struct InsertVectorRequest {
     id: Option<String>,
+    /// May be left out when the collection declares named spaces
+    #[serde(default)]
     data: Vec<f32>,
     metadata: Option<HashMap<String, serde_json::Value>>,
+    #[serde(default)]
+    sparse: Option<BTreeMap<String, SparseVector>>,
+    #[serde(default)]
+    vectors: Option<BTreeMap<String, Vec<f32>>>,
+    #[serde(default)]
+    multivectors: Option<BTreeMap<String, Vec<Vec<f32>>>>,
 }
 
 /// Batch vector insertion request
@@ -111,11 +128,27 @@ struct BatchDeleteResponse {
 /// Query request
 #[derive(Deserialize, Debug)]
 struct QueryVectorsRequest {
-    #[serde(alias = "query_vector")]
+    /// May be left out by a brute-force multi-vector query
+    #[serde(alias = "query_vector", default)]
     vector: Vec<f32>,
     limit: Option<usize>,
     ef_search: Option<usize>,
     filter: Option<HashMap<String, serde_json::Value>>,
+    /// Named vector space to search
+    #[serde(default)]
+    using: Option<String>,
+    /// MaxSim scoring against a multi-vector space
+    #[serde(default)]
+    multivector: Option<MultiVectorQuery>,
+    /// Re-rank a quantized collection's candidates by their original vectors
+    #[serde(default)]
+    rescore: Option<bool>,
+    /// Candidates fetched per result for rescoring
+    #[serde(default)]
+    oversampling: Option<f32>,
+    /// Scan every stored vector instead of searching the index
+    #[serde(default)]
+    exact: bool,
 }
 
 /// Query parameters for search
@@ -140,6 +173,9 @@ async fn create_collection(
         vector_type: payload.vector_type,
         index_config: payload.index_config.unwrap_or_default(),
         quantization: payload.quantization,
+        sparse_vectors: payload.sparse_vectors,
+        vectors: payload.vectors,
+        multivectors: payload.multivectors,
     };
 
     match state.create_collection(&config).await {
@@ -220,6 +256,9 @@ async fn insert_vector(
         id: vector_id,
         data: payload.data,
         metadata: payload.metadata,
+        sparse: payload.sparse,
+        vectors: payload.vectors,
+        multivectors: payload.multivectors,
     };
 
     // Add timeout to prevent indefinite hangs (30 seconds default)
@@ -263,6 +302,9 @@ async fn batch_insert_vectors(
             id: vector_id,
             data: vector_req.data,
             metadata: vector_req.metadata,
+            sparse: vector_req.sparse,
+            vectors: vector_req.vectors,
+            multivectors: vector_req.multivectors,
         });
     }
 
@@ -308,6 +350,9 @@ async fn batch_upsert_vectors(
             id: vector_id,
             data: vector_req.data,
             metadata: vector_req.metadata,
+            sparse: vector_req.sparse,
+            vectors: vector_req.vectors,
+            multivectors: vector_req.multivectors,
         });
     }
 
@@ -383,6 +428,11 @@ async fn query_vectors(
         limit: payload.limit.or(params.limit).unwrap_or(10),
         ef_search: payload.ef_search.or(params.ef_search),
         filter: None,
+        using: payload.using,
+        multivector: payload.multivector,
+        rescore: payload.rescore,
+        oversampling: payload.oversampling,
+        exact: payload.exact,
     };
     
     match state.query(&query_request).await {
@@ -444,6 +494,9 @@ async fn update_vector(
         id: uuid,
         data: payload.data,
         metadata: payload.metadata,
+        sparse: payload.sparse,
+        vectors: payload.vectors,
+        multivectors: payload.multivectors,
     };
 
     match state.update(&collection_name, &vector).await {
@@ -534,6 +587,130 @@ async fn backup_collection(
     }
 }
 
+/// Compact a collection's data file to reclaim space from deletes and overwrites
+#[instrument(skip(state))]
+async fn compact_collection(
+    State(state): State<AppState>,
+    Path(collection_name): Path<String>,
+) -> Result<Json<ApiResponse<vectordb_storage::CompactionReport>>, StatusCode> {
+    match state.compact_collection(&collection_name).await {
+        Ok(report) => Ok(Json(ApiResponse::success(report))),
+        Err(e) => {
+            error!("Failed to compact collection: {}", e);
+            Ok(Json(ApiResponse::error(e.to_string())))
+        }
+    }
+}
+
+/// Train a product quantized collection's codebooks without waiting for its threshold
+#[instrument(skip(state))]
+async fn train_quantizer(
+    State(state): State<AppState>,
+    Path(collection_name): Path<String>,
+) -> Result<Json<ApiResponse<vectordb_common::quantization::TrainingStatus>>, StatusCode> {
+    match state.train_quantizer(&collection_name).await {
+        Ok(status) => Ok(Json(ApiResponse::success(status))),
+        Err(e) => {
+            error!("Failed to train quantizer: {}", e);
+            Ok(Json(ApiResponse::error(e.to_string())))
+        }
+    }
+}
+
+/// Measure the recall of a collection's index search at several `ef_search` values
+#[instrument(skip(state, request))]
+async fn evaluate_collection(
+    State(state): State<AppState>,
+    Path(collection): Path<String>,
+    Json(mut request): Json<vectordb_common::EvaluateRequest>,
+) -> Result<Json<ApiResponse<vectordb_common::EvaluateResponse>>, StatusCode> {
+    request.collection = collection;
+
+    match state.evaluate(&request).await {
+        Ok(response) => Ok(Json(ApiResponse::success(response))),
+        Err(e) => {
+            error!("Failed to evaluate collection: {}", e);
+            Ok(Json(ApiResponse::error(e.to_string())))
+        }
+    }
+}
+
+/// Payload field index creation request
+#[derive(Deserialize, Debug)]
+struct CreatePayloadIndexRequest {
+    field_name: String,
+    field_type: PayloadFieldType,
+    /// Tokenizer settings, only for `text` fields
+    #[serde(default)]
+    text_params: Option<TextIndexParams>,
+}
+
+/// Index a payload field for faster filtering
+#[instrument(skip(state))]
+async fn create_payload_index(
+    State(state): State<AppState>,
+    Path(collection_name): Path<String>,
+    Json(request): Json<CreatePayloadIndexRequest>,
+) -> Result<Json<ApiResponse<PayloadIndexInfo>>, StatusCode> {
+    let created = match (request.field_type, request.text_params) {
+        (PayloadFieldType::Text, params) => {
+            state
+                .create_text_index(&collection_name, &request.field_name, params.unwrap_or_default())
+                .await
+        }
+        (_, Some(_)) => Err(vectordb_common::VectorDbError::InvalidInput {
+            message: "text_params is only valid for text fields".to_string(),
+        }),
+        (field_type, None) => {
+            state
+                .create_payload_index(&collection_name, &request.field_name, field_type)
+                .await
+        }
+    };
+    let result = created.and_then(|()| state.list_payload_indexes(&collection_name));
+
+    match result {
+        Ok(indexes) => match indexes.into_iter().find(|i| i.field_name == request.field_name) {
+            Some(info) => Ok(Json(ApiResponse::success(info))),
+            None => Ok(Json(ApiResponse::error("Payload index was dropped concurrently".to_string()))),
+        },
+        Err(e) => {
+            error!("Failed to create payload index: {}", e);
+            Ok(Json(ApiResponse::error(e.to_string())))
+        }
+    }
+}
+
+/// List a collection's payload field indexes
+#[instrument(skip(state))]
+async fn list_payload_indexes(
+    State(state): State<AppState>,
+    Path(collection_name): Path<String>,
+) -> Result<Json<ApiResponse<Vec<PayloadIndexInfo>>>, StatusCode> {
+    match state.list_payload_indexes(&collection_name) {
+        Ok(indexes) => Ok(Json(ApiResponse::success(indexes))),
+        Err(e) => {
+            error!("Failed to list payload indexes: {}", e);
+            Ok(Json(ApiResponse::error(e.to_string())))
+        }
+    }
+}
+
+/// Drop a payload field index
+#[instrument(skip(state))]
+async fn delete_payload_index(
+    State(state): State<AppState>,
+    Path((collection_name, field_name)): Path<(String, String)>,
+) -> Result<Json<ApiResponse<bool>>, StatusCode> {
+    match state.delete_payload_index(&collection_name, &field_name).await {
+        Ok(removed) => Ok(Json(ApiResponse::success(removed))),
+        Err(e) => {
+            error!("Failed to delete payload index: {}", e);
+            Ok(Json(ApiResponse::error(e.to_string())))
+        }
+    }
+}
+
 /// List soft-deleted collections
 #[instrument(skip(state))]
 async fn list_deleted_collections(
@@ -603,6 +780,9 @@ async fn import_orphaned_collection(
         vector_type: payload.vector_type,
         index_config: IndexConfig::default(),
         quantization: None,
+        sparse_vectors: None,
+        vectors: None,
+        multivectors: None,
     };
 
     match state.import_orphaned_collection(&orphaned_path, &payload.collection_name, &config).await {
@@ -708,6 +888,43 @@ async fn scroll_points(
     }
 }
 
+/// Stream every point matching a filter as newline-delimited JSON
+///
+/// The body is chunked and read from storage a batch at a time, so whole
+/// collections can be dumped in constant memory. A failure part way through
+/// aborts the body rather than ending it cleanly.
+#[instrument(skip(state))]
+async fn export_points(
+    State(state): State<AppState>,
+    Path(collection): Path<String>,
+    Json(mut request): Json<vectordb_common::ExportRequest>,
+) -> Result<Response, StatusCode> {
+    match state.get_collection_config(&collection) {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            error!("Failed to export points: {}", e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    }
+    request.collection = collection;
+
+    let lines = ReceiverStream::new(state.export(request)).map(|batch| {
+        let mut chunk = Vec::new();
+        for point in batch? {
+            serde_json::to_writer(&mut chunk, &point)
+                .map_err(|e| vectordb_common::VectorDbError::Serialization(e.to_string()))?;
+            chunk.push(b'\n');
+        }
+        Ok::<_, vectordb_common::VectorDbError>(chunk)
+    });
+
+    Response::builder()
+        .header(header::CONTENT_TYPE, "application/x-ndjson")
+        .body(Body::from_stream(lines))
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
 /// Count points matching filter
 #[instrument(skip(state))]
 async fn count_points(
@@ -726,6 +943,78 @@ async fn count_points(
     }
 }
 
+/// Merge keys into the payloads of points selected by ID or filter
+#[instrument(skip(state))]
+async fn set_payload(
+    State(state): State<AppState>,
+    Path(collection): Path<String>,
+    Json(mut request): Json<vectordb_common::SetPayloadRequest>,
+) -> Result<Json<ApiResponse<vectordb_common::UpdatePayloadResponse>>, StatusCode> {
+    request.collection = collection;
+
+    match state.set_payload(&request).await {
+        Ok(response) => Ok(Json(ApiResponse::success(response))),
+        Err(e) => {
+            error!("Failed to set payload: {}", e);
+            Ok(Json(ApiResponse::error(e.to_string())))
+        }
+    }
+}
+
+/// Replace the payloads of points selected by ID or filter
+#[instrument(skip(state))]
+async fn overwrite_payload(
+    State(state): State<AppState>,
+    Path(collection): Path<String>,
+    Json(mut request): Json<vectordb_common::SetPayloadRequest>,
+) -> Result<Json<ApiResponse<vectordb_common::UpdatePayloadResponse>>, StatusCode> {
+    request.collection = collection;
+
+    match state.overwrite_payload(&request).await {
+        Ok(response) => Ok(Json(ApiResponse::success(response))),
+        Err(e) => {
+            error!("Failed to overwrite payload: {}", e);
+            Ok(Json(ApiResponse::error(e.to_string())))
+        }
+    }
+}
+
+/// Remove keys from the payloads of points selected by ID or filter
+#[instrument(skip(state))]
+async fn delete_payload_keys(
+    State(state): State<AppState>,
+    Path(collection): Path<String>,
+    Json(mut request): Json<vectordb_common::DeletePayloadKeysRequest>,
+) -> Result<Json<ApiResponse<vectordb_common::UpdatePayloadResponse>>, StatusCode> {
+    request.collection = collection;
+
+    match state.delete_payload_keys(&request).await {
+        Ok(response) => Ok(Json(ApiResponse::success(response))),
+        Err(e) => {
+            error!("Failed to delete payload keys: {}", e);
+            Ok(Json(ApiResponse::error(e.to_string())))
+        }
+    }
+}
+
+/// Remove the payloads of points selected by ID or filter
+#[instrument(skip(state))]
+async fn clear_payload(
+    State(state): State<AppState>,
+    Path(collection): Path<String>,
+    Json(mut request): Json<vectordb_common::ClearPayloadRequest>,
+) -> Result<Json<ApiResponse<vectordb_common::UpdatePayloadResponse>>, StatusCode> {
+    request.collection = collection;
+
+    match state.clear_payload(&request).await {
+        Ok(response) => Ok(Json(ApiResponse::success(response))),
+        Err(e) => {
+            error!("Failed to clear payload: {}", e);
+            Ok(Json(ApiResponse::error(e.to_string())))
+        }
+    }
+}
+
 /// Batch search - multiple queries in one request
 #[instrument(skip(state))]
 async fn batch_search_points(
@@ -744,6 +1033,60 @@ async fn batch_search_points(
     }
 }
 
+/// Search with results grouped by a payload field
+#[instrument(skip(state))]
+async fn search_point_groups(
+    State(state): State<AppState>,
+    Path(collection): Path<String>,
+    Json(mut request): Json<vectordb_common::GroupByRequest>,
+) -> Result<Json<ApiResponse<vectordb_common::GroupByResponse>>, StatusCode> {
+    request.collection = collection;
+
+    match state.search_groups(&request).await {
+        Ok(response) => Ok(Json(ApiResponse::success(response))),
+        Err(e) => {
+            error!("Failed to execute grouped search: {}", e);
+            Ok(Json(ApiResponse::error(e.to_string())))
+        }
+    }
+}
+
+/// Dense and sparse search fused into one ranking
+#[instrument(skip(state))]
+async fn hybrid_search_points(
+    State(state): State<AppState>,
+    Path(collection): Path<String>,
+    Json(mut request): Json<vectordb_common::HybridSearchRequest>,
+) -> Result<Json<ApiResponse<Vec<vectordb_common::ScoredPoint>>>, StatusCode> {
+    request.collection = collection;
+
+    match state.hybrid_search(&request).await {
+        Ok(points) => Ok(Json(ApiResponse::success(points))),
+        Err(e) => {
+            error!("Failed to execute hybrid search: {}", e);
+            Ok(Json(ApiResponse::error(e.to_string())))
+        }
+    }
+}
+
+/// Value distribution of a payload field
+#[instrument(skip(state))]
+async fn facet_points(
+    State(state): State<AppState>,
+    Path(collection): Path<String>,
+    Json(mut request): Json<vectordb_common::FacetRequest>,
+) -> Result<Json<ApiResponse<vectordb_common::FacetResponse>>, StatusCode> {
+    request.collection = collection;
+
+    match state.facet(&request).await {
+        Ok(response) => Ok(Json(ApiResponse::success(response))),
+        Err(e) => {
+            error!("Failed to execute facet: {}", e);
+            Ok(Json(ApiResponse::error(e.to_string())))
+        }
+    }
+}
+
 // ==================== Snapshot Handlers ====================
 
 #[derive(Serialize, Debug)]
@@ -835,6 +1178,12 @@ pub fn create_router(state: AppState) -> Router {
 
         // Recovery operations
         .route("/collections/:collection/backup", post(backup_collection))
+        .route("/collections/:collection/compact", post(compact_collection))
+        .route("/collections/:collection/quantization/train", post(train_quantizer))
+        .route("/collections/:collection/evaluate", post(evaluate_collection))
+        .route("/collections/:collection/index", put(create_payload_index))
+        .route("/collections/:collection/index", get(list_payload_indexes))
+        .route("/collections/:collection/index/:field_name", delete(delete_payload_index))
         .route("/collections/:collection/hard-delete", delete(hard_delete_collection))
         .route("/collections/deleted", get(list_deleted_collections))
         .route("/collections/restore", post(restore_collection))
@@ -850,13 +1199,21 @@ pub fn create_router(state: AppState) -> Router {
         .route("/collections/:collection/vectors/:vector_id", get(get_vector))
         .route("/collections/:collection/vectors/:vector_id", put(update_vector))
         .route("/collections/:collection/vectors/:vector_id", delete(delete_vector))
+        .route("/collections/:collection/points/payload", post(set_payload))
+        .route("/collections/:collection/points/payload", put(overwrite_payload))
+        .route("/collections/:collection/points/payload/delete", post(delete_payload_keys))
+        .route("/collections/:collection/points/payload/clear", post(clear_payload))
 
         // Advanced search operations
         .route("/collections/:collection/points/recommend", post(recommend_points))
         .route("/collections/:collection/points/discover", post(discover_points))
         .route("/collections/:collection/points/scroll", post(scroll_points))
+        .route("/collections/:collection/points/export", post(export_points))
         .route("/collections/:collection/points/count", post(count_points))
+        .route("/collections/:collection/points/facet", post(facet_points))
         .route("/collections/:collection/points/search/batch", post(batch_search_points))
+        .route("/collections/:collection/points/search/groups", post(search_point_groups))
+        .route("/collections/:collection/points/search/hybrid", post(hybrid_search_points))
 
         // Snapshot operations
         .route("/collections/:collection/snapshots", post(create_snapshot))
@@ -0,0 +1,330 @@
+use vectordb_common::{Result, VectorDbError};
+use vectordb_common::types::*;
+use crate::{CollectionFiles, CollectionStorage};
+use crate::id_index::IdIndex;
+use crate::mmap::MMapStorage;
+use crate::record::DataRecord;
+use metrics::{counter, gauge, histogram};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tracing::info;
+
+const COMPACT_DATA_FILE: &str = "vectors.bin.compact";
+const COMPACT_INDEX_FILE: &str = "index.bin.compact";
+
+/// Number of live records copied per append while rewriting the data file
+const COPY_BATCH_RECORDS: usize = 1024;
+
+/// Space usage of a collection's data file, used to decide when to compact
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct CompactionStats {
+    /// Records holding the current version of a vector
+    pub live_records: usize,
+    /// All records in the data file, including superseded writes and tombstones
+    pub total_records: usize,
+    /// Bytes of record data in the data file
+    pub data_bytes: u64,
+}
+
+impl CompactionStats {
+    /// Fraction of records that compaction would drop
+    pub fn dead_ratio(&self) -> f64 {
+        if self.total_records == 0 {
+            return 0.0;
+        }
+        1.0 - self.live_records as f64 / self.total_records as f64
+    }
+}
+
+/// Outcome of a compaction run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompactionReport {
+    pub collection: CollectionId,
+    pub records_before: usize,
+    pub records_after: usize,
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+    pub duration_ms: u64,
+}
+
+/// Remove files left behind by a compaction that was interrupted
+pub(crate) fn remove_stale_files(dir: &Path) -> Result<()> {
+    for name in [COMPACT_DATA_FILE, COMPACT_INDEX_FILE] {
+        let path = dir.join(name);
+        if path.exists() {
+            tracing::warn!("Removing leftover compaction file: {}", path.display());
+            std::fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
+
+impl CollectionStorage {
+    pub async fn compaction_stats(&self) -> Result<CompactionStats> {
+        let files = self.files.read().await;
+        Ok(CompactionStats {
+            live_records: files.id_index.len(),
+            total_records: files.id_index.record_count(),
+            data_bytes: files.data_file.position().await?,
+        })
+    }
+
+    /// Rewrite the data file with only live records and swap it in
+    ///
+    /// Live records are copied while reads and writes continue against the old
+    /// file. Writes that land during the copy are carried over under a short
+    /// exclusive lock before the new files replace the old ones by rename.
+    pub async fn compact(&self) -> Result<CompactionReport> {
+        let name = self.config.name.clone();
+        let _guard = self.compaction_lock.try_lock().map_err(|_| VectorDbError::StorageError {
+            message: format!("Compaction already running for collection '{}'", name),
+        })?;
+
+        let start = std::time::Instant::now();
+        let compact_data_path = self.dir.join(COMPACT_DATA_FILE);
+        let compact_index_path = self.dir.join(COMPACT_INDEX_FILE);
+        remove_stale_files(&self.dir)?;
+
+        gauge!("storage.compaction.running", "collection" => name.clone()).set(1.0);
+        gauge!("storage.compaction.progress", "collection" => name.clone()).set(0.0);
+
+        let new_data = MMapStorage::new(&compact_data_path).await?;
+        let mut entries: Vec<(VectorId, u64, bool)> = Vec::new();
+
+        // Phase 1: copy the live records that exist now, concurrently with traffic
+        let (records_before, copied_until) = {
+            // Writers append and then update the ID index under the read lock,
+            // so the position and the live entries are only consistent with
+            // each other when taken with writers excluded
+            let files = self.files.write().await;
+            let records_before = files.id_index.record_count();
+            let copied_until = files.data_file.position().await?;
+            let mut live = files.id_index.live_entries();
+            let files = files.downgrade();
+
+            live.sort_unstable_by_key(|(_, offset)| *offset);
+            let total = live.len().max(1);
+
+            for (batch_index, batch) in live.chunks(COPY_BATCH_RECORDS).enumerate() {
+                let mut buffer = Vec::new();
+                let mut relative = Vec::with_capacity(batch.len());
+
+                for (id, offset) in batch {
+                    let record = files.data_file.read_record(*offset).await?;
+                    relative.push((*id, buffer.len() as u64));
+                    buffer.extend_from_slice(&(record.len() as u32).to_le_bytes());
+                    buffer.extend_from_slice(&record);
+                }
+
+                let base = new_data.append(&buffer).await?;
+                entries.extend(relative.into_iter().map(|(id, rel)| (id, base + rel, false)));
+
+                let done = (batch_index * COPY_BATCH_RECORDS + batch.len()) as f64;
+                gauge!("storage.compaction.progress", "collection" => name.clone())
+                    .set(done / total as f64);
+            }
+
+            (records_before, copied_until)
+        };
+
+        // Phase 2: block writers, carry over anything appended since the copy
+        // started, then swap the new files in
+        let mut files = self.files.write().await;
+        let bytes_before = files.data_file.position().await?;
+
+        {
+            let mut iter = files.data_file.iter_from(copied_until).await?;
+            while let Some(record) = iter.next().await? {
+                let entry = match DataRecord::decode(&record) {
+                    Ok(DataRecord::Vector(vector)) => Some((vector.id, false)),
+                    Ok(DataRecord::Tombstone(id)) => Some((id, true)),
+                    Err(e) => {
+                        tracing::warn!("Dropping unreadable record during compaction of '{}': {}", name, e);
+                        None
+                    }
+                };
+
+                if let Some((id, deleted)) = entry {
+                    let mut buffer = Vec::with_capacity(4 + record.len());
+                    buffer.extend_from_slice(&(record.len() as u32).to_le_bytes());
+                    buffer.extend_from_slice(&record);
+                    let offset = new_data.append(&buffer).await?;
+                    entries.push((id, offset, deleted));
+                }
+            }
+        }
+
+        new_data.sync().await?;
+        let new_index = IdIndex::create(&compact_index_path, &entries).await?;
+        new_index.sync().await?;
+
+        // Drop the old index first: if we crash before both renames complete,
+        // the index is rebuilt from whichever data file is in place
+        let data_path = self.dir.join("vectors.bin");
+        let index_path = self.dir.join("index.bin");
+        if index_path.exists() {
+            std::fs::remove_file(&index_path)?;
+        }
+        std::fs::rename(&compact_data_path, &data_path)?;
+        std::fs::rename(&compact_index_path, &index_path)?;
+
+        let bytes_after = new_data.position().await?;
+        let records_after = new_index.record_count();
+        *files = CollectionFiles {
+            data_file: new_data,
+            id_index: new_index,
+        };
+        drop(files);
+
+        let duration = start.elapsed();
+        gauge!("storage.compaction.progress", "collection" => name.clone()).set(1.0);
+        gauge!("storage.compaction.running", "collection" => name.clone()).set(0.0);
+        counter!("storage.compaction.runs").increment(1);
+        counter!("storage.compaction.bytes_reclaimed").increment(bytes_before.saturating_sub(bytes_after));
+        histogram!("storage.compaction.duration").record(duration.as_secs_f64());
+
+        info!(
+            "Compacted collection '{}': {} -> {} records, {} -> {} bytes in {:?}",
+            name, records_before, records_after, bytes_before, bytes_after, duration
+        );
+
+        Ok(CompactionReport {
+            collection: name,
+            records_before,
+            records_after,
+            bytes_before,
+            bytes_after,
+            duration_ms: duration.as_millis() as u64,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn test_config() -> CollectionConfig {
+        CollectionConfig {
+            name: "compact".to_string(),
+            dimension: 2,
+            distance_metric: DistanceMetric::Euclidean,
+            vector_type: VectorType::Float32,
+            index_config: IndexConfig::default(),
+            quantization: None,
+            sparse_vectors: None,
+            vectors: None,
+            multivectors: None,
+        }
+    }
+
+    fn test_vector(value: f32) -> Vector {
+        Vector {
+            id: uuid::Uuid::new_v4(),
+            data: vec![value, value],
+            metadata: None,
+            sparse: None,
+            vectors: None,
+            multivectors: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_compaction_drops_dead_records() {
+        let temp_dir = tempdir().unwrap();
+        let dir = temp_dir.path().join("compact");
+        let storage = CollectionStorage::new(&dir, test_config()).await.unwrap();
+
+        let vectors: Vec<Vector> = (0..10).map(|i| test_vector(i as f32)).collect();
+        storage.batch_insert(&vectors).await.unwrap();
+
+        // Overwrite two vectors and delete three
+        let mut updated = vectors[0].clone();
+        updated.data = vec![100.0, 100.0];
+        storage.insert(&updated).await.unwrap();
+        storage.insert(&vectors[1]).await.unwrap();
+        for vector in &vectors[7..] {
+            assert!(storage.delete(&vector.id).await.unwrap());
+        }
+
+        let stats = storage.compaction_stats().await.unwrap();
+        assert_eq!(stats.live_records, 7);
+        assert_eq!(stats.total_records, 15);
+        assert!(stats.dead_ratio() > 0.5);
+
+        let report = storage.compact().await.unwrap();
+        assert_eq!(report.records_before, 15);
+        assert_eq!(report.records_after, 7);
+        assert!(report.bytes_after < report.bytes_before);
+
+        let stats = storage.compaction_stats().await.unwrap();
+        assert_eq!(stats.dead_ratio(), 0.0);
+        assert_eq!(storage.get(&vectors[0].id).await.unwrap().unwrap().data, vec![100.0, 100.0]);
+        assert!(storage.get(&vectors[8].id).await.unwrap().is_none());
+        assert_eq!(storage.iter_vectors().await.unwrap().len(), 7);
+
+        // Writes after compaction go to the new file and survive a reload
+        let late = test_vector(42.0);
+        storage.insert(&late).await.unwrap();
+        storage.sync().await.unwrap();
+        drop(storage);
+
+        let storage = CollectionStorage::load(&dir).await.unwrap();
+        assert_eq!(storage.iter_vectors().await.unwrap().len(), 8);
+        assert_eq!(storage.get(&late.id).await.unwrap().unwrap().data, late.data);
+        assert!(!dir.join(COMPACT_DATA_FILE).exists());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_compaction_keeps_concurrent_writes() {
+        let temp_dir = tempdir().unwrap();
+        let dir = temp_dir.path().join("compact");
+        let storage = std::sync::Arc::new(CollectionStorage::new(&dir, test_config()).await.unwrap());
+
+        let seeded: Vec<Vector> = (0..200).map(|i| test_vector(i as f32)).collect();
+        storage.batch_insert(&seeded).await.unwrap();
+
+        // Each writer deletes every other vector it inserts, so half of what
+        // is written during compaction ends up deleted
+        let writers: Vec<_> = (0..8)
+            .map(|_| {
+                let storage = storage.clone();
+                tokio::spawn(async move {
+                    let mut kept = Vec::new();
+                    let mut deleted = Vec::new();
+                    for i in 0..200 {
+                        let vector = test_vector(i as f32);
+                        storage.insert(&vector).await.unwrap();
+                        if i % 2 == 1 {
+                            assert!(storage.delete(&vector.id).await.unwrap());
+                            deleted.push(vector.id);
+                        } else {
+                            kept.push(vector);
+                        }
+                    }
+                    (kept, deleted)
+                })
+            })
+            .collect();
+
+        while !writers.iter().all(|writer| writer.is_finished()) {
+            storage.compact().await.unwrap();
+        }
+        let mut kept = Vec::new();
+        let mut deleted = Vec::new();
+        for writer in writers {
+            let (k, d) = writer.await.unwrap();
+            kept.extend(k);
+            deleted.extend(d);
+        }
+        storage.compact().await.unwrap();
+
+        for vector in seeded.iter().chain(&kept) {
+            assert_eq!(storage.get(&vector.id).await.unwrap().unwrap().data, vector.data);
+        }
+        for id in &deleted {
+            assert!(storage.get(id).await.unwrap().is_none());
+        }
+        assert_eq!(storage.iter_vectors().await.unwrap().len(), seeded.len() + kept.len());
+    }
+}
@@ -0,0 +1,446 @@
+use vectordb_common::{Result, VectorDbError};
+use vectordb_common::types::*;
+use crate::mmap::MMapStorage;
+use crate::record::DataRecord;
+use std::path::Path;
+use std::collections::{BinaryHeap, HashMap};
+use parking_lot::RwLock;
+use tracing::{info, warn};
+
+/// Size of a serialized index entry: 16-byte UUID + 8-byte offset + 1-byte kind
+const ENTRY_SIZE: usize = 25;
+
+const ENTRY_LIVE: u8 = 0;
+const ENTRY_DELETED: u8 = 1;
+
+/// Persistent mapping from vector ID to record offset in `vectors.bin`
+///
+/// Entries are appended to `index.bin` as length-prefixed records so the file
+/// can be scanned with the same machinery as the data file. Later entries for
+/// the same ID supersede earlier ones, which gives last-write-wins semantics
+/// for upserts without rewriting the file. Deletions are recorded with the
+/// offset of their tombstone so that a later re-insert can supersede them.
+pub struct IdIndex {
+    file: MMapStorage,
+    state: RwLock<IndexState>,
+}
+
+#[derive(Default)]
+struct IndexState {
+    /// Offset of the newest record for each live ID
+    live: HashMap<VectorId, u64>,
+    /// Offset of the newest tombstone for each deleted ID
+    deleted: HashMap<VectorId, u64>,
+    /// Number of data file records indexed, including superseded ones
+    records: usize,
+}
+
+impl IndexState {
+    /// The data file is append-only, so the record with the highest offset for
+    /// an ID is the newest one, regardless of the order entries were written in
+    fn apply(&mut self, id: VectorId, offset: u64, deleted: bool) {
+        self.records += 1;
+
+        let newest = self.live.get(&id).into_iter()
+            .chain(self.deleted.get(&id))
+            .max()
+            .copied();
+        if newest.is_some_and(|newest| newest > offset) {
+            return;
+        }
+
+        if deleted {
+            self.live.remove(&id);
+            self.deleted.insert(id, offset);
+        } else {
+            self.deleted.remove(&id);
+            self.live.insert(id, offset);
+        }
+    }
+}
+
+impl IdIndex {
+    /// Open the index at `path`, rebuilding it from `data_file` if it is
+    /// missing, corrupt or behind the data file
+    pub async fn open<P: AsRef<Path>>(path: P, data_file: &MMapStorage) -> Result<Self> {
+        let path = path.as_ref();
+
+        match Self::load(path, data_file).await {
+            Ok(index) => Ok(index),
+            Err(e) => {
+                warn!("ID index at {} is unusable ({}), rebuilding from data file", path.display(), e);
+                if path.exists() {
+                    std::fs::remove_file(path)?;
+                }
+
+                let index = Self {
+                    file: MMapStorage::new(path).await?,
+                    state: RwLock::new(IndexState::default()),
+                };
+                index.catch_up(data_file, 0).await?;
+                Ok(index)
+            }
+        }
+    }
+
+    async fn load(path: &Path, data_file: &MMapStorage) -> Result<Self> {
+        let file = MMapStorage::new(path).await?;
+        let mut state = IndexState::default();
+        let mut last_entry: Option<(VectorId, u64)> = None;
+
+        {
+            let mut iter = file.iter().await?;
+            while let Some(entry) = iter.next().await? {
+                let (id, offset, deleted) = decode_entry(&entry)?;
+                state.apply(id, offset, deleted);
+                if last_entry.is_none_or(|(_, last)| offset > last) {
+                    last_entry = Some((id, offset));
+                }
+            }
+        }
+
+        // Resume from the end of the newest indexed record; anything after it
+        // was written to the data file but never made it into the index
+        let resume_from = match last_entry {
+            Some((id, offset)) => {
+                let record = data_file.read_record(offset).await?;
+                let record_id = DataRecord::decode(&record)?.id();
+                if record_id != id {
+                    return Err(VectorDbError::Corruption {
+                        message: format!(
+                            "ID index expects {} at offset {} but data file has {}",
+                            id, offset, record_id
+                        ),
+                    });
+                }
+                offset + 4 + record.len() as u64
+            }
+            None => 0,
+        };
+
+        let index = Self {
+            file,
+            state: RwLock::new(state),
+        };
+        index.catch_up(data_file, resume_from).await?;
+
+        Ok(index)
+    }
+
+    /// Create a fresh index at `path` holding exactly `entries`
+    ///
+    /// Each entry is `(id, offset, deleted)`. Used by compaction to write the
+    /// index for a rewritten data file.
+    pub(crate) async fn create<P: AsRef<Path>>(path: P, entries: &[(VectorId, u64, bool)]) -> Result<Self> {
+        let path = path.as_ref();
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+
+        let index = Self {
+            file: MMapStorage::new(path).await?,
+            state: RwLock::new(IndexState::default()),
+        };
+        index.append_entries(entries).await?;
+
+        Ok(index)
+    }
+
+    /// Index every record in `data_file` starting at `position`
+    async fn catch_up(&self, data_file: &MMapStorage, position: u64) -> Result<()> {
+        let mut entries = Vec::new();
+        let mut iter = data_file.iter_from(position).await?;
+
+        loop {
+            let offset = iter.position();
+            let Some(data) = iter.next().await? else {
+                break;
+            };
+
+            match DataRecord::decode(&data) {
+                Ok(DataRecord::Vector(vector)) => entries.push((vector.id, offset, false)),
+                Ok(DataRecord::Tombstone(id)) => entries.push((id, offset, true)),
+                Err(e) => warn!("Skipping unreadable record at offset {} while indexing: {}", offset, e),
+            }
+        }
+
+        if !entries.is_empty() {
+            info!("Indexed {} records missing from ID index", entries.len());
+            self.append_entries(&entries).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Record the offset of a newly written vector
+    pub async fn insert(&self, id: VectorId, offset: u64) -> Result<()> {
+        self.insert_batch(&[(id, offset)]).await
+    }
+
+    /// Record the offsets of a batch of newly written vectors with a single append
+    pub async fn insert_batch(&self, entries: &[(VectorId, u64)]) -> Result<()> {
+        let entries: Vec<(VectorId, u64, bool)> = entries
+            .iter()
+            .map(|(id, offset)| (*id, *offset, false))
+            .collect();
+        self.append_entries(&entries).await
+    }
+
+    /// Record the offset of a tombstone written for `id`
+    pub async fn remove(&self, id: VectorId, tombstone_offset: u64) -> Result<()> {
+        self.append_entries(&[(id, tombstone_offset, true)]).await
+    }
+
+    async fn append_entries(&self, entries: &[(VectorId, u64, bool)]) -> Result<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+
+        let mut buffer = Vec::with_capacity(entries.len() * (4 + ENTRY_SIZE));
+        for (id, offset, deleted) in entries {
+            buffer.extend_from_slice(&(ENTRY_SIZE as u32).to_le_bytes());
+            buffer.extend_from_slice(id.as_bytes());
+            buffer.extend_from_slice(&offset.to_le_bytes());
+            buffer.push(if *deleted { ENTRY_DELETED } else { ENTRY_LIVE });
+        }
+
+        self.file.append(&buffer).await?;
+
+        let mut state = self.state.write();
+        for (id, offset, deleted) in entries {
+            state.apply(*id, *offset, *deleted);
+        }
+
+        Ok(())
+    }
+
+    /// Look up the offset of the live record for `id`
+    pub fn get(&self, id: &VectorId) -> Option<u64> {
+        self.state.read().live.get(id).copied()
+    }
+
+    /// Number of live IDs in the index
+    pub fn len(&self) -> usize {
+        self.state.read().live.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.state.read().live.is_empty()
+    }
+
+    /// Number of IDs whose newest record is a tombstone
+    pub fn deleted_count(&self) -> usize {
+        self.state.read().deleted.len()
+    }
+
+    /// Number of data file records covered by the index, live or not
+    pub fn record_count(&self) -> usize {
+        self.state.read().records
+    }
+
+    /// The `limit` smallest live IDs greater than `after`, in ascending order
+    ///
+    /// Scans every ID but only holds `limit` of them, so paging through the
+    /// collection costs memory proportional to the page.
+    pub fn ids_after(&self, after: Option<&VectorId>, limit: usize) -> Vec<VectorId> {
+        let state = self.state.read();
+        // Max-heap of the smallest IDs seen so far
+        let mut smallest = BinaryHeap::with_capacity(limit + 1);
+        for id in state.live.keys().filter(|id| after.is_none_or(|after| *id > after)) {
+            if smallest.len() < limit {
+                smallest.push(*id);
+            } else if smallest.peek().is_some_and(|largest| id < largest) {
+                smallest.pop();
+                smallest.push(*id);
+            }
+        }
+        smallest.into_sorted_vec()
+    }
+
+    /// Snapshot of the live `(id, offset)` pairs
+    pub fn live_entries(&self) -> Vec<(VectorId, u64)> {
+        self.state.read().live.iter().map(|(id, offset)| (*id, *offset)).collect()
+    }
+
+    /// Size of the backing file in bytes
+    pub async fn size(&self) -> Result<u64> {
+        self.file.size().await
+    }
+
+    pub async fn sync(&self) -> Result<()> {
+        self.file.sync().await
+    }
+}
+
+fn decode_entry(entry: &[u8]) -> Result<(VectorId, u64, bool)> {
+    if entry.len() != ENTRY_SIZE {
+        return Err(VectorDbError::Corruption {
+            message: format!("ID index entry has length {}, expected {}", entry.len(), ENTRY_SIZE),
+        });
+    }
+
+    let mut id_bytes = [0u8; 16];
+    id_bytes.copy_from_slice(&entry[..16]);
+    let mut offset_bytes = [0u8; 8];
+    offset_bytes.copy_from_slice(&entry[16..24]);
+
+    let deleted = match entry[24] {
+        ENTRY_LIVE => false,
+        ENTRY_DELETED => true,
+        kind => {
+            return Err(VectorDbError::Corruption {
+                message: format!("Unknown ID index entry kind {}", kind),
+            })
+        }
+    };
+
+    Ok((uuid::Uuid::from_bytes(id_bytes), u64::from_le_bytes(offset_bytes), deleted))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    async fn write_vector(data_file: &MMapStorage, vector: &Vector) -> u64 {
+        let mut record = Vec::new();
+        crate::record::encode_vector(&mut record, vector, VectorType::Float32).unwrap();
+        data_file.append(&record).await.unwrap()
+    }
+
+    async fn write_tombstone(data_file: &MMapStorage, id: &VectorId) -> u64 {
+        let mut record = Vec::new();
+        crate::record::encode_tombstone(&mut record, id);
+        data_file.append(&record).await.unwrap()
+    }
+
+    fn test_vector(value: f32) -> Vector {
+        Vector {
+            id: uuid::Uuid::new_v4(),
+            data: vec![value, value],
+            metadata: None,
+            sparse: None,
+            vectors: None,
+            multivectors: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_index_persists_across_reopen() {
+        let temp_dir = tempdir().unwrap();
+        let data_file = MMapStorage::new(temp_dir.path().join("vectors.bin")).await.unwrap();
+        let index_path = temp_dir.path().join("index.bin");
+
+        let v1 = test_vector(1.0);
+        let v2 = test_vector(2.0);
+        let o1 = write_vector(&data_file, &v1).await;
+        let o2 = write_vector(&data_file, &v2).await;
+
+        {
+            let index = IdIndex::open(&index_path, &data_file).await.unwrap();
+            assert_eq!(index.len(), 2);
+            assert_eq!(index.get(&v1.id), Some(o1));
+            assert_eq!(index.get(&v2.id), Some(o2));
+            index.sync().await.unwrap();
+        }
+
+        // A record written after the index was closed is picked up on reopen
+        let v3 = test_vector(3.0);
+        let o3 = write_vector(&data_file, &v3).await;
+
+        let index = IdIndex::open(&index_path, &data_file).await.unwrap();
+        assert_eq!(index.len(), 3);
+        assert_eq!(index.get(&v1.id), Some(o1));
+        assert_eq!(index.get(&v3.id), Some(o3));
+    }
+
+    #[tokio::test]
+    async fn test_index_rebuilt_when_missing() {
+        let temp_dir = tempdir().unwrap();
+        let data_file = MMapStorage::new(temp_dir.path().join("vectors.bin")).await.unwrap();
+        let index_path = temp_dir.path().join("index.bin");
+
+        let v1 = test_vector(1.0);
+        let o1 = write_vector(&data_file, &v1).await;
+
+        // Upsert of the same ID supersedes the earlier record
+        let mut v1_updated = v1.clone();
+        v1_updated.data = vec![9.0, 9.0];
+        let o1_updated = write_vector(&data_file, &v1_updated).await;
+        assert_ne!(o1, o1_updated);
+
+        let index = IdIndex::open(&index_path, &data_file).await.unwrap();
+        assert_eq!(index.len(), 1);
+        assert_eq!(index.get(&v1.id), Some(o1_updated));
+    }
+
+    #[tokio::test]
+    async fn test_tombstones_survive_reopen() {
+        let temp_dir = tempdir().unwrap();
+        let data_file = MMapStorage::new(temp_dir.path().join("vectors.bin")).await.unwrap();
+        let index_path = temp_dir.path().join("index.bin");
+
+        let v1 = test_vector(1.0);
+        let v2 = test_vector(2.0);
+        write_vector(&data_file, &v1).await;
+        write_vector(&data_file, &v2).await;
+
+        {
+            let index = IdIndex::open(&index_path, &data_file).await.unwrap();
+            let tombstone = write_tombstone(&data_file, &v1.id).await;
+            index.remove(v1.id, tombstone).await.unwrap();
+            assert_eq!(index.get(&v1.id), None);
+            assert_eq!(index.len(), 1);
+        }
+
+        let index = IdIndex::open(&index_path, &data_file).await.unwrap();
+        assert_eq!(index.get(&v1.id), None);
+        assert_eq!(index.deleted_count(), 1);
+
+        // Re-inserting after a delete brings the ID back
+        let o1 = write_vector(&data_file, &v1).await;
+        index.insert(v1.id, o1).await.unwrap();
+        assert_eq!(index.get(&v1.id), Some(o1));
+        assert_eq!(index.deleted_count(), 0);
+
+        // A rebuild from the data file alone reaches the same state
+        drop(index);
+        std::fs::remove_file(&index_path).unwrap();
+        write_tombstone(&data_file, &v2.id).await;
+        let index = IdIndex::open(&index_path, &data_file).await.unwrap();
+        assert_eq!(index.get(&v1.id), Some(o1));
+        assert_eq!(index.get(&v2.id), None);
+    }
+
+    #[tokio::test]
+    async fn test_ids_after_pages_in_order() {
+        let temp_dir = tempdir().unwrap();
+        let data_file = MMapStorage::new(temp_dir.path().join("vectors.bin")).await.unwrap();
+        let index = IdIndex::open(temp_dir.path().join("index.bin"), &data_file).await.unwrap();
+
+        let mut ids = Vec::new();
+        for i in 0..10 {
+            let vector = test_vector(i as f32);
+            let offset = write_vector(&data_file, &vector).await;
+            index.insert(vector.id, offset).await.unwrap();
+            ids.push(vector.id);
+        }
+        let tombstone = write_tombstone(&data_file, &ids[4]).await;
+        index.remove(ids[4], tombstone).await.unwrap();
+        let removed = ids.remove(4);
+        ids.sort();
+
+        let mut paged = Vec::new();
+        let mut after = None;
+        loop {
+            let page = index.ids_after(after.as_ref(), 4);
+            if page.is_empty() {
+                break;
+            }
+            after = page.last().copied();
+            paged.extend(page);
+        }
+        assert_eq!(paged, ids);
+        assert!(!paged.contains(&removed));
+    }
+}
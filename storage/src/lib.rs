@@ -2,6 +2,9 @@ pub mod wal;
 pub mod mmap;
 pub mod recovery;
 pub mod snapshot;
+pub mod id_index;
+pub mod record;
+pub mod compaction;
 
 use vectordb_common::{Result, VectorDbError};
 use vectordb_common::types::*;
@@ -14,6 +17,9 @@ pub use wal::*;
 pub use mmap::*;
 pub use recovery::*;
 pub use snapshot::*;
+pub use id_index::*;
+pub use record::*;
+pub use compaction::*;
 
 /// Storage engine for persistent vector storage with WAL
 pub struct StorageEngine {
@@ -263,25 +269,14 @@ impl StorageEngine {
     }
     
     pub async fn get_collection_stats(&self, name: &str) -> Result<Option<CollectionStats>> {
-        // We need to restructure this to avoid holding the lock across the await
-        // For now, let's get the config synchronously and compute stats differently
-        let config = {
+        let storage = {
             let collections = self.collections.read();
-            collections.get(name).map(|s| s.config().clone())
+            collections.get(name).cloned()
         };
-        
-        if let Some(_config) = config {
-            // TODO: Implement proper async stats collection without holding locks
-            // For now return a basic stats structure
-            Ok(Some(CollectionStats {
-                name: name.to_string(),
-                vector_count: 0,  // TODO: get actual count
-                dimension: _config.dimension,
-                index_size: 0,   // TODO: get actual size
-                memory_usage: 0, // TODO: get actual usage
-            }))
-        } else {
-            Ok(None)
+
+        match storage {
+            Some(storage) => Ok(Some(storage.stats().await?)),
+            None => Ok(None),
         }
     }
     
@@ -316,6 +311,125 @@ impl StorageEngine {
         storage.iter_vectors().await
     }
 
+    /// Report how much of a collection's data file is dead weight
+    pub async fn get_compaction_stats(&self, collection: &str) -> Result<CompactionStats> {
+        let storage = {
+            let collections = self.collections.read();
+            collections
+                .get(collection)
+                .ok_or_else(|| VectorDbError::CollectionNotFound {
+                    name: collection.to_string(),
+                })?
+                .clone()
+        };
+
+        storage.compaction_stats().await
+    }
+
+    /// Rewrite a collection's data file without superseded records and tombstones
+    pub async fn compact_collection(&self, collection: &str) -> Result<CompactionReport> {
+        let storage = {
+            let collections = self.collections.read();
+            collections
+                .get(collection)
+                .ok_or_else(|| VectorDbError::CollectionNotFound {
+                    name: collection.to_string(),
+                })?
+                .clone()
+        };
+
+        storage.compact().await
+    }
+
+    /// Mark the current end of a collection's data file
+    pub async fn get_data_mark(&self, collection: &str) -> Result<DataMark> {
+        let storage = {
+            let collections = self.collections.read();
+            collections
+                .get(collection)
+                .ok_or_else(|| VectorDbError::CollectionNotFound {
+                    name: collection.to_string(),
+                })?
+                .clone()
+        };
+
+        storage.data_mark().await
+    }
+
+    /// Records written to a collection since `mark`, or `None` if the mark is stale
+    pub async fn get_records_since(&self, collection: &str, mark: &DataMark) -> Result<Option<Vec<DataRecord>>> {
+        let storage = {
+            let collections = self.collections.read();
+            collections
+                .get(collection)
+                .ok_or_else(|| VectorDbError::CollectionNotFound {
+                    name: collection.to_string(),
+                })?
+                .clone()
+        };
+
+        storage.records_since(mark).await
+    }
+
+    /// The `limit` smallest live IDs greater than `after`, in ascending order
+    pub async fn get_ids_after(&self, collection: &str, after: Option<&VectorId>, limit: usize) -> Result<Vec<VectorId>> {
+        let storage = {
+            let collections = self.collections.read();
+            collections
+                .get(collection)
+                .ok_or_else(|| VectorDbError::CollectionNotFound {
+                    name: collection.to_string(),
+                })?
+                .clone()
+        };
+
+        Ok(storage.ids_after(after, limit).await)
+    }
+
+    /// Apply a payload update to the vectors with the given IDs, returning them
+    /// as updated; IDs with no live vector are skipped
+    pub async fn update_payload(&self, collection: &str, ids: &[VectorId], update: &PayloadUpdate) -> Result<Vec<Vector>> {
+        let storage = {
+            let collections = self.collections.read();
+            collections
+                .get(collection)
+                .ok_or_else(|| VectorDbError::CollectionNotFound {
+                    name: collection.to_string(),
+                })?
+                .clone()
+        };
+
+        let op = WALOperation::UpdatePayload {
+            collection: collection.to_string(),
+            ids: ids.to_vec(),
+            update: update.clone(),
+        };
+        self.wal.append(&op).await?;
+
+        storage.update_payload(ids, update).await
+    }
+
+    /// Up to `limit` live vectors stored after `mark` and the mark to continue
+    /// from, or `None` if compaction has rewritten the data file since
+    pub async fn get_vectors_since(
+        &self,
+        collection: &str,
+        mark: &DataMark,
+        limit: usize,
+    ) -> Result<Option<(Vec<Vector>, DataMark)>> {
+        let storage = {
+            let collections = self.collections.read();
+            collections
+                .get(collection)
+                .ok_or_else(|| VectorDbError::CollectionNotFound {
+                    name: collection.to_string(),
+                })?
+                .clone()
+        };
+
+        storage.vectors_since(mark, limit).await
+    }
+
     /// Get recovery manager for backup/restore operations
     pub fn get_recovery_manager(&self) -> RecoveryManager {
         RecoveryManager::new(&self.data_dir)
@@ -348,6 +462,17 @@ impl StorageEngine {
         Ok(())
     }
 
+    /// Stop serving a collection whose directory has been moved away (soft delete)
+    pub async fn unregister_collection(&self, name: &str) -> Result<()> {
+        let op = WALOperation::DeleteCollection(name.to_string());
+        self.wal.append(&op).await?;
+
+        self.collections.write().remove(name);
+
+        tracing::info!("Unregistered collection: {}", name);
+        Ok(())
+    }
+
     /// Get the data directory path
     pub fn get_data_dir(&self) -> &Path {
         &self.data_dir
@@ -414,6 +539,17 @@ impl StorageEngine {
                     storage.delete(&id).await?;
                 }
             }
+            WALOperation::UpdatePayload { collection, ids, update } => {
+                // Clone the storage reference to avoid holding the lock across await points
+                let storage = {
+                    let collections = self.collections.read();
+                    collections.get(&collection).cloned()
+                };
+
+                if let Some(storage) = storage {
+                    storage.update_payload(&ids, &update).await?;
+                }
+            }
         }
         
         Ok(())
@@ -423,27 +559,61 @@ impl StorageEngine {
 /// Storage for a single collection
 pub struct CollectionStorage {
     config: CollectionConfig,
-    data_file: MMapStorage,
-    index_file: MMapStorage,
+    dir: PathBuf,
+    /// Data file and ID index, swapped out wholesale by compaction
+    files: tokio::sync::RwLock<CollectionFiles>,
+    /// Serializes compaction runs for this collection
+    compaction_lock: tokio::sync::Mutex<()>,
     metadata_path: PathBuf,
 }
 
+struct CollectionFiles {
+    data_file: MMapStorage,
+    id_index: IdIndex,
+}
+
+/// Vectors serialized back to back, ready for a single append
+struct EncodedBatch {
+    batch_buffer: Vec<u8>,
+    /// Each vector's offset within `batch_buffer`
+    relative_offsets: Vec<(VectorId, u64)>,
+}
+
+impl CollectionFiles {
+    async fn open(dir: &Path) -> Result<Self> {
+        // Leftovers from a compaction that did not finish are never referenced
+        compaction::remove_stale_files(dir)?;
+
+        let data_file = MMapStorage::new(dir.join("vectors.bin")).await?;
+        let id_index = IdIndex::open(dir.join("index.bin"), &data_file).await?;
+
+        Ok(Self { data_file, id_index })
+    }
+}
+
+/// Contents of a collection's `metadata.json`
+#[derive(serde::Serialize, serde::Deserialize)]
+struct CollectionMetadata {
+    #[serde(flatten)]
+    config: CollectionConfig,
+    /// Live vector count as of the last save
+    #[serde(default)]
+    vector_count: usize,
+}
+
 impl CollectionStorage {
     async fn new<P: AsRef<Path>>(dir: P, config: CollectionConfig) -> Result<Self> {
         let dir = dir.as_ref();
         std::fs::create_dir_all(dir)?;
 
-        let data_path = dir.join("vectors.bin");
-        let index_path = dir.join("index.bin");
         let metadata_path = dir.join("metadata.json");
-
-        let data_file = MMapStorage::new(data_path).await?;
-        let index_file = MMapStorage::new(index_path).await?;
+        let files = CollectionFiles::open(dir).await?;
 
         let storage = Self {
             config: config.clone(),
-            data_file,
-            index_file,
+            dir: dir.to_path_buf(),
+            files: tokio::sync::RwLock::new(files),
+            compaction_lock: tokio::sync::Mutex::new(()),
             metadata_path: metadata_path.clone(),
         };
 
@@ -462,28 +632,38 @@ impl CollectionStorage {
         let metadata_content = std::fs::read_to_string(&metadata_path)
             .map_err(|e| VectorDbError::Io(e))?;
 
-        let config: CollectionConfig = serde_json::from_str(&metadata_content)
+        let metadata: CollectionMetadata = serde_json::from_str(&metadata_content)
             .map_err(|e| VectorDbError::Serialization(format!("Failed to deserialize metadata: {}", e)))?;
-
-        let data_path = dir.join("vectors.bin");
-        let index_path = dir.join("index.bin");
-
-        let data_file = MMapStorage::new(data_path).await?;
-        let index_file = MMapStorage::new(index_path).await?;
+        let config = metadata.config;
+
+        let files = CollectionFiles::open(dir).await?;
+        if files.id_index.len() != metadata.vector_count {
+            tracing::debug!(
+                "Collection '{}' has {} live vectors, metadata recorded {}",
+                config.name,
+                files.id_index.len(),
+                metadata.vector_count
+            );
+        }
 
         tracing::info!("Loaded collection '{}' from metadata", config.name);
 
         Ok(Self {
             config,
-            data_file,
-            index_file,
+            dir: dir.to_path_buf(),
+            files: tokio::sync::RwLock::new(files),
+            compaction_lock: tokio::sync::Mutex::new(()),
             metadata_path,
         })
     }
 
     /// Save collection metadata to disk
     async fn save_metadata(&self) -> Result<()> {
-        let metadata_json = serde_json::to_string_pretty(&self.config)
+        let metadata = CollectionMetadata {
+            config: self.config.clone(),
+            vector_count: self.files.read().await.id_index.len(),
+        };
+        let metadata_json = serde_json::to_string_pretty(&metadata)
             .map_err(|e| VectorDbError::Serialization(format!("Failed to serialize metadata: {}", e)))?;
 
         std::fs::write(&self.metadata_path, metadata_json)
@@ -498,23 +678,14 @@ impl CollectionStorage {
     }
     
     async fn insert(&self, vector: &Vector) -> Result<()> {
-        if vector.data.len() != self.config.dimension {
-            return Err(VectorDbError::InvalidDimension {
-                expected: self.config.dimension,
-                actual: vector.data.len(),
-            });
-        }
+        self.config.validate_default_vector(&vector.data)?;
 
-        let serialized = bincode::serialize(vector)
-            .map_err(|e| VectorDbError::Serialization(e.to_string()))?;
+        let mut record = Vec::with_capacity(4 + self.config.dimension * self.config.vector_type.element_size() + 100);
+        encode_vector(&mut record, vector, self.config.vector_type)?;
 
-        // Write length prefix (4 bytes, u32 little-endian) + data
-        let length = serialized.len() as u32;
-        let mut record = Vec::with_capacity(4 + serialized.len());
-        record.extend_from_slice(&length.to_le_bytes());
-        record.extend_from_slice(&serialized);
-
-        self.data_file.append(&record).await?;
+        let files = self.files.read().await;
+        let offset = files.data_file.append(&record).await?;
+        files.id_index.insert(vector.id, offset).await?;
 
         Ok(())
     }
@@ -526,73 +697,236 @@ impl CollectionStorage {
 
         // Validate all vectors first
         for vector in vectors {
-            if vector.data.len() != self.config.dimension {
-                return Err(VectorDbError::InvalidDimension {
-                    expected: self.config.dimension,
-                    actual: vector.data.len(),
-                });
-            }
+            self.config.validate_default_vector(&vector.data)?;
         }
 
-        // Serialize all vectors into a single buffer to reduce async calls
-        // This is much faster than calling append() for each vector
-        // Format: [length_prefix(4 bytes)][serialized_data][length_prefix][data]...
-        let mut batch_buffer = Vec::with_capacity(vectors.len() * (self.config.dimension * 4 + 100));
+        let batch = self.encode_batch(vectors)?;
+        let files = self.files.read().await;
+        Self::append_batch(&files, batch).await
+    }
+
+    /// Serialize vectors into a single buffer to reduce async calls, along
+    /// with each one's offset within it
+    ///
+    /// This is much faster than calling append() for each vector.
+    /// Format: [length_prefix(4 bytes)][serialized_data][length_prefix][data]...
+    fn encode_batch(&self, vectors: &[Vector]) -> Result<EncodedBatch> {
+        let mut batch_buffer = Vec::with_capacity(
+            vectors.len() * (self.config.dimension * self.config.vector_type.element_size() + 100),
+        );
+        let mut relative_offsets = Vec::with_capacity(vectors.len());
 
         for vector in vectors {
-            let serialized = bincode::serialize(vector)
-                .map_err(|e| VectorDbError::Serialization(e.to_string()))?;
+            relative_offsets.push((vector.id, batch_buffer.len() as u64));
+            encode_vector(&mut batch_buffer, vector, self.config.vector_type)?;
+        }
 
-            // Write length prefix (4 bytes, u32 little-endian)
-            let length = serialized.len() as u32;
-            batch_buffer.extend_from_slice(&length.to_le_bytes());
+        Ok(EncodedBatch { batch_buffer, relative_offsets })
+    }
 
-            // Write serialized vector data
-            batch_buffer.extend_from_slice(&serialized);
-        }
+    /// Append an encoded batch with a single write and index its vectors
+    async fn append_batch(files: &CollectionFiles, batch: EncodedBatch) -> Result<()> {
+        let EncodedBatch { batch_buffer, relative_offsets } = batch;
+        let base_offset = files.data_file.append(&batch_buffer).await?;
 
-        // Single async write for entire batch
-        self.data_file.append(&batch_buffer).await?;
+        let entries: Vec<(VectorId, u64)> = relative_offsets
+            .into_iter()
+            .map(|(id, relative)| (id, base_offset + relative))
+            .collect();
+        files.id_index.insert_batch(&entries).await?;
 
         Ok(())
     }
-    
-    async fn get(&self, _id: &VectorId) -> Result<Option<Vector>> {
-        // TODO: Implement efficient lookup using index
-        // For now, this is a placeholder
-        Ok(None)
+
+    async fn get(&self, id: &VectorId) -> Result<Option<Vector>> {
+        let files = self.files.read().await;
+        Self::read_vector(&files, id).await
+    }
+
+    async fn read_vector(files: &CollectionFiles, id: &VectorId) -> Result<Option<Vector>> {
+        let Some(offset) = files.id_index.get(id) else {
+            return Ok(None);
+        };
+
+        let data = files.data_file.read_record(offset).await?;
+        match DataRecord::decode(&data)? {
+            DataRecord::Vector(vector) => Ok(Some(vector)),
+            DataRecord::Tombstone(_) => Err(VectorDbError::Corruption {
+                message: format!("ID index points at a tombstone for vector {}", id),
+            }),
+        }
     }
     
-    async fn delete(&self, _id: &VectorId) -> Result<bool> {
-        // TODO: Implement deletion with tombstone markers
-        // For now, this is a placeholder
-        Ok(false)
+    async fn delete(&self, id: &VectorId) -> Result<bool> {
+        let files = self.files.read().await;
+        if files.id_index.get(id).is_none() {
+            return Ok(false);
+        }
+
+        // Append a tombstone so the delete survives restarts and index rebuilds
+        let mut record = Vec::with_capacity(64);
+        encode_tombstone(&mut record, id);
+
+        let offset = files.data_file.append(&record).await?;
+        files.id_index.remove(*id, offset).await?;
+
+        Ok(true)
     }
     
+    /// Rewrite the vectors with the given IDs with an updated payload
+    ///
+    /// The read, update and append happen with other writers excluded, so a
+    /// concurrent write to the same vector is never overwritten with its old
+    /// data and a concurrent delete is never undone; IDs deleted by then are
+    /// skipped. Updates are idempotent, so replaying one from the WAL is harmless.
+    async fn update_payload(&self, ids: &[VectorId], update: &PayloadUpdate) -> Result<Vec<Vector>> {
+        let files = self.files.write().await;
+
+        let mut updated = Vec::with_capacity(ids.len());
+        for id in ids {
+            if let Some(mut vector) = Self::read_vector(&files, id).await? {
+                update.apply(&mut vector.metadata);
+                updated.push(vector);
+            }
+        }
+        if !updated.is_empty() {
+            let batch = self.encode_batch(&updated)?;
+            Self::append_batch(&files, batch).await?;
+        }
+
+        Ok(updated)
+    }
+
+    /// Live vector count and on-disk index size
+    ///
+    /// Memory is left at zero: storage is memory-mapped, and the in-memory
+    /// footprint is accounted for by the search index.
     async fn stats(&self) -> Result<CollectionStats> {
+        let files = self.files.read().await;
         Ok(CollectionStats {
             name: self.config.name.clone(),
-            vector_count: 0, // TODO: Track count
+            vector_count: files.id_index.len(),
             dimension: self.config.dimension,
-            index_size: self.index_file.size().await? as usize,
-            memory_usage: (self.data_file.size().await? + self.index_file.size().await?) as usize,
+            index_size: files.id_index.size().await? as usize,
+            memory_usage: 0,
+            memory: MemoryBreakdown::default(),
+            quantization: None,
         })
     }
     
     async fn sync(&self) -> Result<()> {
-        self.data_file.sync().await?;
-        self.index_file.sync().await?;
-        Ok(())
+        {
+            let files = self.files.read().await;
+            files.data_file.sync().await?;
+            files.id_index.sync().await?;
+        }
+        self.save_metadata().await
+    }
+
+    /// Mark the current end of the data file
+    pub async fn data_mark(&self) -> Result<DataMark> {
+        let files = self.files.read().await;
+        let position = files.data_file.position().await?;
+        Self::mark_at(&files.data_file, position).await
     }
 
-    /// Iterate over all vectors in the collection
+    async fn mark_at(data_file: &MMapStorage, position: u64) -> Result<DataMark> {
+        let start = position.saturating_sub(DataMark::WINDOW);
+        let bytes = data_file.read(start, (position - start) as usize).await?;
+        Ok(DataMark {
+            position,
+            checksum: crc32fast::hash(&bytes),
+        })
+    }
+
+    /// Records appended after `mark`, or `None` if the data file no longer
+    /// starts with the content `mark` was taken from
+    pub async fn records_since(&self, mark: &DataMark) -> Result<Option<Vec<DataRecord>>> {
+        let files = self.files.read().await;
+        if mark.position > files.data_file.position().await?
+            || Self::mark_at(&files.data_file, mark.position).await? != *mark
+        {
+            return Ok(None);
+        }
+
+        let mut records = Vec::new();
+        let mut iter = files.data_file.iter_from(mark.position).await?;
+        while let Some(data) = iter.next().await? {
+            match DataRecord::decode(&data) {
+                Ok(record) => records.push(record),
+                Err(e) => tracing::warn!(
+                    "Failed to deserialize record in collection '{}': {}",
+                    self.config.name,
+                    e
+                ),
+            }
+        }
+
+        Ok(Some(records))
+    }
+
+    /// The `limit` smallest live IDs greater than `after`, in ascending order
+    async fn ids_after(&self, after: Option<&VectorId>, limit: usize) -> Vec<VectorId> {
+        self.files.read().await.id_index.ids_after(after, limit)
+    }
+
+    /// Up to `limit` live vectors stored after `mark` and the mark to continue
+    /// from, or `None` if the data file no longer starts with the content
+    /// `mark` was taken from
+    ///
+    /// Only the returned vectors are held in memory, so a whole collection can
+    /// be read in bounded chunks by feeding each returned mark back in.
+    pub async fn vectors_since(&self, mark: &DataMark, limit: usize) -> Result<Option<(Vec<Vector>, DataMark)>> {
+        let files = self.files.read().await;
+        if mark.position > files.data_file.position().await?
+            || Self::mark_at(&files.data_file, mark.position).await? != *mark
+        {
+            return Ok(None);
+        }
+
+        let mut vectors = Vec::new();
+        let mut iter = files.data_file.iter_from(mark.position).await?;
+        while vectors.len() < limit {
+            let offset = iter.position();
+            let Some(data) = iter.next().await? else {
+                break;
+            };
+
+            match DataRecord::decode(&data) {
+                Ok(DataRecord::Vector(vector)) if files.id_index.get(&vector.id) == Some(offset) => {
+                    vectors.push(vector)
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!(
+                    "Failed to deserialize vector in collection '{}': {}",
+                    self.config.name,
+                    e
+                ),
+            }
+        }
+
+        let next = Self::mark_at(&files.data_file, iter.position()).await?;
+        Ok(Some((vectors, next)))
+    }
+
+    /// Iterate over the live version of every vector in the collection
     pub async fn iter_vectors(&self) -> Result<Vec<Vector>> {
+        let files = self.files.read().await;
         let mut vectors = Vec::new();
-        let mut iter = self.data_file.iter().await?;
+        let mut iter = files.data_file.iter().await?;
 
-        while let Some(data) = iter.next().await? {
-            match bincode::deserialize::<Vector>(&data) {
-                Ok(vector) => vectors.push(vector),
+        loop {
+            let offset = iter.position();
+            let Some(data) = iter.next().await? else {
+                break;
+            };
+
+            match DataRecord::decode(&data) {
+                // Skip records that have been superseded by a later write or tombstone
+                Ok(DataRecord::Vector(vector)) if files.id_index.get(&vector.id) == Some(offset) => {
+                    vectors.push(vector)
+                }
+                Ok(_) => {}
                 Err(e) => {
                     tracing::warn!(
                         "Failed to deserialize vector in collection '{}': {}",
@@ -612,4 +946,152 @@ impl CollectionStorage {
 
         Ok(vectors)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn test_config() -> CollectionConfig {
+        CollectionConfig {
+            name: "test".to_string(),
+            dimension: 3,
+            distance_metric: DistanceMetric::Cosine,
+            vector_type: VectorType::Float32,
+            index_config: IndexConfig::default(),
+            quantization: None,
+            sparse_vectors: None,
+            vectors: None,
+            multivectors: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_vector_after_restart() {
+        let temp_dir = tempdir().unwrap();
+
+        let mut metadata = HashMap::new();
+        metadata.insert("tag".to_string(), serde_json::json!("first"));
+        let single = Vector {
+            id: uuid::Uuid::new_v4(),
+            data: vec![1.0, 2.0, 3.0],
+            metadata: Some(metadata),
+            sparse: None,
+            vectors: None,
+            multivectors: None,
+        };
+        let batch: Vec<Vector> = (0..5)
+            .map(|i| Vector {
+                id: uuid::Uuid::new_v4(),
+                data: vec![i as f32, 0.0, 1.0],
+                metadata: None,
+                sparse: None,
+                vectors: None,
+                multivectors: None,
+            })
+            .collect();
+
+        {
+            let engine = StorageEngine::new(temp_dir.path()).await.unwrap();
+            engine.create_collection(&test_config()).await.unwrap();
+            engine.insert_vector("test", &single).await.unwrap();
+            engine.batch_insert("test", &batch).await.unwrap();
+
+            let found = engine.get_vector("test", &batch[3].id).await.unwrap().unwrap();
+            assert_eq!(found.data, batch[3].data);
+            assert!(engine.get_vector("test", &uuid::Uuid::new_v4()).await.unwrap().is_none());
+
+            engine.sync().await.unwrap();
+        }
+
+        let engine = StorageEngine::new(temp_dir.path()).await.unwrap();
+        let mark = engine.get_data_mark("test").await.unwrap();
+        let found = engine.get_vector("test", &single.id).await.unwrap().unwrap();
+        assert_eq!(found.data, single.data);
+        assert_eq!(found.metadata.unwrap()["tag"], serde_json::json!("first"));
+        assert_eq!(engine.get_all_vectors("test").await.unwrap().len(), 6);
+
+        // Records written after a mark are returned in order
+        assert!(engine.delete_vector("test", &batch[0].id).await.unwrap());
+        let records = engine.get_records_since("test", &mark).await.unwrap().unwrap();
+        assert_eq!(records.len(), 1);
+        assert!(matches!(records[0], DataRecord::Tombstone(id) if id == batch[0].id));
+
+        // Live vectors can be read back in chunks, skipping the deleted one
+        let mut chunk_mark = DataMark::START;
+        let mut chunked = Vec::new();
+        loop {
+            let (vectors, next) = engine.get_vectors_since("test", &chunk_mark, 2).await.unwrap().unwrap();
+            if vectors.is_empty() {
+                break;
+            }
+            assert!(vectors.len() <= 2);
+            chunked.extend(vectors.into_iter().map(|v| v.id));
+            chunk_mark = next;
+        }
+        assert_eq!(chunked.len(), 5);
+        assert!(!chunked.contains(&batch[0].id));
+
+        // Compaction rewrites the file, so earlier marks become stale
+        engine.compact_collection("test").await.unwrap();
+        assert!(engine.get_records_since("test", &mark).await.unwrap().is_none());
+        assert!(engine.get_vectors_since("test", &chunk_mark, 2).await.unwrap().is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_payload_updates_race_writes_safely() {
+        let temp_dir = tempdir().unwrap();
+        let engine = Arc::new(StorageEngine::new(temp_dir.path()).await.unwrap());
+        engine.create_collection(&test_config()).await.unwrap();
+
+        let vectors: Vec<Vector> = (0..100)
+            .map(|i| Vector {
+                id: uuid::Uuid::new_v4(),
+                data: vec![i as f32, 0.0, 1.0],
+                metadata: None,
+                sparse: None,
+                vectors: None,
+                multivectors: None,
+            })
+            .collect();
+        engine.batch_insert("test", &vectors).await.unwrap();
+        let ids: Arc<Vec<VectorId>> = Arc::new(vectors.iter().map(|v| v.id).collect());
+
+        // Two updates setting different keys, racing each other and deletes
+        let set = |key: &str| {
+            let (engine, ids) = (engine.clone(), ids.clone());
+            let update = PayloadUpdate::Set([(key.to_string(), serde_json::json!(1))].into_iter().collect());
+            tokio::spawn(async move {
+                for id in ids.iter() {
+                    engine.update_payload("test", std::slice::from_ref(id), &update).await.unwrap();
+                }
+            })
+        };
+        let tasks = vec![
+            set("a"),
+            set("b"),
+            {
+                let (engine, ids) = (engine.clone(), ids.clone());
+                tokio::spawn(async move {
+                    for id in ids.iter().step_by(2) {
+                        engine.delete_vector("test", id).await.unwrap();
+                    }
+                })
+            },
+        ];
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        for (i, id) in ids.iter().enumerate() {
+            let vector = engine.get_vector("test", id).await.unwrap();
+            if i % 2 == 0 {
+                assert!(vector.is_none(), "deleted vector {} came back", i);
+            } else {
+                let metadata = vector.unwrap().metadata.unwrap();
+                assert!(metadata.contains_key("a") && metadata.contains_key("b"));
+            }
+        }
+    }
+}
@@ -198,12 +198,44 @@ impl MMapStorage {
     }
     
     /// Iterate over all records in the storage
-    pub async fn iter(&self) -> Result<StorageIterator> {
+    pub async fn iter(&self) -> Result<StorageIterator<'_>> {
+        self.iter_from(0).await
+    }
+
+    /// Iterate over records starting at a known record boundary
+    pub async fn iter_from(&self, position: u64) -> Result<StorageIterator<'_>> {
         Ok(StorageIterator {
             storage: self,
-            position: 0,
+            position,
         })
     }
+
+    /// Read a single length-prefixed record starting at `offset`
+    pub async fn read_record(&self, offset: u64) -> Result<Vec<u8>> {
+        let data_end = self.position().await?;
+
+        if offset + 4 > data_end {
+            return Err(VectorDbError::StorageError {
+                message: format!("Record offset {} is beyond end of data ({})", offset, data_end),
+            });
+        }
+
+        let length_bytes = self.read(offset, 4).await?;
+        let length = u32::from_le_bytes([
+            length_bytes[0],
+            length_bytes[1],
+            length_bytes[2],
+            length_bytes[3],
+        ]) as u64;
+
+        if length == 0 || offset + 4 + length > data_end {
+            return Err(VectorDbError::Corruption {
+                message: format!("Invalid record length {} at offset {}", length, offset),
+            });
+        }
+
+        self.read(offset + 4, length as usize).await
+    }
 }
 
 /// Iterator over storage records
@@ -213,6 +245,11 @@ pub struct StorageIterator<'a> {
 }
 
 impl<'a> StorageIterator<'a> {
+    /// Offset of the record that the next call to `next` will return
+    pub fn position(&self) -> u64 {
+        self.position
+    }
+
     /// Get the next record
     pub async fn next(&mut self) -> Result<Option<Vec<u8>>> {
         let current_size = self.storage.position().await?;
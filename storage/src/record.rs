@@ -0,0 +1,382 @@
+use vectordb_common::{Result, VectorDbError};
+use vectordb_common::types::*;
+use vectordb_common::packed::PackedVector;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use vectordb_common::sparse::SparseVector;
+
+/// Marker at the start of a tombstone record
+///
+/// bincode encodes a `Vector` starting with the u64 length of its UUID (16), so
+/// a record starting with these bytes can never be mistaken for a vector.
+const TOMBSTONE_MAGIC: [u8; 8] = *b"DVTOMBST";
+
+/// Size of a tombstone payload: magic + 16-byte UUID
+const TOMBSTONE_SIZE: usize = 8 + 16;
+
+/// Marker at the start of a vector record packed into a Float16 or Int8
+/// collection's element type; never the start of a bincode `Vector` either
+const PACKED_MAGIC: [u8; 8] = *b"DVPACKED";
+
+/// A vector record whose dense vectors are in the collection's element type
+#[derive(Serialize, Deserialize)]
+struct PackedRecord {
+    id: VectorId,
+    data: PackedVector,
+    /// Metadata as JSON with sorted keys, as `Vector` encodes it
+    metadata: Option<String>,
+    sparse: Option<BTreeMap<String, SparseVector>>,
+    vectors: Option<BTreeMap<String, PackedVector>>,
+    multivectors: Option<BTreeMap<String, Vec<PackedVector>>>,
+}
+
+impl PackedRecord {
+    fn pack(vector: &Vector, vector_type: VectorType) -> Result<Self> {
+        let metadata = vector
+            .metadata
+            .as_ref()
+            .map(|map| serde_json::to_string(&map.iter().collect::<BTreeMap<_, _>>()))
+            .transpose()?;
+        let pack = |values: &Vec<f32>| PackedVector::pack(values, vector_type);
+        Ok(PackedRecord {
+            id: vector.id,
+            data: pack(&vector.data),
+            metadata,
+            sparse: vector.sparse.clone(),
+            vectors: vector.vectors.as_ref().map(|vectors| {
+                vectors.iter().map(|(name, values)| (name.clone(), pack(values))).collect()
+            }),
+            multivectors: vector.multivectors.as_ref().map(|multivectors| {
+                multivectors
+                    .iter()
+                    .map(|(name, rows)| (name.clone(), rows.iter().map(pack).collect()))
+                    .collect()
+            }),
+        })
+    }
+
+    fn unpack(self) -> Result<Vector> {
+        let metadata = self.metadata
+            .map(|json| serde_json::from_str(&json))
+            .transpose()?;
+        Ok(Vector {
+            id: self.id,
+            data: self.data.unpack(),
+            metadata,
+            sparse: self.sparse,
+            vectors: self.vectors.map(|vectors| {
+                vectors.into_iter().map(|(name, packed)| (name, packed.unpack())).collect()
+            }),
+            multivectors: self.multivectors.map(|multivectors| {
+                multivectors
+                    .into_iter()
+                    .map(|(name, rows)| (name, rows.iter().map(PackedVector::unpack).collect()))
+                    .collect()
+            }),
+        })
+    }
+}
+
+/// Vector record layout from before points carried sparse vectors
+///
+/// Metadata is bincode-encoded as an optional JSON string, as `Vector` still does.
+#[derive(Deserialize)]
+struct VectorV1 {
+    id: VectorId,
+    data: Vec<f32>,
+    metadata: Option<String>,
+}
+
+/// Vector record layout from before points carried named dense vectors
+#[derive(Deserialize)]
+struct VectorV2 {
+    id: VectorId,
+    data: Vec<f32>,
+    metadata: Option<String>,
+    sparse: Option<BTreeMap<String, SparseVector>>,
+}
+
+/// Vector record layout from before points carried multi-vectors
+#[derive(Deserialize)]
+struct VectorV3 {
+    id: VectorId,
+    data: Vec<f32>,
+    metadata: Option<String>,
+    sparse: Option<BTreeMap<String, SparseVector>>,
+    vectors: Option<BTreeMap<String, Vec<f32>>>,
+}
+
+impl VectorV3 {
+    fn into_vector(self) -> Result<Vector> {
+        let metadata = self.metadata
+            .map(|json| serde_json::from_str(&json))
+            .transpose()?;
+        Ok(Vector {
+            id: self.id,
+            data: self.data,
+            metadata,
+            sparse: self.sparse,
+            vectors: self.vectors,
+            multivectors: None,
+        })
+    }
+}
+
+impl From<VectorV2> for VectorV3 {
+    fn from(v2: VectorV2) -> Self {
+        VectorV3 {
+            id: v2.id,
+            data: v2.data,
+            metadata: v2.metadata,
+            sparse: v2.sparse,
+            vectors: None,
+        }
+    }
+}
+
+impl From<VectorV1> for VectorV2 {
+    fn from(v1: VectorV1) -> Self {
+        VectorV2 {
+            id: v1.id,
+            data: v1.data,
+            metadata: v1.metadata,
+            sparse: None,
+        }
+    }
+}
+
+/// A decoded record from a collection's `vectors.bin`
+#[derive(Debug, Clone)]
+pub enum DataRecord {
+    /// A full vector written by an insert or upsert
+    Vector(Vector),
+    /// Marks every earlier record for this ID as deleted
+    Tombstone(VectorId),
+}
+
+impl DataRecord {
+    pub fn id(&self) -> VectorId {
+        match self {
+            DataRecord::Vector(vector) => vector.id,
+            DataRecord::Tombstone(id) => *id,
+        }
+    }
+
+    /// Decode a record payload (without its length prefix)
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        if data.len() == TOMBSTONE_SIZE && data[..8] == TOMBSTONE_MAGIC {
+            let mut id_bytes = [0u8; 16];
+            id_bytes.copy_from_slice(&data[8..]);
+            return Ok(DataRecord::Tombstone(uuid::Uuid::from_bytes(id_bytes)));
+        }
+        if data.len() >= 8 && data[..8] == PACKED_MAGIC {
+            return bincode::deserialize::<PackedRecord>(&data[8..])
+                .map_err(|e| VectorDbError::Serialization(e.to_string()))?
+                .unpack()
+                .map(DataRecord::Vector);
+        }
+
+        // Older records stop short of the fields added since, so each layout
+        // fails to decode as any newer one; try the newest first
+        let error = match bincode::deserialize::<Vector>(data) {
+            Ok(vector) => return Ok(DataRecord::Vector(vector)),
+            Err(e) => VectorDbError::Serialization(e.to_string()),
+        };
+        bincode::deserialize::<VectorV3>(data)
+            .or_else(|_| bincode::deserialize::<VectorV2>(data).map(VectorV3::from))
+            .or_else(|_| bincode::deserialize::<VectorV1>(data).map(|v1| VectorV3::from(VectorV2::from(v1))))
+            .map_err(|_| error)?
+            .into_vector()
+            .map(DataRecord::Vector)
+    }
+}
+
+/// A position in a collection's data file, fingerprinted by the bytes before it
+///
+/// Anything derived from the data file up to `position` (such as a persisted
+/// search index) can record a mark and later check that the file still has the
+/// same prefix. Compaction rewrites the file, which invalidates older marks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DataMark {
+    pub position: u64,
+    /// CRC32 of up to `DataMark::WINDOW` bytes immediately before `position`
+    pub checksum: u32,
+}
+
+impl DataMark {
+    pub const WINDOW: u64 = 4096;
+    /// Start of any data file: no bytes precede it, and the CRC32 of nothing is zero
+    pub const START: DataMark = DataMark { position: 0, checksum: 0 };
+}
+
+/// Append a length-prefixed vector record to `buffer`
+///
+/// Float32 vectors are written as a plain bincode `Vector`; other element
+/// types are packed, so they read back rounded to what the type can hold.
+pub fn encode_vector(buffer: &mut Vec<u8>, vector: &Vector, vector_type: VectorType) -> Result<()> {
+    let serialized = match vector_type {
+        VectorType::Float32 => bincode::serialize(vector),
+        _ => bincode::serialize(&PackedRecord::pack(vector, vector_type)?).map(|packed| {
+            let mut serialized = PACKED_MAGIC.to_vec();
+            serialized.extend_from_slice(&packed);
+            serialized
+        }),
+    }
+    .map_err(|e| VectorDbError::Serialization(e.to_string()))?;
+
+    // Write length prefix (4 bytes, u32 little-endian) + data
+    buffer.extend_from_slice(&(serialized.len() as u32).to_le_bytes());
+    buffer.extend_from_slice(&serialized);
+    Ok(())
+}
+
+/// Append a length-prefixed tombstone record to `buffer`
+pub fn encode_tombstone(buffer: &mut Vec<u8>, id: &VectorId) {
+    buffer.extend_from_slice(&(TOMBSTONE_SIZE as u32).to_le_bytes());
+    buffer.extend_from_slice(&TOMBSTONE_MAGIC);
+    buffer.extend_from_slice(id.as_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_roundtrip() {
+        let vector = Vector {
+            id: uuid::Uuid::new_v4(),
+            data: vec![1.0, 2.0, 3.0],
+            metadata: None,
+            sparse: None,
+            vectors: None,
+            multivectors: None,
+        };
+
+        let mut buffer = Vec::new();
+        encode_vector(&mut buffer, &vector, VectorType::Float32).unwrap();
+        let vector_len = buffer.len();
+        encode_tombstone(&mut buffer, &vector.id);
+
+        match DataRecord::decode(&buffer[4..vector_len]).unwrap() {
+            DataRecord::Vector(decoded) => assert_eq!(decoded.data, vector.data),
+            other => panic!("expected vector, got {:?}", other),
+        }
+
+        match DataRecord::decode(&buffer[vector_len + 4..]).unwrap() {
+            DataRecord::Tombstone(id) => assert_eq!(id, vector.id),
+            other => panic!("expected tombstone, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_packed_record_roundtrip() {
+        let mut metadata = std::collections::HashMap::new();
+        metadata.insert("tag".to_string(), serde_json::json!("packed"));
+        let vector = Vector {
+            id: uuid::Uuid::new_v4(),
+            data: vec![0.1; 64],
+            metadata: Some(metadata),
+            sparse: Some(BTreeMap::from([("keywords".to_string(), SparseVector::new(vec![3], vec![1.0]))])),
+            vectors: Some(BTreeMap::from([("title".to_string(), vec![1.0, -2.0])])),
+            multivectors: Some(BTreeMap::from([("tokens".to_string(), vec![vec![0.5, 0.25]])])),
+        };
+
+        let mut float32 = Vec::new();
+        encode_vector(&mut float32, &vector, VectorType::Float32).unwrap();
+
+        for (vector_type, tolerance) in [(VectorType::Float16, 1e-3), (VectorType::Int8, 1e-2)] {
+            let mut buffer = Vec::new();
+            encode_vector(&mut buffer, &vector, vector_type).unwrap();
+            // The default vector dominates the record, at 2 or 1 bytes per
+            // component; the magic and enum tags add a few bytes back
+            assert!(buffer.len() <= float32.len() - 64 * (4 - vector_type.element_size()) + 32);
+
+            let DataRecord::Vector(decoded) = DataRecord::decode(&buffer[4..]).unwrap() else {
+                panic!("expected vector");
+            };
+            assert_eq!(decoded.id, vector.id);
+            let close = |decoded: &[f32], original: &[f32]| {
+                decoded.iter().zip(original).all(|(d, o)| (d - o).abs() <= o.abs().max(1.0) * tolerance)
+            };
+            assert!(close(&decoded.data, &vector.data), "{:?}", vector_type);
+            assert_eq!(decoded.metadata.unwrap()["tag"], "packed");
+            assert_eq!(decoded.sparse.unwrap()["keywords"].indices, vec![3]);
+            assert!(close(&decoded.vectors.unwrap()["title"], &[1.0, -2.0]), "{:?}", vector_type);
+            assert!(close(&decoded.multivectors.unwrap()["tokens"][0], &[0.5, 0.25]), "{:?}", vector_type);
+        }
+    }
+
+    #[test]
+    fn test_decode_legacy_vector_records() {
+        #[derive(Serialize)]
+        struct V1 {
+            id: VectorId,
+            data: Vec<f32>,
+            metadata: Option<String>,
+        }
+
+        #[derive(Serialize)]
+        struct V2 {
+            id: VectorId,
+            data: Vec<f32>,
+            metadata: Option<String>,
+            sparse: Option<BTreeMap<String, SparseVector>>,
+        }
+
+        #[derive(Serialize)]
+        struct V3 {
+            id: VectorId,
+            data: Vec<f32>,
+            metadata: Option<String>,
+            sparse: Option<BTreeMap<String, SparseVector>>,
+            vectors: Option<BTreeMap<String, Vec<f32>>>,
+        }
+
+        let v1 = V1 {
+            id: uuid::Uuid::new_v4(),
+            data: vec![0.5, 1.5],
+            metadata: Some(r#"{"tag":"old"}"#.to_string()),
+        };
+        match DataRecord::decode(&bincode::serialize(&v1).unwrap()).unwrap() {
+            DataRecord::Vector(vector) => {
+                assert_eq!(vector.id, v1.id);
+                assert_eq!(vector.data, v1.data);
+                assert_eq!(vector.metadata.unwrap()["tag"], "old");
+                assert!(vector.sparse.is_none());
+            }
+            other => panic!("expected vector, got {:?}", other),
+        }
+
+        let keywords = SparseVector::new(vec![3], vec![1.0]);
+        let v2 = V2 {
+            id: uuid::Uuid::new_v4(),
+            data: vec![2.5],
+            metadata: None,
+            sparse: Some(BTreeMap::from([("keywords".to_string(), keywords)])),
+        };
+        match DataRecord::decode(&bincode::serialize(&v2).unwrap()).unwrap() {
+            DataRecord::Vector(vector) => {
+                assert_eq!(vector.id, v2.id);
+                assert_eq!(vector.sparse.unwrap()["keywords"].indices, vec![3]);
+                assert!(vector.vectors.is_none());
+            }
+            other => panic!("expected vector, got {:?}", other),
+        }
+
+        let v3 = V3 {
+            id: uuid::Uuid::new_v4(),
+            data: vec![2.5],
+            metadata: None,
+            sparse: None,
+            vectors: Some(BTreeMap::from([("title".to_string(), vec![1.0, 2.0])])),
+        };
+        match DataRecord::decode(&bincode::serialize(&v3).unwrap()).unwrap() {
+            DataRecord::Vector(vector) => {
+                assert_eq!(vector.id, v3.id);
+                assert_eq!(vector.vectors.unwrap()["title"], vec![1.0, 2.0]);
+                assert!(vector.multivectors.is_none());
+            }
+            other => panic!("expected vector, got {:?}", other),
+        }
+    }
+}
@@ -102,7 +102,7 @@ impl RecoveryManager {
                     }
                 }
             }
-            WALOperation::DeleteVector { collection, .. } => {
+            WALOperation::DeleteVector { collection, .. } | WALOperation::UpdatePayload { collection, .. } => {
                 if !existing_collections.contains(collection) {
                     return Err(VectorDbError::Internal {
                         message: format!("Collection {} does not exist", collection),
@@ -473,6 +473,10 @@ mod tests {
             distance_metric: DistanceMetric::Cosine,
             vector_type: VectorType::Float32,
             index_config: IndexConfig::default(),
+            quantization: None,
+            sparse_vectors: None,
+            vectors: None,
+            multivectors: None,
         };
         
         let operations = vec![
@@ -483,6 +487,9 @@ mod tests {
                     id: uuid::Uuid::new_v4(),
                     data: vec![0.1; 128],
                     metadata: None,
+                    sparse: None,
+                    vectors: None,
+                    multivectors: None,
                 },
             },
         ];
@@ -25,6 +25,11 @@ pub enum WALOperation {
         collection: CollectionId,
         id: VectorId,
     },
+    UpdatePayload {
+        collection: CollectionId,
+        ids: Vec<VectorId>,
+        update: PayloadUpdate,
+    },
 }
 
 /// WAL entry with metadata
@@ -302,6 +307,10 @@ mod tests {
             distance_metric: DistanceMetric::Cosine,
             vector_type: VectorType::Float32,
             index_config: IndexConfig::default(),
+            quantization: None,
+            sparse_vectors: None,
+            vectors: None,
+            multivectors: None,
         };
         
         let op = WALOperation::CreateCollection(config);
@@ -317,4 +326,67 @@ mod tests {
             _ => panic!("Unexpected operation type"),
         }
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn test_wal_keeps_entries_with_metadata() {
+        let temp_dir = tempdir().unwrap();
+        let wal = WriteAheadLog::new(temp_dir.path().join("test.wal")).await.unwrap();
+
+        // Enough keys that a map's iteration order is very unlikely to repeat
+        let metadata = (0..16)
+            .map(|i| (format!("key{}", i), serde_json::json!(i)))
+            .collect();
+        let op = WALOperation::InsertVector {
+            collection: "test".to_string(),
+            vector: Vector {
+                id: Uuid::new_v4(),
+                data: vec![1.0, 2.0],
+                metadata: Some(metadata),
+                sparse: None,
+                vectors: None,
+                multivectors: None,
+            },
+        };
+        for _ in 0..8 {
+            wal.append(&op).await.unwrap();
+        }
+
+        assert_eq!(wal.read_all().await.unwrap().len(), 8);
+    }
+
+    #[tokio::test]
+    async fn test_wal_round_trips_payload_updates() {
+        let temp_dir = tempdir().unwrap();
+        let wal = WriteAheadLog::new(temp_dir.path().join("test.wal")).await.unwrap();
+
+        let payload = (0..16)
+            .map(|i| (format!("key{}", i), serde_json::json!({ "n": i, "tags": ["a", "b"] })))
+            .collect();
+        let updates = vec![
+            PayloadUpdate::Set(payload),
+            PayloadUpdate::Overwrite(Default::default()),
+            PayloadUpdate::DeleteKeys(vec!["key1".to_string()]),
+            PayloadUpdate::Clear,
+        ];
+        for update in &updates {
+            let op = WALOperation::UpdatePayload {
+                collection: "test".to_string(),
+                ids: vec![Uuid::new_v4()],
+                update: update.clone(),
+            };
+            wal.append(&op).await.unwrap();
+        }
+
+        let replayed: Vec<PayloadUpdate> = wal
+            .read_all()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|op| match op {
+                WALOperation::UpdatePayload { update, .. } => update,
+                _ => panic!("Unexpected operation type"),
+            })
+            .collect();
+        assert_eq!(replayed, updates);
+    }
+}
@@ -1,16 +1,244 @@
 use vectordb_common::{Result, VectorDbError};
 use vectordb_common::types::*;
-use vectordb_storage::StorageEngine;
-use vectordb_index::{VectorIndex, HnswRsIndex};  // Use production-ready HNSW
+use vectordb_common::filter::filter_matches_with;
+use vectordb_common::quantization::{ProductQuantizer, QuantizationConfig, TrainingStatus};
+use vectordb_storage::{DataMark, DataRecord, StorageEngine};
+use vectordb_index::{VectorIndex, BinaryIndex, FlatIndex, HnswRsIndex, IvfIndex, PqIndex, SearchResult, PayloadIndex, SparseIndex, TextMatches};  // Use production-ready HNSW
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use dashmap::DashMap;
-use tracing::{info, error};
+use tokio::sync::mpsc;
+use dashmap::{DashMap, DashSet};
+use tracing::{info, warn, error};
 use metrics::{counter, histogram, gauge};
+use rayon::prelude::*;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+
+/// Serialized search index, stored next to a collection's data file
+const INDEX_DUMP_FILE: &str = "hnsw.dump";
+
+/// On-disk form of a persisted index
+///
+/// `mark` is the end of the data file when the index was dumped; records
+/// written after it are replayed into the index on load.
+#[derive(Serialize, Deserialize)]
+struct IndexDump {
+    mark: DataMark,
+    index: Vec<u8>,
+}
+
+/// Serialized payload field indexes, stored next to the search index
+const PAYLOAD_INDEX_FILE: &str = "payload_index.bin";
+
+/// On-disk form of a collection's payload indexes, caught up the same way as [`IndexDump`]
+#[derive(Serialize, Deserialize)]
+struct PayloadIndexDump {
+    mark: DataMark,
+    index: PayloadIndex,
+}
+
+/// Serialized indexes over a collection's named vectors, stored next to the search index
+const NAMED_INDEX_DUMP_FILE: &str = "named.dump";
+
+/// On-disk form of a collection's named vector indexes, caught up the same way as [`IndexDump`]
+#[derive(Serialize, Deserialize)]
+struct NamedIndexDump {
+    mark: DataMark,
+    /// Serialized dense index per named vector space
+    dense: HashMap<String, Vec<u8>>,
+    sparse: HashMap<String, SparseIndex>,
+}
+
+/// Trained product quantization codebooks, stored next to a collection's metadata
+///
+/// The index is normally loaded from its dump; when that is missing or stale
+/// it is rebuilt from storage, and keeping the codebooks means only encoding
+/// is repeated, not training.
+const PQ_CODEBOOKS_FILE: &str = "pq_codebooks.bin";
+
+/// On-disk form of a collection's product quantizer
+#[derive(Serialize, Deserialize)]
+struct PqCodebooks {
+    quantizer: ProductQuantizer,
+    sample_size: usize,
+}
+
+/// How many candidates each side of a hybrid search fetches, per requested result
+const HYBRID_PREFETCH: usize = 4;
+
+/// How many dense search candidates a multi-vector query rescores, per requested result
+const MULTIVECTOR_PREFETCH: usize = 4;
+
+/// How many keys a scroll reads at a time while filling a page
+const SCROLL_CHUNK: usize = 256;
+
+/// Position after the last point of a scroll page, carried base64-encoded
+/// as the page's opaque cursor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ScrollCursor {
+    id: VectorId,
+    /// The point's value of the `order_by` field, when ordered by one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    value: Option<serde_json::Value>,
+}
+
+impl ScrollCursor {
+    fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).expect("scroll cursors serialize"))
+    }
+
+    fn decode(cursor: &str) -> Result<Self> {
+        URL_SAFE_NO_PAD
+            .decode(cursor)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .ok_or_else(|| VectorDbError::InvalidInput {
+                message: format!("Invalid scroll cursor: {}", cursor),
+            })
+    }
+}
+
+/// How many filtered batches an export reads ahead of its consumer
+const EXPORT_BUFFER: usize = 2;
+
+/// How many points a payload update rewrites, and logs to the WAL, at a time
+const PAYLOAD_UPDATE_CHUNK: usize = 256;
+
+/// How many stored vectors quantizer training and exact scans read at a time
+const SCAN_CHUNK: usize = 1024;
+
+/// Indexes over a collection's named vectors
+///
+/// Persisted alongside the default vector's index and loaded from that dump,
+/// replaying what was written since; rebuilt from storage when it is missing
+/// or stale.
+#[derive(Default)]
+struct NamedIndexes {
+    /// Dense index per named vector space
+    dense: HashMap<String, Box<dyn VectorIndex>>,
+    /// Inverted index per named sparse vector space
+    sparse: HashMap<String, SparseIndex>,
+}
+
+impl NamedIndexes {
+    /// One empty index per named vector space the collection declares
+    fn new(config: &CollectionConfig) -> Self {
+        let dense = config
+            .vectors
+            .iter()
+            .flatten()
+            .map(|(name, params)| {
+                let index_config = params.index_config.as_ref().unwrap_or(&config.index_config);
+                let index = dense_index(index_config, params.distance_metric, params.dimension, config.vector_type);
+                (name.clone(), index)
+            })
+            .collect();
+        let sparse = config
+            .sparse_vectors
+            .iter()
+            .flatten()
+            .map(|(name, params)| (name.clone(), SparseIndex::new(params)))
+            .collect();
+        Self { dense, sparse }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.dense.is_empty() && self.sparse.is_empty()
+    }
+
+    /// Index a point's named vectors, replacing what its ID had before
+    fn insert(&mut self, vector: &Vector) -> Result<()> {
+        for (name, index) in self.dense.iter_mut() {
+            match vector.vectors.as_ref().and_then(|vectors| vectors.get(name)) {
+                Some(data) => index.update(vector.id, data, vector.metadata.clone())?,
+                None => {
+                    index.delete(&vector.id)?;
+                }
+            }
+        }
+        for (name, index) in self.sparse.iter_mut() {
+            match vector.sparse.as_ref().and_then(|sparse| sparse.get(name)) {
+                Some(sparse) => index.insert(vector.id, sparse),
+                None => index.remove(&vector.id),
+            }
+        }
+        Ok(())
+    }
+
+    /// Replace the payload the dense indexes hold for a point
+    fn set_metadata(&mut self, vector: &Vector) {
+        for index in self.dense.values_mut() {
+            index.set_metadata(&vector.id, vector.metadata.clone());
+        }
+    }
+
+    fn remove(&mut self, id: &VectorId) {
+        for index in self.dense.values_mut() {
+            let _ = index.delete(id);
+        }
+        for index in self.sparse.values_mut() {
+            index.remove(id);
+        }
+    }
+
+    /// Bring reloaded indexes up to date with records written after their dump
+    ///
+    /// Like the default index, dense indexes only touch vectors that changed,
+    /// since the data file can repeat records the dump already covers.
+    fn replay(&mut self, records: Vec<DataRecord>) -> Result<()> {
+        let mut latest: HashMap<VectorId, DataRecord> = HashMap::new();
+        for record in records {
+            latest.insert(record.id(), record);
+        }
+
+        for (name, index) in self.dense.iter_mut() {
+            let mut upserts = Vec::new();
+            for (id, record) in &latest {
+                match record {
+                    DataRecord::Vector(vector) => match vector.vectors.as_ref().and_then(|vectors| vectors.get(name)) {
+                        Some(data) => upserts.push((*id, data.clone(), vector.metadata.clone())),
+                        None => {
+                            index.delete(id)?;
+                        }
+                    },
+                    DataRecord::Tombstone(_) => {
+                        index.delete(id)?;
+                    }
+                }
+            }
+            index.upsert_changed(upserts)?;
+        }
+        for (id, record) in &latest {
+            for (name, index) in self.sparse.iter_mut() {
+                match record {
+                    DataRecord::Vector(vector) => match vector.sparse.as_ref().and_then(|sparse| sparse.get(name)) {
+                        Some(sparse) => index.insert(*id, sparse),
+                        None => index.remove(id),
+                    },
+                    DataRecord::Tombstone(_) => index.remove(id),
+                }
+            }
+        }
+        Ok(())
+    }
+}
 
 /// Main vector store engine that coordinates storage and indexing
+///
+/// Clones are cheap handles onto the same store.
+#[derive(Clone)]
 pub struct VectorStore {
-    storage: StorageEngine,
+    storage: Arc<StorageEngine>,
     indexes: Arc<DashMap<CollectionId, Box<dyn VectorIndex>>>,
+    payload_indexes: Arc<DashMap<CollectionId, PayloadIndex>>,
+    named_indexes: Arc<DashMap<CollectionId, NamedIndexes>>,
+    /// Collections whose product quantizer is being trained
+    training: Arc<DashSet<CollectionId>>,
+    /// Per-collection gate that writers hold shared from their storage write
+    /// until the indexes reflect it, so a data mark taken while holding it
+    /// exclusively is not ahead of any write the indexes are missing
+    write_gates: Arc<DashMap<CollectionId, Arc<tokio::sync::RwLock<()>>>>,
 }
 
 impl VectorStore {
@@ -19,8 +247,12 @@ impl VectorStore {
         let storage = StorageEngine::new(data_dir).await?;
 
         let mut store = Self {
-            storage,
+            storage: Arc::new(storage),
             indexes: Arc::new(DashMap::new()),
+            payload_indexes: Arc::new(DashMap::new()),
+            named_indexes: Arc::new(DashMap::new()),
+            training: Arc::new(DashSet::new()),
+            write_gates: Arc::new(DashMap::new()),
         };
 
         // Rebuild indexes for existing collections
@@ -34,17 +266,17 @@ impl VectorStore {
         info!("Creating collection: {}", config.name);
         counter!("vectorstore.collections.created").increment(1);
         
+        validate_quantization(config)?;
+
         // Create storage
         self.storage.create_collection(config).await?;
 
         // Create index - using production-ready hnsw_rs
-        let index = Box::new(HnswRsIndex::new(
-            config.index_config.clone(),
-            config.distance_metric,
-            config.dimension,
-        ));
+        let index = self.open_index(&config.name, config).await;
 
         self.indexes.insert(config.name.clone(), index);
+        self.payload_indexes.insert(config.name.clone(), PayloadIndex::new());
+        self.named_indexes.insert(config.name.clone(), NamedIndexes::new(config));
         
         info!("Collection created successfully: {}", config.name);
         Ok(())
@@ -58,7 +290,17 @@ impl VectorStore {
         // Use soft delete via recovery manager
         let recovery = self.storage.get_recovery_manager();
         recovery.soft_delete_collection(name).await?;
+        self.storage.unregister_collection(name).await?;
+
+        gauge!("vectorstore.collection.vectors", "collection" => name.to_string()).set(0.0);
+        for (component, _) in memory_components(&MemoryBreakdown::default()) {
+            gauge!("vectorstore.collection.memory", "collection" => name.to_string(), "component" => component)
+                .set(0.0);
+        }
         self.indexes.remove(name);
+        self.payload_indexes.remove(name);
+        self.named_indexes.remove(name);
+        self.write_gates.remove(name);
 
         info!("Collection soft-deleted successfully: {} (recoverable for 24 hours)", name);
         Ok(())
@@ -71,6 +313,9 @@ impl VectorStore {
 
         self.storage.delete_collection(name).await?;
         self.indexes.remove(name);
+        self.payload_indexes.remove(name);
+        self.named_indexes.remove(name);
+        self.write_gates.remove(name);
 
         info!("Collection permanently deleted: {}", name);
         Ok(())
@@ -85,12 +330,11 @@ impl VectorStore {
 
         // Rebuild index for restored collection
         if let Some(config) = self.storage.get_collection_config(&name)? {
-            let index = Box::new(HnswRsIndex::new(
-                config.index_config.clone(),
-                config.distance_metric,
-                config.dimension,
-            ));
+            let index = self.open_index(&name, &config).await;
             self.indexes.insert(name.clone(), index);
+            self.payload_indexes.insert(name.clone(), PayloadIndex::new());
+            let named_indexes = self.load_named_indexes(&name, &config).await;
+            self.named_indexes.insert(name.clone(), named_indexes);
         }
 
         info!("Collection restored successfully: {}", name);
@@ -108,12 +352,11 @@ impl VectorStore {
         self.storage.register_imported_collection(config).await?;
 
         // Create index for imported collection
-        let index = Box::new(HnswRsIndex::new(
-            config.index_config.clone(),
-            config.distance_metric,
-            config.dimension,
-        ));
+        let index = self.open_index(&config.name, config).await;
         self.indexes.insert(config.name.clone(), index);
+        self.payload_indexes.insert(config.name.clone(), PayloadIndex::new());
+        let named_indexes = self.load_named_indexes(&config.name, config).await;
+        self.named_indexes.insert(config.name.clone(), named_indexes);
 
         info!("Orphaned collection imported successfully as: {}", new_collection_name);
         Ok(())
@@ -149,21 +392,26 @@ impl VectorStore {
             })?;
         
         // Validate vector dimension
-        if vector.data.len() != config.dimension {
-            return Err(VectorDbError::InvalidDimension {
-                expected: config.dimension,
-                actual: vector.data.len(),
-            });
-        }
-        
+        config.validate_default_vector(&vector.data)?;
+        validate_named(&config, vector)?;
+
+        let gate = self.write_gate(collection);
+        let writing = gate.read().await;
+
         // Insert into storage
         self.storage.insert_vector(collection, vector).await?;
 
         // OPTIMIZATION: Direct insert without spawn_blocking overhead
         // hnsw_rs is thread-safe, DashMap provides lock-free access
         if let Some(mut index) = self.indexes.get_mut(collection) {
-            index.insert(vector.id, &vector.data, vector.metadata.clone())?;
+            index_default(index.as_mut(), vector.id, &vector.data, vector.metadata.clone())?;
+        }
+        if let Some(mut payload_index) = self.payload_indexes.get_mut(collection) {
+            payload_index.insert(vector.id, vector.metadata.as_ref());
         }
+        self.index_named(collection, std::slice::from_ref(vector))?;
+        drop(writing);
+        self.train_quantizer_if_due(collection);
 
         histogram!("vectorstore.insert.duration").record(start.elapsed().as_secs_f64());
         Ok(())
@@ -186,14 +434,13 @@ impl VectorStore {
 
         // Validate all vector dimensions
         for vector in vectors {
-            if vector.data.len() != config.dimension {
-                return Err(VectorDbError::InvalidDimension {
-                    expected: config.dimension,
-                    actual: vector.data.len(),
-                });
-            }
+            config.validate_default_vector(&vector.data)?;
+            validate_named(&config, vector)?;
         }
 
+        let gate = self.write_gate(collection);
+        let writing = gate.read().await;
+
         // Insert into storage (async operation)
         self.storage.batch_insert(collection, vectors).await?;
 
@@ -201,16 +448,31 @@ impl VectorStore {
         // hnsw_rs is already thread-safe with internal parallel processing
         // Avoiding spawn_blocking + cloning saves significant overhead
 
-        // Prepare vectors for batch insert (zero-copy where possible)
+        // Prepare vectors for batch insert (zero-copy where possible);
+        // points without a default vector stay out of the default index
         let vectors_to_insert: Vec<(VectorId, Vec<f32>, Option<_>)> = vectors.iter()
+            .filter(|v| !v.data.is_empty())
             .map(|v| (v.id, v.data.clone(), v.metadata.clone()))
             .collect();
 
         // Direct call to batch_insert - DashMap provides lock-free access
         // hnsw_rs::parallel_insert uses rayon internally for multi-threading
         if let Some(mut index) = self.indexes.get_mut(collection) {
-            index.batch_insert(vectors_to_insert)?;
+            for vector in vectors.iter().filter(|v| v.data.is_empty()) {
+                index.delete(&vector.id)?;
+            }
+            if !vectors_to_insert.is_empty() {
+                index.batch_insert(vectors_to_insert)?;
+            }
         }
+        if let Some(mut payload_index) = self.payload_indexes.get_mut(collection) {
+            for vector in vectors {
+                payload_index.insert(vector.id, vector.metadata.as_ref());
+            }
+        }
+        self.index_named(collection, vectors)?;
+        drop(writing);
+        self.train_quantizer_if_due(collection);
 
         histogram!("vectorstore.batch_insert.duration").record(start.elapsed().as_secs_f64());
         info!("Batch inserted {} vectors into {}", vectors.len(), collection);
@@ -219,6 +481,12 @@ impl VectorStore {
     
     /// Query vectors for nearest neighbors
     pub async fn query(&self, request: &QueryRequest) -> Result<Vec<QueryResult>> {
+        self.query_counted(request, None).await
+    }
+
+    /// Query, storing in `visited` how many vectors an unfiltered index search
+    /// compared the query against
+    async fn query_counted(&self, request: &QueryRequest, visited: Option<&mut usize>) -> Result<Vec<QueryResult>> {
         let start = std::time::Instant::now();
         counter!("vectorstore.queries").increment(1);
 
@@ -228,57 +496,232 @@ impl VectorStore {
                 name: request.collection.clone(),
             })?;
 
-        // Validate query vector dimension
-        if request.vector.len() != config.dimension {
+        // A brute-force or exact multi-vector query never touches the dense indexes
+        let multivector = match &request.multivector {
+            Some(query) => {
+                let params = multivector_params(&config, &query.using)?;
+                validate_multivector(params, &query.vectors)?;
+                Some((query, params.distance_metric))
+            }
+            None => None,
+        };
+        if let Some((query, metric)) = multivector.filter(|(query, _)| request.exact || query.mode == MultiVectorMode::BruteForce) {
+            let results = self.max_sim_scan(request, query, metric).await?;
+            histogram!("vectorstore.query.duration").record(start.elapsed().as_secs_f64());
+            histogram!("vectorstore.query.results").record(results.len() as f64);
+            return Ok(results);
+        }
+
+        // Validate query vector dimension against the space being searched
+        let (dimension, metric) = match &request.using {
+            Some(name) => {
+                let params = named_vector_params(&config, name)?;
+                (params.dimension, params.distance_metric)
+            }
+            None => (config.dimension, config.distance_metric),
+        };
+        if request.vector.len() != dimension {
             return Err(VectorDbError::InvalidDimension {
-                expected: config.dimension,
+                expected: dimension,
                 actual: request.vector.len(),
             });
         }
 
-        // Search index - DashMap provides lock-free reads
-        let index = self.indexes
-            .get(&request.collection)
-            .ok_or_else(|| VectorDbError::CollectionNotFound {
-                name: request.collection.clone(),
-            })?;
+        if request.exact {
+            counter!("vectorstore.queries.exact").increment(1);
+            let results = self.exact_scan(request, metric).await?;
+            histogram!("vectorstore.query.duration").record(start.elapsed().as_secs_f64());
+            histogram!("vectorstore.query.results").record(results.len() as f64);
+            return Ok(results);
+        }
 
-        // If filter is present, search with larger candidate pool for post-filtering
-        let search_limit = if request.filter.is_some() {
-            // Search more candidates to account for filtering
-            request.limit * 3
-        } else {
-            request.limit
+        // Distances from a quantized index, from the int8 codes an Int8
+        // collection indexes, or from IVF-PQ codes are approximate, so their
+        // candidates are re-ranked by their stored vectors unless the query
+        // opts out
+        let oversampling = match &config.quantization {
+            Some(QuantizationConfig::Binary(binary_config)) => request.oversampling.unwrap_or(binary_config.oversampling),
+            _ => request.oversampling.unwrap_or(1.0),
+        };
+        if !(1.0..).contains(&oversampling) {
+            return Err(VectorDbError::InvalidInput {
+                message: format!("oversampling must be at least 1, got {}", oversampling),
+            });
+        }
+        let rescore = request.using.is_none()
+            && multivector.is_none()
+            && (matches!(
+                config.quantization,
+                Some(QuantizationConfig::Scalar(_) | QuantizationConfig::Product(_) | QuantizationConfig::Binary(_))
+            ) || config.vector_type == VectorType::Int8
+                || config.index_config.index_type == IndexType::IvfPq)
+            && request.rescore.unwrap_or(true);
+
+        let limit = match multivector {
+            Some(_) => request.limit.saturating_mul(MULTIVECTOR_PREFETCH),
+            None if rescore => (request.limit as f64 * oversampling as f64).ceil() as usize,
+            None => request.limit,
         };
 
-        let search_results = index.search(&request.vector, search_limit, request.ef_search)?;
+        // Search index - DashMap provides lock-free reads
+        let search_results = match &request.using {
+            Some(name) => {
+                let indexes = self.named_indexes
+                    .get(&request.collection)
+                    .ok_or_else(|| VectorDbError::CollectionNotFound {
+                        name: request.collection.clone(),
+                    })?;
+                let index = indexes.dense.get(name).ok_or_else(|| VectorDbError::InvalidInput {
+                    message: format!("Collection '{}' has no vector named '{}'", request.collection, name),
+                })?;
+                self.search_index(index.as_ref(), request, limit, visited)?
+            }
+            None => {
+                let index = self.indexes
+                    .get(&request.collection)
+                    .ok_or_else(|| VectorDbError::CollectionNotFound {
+                        name: request.collection.clone(),
+                    })?;
+                self.search_index(index.as_ref(), request, limit, visited)?
+            }
+        };
 
-        // Apply payload filter if present
-        let filtered_results: Vec<vectordb_index::SearchResult> = if let Some(filter) = &request.filter {
-            search_results
+        let results: Vec<QueryResult> = match multivector {
+            Some((query, metric)) => {
+                let mut candidates = Vec::with_capacity(search_results.len());
+                for result in search_results {
+                    if let Some(vector) = self.storage.get_vector(&request.collection, &result.id).await? {
+                        candidates.push(vector);
+                    }
+                }
+                rank_max_sim(query, metric, candidates, request.limit)
+            }
+            None if rescore => {
+                counter!("vectorstore.queries.rescored").increment(1);
+                let mut rescored = Vec::with_capacity(search_results.len());
+                for result in search_results {
+                    if let Some(vector) = self.storage.get_vector(&request.collection, &result.id).await? {
+                        rescored.push(QueryResult {
+                            id: result.id,
+                            distance: index_distance(&request.vector, &vector.data, config.distance_metric),
+                            metadata: result.metadata,
+                        });
+                    }
+                }
+                rescored.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+                rescored.truncate(request.limit);
+                rescored
+            }
+            // Convert to QueryResult
+            None => search_results
                 .into_iter()
-                .filter(|r| vectordb_common::filter::evaluate_filter(filter, &r.metadata))
-                .take(request.limit)
-                .collect()
-        } else {
-            search_results.into_iter().take(request.limit).collect()
+                .map(|r| QueryResult {
+                    id: r.id,
+                    distance: r.distance,
+                    metadata: r.metadata,
+                })
+                .collect(),
         };
 
-        // Convert to QueryResult
-        let results: Vec<QueryResult> = filtered_results
-            .into_iter()
-            .map(|r| QueryResult {
-                id: r.id,
-                distance: r.distance,
-                metadata: r.metadata,
-            })
-            .collect();
-
         histogram!("vectorstore.query.duration").record(start.elapsed().as_secs_f64());
         histogram!("vectorstore.query.results").record(results.len() as f64);
         Ok(results)
     }
 
+    /// Run a query's search, with its filter, against one vector index
+    fn search_index(
+        &self,
+        index: &dyn VectorIndex,
+        request: &QueryRequest,
+        limit: usize,
+        visited: Option<&mut usize>,
+    ) -> Result<Vec<SearchResult>> {
+        // Filters are applied inside the index so they can steer the search;
+        // payload indexes cut them down to a candidate set first when they can
+        match (&request.filter, visited) {
+            (Some(filter), _) => {
+                let (candidates, text) = self.plan_filter(&request.collection, filter);
+                index.search_filtered(
+                    &request.vector,
+                    limit,
+                    request.ef_search,
+                    &|id, metadata| {
+                        candidates.as_ref().is_none_or(|c| c.contains(id))
+                            && filter_matches_with(filter, metadata, &|m| text.get(id, m))
+                    },
+                )
+            }
+            (None, Some(visited)) => {
+                let (results, count) = index.search_counted(&request.vector, limit, request.ef_search)?;
+                *visited = count;
+                Ok(results)
+            }
+            (None, None) => index.search(&request.vector, limit, request.ef_search),
+        }
+    }
+
+    /// Score every point matching the query's filter by MaxSim
+    async fn max_sim_scan(&self, request: &QueryRequest, query: &MultiVectorQuery, metric: DistanceMetric) -> Result<Vec<QueryResult>> {
+        let mut best = Vec::new();
+        self.scan_points(request, |points| {
+            best.extend(rank_max_sim(query, metric, points, request.limit));
+            keep_nearest(&mut best, request.limit);
+            Ok(())
+        })
+        .await?;
+        best.sort_by(|a, b| a.distance.partial_cmp(&b.distance).unwrap_or(std::cmp::Ordering::Equal));
+        Ok(best)
+    }
+
+    /// Score every point matching the query's filter against its stored vector
+    ///
+    /// Distances follow the indexes' convention, so exact results line up
+    /// with those of an index search for recall measurement. Only the best
+    /// `limit` points seen so far are held between chunks.
+    async fn exact_scan(&self, request: &QueryRequest, metric: DistanceMetric) -> Result<Vec<QueryResult>> {
+        let mut best = Vec::new();
+        self.scan_points(request, |points| {
+            best.par_extend(points.into_par_iter().filter_map(|point| {
+                let data = match &request.using {
+                    Some(name) => point.vectors.as_ref()?.get(name)?,
+                    None if point.data.is_empty() => return None,
+                    None => &point.data,
+                };
+                Some(QueryResult {
+                    id: point.id,
+                    distance: index_distance(&request.vector, data, metric),
+                    metadata: point.metadata,
+                })
+            }));
+            keep_nearest(&mut best, request.limit);
+            Ok(())
+        })
+        .await?;
+        best.sort_unstable_by(|a, b| a.distance.total_cmp(&b.distance));
+        Ok(best)
+    }
+
+    /// Pass the stored points that pass the query's filter to `visit`, a chunk at a time
+    async fn scan_points(&self, request: &QueryRequest, mut visit: impl FnMut(Vec<Vector>) -> Result<()>) -> Result<()> {
+        let plan = request
+            .filter
+            .as_ref()
+            .map(|filter| (filter, self.plan_filter(&request.collection, filter)));
+        self.scan_stored(&request.collection, |points| match &plan {
+            None => visit(points),
+            Some((filter, (candidates, text))) => visit(
+                points
+                    .into_par_iter()
+                    .filter(|v| {
+                        candidates.as_ref().is_none_or(|c| c.contains(&v.id))
+                            && filter_matches_with(filter, v.metadata.as_ref(), &|m| text.get(&v.id, m))
+                    })
+                    .collect(),
+            ),
+        })
+        .await
+    }
+
     /// Recommend vectors based on positive and negative examples
     pub async fn recommend(&self, request: &vectordb_common::RecommendRequest) -> Result<Vec<QueryResult>> {
         let start = std::time::Instant::now();
@@ -287,7 +730,7 @@ impl VectorStore {
         // Get positive example vectors
         let mut positive_vectors = Vec::new();
         for id in &request.positive {
-            if let Some(vector) = self.get(&request.collection, id).await? {
+            if let Some(vector) = self.get(&request.collection, id).await?.filter(|v| !v.data.is_empty()) {
                 positive_vectors.push(vector.data);
             }
         }
@@ -301,7 +744,7 @@ impl VectorStore {
         // Get negative example vectors
         let mut negative_vectors = Vec::new();
         for id in &request.negative {
-            if let Some(vector) = self.get(&request.collection, id).await? {
+            if let Some(vector) = self.get(&request.collection, id).await?.filter(|v| !v.data.is_empty()) {
                 negative_vectors.push(vector.data);
             }
         }
@@ -322,6 +765,11 @@ impl VectorStore {
             limit: request.limit + request.offset,
             ef_search: None,
             filter: request.filter.clone(),
+            using: None,
+            multivector: None,
+            rescore: None,
+            oversampling: None,
+            exact: false,
         };
 
         let mut results = self.query(&query_request).await?;
@@ -348,7 +796,7 @@ impl VectorStore {
                     .ok_or_else(|| VectorDbError::NotFound {
                         message: format!("Target vector not found: {}", id),
                     })?;
-                vector.data
+                default_vector(vector)?
             }
             vectordb_common::DiscoveryTarget::Vector(v) => v.clone(),
         };
@@ -366,7 +814,7 @@ impl VectorStore {
                     message: format!("Negative vector not found: {}", pair.negative),
                 })?;
 
-            context_vectors.push((positive.data, negative.data));
+            context_vectors.push((default_vector(positive)?, default_vector(negative)?));
         }
 
         // Compute discovery direction
@@ -385,6 +833,11 @@ impl VectorStore {
             limit: request.limit + request.offset,
             ef_search: None,
             filter: request.filter.clone(),
+            using: None,
+            multivector: None,
+            rescore: None,
+            oversampling: None,
+            exact: false,
         };
 
         let mut results = self.query(&query_request).await?;
@@ -399,38 +852,78 @@ impl VectorStore {
         Ok(results)
     }
 
-    /// Scroll through all vectors in a collection
+    /// Page through the points matching a filter, by ID or by an indexed payload field
+    ///
+    /// Each page ends with an opaque cursor holding the last key returned, so
+    /// writes between pages neither shift later pages nor repeat earlier
+    /// points; only points whose key itself changes can move across the
+    /// cursor. A cursor is returned only when at least one more point matches.
     pub async fn scroll(&self, request: &vectordb_common::ScrollRequest) -> Result<vectordb_common::ScrollResponse> {
         let start = std::time::Instant::now();
         counter!("vectorstore.scroll").increment(1);
 
-        // Parse offset (simple index-based for now)
-        let offset = request.offset.as_ref()
-            .and_then(|s| s.parse::<usize>().ok())
-            .unwrap_or(0);
+        if request.limit == 0 {
+            return Err(VectorDbError::InvalidInput {
+                message: "Scroll limit must be positive".to_string(),
+            });
+        }
+        if self.get_collection_config(&request.collection)?.is_none() {
+            return Err(VectorDbError::CollectionNotFound {
+                name: request.collection.clone(),
+            });
+        }
 
-        // Get all vectors from storage
-        let all_vectors = self.storage.get_all_vectors(&request.collection).await?;
+        let cursor = request.offset.as_deref().map(ScrollCursor::decode).transpose()?;
+        if cursor.as_ref().is_some_and(|c| c.value.is_some() != request.order_by.is_some()) {
+            return Err(VectorDbError::InvalidInput {
+                message: "Scroll cursor was issued for a different ordering".to_string(),
+            });
+        }
 
-        // Apply filter if present
-        let filtered_vectors: Vec<_> = if let Some(filter) = &request.filter {
-            all_vectors.into_iter()
-                .filter(|v| vectordb_common::filter::evaluate_filter(filter, &v.metadata))
-                .collect()
-        } else {
-            all_vectors
+        let (candidates, text) = match &request.filter {
+            Some(filter) => self.plan_filter(&request.collection, filter),
+            None => (None, TextMatches::default()),
+        };
+        let matches = |v: &Vector| match &request.filter {
+            Some(filter) => {
+                candidates.as_ref().is_none_or(|c| c.contains(&v.id))
+                    && filter_matches_with(filter, v.metadata.as_ref(), &|m| text.get(&v.id, m))
+            }
+            None => true,
         };
 
-        // Apply pagination
-        let total = filtered_vectors.len();
-        let end = (offset + request.limit).min(total);
-        let page_vectors: Vec<_> = filtered_vectors.into_iter()
-            .skip(offset)
-            .take(request.limit)
-            .collect();
+        // Walk keys in order, stopping one match past the page
+        let mut after = cursor;
+        let mut last = None;
+        let mut page = Vec::new();
+        let mut next_offset = None;
+        'scan: loop {
+            let keys = self.scroll_keys(request, candidates.as_ref(), after.as_ref()).await?;
+            if keys.is_empty() {
+                break;
+            }
+
+            for key in keys {
+                let vector = self.storage.get_vector(&request.collection, &key.id).await?;
+                after = Some(key);
+                // Deleted since its key was read
+                let Some(vector) = vector else {
+                    continue;
+                };
+                if !matches(&vector) {
+                    continue;
+                }
+                if page.len() == request.limit {
+                    next_offset = last.as_ref().map(ScrollCursor::encode);
+                    break 'scan;
+                }
+                page.push(vector);
+                last = after.clone();
+            }
+        }
 
         // Convert to ScoredPoint
-        let points: Vec<vectordb_common::ScoredPoint> = page_vectors.into_iter()
+        let points: Vec<vectordb_common::ScoredPoint> = page.into_iter()
             .map(|v| vectordb_common::ScoredPoint {
                 id: v.id,
                 score: 0.0, // Scroll doesn't have scores
@@ -439,13 +932,6 @@ impl VectorStore {
             })
             .collect();
 
-        // Compute next offset
-        let next_offset = if end < total {
-            Some(end.to_string())
-        } else {
-            None
-        };
-
         histogram!("vectorstore.scroll.duration").record(start.elapsed().as_secs_f64());
 
         Ok(vectordb_common::ScrollResponse {
@@ -454,16 +940,163 @@ impl VectorStore {
         })
     }
 
+    /// The next keys a scroll visits after `after`, in order; empty once
+    /// there are none left
+    async fn scroll_keys(
+        &self,
+        request: &vectordb_common::ScrollRequest,
+        candidates: Option<&HashSet<VectorId>>,
+        after: Option<&ScrollCursor>,
+    ) -> Result<Vec<ScrollCursor>> {
+        let Some(order_by) = &request.order_by else {
+            let after = after.map(|c| &c.id);
+            let ids = match candidates {
+                // Already in memory, so they are all sorted at once
+                Some(candidates) => {
+                    let mut ids: Vec<_> = candidates
+                        .iter()
+                        .filter(|id| after.is_none_or(|after| *id > after))
+                        .copied()
+                        .collect();
+                    ids.sort_unstable();
+                    ids
+                }
+                None => self.storage.get_ids_after(&request.collection, after, SCROLL_CHUNK).await?,
+            };
+            return Ok(ids.into_iter().map(|id| ScrollCursor { id, value: None }).collect());
+        };
+
+        let after = after.and_then(|c| Some((c.value.as_ref()?, &c.id)));
+        let keys = self.payload_indexes
+            .get(&request.collection)
+            .and_then(|index| index.ordered(&order_by.key, order_by.direction, after, SCROLL_CHUNK))
+            .ok_or_else(|| VectorDbError::InvalidInput {
+                message: format!(
+                    "Cannot order by '{}': it needs a keyword, integer, float or bool payload index",
+                    order_by.key
+                ),
+            })?;
+
+        Ok(keys.into_iter().map(|(value, id)| ScrollCursor { id, value: Some(value) }).collect())
+    }
+
+    /// Stream the live points of a collection that match the request's filter
+    ///
+    /// Storage is read `batch_size` records at a time and at most
+    /// `EXPORT_BUFFER` filtered batches wait for the consumer, so memory stays
+    /// bounded whatever the size of the collection. Points written while the
+    /// export runs may be missed or sent twice; a compaction ends it with an
+    /// error. Dropping the receiver stops the export.
+    pub fn export(self: &Arc<Self>, request: vectordb_common::ExportRequest) -> mpsc::Receiver<Result<Vec<Vector>>> {
+        let (batches, rx) = mpsc::channel(EXPORT_BUFFER);
+        let store = Arc::clone(self);
+
+        tokio::spawn(async move {
+            let start = std::time::Instant::now();
+            counter!("vectorstore.export").increment(1);
+
+            match store.export_into(&request, &batches).await {
+                Ok(exported) => info!(
+                    "Exported {} points from collection '{}'",
+                    exported, request.collection
+                ),
+                Err(e) => {
+                    warn!("Export of collection '{}' failed: {}", request.collection, e);
+                    let _ = batches.send(Err(e)).await;
+                }
+            }
+
+            histogram!("vectorstore.export.duration").record(start.elapsed().as_secs_f64());
+        });
+
+        rx
+    }
+
+    /// Send the points `export` streams in batches, returning how many were sent
+    async fn export_into(
+        &self,
+        request: &vectordb_common::ExportRequest,
+        batches: &mpsc::Sender<Result<Vec<Vector>>>,
+    ) -> Result<usize> {
+        let (candidates, text) = match &request.filter {
+            Some(filter) => self.plan_filter(&request.collection, filter),
+            None => (None, TextMatches::default()),
+        };
+
+        let mut mark = DataMark::START;
+        let mut exported = 0;
+        loop {
+            let (vectors, next) = self.storage
+                .get_vectors_since(&request.collection, &mark, request.batch_size.max(1))
+                .await?
+                .ok_or_else(|| VectorDbError::StorageError {
+                    message: format!("Collection '{}' was compacted during export", request.collection),
+                })?;
+            if vectors.is_empty() {
+                return Ok(exported);
+            }
+            mark = next;
+
+            let batch: Vec<Vector> = vectors
+                .into_iter()
+                .filter(|v| match &request.filter {
+                    Some(filter) => {
+                        candidates.as_ref().is_none_or(|c| c.contains(&v.id))
+                            && filter_matches_with(filter, v.metadata.as_ref(), &|m| text.get(&v.id, m))
+                    }
+                    None => true,
+                })
+                .map(|mut v| {
+                    if !request.with_vectors {
+                        v.data = Vec::new();
+                        v.sparse = None;
+                        v.vectors = None;
+                        v.multivectors = None;
+                    }
+                    if !request.with_payload {
+                        v.metadata = None;
+                    }
+                    v
+                })
+                .collect();
+            if batch.is_empty() {
+                continue;
+            }
+
+            exported += batch.len();
+            if batches.send(Ok(batch)).await.is_err() {
+                // Consumer went away
+                return Ok(exported);
+            }
+        }
+    }
+
     /// Count vectors matching a filter
     pub async fn count(&self, request: &vectordb_common::CountRequest) -> Result<vectordb_common::CountResponse> {
         let start = std::time::Instant::now();
         counter!("vectorstore.count").increment(1);
 
-        let count = if let Some(filter) = &request.filter {
+        let (candidates, text) = match &request.filter {
+            Some(filter) => self.plan_filter(&request.collection, filter),
+            None => (None, TextMatches::default()),
+        };
+
+        let count = if let (Some(filter), Some(candidates)) = (&request.filter, candidates) {
+            // Only the candidates from the payload indexes can match
+            let mut count = 0;
+            for id in &candidates {
+                if let Some(vector) = self.storage.get_vector(&request.collection, id).await? {
+                    if filter_matches_with(filter, vector.metadata.as_ref(), &|m| text.get(id, m)) {
+                        count += 1;
+                    }
+                }
+            }
+            count
+        } else if let Some(filter) = &request.filter {
             // Get all vectors and filter
             let all_vectors = self.storage.get_all_vectors(&request.collection).await?;
             all_vectors.into_iter()
-                .filter(|v| vectordb_common::filter::evaluate_filter(filter, &v.metadata))
+                .filter(|v| filter_matches_with(filter, v.metadata.as_ref(), &|m| text.get(&v.id, m)))
                 .count()
         } else {
             // No filter - just get total count from stats
@@ -491,6 +1124,11 @@ impl VectorStore {
                 limit: search.limit + search.offset,
                 ef_search: None,
                 filter: search.filter.clone(),
+                using: None,
+                multivector: None,
+                rescore: None,
+                oversampling: None,
+                exact: false,
             };
 
             let mut results = self.query(&query_request).await?;
@@ -508,76 +1146,497 @@ impl VectorStore {
         Ok(all_results)
     }
 
-    /// Delete a vector
-    pub async fn delete(&self, collection: &str, id: &VectorId) -> Result<bool> {
-        counter!("vectorstore.vectors.deleted").increment(1);
-        
-        // Delete from storage
-        let storage_deleted = self.storage.delete_vector(collection, id).await?;
+    /// Search and group hits by a payload field, keeping the best hits per group
+    ///
+    /// Hits are fetched in growing batches until `limit` groups are full or
+    /// the collection runs out of matches.
+    pub async fn search_groups(&self, request: &vectordb_common::GroupByRequest) -> Result<vectordb_common::GroupByResponse> {
+        let start = std::time::Instant::now();
+        counter!("vectorstore.search_groups").increment(1);
 
-        // Delete from index - DashMap provides lock-free access
-        if let Some(mut index) = self.indexes.get_mut(collection) {
-            index.delete(id)?;
+        if request.group_size == 0 {
+            return Err(VectorDbError::InvalidInput {
+                message: "group_size must be at least 1".to_string(),
+            });
+        }
+        if let Some(lookup) = &request.with_lookup {
+            self.get_collection_config(&lookup.collection)?
+                .ok_or_else(|| VectorDbError::CollectionNotFound {
+                    name: lookup.collection.clone(),
+                })?;
         }
 
-        Ok(storage_deleted)
-    }
-    
-    /// Update a vector
-    pub async fn update(&self, collection: &str, vector: &Vector) -> Result<()> {
-        counter!("vectorstore.vectors.updated").increment(1);
+        let mut fetch = (request.limit * request.group_size).max(1) * GROUP_OVERFETCH;
+        let mut groups = loop {
+            let query_request = QueryRequest {
+                collection: request.collection.clone(),
+                vector: request.vector.clone(),
+                limit: fetch,
+                ef_search: None,
+                filter: request.filter.clone(),
+                using: None,
+                multivector: None,
+                rescore: None,
+                oversampling: None,
+                exact: false,
+            };
+            let results = self.query(&query_request).await?;
+            let exhausted = results.len() < fetch;
+
+            let groups = group_hits(results, &request.group_by, request.limit, request.group_size);
+            let full = groups.len() == request.limit
+                && groups.iter().all(|g| g.hits.len() == request.group_size);
+            if full || exhausted {
+                break groups;
+            }
+            fetch *= 2;
+        };
 
-        // For now, implement as delete + insert
-        // A more efficient implementation would update in-place
-        self.delete(collection, &vector.id).await?;
-        self.insert(collection, vector).await?;
+        if let Some(lookup) = &request.with_lookup {
+            for group in &mut groups {
+                // Only string keys can name a parent record
+                let Some(id) = group.id.as_str().and_then(|id| uuid::Uuid::parse_str(id).ok()) else {
+                    continue;
+                };
+                group.lookup = self.get(&lookup.collection, &id).await?
+                    .map(|v| vectordb_common::ScoredPoint {
+                        id: v.id,
+                        score: 0.0,
+                        vector: if lookup.with_vectors { Some(v.data) } else { None },
+                        payload: if lookup.with_payload { v.metadata } else { None },
+                    });
+            }
+        }
 
-        Ok(())
+        histogram!("vectorstore.search_groups.duration").record(start.elapsed().as_secs_f64());
+        Ok(vectordb_common::GroupByResponse { groups })
     }
 
-    /// Batch delete vectors
-    pub async fn batch_delete(&self, collection: &str, ids: &[VectorId]) -> Result<usize> {
+    /// Most frequent values of a payload field among the points matching a filter
+    ///
+    /// Approximate requests read counts from the field's payload index; a
+    /// filter is still checked against each point the payload indexes leave as
+    /// a candidate. Fields without a keyword, integer or bool index are always
+    /// counted exactly. In both modes a point with an array value counts once
+    /// toward each element.
+    pub async fn facet(&self, request: &vectordb_common::FacetRequest) -> Result<vectordb_common::FacetResponse> {
         let start = std::time::Instant::now();
-        counter!("vectorstore.vectors.batch_deleted").increment(ids.len() as u64);
-
-        if ids.is_empty() {
-            return Ok(0);
-        }
+        counter!("vectorstore.facet").increment(1);
 
-        // Validate collection exists
-        self.get_collection_config(collection)?
+        self.get_collection_config(&request.collection)?
             .ok_or_else(|| VectorDbError::CollectionNotFound {
-                name: collection.to_string(),
+                name: request.collection.clone(),
             })?;
 
-        let mut deleted_count = 0;
+        let (candidates, text) = match &request.filter {
+            Some(filter) => self.plan_filter(&request.collection, filter),
+            None => (None, TextMatches::default()),
+        };
 
-        // Delete from storage (batch operation)
-        for id in ids {
-            if self.storage.delete_vector(collection, id).await? {
-                deleted_count += 1;
+        // Candidates can include points an unindexed condition rejects, so the
+        // filter is evaluated on each before anything is counted
+        let matched = match &request.filter {
+            Some(filter) => {
+                let vectors = match candidates {
+                    Some(candidates) => {
+                        let mut vectors = Vec::with_capacity(candidates.len());
+                        for id in &candidates {
+                            if let Some(vector) = self.storage.get_vector(&request.collection, id).await? {
+                                vectors.push(vector);
+                            }
+                        }
+                        vectors
+                    }
+                    None => self.storage.get_all_vectors(&request.collection).await?,
+                };
+                let matched: Vec<Vector> = vectors
+                    .into_iter()
+                    .filter(|vector| filter_matches_with(filter, vector.metadata.as_ref(), &|m| text.get(&vector.id, m)))
+                    .collect();
+                Some(matched)
             }
-        }
+            None => None,
+        };
 
-        // Delete from index
-        if let Some(mut index) = self.indexes.get_mut(collection) {
-            for id in ids {
-                let _ = index.delete(id); // Continue even if some deletes fail
+        let approximate = match request.exact {
+            true => None,
+            false => {
+                let among: Option<HashSet<VectorId>> = matched
+                    .as_ref()
+                    .map(|vectors| vectors.iter().map(|vector| vector.id).collect());
+                self.payload_indexes
+                    .get(&request.collection)
+                    .and_then(|index| index.facet(&request.field, among.as_ref()))
             }
-        }
+        };
 
-        histogram!("vectorstore.batch_delete.duration").record(start.elapsed().as_secs_f64());
-        histogram!("vectorstore.batch_delete.count").record(deleted_count as f64);
+        let mut counts = match approximate {
+            Some(counts) => counts,
+            None => {
+                let vectors = match matched {
+                    Some(vectors) => vectors,
+                    None => self.storage.get_all_vectors(&request.collection).await?,
+                };
+
+                let mut counts: HashMap<String, (serde_json::Value, usize)> = HashMap::new();
+                for vector in vectors {
+                    for value in facet_values(vector.metadata.as_ref(), &request.field) {
+                        counts.entry(value.to_string()).or_insert((value, 0)).1 += 1;
+                    }
+                }
+                counts.into_values().collect()
+            }
+        };
 
-        Ok(deleted_count)
+        // Most frequent first, ties in a stable order
+        counts.sort_by(|(a, a_count), (b, b_count)| {
+            b_count.cmp(a_count).then_with(|| a.to_string().cmp(&b.to_string()))
+        });
+        counts.truncate(request.limit);
+
+        histogram!("vectorstore.facet.duration").record(start.elapsed().as_secs_f64());
+        Ok(vectordb_common::FacetResponse {
+            field: request.field.clone(),
+            values: counts
+                .into_iter()
+                .map(|(value, count)| vectordb_common::FacetValue { value, count })
+                .collect(),
+        })
     }
 
-    /// Batch upsert vectors (update if exists, insert if not)
-    pub async fn batch_upsert(&self, collection: &str, vectors: &[Vector]) -> Result<usize> {
+    /// Search a dense and a sparse query side by side and fuse the two rankings
+    ///
+    /// Either query may be left out. Each side fetches a few times `limit`
+    /// candidates that pass the filter before fusion, and fused scores are
+    /// returned with each point's payload.
+    pub async fn hybrid_search(&self, request: &vectordb_common::HybridSearchRequest) -> Result<Vec<vectordb_common::ScoredPoint>> {
         let start = std::time::Instant::now();
-        counter!("vectorstore.vectors.batch_upserted").increment(vectors.len() as u64);
+        counter!("vectorstore.hybrid_search").increment(1);
 
-        if vectors.is_empty() {
+        let config = self.get_collection_config(&request.collection)?
+            .ok_or_else(|| VectorDbError::CollectionNotFound {
+                name: request.collection.clone(),
+            })?;
+
+        if request.dense.is_none() && request.sparse.is_none() {
+            return Err(VectorDbError::InvalidInput {
+                message: "Hybrid search needs a dense or a sparse query".to_string(),
+            });
+        }
+        if request.limit == 0 {
+            return Ok(Vec::new());
+        }
+        let prefetch = request.limit.saturating_mul(HYBRID_PREFETCH);
+
+        let (candidates, text) = match &request.filter {
+            Some(filter) => self.plan_filter(&request.collection, filter),
+            None => (None, TextMatches::default()),
+        };
+
+        let dense_results: Vec<(VectorId, f32)> = match &request.dense {
+            Some(dense) => {
+                if dense.len() != config.dimension {
+                    return Err(VectorDbError::InvalidDimension {
+                        expected: config.dimension,
+                        actual: dense.len(),
+                    });
+                }
+
+                let index = self.indexes
+                    .get(&request.collection)
+                    .ok_or_else(|| VectorDbError::CollectionNotFound {
+                        name: request.collection.clone(),
+                    })?;
+                let results = match &request.filter {
+                    Some(filter) => index.search_filtered(dense, prefetch, None, &|id, metadata| {
+                        candidates.as_ref().is_none_or(|c| c.contains(id))
+                            && filter_matches_with(filter, metadata, &|m| text.get(id, m))
+                    })?,
+                    None => index.search(dense, prefetch, None)?,
+                };
+
+                // Fusion expects higher-is-better scores
+                results.into_iter().map(|r| (r.id, -r.distance)).collect()
+            }
+            None => Vec::new(),
+        };
+
+        let sparse_results: Vec<(VectorId, f32)> = match &request.sparse {
+            Some(query) => {
+                query.validate().map_err(|message| VectorDbError::InvalidInput { message })?;
+                let name = sparse_space(&config, request.sparse_name.as_deref())?;
+                let ranked = self.named_indexes
+                    .get(&request.collection)
+                    .and_then(|indexes| indexes.sparse.get(name).map(|index| index.search(query)))
+                    .unwrap_or_default();
+
+                let mut matched = Vec::new();
+                for (id, score) in ranked {
+                    if matched.len() == prefetch {
+                        break;
+                    }
+                    if let Some(filter) = &request.filter {
+                        if !candidates.as_ref().is_none_or(|c| c.contains(&id)) {
+                            continue;
+                        }
+                        let metadata = self.storage.get_vector(&request.collection, &id).await?
+                            .and_then(|v| v.metadata);
+                        if !filter_matches_with(filter, metadata.as_ref(), &|m| text.get(&id, m)) {
+                            continue;
+                        }
+                    }
+                    matched.push((id, score));
+                }
+                matched
+            }
+            None => Vec::new(),
+        };
+
+        let fused = vectordb_common::fuse_results(dense_results, sparse_results, request.fusion);
+
+        let mut points = Vec::with_capacity(request.limit.min(fused.len()));
+        for (id, score) in fused.into_iter().take(request.limit) {
+            let payload = self.storage.get_vector(&request.collection, &id).await?
+                .and_then(|v| v.metadata);
+            points.push(vectordb_common::ScoredPoint {
+                id,
+                score,
+                vector: None,
+                payload,
+            });
+        }
+
+        histogram!("vectorstore.hybrid_search.duration").record(start.elapsed().as_secs_f64());
+        Ok(points)
+    }
+
+    /// Delete a vector
+    pub async fn delete(&self, collection: &str, id: &VectorId) -> Result<bool> {
+        counter!("vectorstore.vectors.deleted").increment(1);
+
+        let gate = self.write_gate(collection);
+        let _writing = gate.read().await;
+
+        // Delete from storage
+        let storage_deleted = self.storage.delete_vector(collection, id).await?;
+
+        // Delete from index - DashMap provides lock-free access
+        if let Some(mut index) = self.indexes.get_mut(collection) {
+            index.delete(id)?;
+        }
+        if let Some(mut payload_index) = self.payload_indexes.get_mut(collection) {
+            payload_index.remove(id);
+        }
+        self.unindex_named(collection, std::slice::from_ref(id));
+
+        Ok(storage_deleted)
+    }
+    
+    /// Update a vector
+    pub async fn update(&self, collection: &str, vector: &Vector) -> Result<()> {
+        counter!("vectorstore.vectors.updated").increment(1);
+
+        // For now, implement as delete + insert
+        // A more efficient implementation would update in-place
+        self.delete(collection, &vector.id).await?;
+        self.insert(collection, vector).await?;
+
+        Ok(())
+    }
+
+    /// Merge keys into the payloads of the selected points
+    pub async fn set_payload(&self, request: &vectordb_common::SetPayloadRequest) -> Result<vectordb_common::UpdatePayloadResponse> {
+        let update = PayloadUpdate::Set(request.payload.clone());
+        self.update_payload(&request.collection, &request.points, &update).await
+    }
+
+    /// Replace the payloads of the selected points
+    pub async fn overwrite_payload(&self, request: &vectordb_common::SetPayloadRequest) -> Result<vectordb_common::UpdatePayloadResponse> {
+        let update = PayloadUpdate::Overwrite(request.payload.clone());
+        self.update_payload(&request.collection, &request.points, &update).await
+    }
+
+    /// Remove keys from the payloads of the selected points
+    pub async fn delete_payload_keys(&self, request: &vectordb_common::DeletePayloadKeysRequest) -> Result<vectordb_common::UpdatePayloadResponse> {
+        let update = PayloadUpdate::DeleteKeys(request.keys.clone());
+        self.update_payload(&request.collection, &request.points, &update).await
+    }
+
+    /// Remove the payloads of the selected points entirely
+    pub async fn clear_payload(&self, request: &vectordb_common::ClearPayloadRequest) -> Result<vectordb_common::UpdatePayloadResponse> {
+        self.update_payload(&request.collection, &request.points, &PayloadUpdate::Clear).await
+    }
+
+    /// Apply a payload update to the selected points
+    ///
+    /// Unlike `update`, the search indexes only have the payloads they hold
+    /// replaced; vectors and graphs are left alone. Points are rewritten and
+    /// logged `PAYLOAD_UPDATE_CHUNK` at a time, and a filter is evaluated
+    /// against one chunk of IDs at a time, so memory stays bounded however
+    /// many points are selected.
+    async fn update_payload(
+        &self,
+        collection: &str,
+        points: &vectordb_common::PointSelector,
+        update: &PayloadUpdate,
+    ) -> Result<vectordb_common::UpdatePayloadResponse> {
+        let start = std::time::Instant::now();
+
+        if self.get_collection_config(collection)?.is_none() {
+            return Err(VectorDbError::CollectionNotFound {
+                name: collection.to_string(),
+            });
+        }
+
+        let mut updated = 0;
+        match points {
+            vectordb_common::PointSelector::Ids(ids) => {
+                for chunk in ids.chunks(PAYLOAD_UPDATE_CHUNK) {
+                    updated += self.apply_payload_update(collection, chunk, update).await?;
+                }
+            }
+            vectordb_common::PointSelector::Filter(filter) => {
+                let (candidates, text) = self.plan_filter(collection, filter);
+                let matching = |vector: &Vector| {
+                    filter_matches_with(filter, vector.metadata.as_ref(), &|m| text.get(&vector.id, m))
+                };
+
+                match candidates {
+                    // Only the candidates from the payload indexes can match
+                    Some(candidates) => {
+                        let candidates: Vec<VectorId> = candidates.into_iter().collect();
+                        for chunk in candidates.chunks(PAYLOAD_UPDATE_CHUNK) {
+                            let ids = self.ids_matching(collection, chunk, &matching).await?;
+                            updated += self.apply_payload_update(collection, &ids, update).await?;
+                        }
+                    }
+                    // Rewritten points keep their IDs, so paging by ID visits each once
+                    None => {
+                        let mut after = None;
+                        loop {
+                            let chunk = self.storage.get_ids_after(collection, after.as_ref(), PAYLOAD_UPDATE_CHUNK).await?;
+                            let Some(&last) = chunk.last() else {
+                                break;
+                            };
+                            after = Some(last);
+                            let ids = self.ids_matching(collection, &chunk, &matching).await?;
+                            updated += self.apply_payload_update(collection, &ids, update).await?;
+                        }
+                    }
+                }
+            }
+        }
+
+        counter!("vectorstore.payload.updated").increment(updated as u64);
+        histogram!("vectorstore.update_payload.duration").record(start.elapsed().as_secs_f64());
+
+        Ok(vectordb_common::UpdatePayloadResponse { updated })
+    }
+
+    /// The IDs among `ids` of live points that `matching` accepts
+    async fn ids_matching(
+        &self,
+        collection: &str,
+        ids: &[VectorId],
+        matching: &impl Fn(&Vector) -> bool,
+    ) -> Result<Vec<VectorId>> {
+        let mut matched = Vec::new();
+        for id in ids {
+            if let Some(vector) = self.storage.get_vector(collection, id).await? {
+                if matching(&vector) {
+                    matched.push(*id);
+                }
+            }
+        }
+        Ok(matched)
+    }
+
+    /// Rewrite the given points with an updated payload and swap the new
+    /// payloads into the indexes, returning how many points exist
+    async fn apply_payload_update(&self, collection: &str, ids: &[VectorId], update: &PayloadUpdate) -> Result<usize> {
+        if ids.is_empty() {
+            return Ok(0);
+        }
+
+        // Indexes swap payloads in rather than re-reading storage, so a chunk
+        // holds the gate alone: two updates to the same point then reach the
+        // indexes in the order storage applied them
+        let gate = self.write_gate(collection);
+        let _writing = gate.write().await;
+
+        let updated = self.storage.update_payload(collection, ids, update).await?;
+
+        if let Some(mut index) = self.indexes.get_mut(collection) {
+            for vector in &updated {
+                index.set_metadata(&vector.id, vector.metadata.clone());
+            }
+        }
+        if let Some(mut payload_index) = self.payload_indexes.get_mut(collection) {
+            for vector in &updated {
+                payload_index.insert(vector.id, vector.metadata.as_ref());
+            }
+        }
+        if let Some(mut indexes) = self.named_indexes.get_mut(collection) {
+            for vector in &updated {
+                indexes.set_metadata(vector);
+            }
+        }
+
+        Ok(updated.len())
+    }
+
+    /// Batch delete vectors
+    pub async fn batch_delete(&self, collection: &str, ids: &[VectorId]) -> Result<usize> {
+        let start = std::time::Instant::now();
+        counter!("vectorstore.vectors.batch_deleted").increment(ids.len() as u64);
+
+        if ids.is_empty() {
+            return Ok(0);
+        }
+
+        // Validate collection exists
+        self.get_collection_config(collection)?
+            .ok_or_else(|| VectorDbError::CollectionNotFound {
+                name: collection.to_string(),
+            })?;
+
+        let gate = self.write_gate(collection);
+        let _writing = gate.read().await;
+
+        let mut deleted_count = 0;
+
+        // Delete from storage (batch operation)
+        for id in ids {
+            if self.storage.delete_vector(collection, id).await? {
+                deleted_count += 1;
+            }
+        }
+
+        // Delete from index
+        if let Some(mut index) = self.indexes.get_mut(collection) {
+            for id in ids {
+                let _ = index.delete(id); // Continue even if some deletes fail
+            }
+        }
+        if let Some(mut payload_index) = self.payload_indexes.get_mut(collection) {
+            for id in ids {
+                payload_index.remove(id);
+            }
+        }
+        self.unindex_named(collection, ids);
+
+        histogram!("vectorstore.batch_delete.duration").record(start.elapsed().as_secs_f64());
+        histogram!("vectorstore.batch_delete.count").record(deleted_count as f64);
+
+        Ok(deleted_count)
+    }
+
+    /// Batch upsert vectors (update if exists, insert if not)
+    pub async fn batch_upsert(&self, collection: &str, vectors: &[Vector]) -> Result<usize> {
+        let start = std::time::Instant::now();
+        counter!("vectorstore.vectors.batch_upserted").increment(vectors.len() as u64);
+
+        if vectors.is_empty() {
             return Ok(0);
         }
 
@@ -589,14 +1648,13 @@ impl VectorStore {
 
         // Validate all vector dimensions
         for vector in vectors {
-            if vector.data.len() != config.dimension {
-                return Err(VectorDbError::InvalidDimension {
-                    expected: config.dimension,
-                    actual: vector.data.len(),
-                });
-            }
+            config.validate_default_vector(&vector.data)?;
+            validate_named(&config, vector)?;
         }
 
+        let gate = self.write_gate(collection);
+        let _writing = gate.read().await;
+
         let mut upserted_count = 0;
 
         // Check which vectors exist and need updating
@@ -626,10 +1684,16 @@ impl VectorStore {
         // Add all vectors to index
         if let Some(mut index) = self.indexes.get_mut(collection) {
             for vector in &vectors_to_insert {
-                index.insert(vector.id, &vector.data, vector.metadata.clone())?;
+                index_default(index.as_mut(), vector.id, &vector.data, vector.metadata.clone())?;
                 upserted_count += 1;
             }
         }
+        if let Some(mut payload_index) = self.payload_indexes.get_mut(collection) {
+            for vector in &vectors_to_insert {
+                payload_index.insert(vector.id, vector.metadata.as_ref());
+            }
+        }
+        self.index_named(collection, &vectors_to_insert)?;
 
         histogram!("vectorstore.batch_upsert.duration").record(start.elapsed().as_secs_f64());
         histogram!("vectorstore.batch_upsert.count").record(upserted_count as f64);
@@ -657,74 +1721,916 @@ impl VectorStore {
         let mut stats = self.storage.get_collection_stats(name).await?;
 
         if let Some(ref mut stats) = stats {
-            // Add index statistics - DashMap provides lock-free reads
+            // Add index statistics - DashMap provides lock-free reads.
+            // The vector count comes from storage, which tracks every insert,
+            // upsert and delete durably.
             if let Some(index) = self.indexes.get(name) {
                 let index_stats = index.stats();
-                stats.vector_count = index_stats.vector_count;
+                stats.memory = index_stats.memory;
                 stats.memory_usage += index_stats.memory_usage;
+                stats.quantization = index.training_status();
+            }
+            if let Some(indexes) = self.named_indexes.get(name) {
+                for index in indexes.dense.values() {
+                    let index_stats = index.stats();
+                    stats.memory += index_stats.memory;
+                    stats.memory_usage += index_stats.memory_usage;
+                }
+            }
+
+            gauge!("vectorstore.collection.vectors", "collection" => name.to_string())
+                .set(stats.vector_count as f64);
+            for (component, bytes) in memory_components(&stats.memory) {
+                gauge!("vectorstore.collection.memory", "collection" => name.to_string(), "component" => component)
+                    .set(bytes as f64);
             }
         }
 
         Ok(stats)
     }
     
-    /// Sync all data to disk
+    /// Sync all data to disk, including the search indexes
     pub async fn sync(&self) -> Result<()> {
-        self.storage.sync().await
+        self.storage.sync().await?;
+        self.persist_indexes().await
+    }
+
+    /// Write every collection's search index to disk so startup can skip a rebuild
+    pub async fn persist_indexes(&self) -> Result<()> {
+        for collection in self.list_collections() {
+            match self.persist_index(&collection).await {
+                Ok(()) | Err(VectorDbError::CollectionNotFound { .. }) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    /// Write a collection's search index to disk
+    pub async fn persist_index(&self, collection: &str) -> Result<()> {
+        let start = std::time::Instant::now();
+
+        // Take the mark first, with no write caught between storage and the
+        // indexes: everything before it is then in the dump, and anything
+        // written between the mark and the dump is harmlessly replayed again
+        // on load
+        let mark = self.settled_mark(collection).await?;
+        let index = {
+            let index = self.indexes.get(collection).ok_or_else(|| VectorDbError::CollectionNotFound {
+                name: collection.to_string(),
+            })?;
+            index.serialize()?
+        };
+
+        let dump = bincode::serialize(&IndexDump { mark, index })
+            .map_err(|e| VectorDbError::Serialization(e.to_string()))?;
+        self.write_collection_file(collection, INDEX_DUMP_FILE, &dump).await?;
+        self.persist_named_indexes(collection, mark).await?;
+        self.persist_payload_index(collection).await?;
+
+        histogram!("vectorstore.index.persist.duration").record(start.elapsed().as_secs_f64());
+        info!("Persisted index for collection '{}' ({} bytes)", collection, dump.len());
+        Ok(())
+    }
+
+    /// The gate writes to a collection hold; see `write_gates`
+    fn write_gate(&self, collection: &str) -> Arc<tokio::sync::RwLock<()>> {
+        self.write_gates.entry(collection.to_string()).or_default().clone()
+    }
+
+    /// A data mark that every write before it has reached the indexes by
+    async fn settled_mark(&self, collection: &str) -> Result<DataMark> {
+        let gate = self.write_gate(collection);
+        let _writes_paused = gate.write().await;
+        self.storage.get_data_mark(collection).await
+    }
+
+    /// Write a collection's named vector indexes to disk, under the mark of its search index dump
+    async fn persist_named_indexes(&self, collection: &str, mark: DataMark) -> Result<()> {
+        let dump = {
+            let Some(indexes) = self.named_indexes.get(collection) else {
+                return Ok(());
+            };
+            if indexes.is_empty() {
+                return Ok(());
+            }
+            let dense = indexes
+                .dense
+                .iter()
+                .map(|(name, index)| Ok((name.clone(), index.serialize()?)))
+                .collect::<Result<_>>()?;
+            NamedIndexDump { mark, dense, sparse: indexes.sparse.clone() }
+        };
+
+        let dump = bincode::serialize(&dump)
+            .map_err(|e| VectorDbError::Serialization(e.to_string()))?;
+        self.write_collection_file(collection, NAMED_INDEX_DUMP_FILE, &dump).await
+    }
+
+    /// Write a collection's payload indexes to disk
+    async fn persist_payload_index(&self, collection: &str) -> Result<()> {
+        let mark = self.settled_mark(collection).await?;
+        let index = self.payload_indexes
+            .get(collection)
+            .map(|index| index.clone())
+            .unwrap_or_default();
+
+        let dump = bincode::serialize(&PayloadIndexDump { mark, index })
+            .map_err(|e| VectorDbError::Serialization(e.to_string()))?;
+        self.write_collection_file(collection, PAYLOAD_INDEX_FILE, &dump).await
+    }
+
+    /// Atomically replace a file in a collection's directory
+    async fn write_collection_file(&self, collection: &str, file_name: &str, contents: &[u8]) -> Result<()> {
+        let dir = self.storage.get_collection_dir(collection)?;
+        let path = dir.join(file_name);
+        let tmp_path = dir.join(format!("{}.tmp", file_name));
+        tokio::fs::write(&tmp_path, contents).await?;
+        tokio::fs::File::open(&tmp_path).await?.sync_all().await?;
+        tokio::fs::rename(&tmp_path, &path).await?;
+        Ok(())
+    }
+
+    /// Index a payload field so filters on it can skip non-matching vectors
+    ///
+    /// Builds the index from the vectors already in the collection and
+    /// persists it straight away. Replaces any existing index on the field.
+    pub async fn create_payload_index(&self, collection: &str, field_name: &str, field_type: PayloadFieldType) -> Result<()> {
+        info!("Creating {:?} payload index on '{}' in collection '{}'", field_type, field_name, collection);
+        self.add_payload_field(collection, field_name, |index, vectors| {
+            index.create_field(field_name, field_type, vectors.iter().map(|v| (v.id, v.metadata.as_ref())));
+        })
+        .await
+    }
+
+    /// Index a payload field for full-text `MatchText` conditions
+    ///
+    /// Once indexed, text conditions on the field match by token using the
+    /// given tokenizer settings rather than by substring.
+    pub async fn create_text_index(&self, collection: &str, field_name: &str, params: TextIndexParams) -> Result<()> {
+        vectordb_index::Tokenizer::validate(&params)?;
+
+        info!("Creating text index on '{}' in collection '{}' ({:?})", field_name, collection, params);
+        self.add_payload_field(collection, field_name, |index, vectors| {
+            index.create_text_field(field_name, params.clone(), vectors.iter().map(|v| (v.id, v.metadata.as_ref())));
+        })
+        .await
+    }
+
+    /// Add a field to a collection's payload indexes, built by `build` from the vectors in storage
+    async fn add_payload_field(&self, collection: &str, field_name: &str, build: impl Fn(&mut PayloadIndex, &[Vector])) -> Result<()> {
+        if field_name.is_empty() {
+            return Err(VectorDbError::InvalidInput {
+                message: "Payload index field name must not be empty".to_string(),
+            });
+        }
+        if !self.payload_indexes.contains_key(collection) {
+            return Err(VectorDbError::CollectionNotFound {
+                name: collection.to_string(),
+            });
+        }
+
+        // Writes that land during the scan only reach the fields that already
+        // exist, so replay everything past the mark once the new field is in.
+        // Writers wait from the replay until the field is built, as one that
+        // indexed its payload in between would be missed by both.
+        let mark = self.settled_mark(collection).await?;
+        let vectors = self.storage.get_all_vectors(collection).await?;
+
+        let gate = self.write_gate(collection);
+        let writes_paused = gate.write().await;
+        let (vectors, changes) = match self.storage.get_records_since(collection, &mark).await? {
+            Some(records) => (vectors, records),
+            // The data file was compacted during the scan, so the mark is
+            // gone; build again from what storage holds now
+            None => (self.storage.get_all_vectors(collection).await?, Vec::new()),
+        };
+        {
+            let mut payload_index = self.payload_indexes.get_mut(collection).ok_or_else(|| {
+                VectorDbError::CollectionNotFound {
+                    name: collection.to_string(),
+                }
+            })?;
+            build(&mut payload_index, &vectors);
+            apply_records(&mut payload_index, changes);
+        }
+        drop(writes_paused);
+
+        self.persist_payload_index(collection).await
+    }
+
+    /// Drop a payload field index, returning whether it existed
+    pub async fn delete_payload_index(&self, collection: &str, field_name: &str) -> Result<bool> {
+        let removed = self.payload_indexes
+            .get_mut(collection)
+            .ok_or_else(|| VectorDbError::CollectionNotFound {
+                name: collection.to_string(),
+            })?
+            .drop_field(field_name);
+
+        if removed {
+            info!("Dropped payload index on '{}' in collection '{}'", field_name, collection);
+            self.persist_payload_index(collection).await?;
+        }
+        Ok(removed)
+    }
+
+    /// List a collection's payload field indexes
+    pub fn list_payload_indexes(&self, collection: &str) -> Result<Vec<PayloadIndexInfo>> {
+        self.payload_indexes
+            .get(collection)
+            .map(|index| index.info())
+            .ok_or_else(|| VectorDbError::CollectionNotFound {
+                name: collection.to_string(),
+            })
+    }
+
+    /// Consult the payload indexes for a filter
+    ///
+    /// Returns the candidate vectors, if the indexes can narrow the filter, and
+    /// the text index results the filter must be evaluated with.
+    fn plan_filter(&self, collection: &str, filter: &vectordb_common::filter::Filter) -> (Option<HashSet<VectorId>>, TextMatches) {
+        match self.payload_indexes.get(collection) {
+            Some(index) => (index.candidates(filter), index.text_matches(filter)),
+            None => (None, TextMatches::default()),
+        }
+    }
+
+    /// Measure the recall@k of index search against exact search at each
+    /// requested `ef_search`
+    ///
+    /// Queries run one at a time, so their latencies do not include each
+    /// other's. Recall counts how many of a query's exact results the index
+    /// search also returned.
+    pub async fn evaluate(&self, request: &vectordb_common::EvaluateRequest) -> Result<vectordb_common::EvaluateResponse> {
+        counter!("vectorstore.evaluations").increment(1);
+
+        let config = self.get_collection_config(&request.collection)?
+            .ok_or_else(|| VectorDbError::CollectionNotFound {
+                name: request.collection.clone(),
+            })?;
+        if request.limit == 0 {
+            return Err(VectorDbError::InvalidInput {
+                message: "Evaluation limit must be at least 1".to_string(),
+            });
+        }
+        let index_config = match &request.using {
+            Some(name) => named_vector_params(&config, name)?.index_config.as_ref().unwrap_or(&config.index_config),
+            None => &config.index_config,
+        };
+        let ef_values = match request.ef_search.is_empty() {
+            true => vec![index_config.ef_search],
+            false => request.ef_search.clone(),
+        };
+
+        let queries = match request.queries.is_empty() {
+            true => self.sample_queries(&request.collection, request.using.as_deref(), request.sample).await?,
+            false => request.queries.clone(),
+        };
+        if queries.is_empty() {
+            return Err(VectorDbError::InvalidInput {
+                message: format!("Collection '{}' has no vectors to evaluate with", request.collection),
+            });
+        }
+        let query_request = |vector: &[f32], ef_search: Option<usize>, exact: bool| QueryRequest {
+            collection: request.collection.clone(),
+            vector: vector.to_vec(),
+            limit: request.limit,
+            ef_search,
+            filter: None,
+            using: request.using.clone(),
+            multivector: None,
+            rescore: None,
+            oversampling: None,
+            exact,
+        };
+
+        let mut exact_ms = Vec::with_capacity(queries.len());
+        let mut expected = Vec::with_capacity(queries.len());
+        for query in &queries {
+            let started = std::time::Instant::now();
+            let results = self.query(&query_request(query, None, true)).await?;
+            exact_ms.push(started.elapsed().as_secs_f64() * 1000.0);
+            expected.push(results.into_iter().map(|r| r.id).collect::<HashSet<_>>());
+        }
+
+        let mut runs = Vec::with_capacity(ef_values.len());
+        for ef_search in ef_values {
+            let mut recall = 0.0;
+            let mut latency_ms = Vec::with_capacity(queries.len());
+            let mut visited_total = 0;
+            let mut visited_max = 0;
+            for (query, expected) in queries.iter().zip(&expected) {
+                let mut visited = 0;
+                let started = std::time::Instant::now();
+                let results = self.query_counted(&query_request(query, Some(ef_search), false), Some(&mut visited)).await?;
+                latency_ms.push(started.elapsed().as_secs_f64() * 1000.0);
+
+                recall += match expected.len() {
+                    0 => 1.0,
+                    n => results.iter().filter(|r| expected.contains(&r.id)).count() as f64 / n as f64,
+                };
+                visited_total += visited;
+                visited_max = visited_max.max(visited);
+            }
+            runs.push(vectordb_common::EvaluationRun {
+                ef_search,
+                recall: recall / queries.len() as f64,
+                latency: vectordb_common::LatencySummary::from_samples(latency_ms),
+                visited_mean: visited_total as f64 / queries.len() as f64,
+                visited_max,
+            });
+        }
+
+        Ok(vectordb_common::EvaluateResponse {
+            queries: queries.len(),
+            limit: request.limit,
+            exact_latency: vectordb_common::LatencySummary::from_samples(exact_ms),
+            runs,
+        })
+    }
+
+    /// Up to `sample` stored vectors spread evenly over the collection, from
+    /// the named space `using` or the default vector
+    async fn sample_queries(&self, collection: &str, using: Option<&str>, sample: usize) -> Result<Vec<Vec<f32>>> {
+        let vectors: Vec<Vec<f32>> = self.storage.get_all_vectors(collection).await?
+            .into_iter()
+            .filter_map(|mut v| match using {
+                Some(name) => v.vectors.as_mut()?.remove(name),
+                None => Some(v.data).filter(|data| !data.is_empty()),
+            })
+            .collect();
+        let step = vectors.len().div_ceil(sample.max(1)).max(1);
+        Ok(vectors.into_iter().step_by(step).take(sample).collect())
+    }
+
+    /// Train a product quantized collection's codebooks and re-encode its vectors
+    ///
+    /// Happens automatically, in the background, once the collection reaches
+    /// its training threshold; calling it again retrains on a fresh sample.
+    pub async fn train_quantizer(&self, collection: &str) -> Result<TrainingStatus> {
+        self.product_quantization(collection)?;
+
+        if !self.training.insert(collection.to_string()) {
+            return Err(VectorDbError::InvalidInput {
+                message: format!("Quantizer for collection '{}' is already being trained", collection),
+            });
+        }
+        let result = self.train_product_quantizer(collection).await;
+        self.training.remove(collection);
+        result
+    }
+
+    /// A collection's config, provided it is product quantized
+    fn product_quantization(
+        &self,
+        collection: &str,
+    ) -> Result<(CollectionConfig, vectordb_common::quantization::ProductQuantizationConfig)> {
+        let config = self.get_collection_config(collection)?
+            .ok_or_else(|| VectorDbError::CollectionNotFound {
+                name: collection.to_string(),
+            })?;
+        match &config.quantization {
+            Some(QuantizationConfig::Product(pq_config)) => {
+                let pq_config = pq_config.clone();
+                Ok((config, pq_config))
+            }
+            _ => Err(VectorDbError::InvalidInput {
+                message: format!("Collection '{}' does not use product quantization", collection),
+            }),
+        }
+    }
+
+    /// Train a collection's product quantizer; the caller has claimed it in `training`
+    ///
+    /// Storage is read `SCAN_CHUNK` vectors at a time, once to sample it
+    /// and once to encode it, so only the sample and one chunk are held in
+    /// memory however large the collection is.
+    async fn train_product_quantizer(&self, collection: &str) -> Result<TrainingStatus> {
+        let start = std::time::Instant::now();
+        counter!("vectorstore.quantizer.trainings").increment(1);
+
+        let (config, pq_config) = self.product_quantization(collection)?;
+        let mark = self.settled_mark(collection).await?;
+
+        // An evenly spaced sample of the collection
+        let live = self.storage.get_compaction_stats(collection).await?.live_records;
+        let step = live.div_ceil(pq_config.training_sample.max(1)).max(1);
+        let mut sample: Vec<Vec<f32>> = Vec::new();
+        let mut seen = 0;
+        self.scan_stored(collection, |vectors| {
+            for vector in vectors.into_iter().filter(|v| !v.data.is_empty()) {
+                if seen % step == 0 && sample.len() < pq_config.training_sample.max(1) {
+                    sample.push(vector.data);
+                }
+                seen += 1;
+            }
+            Ok(())
+        })
+        .await?;
+        if sample.is_empty() {
+            return Err(VectorDbError::InvalidInput {
+                message: format!("Collection '{}' has no vectors to train on", collection),
+            });
+        }
+        let sample_size = sample.len();
+
+        // k-means is CPU bound, so keep it off the async workers
+        let (metric, dimension, training_config) = (config.distance_metric, config.dimension, pq_config.clone());
+        let quantizer = tokio::task::spawn_blocking(move || {
+            PqIndex::train_quantizer(metric, dimension, &training_config, &sample)
+        })
+        .await
+        .map_err(|e| VectorDbError::Internal {
+            message: format!("Quantizer training failed: {}", e),
+        })??;
+
+        let codebooks = bincode::serialize(&PqCodebooks { quantizer: quantizer.clone(), sample_size })
+            .map_err(|e| VectorDbError::Serialization(e.to_string()))?;
+        self.write_collection_file(collection, PQ_CODEBOOKS_FILE, &codebooks).await?;
+
+        let mut index: Box<dyn VectorIndex> =
+            Box::new(PqIndex::trained(metric, dimension, &pq_config, quantizer, sample_size));
+        self.scan_stored(collection, |vectors| {
+            index.batch_insert(
+                vectors
+                    .into_iter()
+                    .filter(|v| !v.data.is_empty())
+                    .map(|v| (v.id, v.data, v.metadata))
+                    .collect(),
+            )
+        })
+        .await?;
+
+        // Writes made while training only reached the old index. Replay them
+        // before the swap, then once more for any that landed just before it;
+        // replaying a write twice is harmless.
+        let next_mark = self.settled_mark(collection).await?;
+        let records = self.storage.get_records_since(collection, &mark).await?.ok_or_else(|| {
+            VectorDbError::StorageError {
+                message: format!("Collection '{}' was compacted while its quantizer was trained", collection),
+            }
+        })?;
+        apply_index_records(index.as_mut(), records)?;
+        self.indexes.insert(collection.to_string(), index);
+
+        let records = self.storage.get_records_since(collection, &next_mark).await?.unwrap_or_default();
+        if let Some(mut index) = self.indexes.get_mut(collection) {
+            apply_index_records(index.as_mut(), records)?;
+        }
+        // A dump from before training would hold codes from the old codebooks
+        self.persist_index(collection).await?;
+
+        histogram!("vectorstore.quantizer.training.duration").record(start.elapsed().as_secs_f64());
+        info!("Trained product quantizer for collection '{}' on {} vectors", collection, sample_size);
+        Ok(TrainingStatus::Trained { sample_size })
+    }
+
+    /// Pass every live vector of a collection to `visit`, `SCAN_CHUNK` at a time
+    async fn scan_stored(&self, collection: &str, mut visit: impl FnMut(Vec<Vector>) -> Result<()>) -> Result<()> {
+        let mut mark = DataMark::START;
+        loop {
+            let (vectors, next) = self.storage
+                .get_vectors_since(collection, &mark, SCAN_CHUNK)
+                .await?
+                .ok_or_else(|| VectorDbError::StorageError {
+                    message: format!("Collection '{}' was compacted while it was being scanned", collection),
+                })?;
+            if vectors.is_empty() {
+                return Ok(());
+            }
+            mark = next;
+            visit(vectors)?;
+        }
+    }
+
+    /// Start training a product quantized collection in the background once
+    /// it has grown to its training threshold
+    fn train_quantizer_if_due(&self, collection: &str) {
+        let due = self
+            .indexes
+            .get(collection)
+            .and_then(|index| index.training_status())
+            .is_some_and(|status| {
+                matches!(status, TrainingStatus::Untrained { vectors, threshold } if vectors >= threshold)
+            });
+
+        if due && self.training.insert(collection.to_string()) {
+            let store = self.clone();
+            let collection = collection.to_string();
+            tokio::spawn(async move {
+                if let Err(e) = store.train_product_quantizer(&collection).await {
+                    warn!("Failed to train quantizer for collection '{}': {}", collection, e);
+                }
+                store.training.remove(&collection);
+            });
+        }
+    }
+
+    /// Compact a collection's data file on demand
+    pub async fn compact_collection(&self, collection: &str) -> Result<vectordb_storage::CompactionReport> {
+        info!("Compacting collection: {}", collection);
+        let report = self.storage.compact_collection(collection).await?;
+
+        // Compaction invalidates the mark in the existing dump
+        self.persist_index(collection).await?;
+        Ok(report)
+    }
+
+    /// Compact every collection whose fraction of dead records is at least `dead_ratio`
+    pub async fn compact_if_needed(&self, dead_ratio: f64) -> Result<Vec<vectordb_storage::CompactionReport>> {
+        let mut reports = Vec::new();
+
+        for collection in self.storage.list_collections() {
+            let stats = match self.storage.get_compaction_stats(&collection).await {
+                Ok(stats) => stats,
+                // Collection was dropped while we were iterating
+                Err(VectorDbError::CollectionNotFound { .. }) => continue,
+                Err(e) => return Err(e),
+            };
+
+            gauge!("storage.compaction.dead_ratio", "collection" => collection.clone())
+                .set(stats.dead_ratio());
+
+            if stats.total_records > 0 && stats.dead_ratio() >= dead_ratio {
+                info!(
+                    "Collection '{}' has {:.0}% dead records, compacting",
+                    collection,
+                    stats.dead_ratio() * 100.0
+                );
+                reports.push(self.compact_collection(&collection).await?);
+            }
+        }
+
+        Ok(reports)
     }
     
-    /// Rebuild indexes from storage (used during startup)
+    /// Load or rebuild indexes from storage (used during startup)
     async fn rebuild_indexes(&mut self) -> Result<()> {
-        info!("Rebuilding indexes from storage...");
+        info!("Loading indexes from storage...");
 
         let collections = self.storage.list_collections();
 
         for collection_name in collections {
             if let Some(config) = self.storage.get_collection_config(&collection_name)? {
-                info!("Rebuilding index for collection: {}", collection_name);
-
-                // Create new index
-                let mut index = Box::new(HnswRsIndex::new(
-                    config.index_config.clone(),
-                    config.distance_metric,
-                    config.dimension,
-                ));
-
-                // Load all vectors from storage and rebuild the index
-                match self.storage.get_all_vectors(&collection_name).await {
-                    Ok(vectors) => {
-                        info!("Loading {} vectors into index for collection '{}'", vectors.len(), collection_name);
-
-                        // Prepare vectors for batch insert
-                        let vectors_to_insert: Vec<(uuid::Uuid, Vec<f32>, Option<_>)> = vectors
-                            .iter()
-                            .map(|v| (v.id, v.data.clone(), v.metadata.clone()))
-                            .collect();
-
-                        // Batch insert all vectors into the index
-                        if !vectors_to_insert.is_empty() {
-                            if let Err(e) = index.batch_insert(vectors_to_insert) {
-                                error!("Failed to rebuild index for collection '{}': {}", collection_name, e);
-                                // Continue with empty index rather than failing completely
-                            } else {
-                                info!("Successfully rebuilt index for collection '{}' with {} vectors",
-                                      collection_name, vectors.len());
-                            }
-                        }
+                let index = match self.load_persisted_index(&collection_name, &config).await {
+                    Ok(Some(index)) => {
+                        counter!("vectorstore.index.loads").increment(1);
+                        index
+                    }
+                    Ok(None) => {
+                        let index = self.build_index(&collection_name, &config).await;
+                        counter!("vectorstore.index.rebuilds").increment(1);
+                        index
+                    }
+                    Err(e) => {
+                        warn!("Ignoring persisted index for collection '{}': {}", collection_name, e);
+                        let index = self.build_index(&collection_name, &config).await;
+                        counter!("vectorstore.index.rebuilds").increment(1);
+                        index
                     }
+                };
+
+                self.indexes.insert(collection_name.clone(), index);
+
+                let payload_index = self.load_payload_index(&collection_name).await;
+                self.payload_indexes.insert(collection_name.clone(), payload_index);
+
+                let named_indexes = self.load_named_indexes(&collection_name, &config).await;
+                self.named_indexes.insert(collection_name.clone(), named_indexes);
+            }
+        }
+
+        info!("Index loading completed");
+        Ok(())
+    }
+
+    /// Load a collection's persisted index and catch it up with the data file
+    ///
+    /// Returns `None` when there is no usable dump: none was written, the data
+    /// file was rewritten since, or the result disagrees with storage.
+    async fn load_persisted_index(&self, collection_name: &str, config: &CollectionConfig) -> Result<Option<Box<dyn VectorIndex>>> {
+        let path = self.storage.get_collection_dir(collection_name)?.join(INDEX_DUMP_FILE);
+        let bytes = match tokio::fs::read(&path).await {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        let dump: IndexDump = bincode::deserialize(&bytes)
+            .map_err(|e| VectorDbError::Serialization(e.to_string()))?;
+
+        let Some(records) = self.storage.get_records_since(collection_name, &dump.mark).await? else {
+            info!("Persisted index for collection '{}' is stale, rebuilding", collection_name);
+            return Ok(None);
+        };
+
+        // The data file can repeat records the dump already covers (the WAL is
+        // replayed on every start), so collapse to the final state per ID and
+        // only touch the graph for vectors that actually changed
+        let replayed = records.len();
+        let mut latest: std::collections::HashMap<VectorId, DataRecord> = std::collections::HashMap::new();
+        for record in records {
+            latest.insert(record.id(), record);
+        }
+
+        let (index, added): (Box<dyn VectorIndex>, usize) = if config.index_config.index_type == IndexType::Hnsw {
+            let mut index = Box::new(HnswRsIndex::for_collection(config));
+            index.deserialize(&dump.index)?;
+
+            let mut upserts = Vec::new();
+            for (id, record) in latest {
+                match record {
+                    DataRecord::Vector(vector) if vector.data.is_empty() => {
+                        index.delete(&id)?;
+                    }
+                    DataRecord::Vector(vector) => upserts.push((vector.id, vector.data, vector.metadata)),
+                    DataRecord::Tombstone(_) => {
+                        index.delete(&id)?;
+                    }
+                }
+            }
+            let added = index.upsert_changed(upserts)?;
+
+            if index.orphaned_count() > index.stats().vector_count {
+                info!(
+                    "Persisted index for collection '{}' is mostly deleted points, rebuilding",
+                    collection_name
+                );
+                return Ok(None);
+            }
+            (index, added)
+        } else {
+            // Flat, IVF, product and binary quantized indexes update in place,
+            // so replaying is cheap. Binary codes go back where `open_index`
+            // keeps them, in RAM or mapped from disk.
+            let mut index = self.open_index(collection_name, config).await;
+            index.deserialize(&dump.index)?;
+            let added = latest
+                .values()
+                .filter(|record| matches!(record, DataRecord::Vector(v) if !v.data.is_empty()))
+                .count();
+            apply_index_records(index.as_mut(), latest.into_values().collect())?;
+            (index, added)
+        };
+
+        // Points with only named vectors are stored but not in this index
+        let expected = self.storage.get_compaction_stats(collection_name).await?.live_records;
+        let loaded = index.stats().vector_count;
+        if loaded > expected || (loaded < expected && !config.has_named_spaces()) {
+            warn!(
+                "Persisted index for collection '{}' has {} vectors but storage has {}, rebuilding",
+                collection_name, loaded, expected
+            );
+            return Ok(None);
+        }
+
+        info!(
+            "Loaded persisted index for collection '{}' with {} vectors ({} records replayed, {} changed)",
+            collection_name, loaded, replayed, added
+        );
+        Ok(Some(index))
+    }
+
+    /// Load a collection's payload indexes, replaying records written since they were saved
+    ///
+    /// Field declarations survive a stale or unreadable dump by way of a rebuild
+    /// from storage; only a missing or corrupt file loses them.
+    async fn load_payload_index(&self, collection_name: &str) -> PayloadIndex {
+        let path = match self.storage.get_collection_dir(collection_name) {
+            Ok(dir) => dir.join(PAYLOAD_INDEX_FILE),
+            Err(_) => return PayloadIndex::new(),
+        };
+        let dump: PayloadIndexDump = match tokio::fs::read(&path).await {
+            Ok(bytes) => match bincode::deserialize(&bytes) {
+                Ok(dump) => dump,
+                Err(e) => {
+                    warn!("Ignoring corrupt payload index for collection '{}': {}", collection_name, e);
+                    return PayloadIndex::new();
+                }
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return PayloadIndex::new(),
+            Err(e) => {
+                warn!("Failed to read payload index for collection '{}': {}", collection_name, e);
+                return PayloadIndex::new();
+            }
+        };
+
+        let mut index = dump.index;
+        if index.is_empty() {
+            return index;
+        }
+
+        match self.storage.get_records_since(collection_name, &dump.mark).await {
+            Ok(Some(records)) => {
+                apply_records(&mut index, records);
+                return index;
+            }
+            Ok(None) => info!("Payload index for collection '{}' is stale, rebuilding", collection_name),
+            Err(e) => warn!("Failed to replay payload index for collection '{}': {}, rebuilding", collection_name, e),
+        }
+
+        let mut rebuilt = index.cleared();
+        match self.storage.get_all_vectors(collection_name).await {
+            Ok(vectors) => {
+                for vector in &vectors {
+                    rebuilt.insert(vector.id, vector.metadata.as_ref());
+                }
+            }
+            Err(e) => error!("Failed to load vectors for collection '{}': {}", collection_name, e),
+        }
+        rebuilt
+    }
+
+    /// Empty index for a collection, set up with its trained codebooks if it
+    /// has any, or with its bit codes mapped from disk if they are not kept in RAM
+    async fn open_index(&self, collection_name: &str, config: &CollectionConfig) -> Box<dyn VectorIndex> {
+        let pq_config = match &config.quantization {
+            Some(QuantizationConfig::Product(pq_config)) => pq_config,
+            Some(QuantizationConfig::Binary(binary_config)) if !binary_config.always_ram => {
+                let mapped = self
+                    .storage
+                    .get_collection_dir(collection_name)
+                    .and_then(|dir| BinaryIndex::mapped(config.dimension, &dir));
+                return match mapped {
+                    Ok(index) => Box::new(index),
                     Err(e) => {
-                        error!("Failed to load vectors for collection '{}': {}", collection_name, e);
+                        warn!("Keeping binary codes for collection '{}' in RAM: {}", collection_name, e);
+                        new_index(config)
+                    }
+                };
+            }
+            _ => return new_index(config),
+        };
+        let path = match self.storage.get_collection_dir(collection_name) {
+            Ok(dir) => dir.join(PQ_CODEBOOKS_FILE),
+            Err(_) => return new_index(config),
+        };
+
+        match tokio::fs::read(&path).await {
+            Ok(bytes) => match bincode::deserialize::<PqCodebooks>(&bytes) {
+                Ok(codebooks) => {
+                    return Box::new(PqIndex::trained(
+                        config.distance_metric,
+                        config.dimension,
+                        pq_config,
+                        codebooks.quantizer,
+                        codebooks.sample_size,
+                    ));
+                }
+                Err(e) => warn!("Ignoring corrupt codebooks for collection '{}': {}", collection_name, e),
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => warn!("Failed to read codebooks for collection '{}': {}", collection_name, e),
+        }
+        new_index(config)
+    }
+
+    /// Build a collection's index from every vector in storage
+    async fn build_index(&self, collection_name: &str, config: &CollectionConfig) -> Box<dyn VectorIndex> {
+        info!("Rebuilding index for collection: {}", collection_name);
+
+        // Create new index
+        let mut index = self.open_index(collection_name, config).await;
+
+        // Load all vectors from storage and rebuild the index
+        match self.storage.get_all_vectors(collection_name).await {
+            Ok(vectors) => {
+                info!("Loading {} vectors into index for collection '{}'", vectors.len(), collection_name);
+
+                // Prepare vectors for batch insert
+                let vectors_to_insert: Vec<(uuid::Uuid, Vec<f32>, Option<_>)> = vectors
+                    .iter()
+                    .filter(|v| !v.data.is_empty())
+                    .map(|v| (v.id, v.data.clone(), v.metadata.clone()))
+                    .collect();
+
+                // Batch insert all vectors into the index
+                if !vectors_to_insert.is_empty() {
+                    if let Err(e) = index.batch_insert(vectors_to_insert) {
+                        error!("Failed to rebuild index for collection '{}': {}", collection_name, e);
                         // Continue with empty index rather than failing completely
+                    } else {
+                        info!("Successfully rebuilt index for collection '{}' with {} vectors",
+                              collection_name, vectors.len());
                     }
                 }
+            }
+            Err(e) => {
+                error!("Failed to load vectors for collection '{}': {}", collection_name, e);
+                // Continue with empty index rather than failing completely
+            }
+        }
 
-                self.indexes.insert(collection_name.clone(), index);
+        index
+    }
+
+    /// Build a collection's named vector indexes from every vector in storage
+    async fn load_named_indexes(&self, collection_name: &str, config: &CollectionConfig) -> NamedIndexes {
+        let mut indexes = NamedIndexes::new(config);
+        if indexes.is_empty() {
+            return indexes;
+        }
+
+        match self.load_persisted_named_indexes(collection_name, config).await {
+            Ok(Some(persisted)) => return persisted,
+            Ok(None) => {}
+            Err(e) => warn!(
+                "Failed to load persisted named vector indexes for collection '{}': {}, rebuilding",
+                collection_name, e
+            ),
+        }
+
+        match self.storage.get_all_vectors(collection_name).await {
+            Ok(vectors) => {
+                for (name, index) in indexes.dense.iter_mut() {
+                    let batch: Vec<(VectorId, Vec<f32>, Option<_>)> = vectors
+                        .iter()
+                        .filter_map(|v| {
+                            let data = v.vectors.as_ref()?.get(name)?;
+                            Some((v.id, data.clone(), v.metadata.clone()))
+                        })
+                        .collect();
+                    if let Err(e) = index.batch_insert(batch) {
+                        error!("Failed to rebuild index for vector '{}' in collection '{}': {}", name, collection_name, e);
+                    }
+                }
+                for vector in &vectors {
+                    for (name, sparse) in vector.sparse.iter().flatten() {
+                        if let Some(index) = indexes.sparse.get_mut(name) {
+                            index.insert(vector.id, sparse);
+                        }
+                    }
+                }
             }
+            Err(e) => error!("Failed to load named vectors for collection '{}': {}", collection_name, e),
+        }
+        indexes
+    }
+
+    /// Load a collection's named vector indexes from their dump, replaying records written since
+    ///
+    /// Returns `None` when there is no usable dump and the indexes need rebuilding.
+    async fn load_persisted_named_indexes(
+        &self,
+        collection_name: &str,
+        config: &CollectionConfig,
+    ) -> Result<Option<NamedIndexes>> {
+        let path = self.storage.get_collection_dir(collection_name)?.join(NAMED_INDEX_DUMP_FILE);
+        let bytes = match tokio::fs::read(&path).await {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut dump: NamedIndexDump = bincode::deserialize(&bytes)
+            .map_err(|e| VectorDbError::Serialization(e.to_string()))?;
+
+        let Some(records) = self.storage.get_records_since(collection_name, &dump.mark).await? else {
+            info!("Persisted named vector indexes for collection '{}' are stale, rebuilding", collection_name);
+            return Ok(None);
+        };
+
+        let mut indexes = NamedIndexes::new(config);
+        for (name, index) in indexes.dense.iter_mut() {
+            let Some(bytes) = dump.dense.get(name) else {
+                return Ok(None);
+            };
+            index.deserialize(bytes)?;
+        }
+        for (name, index) in indexes.sparse.iter_mut() {
+            let Some(dumped) = dump.sparse.remove(name) else {
+                return Ok(None);
+            };
+            *index = dumped;
         }
 
-        info!("Index rebuild completed");
+        let replayed = records.len();
+        indexes.replay(records)?;
+        info!(
+            "Loaded persisted named vector indexes for collection '{}' ({} records replayed)",
+            collection_name, replayed
+        );
+        Ok(Some(indexes))
+    }
+
+    /// Index the named vectors of freshly written points
+    fn index_named(&self, collection: &str, vectors: &[Vector]) -> Result<()> {
+        if let Some(mut indexes) = self.named_indexes.get_mut(collection) {
+            for vector in vectors {
+                indexes.insert(vector)?;
+            }
+        }
         Ok(())
     }
+
+    fn unindex_named(&self, collection: &str, ids: &[VectorId]) {
+        if let Some(mut indexes) = self.named_indexes.get_mut(collection) {
+            for id in ids {
+                indexes.remove(id);
+            }
+        }
+    }
     
     /// Get server statistics
     pub async fn get_server_stats(&self) -> Result<ServerStats> {
@@ -733,11 +2639,13 @@ impl VectorStore {
         
         let mut total_vectors = 0u64;
         let mut memory_usage = 0u64;
+        let mut memory = MemoryBreakdown::default();
         
         for collection in &collections {
             if let Some(stats) = self.get_collection_stats(collection).await? {
                 total_vectors += stats.vector_count as u64;
                 memory_usage += stats.memory_usage as u64;
+                memory += stats.memory;
             }
         }
         
@@ -745,11 +2653,15 @@ impl VectorStore {
         gauge!("vectorstore.collections.total").set(total_collections as f64);
         gauge!("vectorstore.vectors.total").set(total_vectors as f64);
         gauge!("vectorstore.memory.usage").set(memory_usage as f64);
+        for (component, bytes) in memory_components(&memory) {
+            gauge!("vectorstore.memory.usage.component", "component" => component).set(bytes as f64);
+        }
         
         Ok(ServerStats {
             total_vectors,
             total_collections,
             memory_usage,
+            memory,
             disk_usage: 0, // TODO: Calculate actual disk usage
             uptime_seconds: 0, // TODO: Track server uptime
         })
@@ -812,11 +2724,7 @@ impl VectorStore {
 
         // Rebuild index for restored collection
         if let Some(config) = self.storage.get_collection_config(collection_name)? {
-            let index = Box::new(vectordb_index::HnswRsIndex::new(
-                config.index_config.clone(),
-                config.distance_metric,
-                config.dimension,
-            ));
+            let index = self.open_index(collection_name, &config).await;
             self.indexes.insert(collection_name.to_string(), index);
 
             // Load vectors and rebuild index
@@ -824,6 +2732,7 @@ impl VectorStore {
                 if let Some(mut index) = self.indexes.get_mut(collection_name) {
                     let vectors_to_insert: Vec<(uuid::Uuid, Vec<f32>, Option<_>)> = vectors
                         .iter()
+                        .filter(|v| !v.data.is_empty())
                         .map(|v| (v.id, v.data.clone(), v.metadata.clone()))
                         .collect();
 
@@ -832,6 +2741,9 @@ impl VectorStore {
                     }
                 }
             }
+
+            let named_indexes = self.load_named_indexes(collection_name, &config).await;
+            self.named_indexes.insert(collection_name.to_string(), named_indexes);
         }
 
         info!("Snapshot restored successfully: {}", collection_name);
@@ -839,12 +2751,328 @@ impl VectorStore {
     }
 }
 
+/// Reject named vectors that are malformed or belong to no declared space
+fn validate_named(config: &CollectionConfig, vector: &Vector) -> Result<()> {
+    for (name, data) in vector.vectors.iter().flatten() {
+        let params = named_vector_params(config, name)?;
+        if data.len() != params.dimension {
+            return Err(VectorDbError::InvalidDimension {
+                expected: params.dimension,
+                actual: data.len(),
+            });
+        }
+    }
+    for (name, rows) in vector.multivectors.iter().flatten() {
+        validate_multivector(multivector_params(config, name)?, rows)?;
+    }
+    for (name, sparse) in vector.sparse.iter().flatten() {
+        if !config.sparse_vectors.as_ref().is_some_and(|spaces| spaces.contains_key(name)) {
+            return Err(VectorDbError::InvalidInput {
+                message: format!("Collection '{}' has no sparse vector named '{}'", config.name, name),
+            });
+        }
+        sparse.validate().map_err(|message| VectorDbError::InvalidInput { message })?;
+    }
+    Ok(())
+}
+
+/// Parameters of a named multi-vector space
+fn multivector_params<'a>(config: &'a CollectionConfig, name: &str) -> Result<&'a MultiVectorParams> {
+    config
+        .multivectors
+        .as_ref()
+        .and_then(|spaces| spaces.get(name))
+        .ok_or_else(|| VectorDbError::InvalidInput {
+            message: format!("Collection '{}' has no multi-vector named '{}'", config.name, name),
+        })
+}
+
+/// A multi-vector needs at least one row, and every row the space's dimension
+fn validate_multivector(params: &MultiVectorParams, rows: &[Vec<f32>]) -> Result<()> {
+    if rows.is_empty() {
+        return Err(VectorDbError::InvalidInput {
+            message: "Multi-vector must have at least one row".to_string(),
+        });
+    }
+    match rows.iter().find(|row| row.len() != params.dimension) {
+        Some(row) => Err(VectorDbError::InvalidDimension {
+            expected: params.dimension,
+            actual: row.len(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Empty search index for a collection's default vector
+fn new_index(config: &CollectionConfig) -> Box<dyn VectorIndex> {
+    match &config.quantization {
+        Some(QuantizationConfig::Product(pq_config)) => {
+            Box::new(PqIndex::new(config.distance_metric, config.dimension, pq_config))
+        }
+        Some(QuantizationConfig::Binary(_)) => Box::new(BinaryIndex::new(config.dimension)),
+        Some(QuantizationConfig::Scalar(_)) => Box::new(HnswRsIndex::for_collection(config)),
+        _ => dense_index(&config.index_config, config.distance_metric, config.dimension, config.vector_type),
+    }
+}
+
+/// Empty index of the type `index_config` selects, for unquantized vectors
+fn dense_index(
+    index_config: &IndexConfig,
+    distance_metric: DistanceMetric,
+    dimension: usize,
+    vector_type: VectorType,
+) -> Box<dyn VectorIndex> {
+    match index_config.index_type {
+        IndexType::Hnsw => Box::new(HnswRsIndex::for_vector_type(
+            index_config.clone(),
+            distance_metric,
+            dimension,
+            vector_type,
+        )),
+        IndexType::Flat => Box::new(FlatIndex::new(distance_metric, dimension, vector_type)),
+        IndexType::IvfFlat | IndexType::IvfPq => Box::new(IvfIndex::new(
+            distance_metric,
+            dimension,
+            vector_type,
+            index_config.index_type,
+            &index_config.ivf,
+        )),
+    }
+}
+
+/// Reject quantization and index settings the collection's indexes could not be built with
+fn validate_quantization(config: &CollectionConfig) -> Result<()> {
+    let index_type = config.index_config.index_type;
+    if index_type != IndexType::Hnsw
+        && !matches!(config.quantization, None | Some(QuantizationConfig::None))
+    {
+        return Err(VectorDbError::Configuration {
+            message: format!("Quantization picks its own index and cannot be combined with {:?}", index_type),
+        });
+    }
+    IvfIndex::validate(config.dimension, index_type, &config.index_config.ivf)?;
+    for params in config.vectors.iter().flat_map(|vectors| vectors.values()) {
+        if let Some(index_config) = &params.index_config {
+            IvfIndex::validate(params.dimension, index_config.index_type, &index_config.ivf)?;
+        }
+    }
+
+    match &config.quantization {
+        Some(QuantizationConfig::Product(pq_config)) => {
+            ProductQuantizer::new(config.dimension, pq_config.num_segments, pq_config.num_centroids)?;
+        }
+        Some(QuantizationConfig::Binary(binary_config)) if !(1.0..).contains(&binary_config.oversampling) => {
+            return Err(VectorDbError::Configuration {
+                message: format!("oversampling must be at least 1, got {}", binary_config.oversampling),
+            });
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Distance between two vectors as the HNSW index reports it, which searches
+/// every metric but Euclidean by cosine distance
+fn index_distance(a: &[f32], b: &[f32], metric: DistanceMetric) -> f32 {
+    match metric {
+        DistanceMetric::Euclidean => vectordb_common::distance::distance(a, b, DistanceMetric::Euclidean),
+        _ => vectordb_common::distance::distance(a, b, DistanceMetric::Cosine),
+    }
+}
+
+/// Cut `results` down to the `limit` with the smallest distances, in no particular order
+fn keep_nearest(results: &mut Vec<QueryResult>, limit: usize) {
+    if results.len() <= limit {
+        return;
+    }
+    if limit > 0 {
+        results.select_nth_unstable_by(limit - 1, |a, b| a.distance.total_cmp(&b.distance));
+    }
+    results.truncate(limit);
+}
+
+/// Rank points by MaxSim against a multi-vector query, best first
+///
+/// Points without a multi-vector in the queried space are left out.
+fn rank_max_sim(query: &MultiVectorQuery, metric: DistanceMetric, points: Vec<Vector>, limit: usize) -> Vec<QueryResult> {
+    let mut results: Vec<QueryResult> = points
+        .into_iter()
+        .filter_map(|point| {
+            let rows = point.multivectors.as_ref()?.get(&query.using)?;
+            Some(QueryResult {
+                id: point.id,
+                distance: -vectordb_common::max_sim(&query.vectors, rows, metric),
+                metadata: point.metadata,
+            })
+        })
+        .collect();
+    results.sort_by(|a, b| a.distance.partial_cmp(&b.distance).unwrap_or(std::cmp::Ordering::Equal));
+    results.truncate(limit);
+    results
+}
+
+/// Parameters of a named dense vector space
+fn named_vector_params<'a>(config: &'a CollectionConfig, name: &str) -> Result<&'a VectorParams> {
+    config
+        .vectors
+        .as_ref()
+        .and_then(|spaces| spaces.get(name))
+        .ok_or_else(|| VectorDbError::InvalidInput {
+            message: format!("Collection '{}' has no vector named '{}'", config.name, name),
+        })
+}
+
+/// Sparse vector space a query targets; it may go unnamed when the collection has only one
+fn sparse_space<'a>(config: &'a CollectionConfig, requested: Option<&'a str>) -> Result<&'a str> {
+    let spaces = config.sparse_vectors.as_ref().filter(|spaces| !spaces.is_empty());
+    match (spaces, requested) {
+        (Some(spaces), Some(name)) if spaces.contains_key(name) => Ok(name),
+        (Some(spaces), None) if spaces.len() == 1 => Ok(spaces.keys().next().unwrap()),
+        (Some(_), None) => Err(VectorDbError::InvalidInput {
+            message: format!(
+                "Collection '{}' has several sparse vectors, pick one with sparse_name",
+                config.name
+            ),
+        }),
+        (_, Some(name)) => Err(VectorDbError::InvalidInput {
+            message: format!("Collection '{}' has no sparse vector named '{}'", config.name, name),
+        }),
+        (None, None) => Err(VectorDbError::InvalidInput {
+            message: format!("Collection '{}' has no sparse vectors", config.name),
+        }),
+    }
+}
+
+/// Replay data file records into a search index whose inserts replace earlier versions
+fn apply_index_records(index: &mut dyn VectorIndex, records: Vec<DataRecord>) -> Result<()> {
+    for record in records {
+        match record {
+            DataRecord::Vector(vector) => index_default(index, vector.id, &vector.data, vector.metadata)?,
+            DataRecord::Tombstone(id) => {
+                index.delete(&id)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// A stored point's default vector, for use as an example
+fn default_vector(vector: Vector) -> Result<Vec<f32>> {
+    if vector.data.is_empty() {
+        return Err(VectorDbError::InvalidInput {
+            message: format!("Point {} has no default vector", vector.id),
+        });
+    }
+    Ok(vector.data)
+}
+
+/// Put a point's default vector in the index, or keep the point out of it if
+/// it only has named vectors
+fn index_default(
+    index: &mut dyn VectorIndex,
+    id: VectorId,
+    data: &[f32],
+    metadata: Option<HashMap<String, serde_json::Value>>,
+) -> Result<()> {
+    if data.is_empty() {
+        index.delete(&id)?;
+        return Ok(());
+    }
+    index.insert(id, data, metadata)
+}
+
+/// Replay data file records into payload indexes, in file order
+fn apply_records(index: &mut PayloadIndex, records: Vec<DataRecord>) {
+    for record in records {
+        match record {
+            DataRecord::Vector(vector) => index.insert(vector.id, vector.metadata.as_ref()),
+            DataRecord::Tombstone(id) => index.remove(&id),
+        }
+    }
+}
+
+/// Distinct facetable values of `field`: strings, integers and bools, with
+/// array elements counted individually
+fn facet_values(metadata: Option<&HashMap<String, serde_json::Value>>, field: &str) -> Vec<serde_json::Value> {
+    let mut values = match metadata.and_then(|m| m.get(field)) {
+        Some(serde_json::Value::Array(values)) => values.clone(),
+        Some(value) => vec![value.clone()],
+        None => return Vec::new(),
+    };
+    values.retain(|v| v.is_string() || v.is_i64() || v.is_u64() || v.is_boolean());
+    values.sort_by_key(|v| v.to_string());
+    values.dedup();
+    values
+}
+
+/// Hits fetched per requested group slot on the first grouped search
+const GROUP_OVERFETCH: usize = 4;
+
+/// Group hits by the value of `field`, in order of each group's best hit
+///
+/// Points with an array value join the group of every element. Only string
+/// and integer values form groups; other points are skipped.
+fn group_hits(
+    results: Vec<QueryResult>,
+    field: &str,
+    limit: usize,
+    group_size: usize,
+) -> Vec<vectordb_common::PointGroup> {
+    let mut groups: Vec<vectordb_common::PointGroup> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+
+    for result in results {
+        let keys: Vec<serde_json::Value> = match result.metadata.as_ref().and_then(|m| m.get(field)) {
+            Some(serde_json::Value::Array(values)) => values.clone(),
+            Some(value) => vec![value.clone()],
+            None => continue,
+        };
+
+        for key in keys {
+            if !(key.is_string() || key.is_i64() || key.is_u64()) {
+                continue;
+            }
+            let position = match positions.get(&key.to_string()) {
+                Some(&position) => position,
+                None if groups.len() < limit => {
+                    positions.insert(key.to_string(), groups.len());
+                    groups.push(vectordb_common::PointGroup {
+                        id: key,
+                        hits: Vec::new(),
+                        lookup: None,
+                    });
+                    groups.len() - 1
+                }
+                None => continue,
+            };
+            let hits = &mut groups[position].hits;
+            if hits.len() < group_size && hits.last().is_none_or(|last| last.id != result.id) {
+                hits.push(result.clone());
+            }
+        }
+    }
+
+    groups
+}
+
+/// Label values for the components of a memory breakdown
+fn memory_components(memory: &MemoryBreakdown) -> [(&'static str, usize); 4] {
+    [
+        ("vectors", memory.vectors),
+        ("index", memory.index),
+        ("payload", memory.payload),
+        ("quantized", memory.quantized),
+    ]
+}
+
 /// Server statistics
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct ServerStats {
     pub total_vectors: u64,
     pub total_collections: u32,
     pub memory_usage: u64,
+    /// Memory usage summed across collections, by component
+    pub memory: MemoryBreakdown,
     pub disk_usage: u64,
     pub uptime_seconds: u64,
 }
@@ -853,6 +3081,7 @@ pub struct ServerStats {
 mod tests {
     use super::*;
     use tempfile::tempdir;
+    use uuid::Uuid;
     
     async fn create_test_store() -> VectorStore {
         let temp_dir = tempdir().unwrap();
@@ -869,6 +3098,10 @@ mod tests {
             distance_metric: DistanceMetric::Cosine,
             vector_type: VectorType::Float32,
             index_config: IndexConfig::default(),
+            quantization: None,
+            sparse_vectors: None,
+            vectors: None,
+            multivectors: None,
         };
         
         store.create_collection(&config).await.unwrap();
@@ -887,6 +3120,10 @@ mod tests {
             distance_metric: DistanceMetric::Cosine,
             vector_type: VectorType::Float32,
             index_config: IndexConfig::default(),
+            quantization: None,
+            sparse_vectors: None,
+            vectors: None,
+            multivectors: None,
         };
         
         store.create_collection(&config).await.unwrap();
@@ -895,9 +3132,15 @@ mod tests {
             id: Uuid::new_v4(),
             data: vec![1.0, 0.0, 0.0],
             metadata: None,
+            sparse: None,
+            vectors: None,
+            multivectors: None,
         };
         
         store.insert("test", &vector).await.unwrap();
+
+        let stored = store.get("test", &vector.id).await.unwrap().unwrap();
+        assert_eq!(stored.data, vector.data);
         
         let query = QueryRequest {
             collection: "test".to_string(),
@@ -905,10 +3148,37 @@ mod tests {
             limit: 1,
             ef_search: None,
             filter: None,
+            using: None,
+            multivector: None,
+            rescore: None,
+            oversampling: None,
+            exact: false,
         };
         
         let results = store.query(&query).await.unwrap();
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].id, vector.id);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_group_hits_by_array_values() {
+        let hit = |tags: serde_json::Value| QueryResult {
+            id: Uuid::new_v4(),
+            distance: 0.0,
+            metadata: Some(vec![("tags".to_string(), tags)].into_iter().collect()),
+        };
+        let results = vec![
+            hit(serde_json::json!(["a", "b", "a"])),
+            hit(serde_json::json!(1.5)),
+            hit(serde_json::json!("b")),
+            hit(serde_json::json!(7)),
+            hit(serde_json::json!("c")),
+        ];
+
+        let groups = group_hits(results.clone(), "tags", 3, 2);
+        let ids: Vec<_> = groups.iter().map(|g| g.id.clone()).collect();
+        assert_eq!(ids, vec![serde_json::json!("a"), serde_json::json!("b"), serde_json::json!(7)]);
+        assert_eq!(groups[0].hits.len(), 1);
+        assert_eq!(groups[1].hits.iter().map(|h| h.id).collect::<Vec<_>>(), vec![results[0].id, results[2].id]);
+    }
+}
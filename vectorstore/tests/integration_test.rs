@@ -15,6 +15,9 @@ async fn test_collection_lifecycle() {
         vector_type: VectorType::Float32,
         index_config: IndexConfig::default(),
         quantization: None,
+        sparse_vectors: None,
+        vectors: None,
+        multivectors: None,
     };
 
     store.create_collection(&config).await.unwrap();
@@ -49,6 +52,9 @@ async fn test_vector_operations() {
         vector_type: VectorType::Float32,
         index_config: IndexConfig::default(),
         quantization: None,
+        sparse_vectors: None,
+        vectors: None,
+        multivectors: None,
     };
 
     store.create_collection(&config).await.unwrap();
@@ -63,6 +69,9 @@ async fn test_vector_operations() {
                 .into_iter()
                 .collect(),
         ),
+        sparse: None,
+        vectors: None,
+        multivectors: None,
     };
 
     store.insert("vectors", &vector).await.unwrap();
@@ -88,6 +97,807 @@ async fn test_vector_operations() {
     assert!(after_delete.is_none());
 }
 
+#[tokio::test]
+async fn test_deletes_survive_restart() {
+    let temp_dir = tempfile::tempdir().unwrap();
+
+    let config = CollectionConfig {
+        name: "restart".to_string(),
+        dimension: 2,
+        distance_metric: DistanceMetric::Euclidean,
+        vector_type: VectorType::Float32,
+        index_config: IndexConfig::default(),
+        quantization: None,
+        sparse_vectors: None,
+        vectors: None,
+        multivectors: None,
+    };
+
+    let vectors: Vec<Vector> = (0..4)
+        .map(|i| Vector {
+            id: Uuid::new_v4(),
+            data: vec![i as f32, 0.0],
+            metadata: None,
+            sparse: None,
+            vectors: None,
+            multivectors: None,
+        })
+        .collect();
+
+    {
+        let store = VectorStore::new(temp_dir.path()).await.unwrap();
+        store.create_collection(&config).await.unwrap();
+        store.batch_insert("restart", &vectors).await.unwrap();
+        assert!(store.delete("restart", &vectors[0].id).await.unwrap());
+        assert!(!store.delete("restart", &vectors[0].id).await.unwrap());
+        store.sync().await.unwrap();
+    }
+
+    let store = VectorStore::new(temp_dir.path()).await.unwrap();
+    assert!(store.get("restart", &vectors[0].id).await.unwrap().is_none());
+
+    let query = QueryRequest {
+        collection: "restart".to_string(),
+        vector: vec![0.0, 0.0],
+        limit: 4,
+        ef_search: None,
+        filter: None,
+        using: None,
+        multivector: None,
+        rescore: None,
+        oversampling: None,
+        exact: false,
+    };
+    let results = store.query(&query).await.unwrap();
+    assert_eq!(results.len(), 3);
+    assert!(results.iter().all(|r| r.id != vectors[0].id));
+}
+
+#[tokio::test]
+async fn test_index_persisted_across_restart() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let dump_path = temp_dir.path().join("persisted").join("hnsw.dump");
+    let old_dump_path = temp_dir.path().join("hnsw.dump.old");
+
+    let config = CollectionConfig {
+        name: "persisted".to_string(),
+        dimension: 2,
+        distance_metric: DistanceMetric::Euclidean,
+        vector_type: VectorType::Float32,
+        index_config: IndexConfig::default(),
+        quantization: None,
+        sparse_vectors: None,
+        vectors: None,
+        multivectors: None,
+    };
+
+    let vectors: Vec<Vector> = (0..20)
+        .map(|i| Vector {
+            id: Uuid::new_v4(),
+            data: vec![(i % 5) as f32, (i / 5) as f32],
+            metadata: None,
+            sparse: None,
+            vectors: None,
+            multivectors: None,
+        })
+        .collect();
+    let late = Vector {
+        id: Uuid::new_v4(),
+        data: vec![10.0, 10.0],
+        metadata: None,
+        sparse: None,
+        vectors: None,
+        multivectors: None,
+    };
+
+    {
+        let store = VectorStore::new(temp_dir.path()).await.unwrap();
+        store.create_collection(&config).await.unwrap();
+        store.batch_insert("persisted", &vectors).await.unwrap();
+        store.sync().await.unwrap();
+        std::fs::copy(&dump_path, &old_dump_path).unwrap();
+
+        store.insert("persisted", &late).await.unwrap();
+        assert!(store.delete("persisted", &vectors[0].id).await.unwrap());
+        store.sync().await.unwrap();
+    }
+
+    // Put back the dump from before the last writes, so loading has to
+    // replay them from the data file
+    std::fs::copy(&old_dump_path, &dump_path).unwrap();
+
+    let check = |results: Vec<QueryResult>| {
+        assert_eq!(results[0].id, late.id);
+        assert_eq!(results[0].distance, 0.0);
+        assert!(results.iter().all(|r| r.id != vectors[0].id));
+    };
+    let query = QueryRequest {
+        collection: "persisted".to_string(),
+        vector: late.data.clone(),
+        limit: 5,
+        ef_search: None,
+        filter: None,
+        using: None,
+        multivector: None,
+        rescore: None,
+        oversampling: None,
+        exact: false,
+    };
+
+    {
+        let store = VectorStore::new(temp_dir.path()).await.unwrap();
+        check(store.query(&query).await.unwrap());
+        let stats = store.get_collection_stats("persisted").await.unwrap().unwrap();
+        assert_eq!(stats.vector_count, 20);
+    }
+
+    // A corrupt dump falls back to rebuilding from the data file
+    std::fs::write(&dump_path, b"garbage").unwrap();
+    let store = VectorStore::new(temp_dir.path()).await.unwrap();
+    check(store.query(&query).await.unwrap());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_index_persisted_during_writes_keeps_them() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use vectordb_common::{PointSelector, SetPayloadRequest};
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let collections: Vec<String> = (0..8).map(|c| format!("racing_{}", c)).collect();
+    let points: Vec<Vector> = (0..32)
+        .map(|i| Vector {
+            id: Uuid::new_v4(),
+            data: vec![i as f32, 1.0],
+            metadata: None,
+            sparse: None,
+            vectors: None,
+            multivectors: None,
+        })
+        .collect();
+
+    {
+        let store = VectorStore::new(temp_dir.path()).await.unwrap();
+        for name in &collections {
+            store
+                .create_collection(&CollectionConfig {
+                    name: name.clone(),
+                    dimension: 2,
+                    distance_metric: DistanceMetric::Euclidean,
+                    vector_type: VectorType::Float32,
+                    index_config: IndexConfig {
+                        index_type: IndexType::Flat,
+                        ..IndexConfig::default()
+                    },
+                    quantization: None,
+                    sparse_vectors: None,
+                    vectors: None,
+                    multivectors: None,
+                })
+                .await
+                .unwrap();
+            store.batch_insert(name, &points).await.unwrap();
+        }
+    }
+
+    // A write only goes missing when a dump lands between its storage write
+    // and its index update, so race several times over
+    for _ in 0..12 {
+        {
+            let store = Arc::new(VectorStore::new(temp_dir.path()).await.unwrap());
+
+            // Each collection is dumped while several writers rewrite its
+            // payloads, and nothing is persisted after that
+            let mut tasks = Vec::new();
+            for name in &collections {
+                let persisted = Arc::new(AtomicBool::new(false));
+                for chunk in points.chunks(4) {
+                    let (writer, collection, done) = (store.clone(), name.clone(), persisted.clone());
+                    let ids: Vec<VectorId> = chunk.iter().map(|p| p.id).collect();
+                    tasks.push(tokio::spawn(async move {
+                        for round in 0.. {
+                            if done.load(Ordering::SeqCst) {
+                                return;
+                            }
+                            let request = SetPayloadRequest {
+                                collection: collection.clone(),
+                                points: PointSelector::Ids(ids.clone()),
+                                payload: [("round".to_string(), serde_json::json!(round))].into_iter().collect(),
+                            };
+                            writer.set_payload(&request).await.unwrap();
+                        }
+                    }));
+                }
+                let (store, name) = (store.clone(), name.clone());
+                tasks.push(tokio::spawn(async move {
+                    tokio::task::yield_now().await;
+                    store.persist_index(&name).await.unwrap();
+                    persisted.store(true, Ordering::SeqCst);
+                }));
+            }
+            for task in tasks {
+                task.await.unwrap();
+            }
+        }
+
+        // Replaying the WAL appends every write again, past any mark;
+        // without it only the dumps and what follows their marks are loaded
+        let wal_path = temp_dir.path().join("wal");
+        if wal_path.is_dir() {
+            std::fs::remove_dir_all(&wal_path).unwrap();
+        } else {
+            std::fs::remove_file(&wal_path).unwrap();
+        }
+
+        // The index loaded from each dump holds the same payloads as storage
+        let store = VectorStore::new(temp_dir.path()).await.unwrap();
+        for name in &collections {
+            let query = QueryRequest {
+                collection: name.clone(),
+                vector: vec![0.0, 1.0],
+                limit: points.len(),
+                ef_search: None,
+                filter: None,
+                using: None,
+                multivector: None,
+                rescore: None,
+                oversampling: None,
+                exact: false,
+            };
+            let hits = store.query(&query).await.unwrap();
+            assert_eq!(hits.len(), points.len());
+            for hit in hits {
+                let stored = store.get(name, &hit.id).await.unwrap().unwrap();
+                assert_eq!(hit.metadata, stored.metadata, "stale payload for {} in {}", hit.id, name);
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_collection_stats_track_writes() {
+    let temp_dir = tempfile::tempdir().unwrap();
+
+    let config = CollectionConfig {
+        name: "stats".to_string(),
+        dimension: 4,
+        distance_metric: DistanceMetric::Cosine,
+        vector_type: VectorType::Float32,
+        index_config: IndexConfig::default(),
+        quantization: None,
+        sparse_vectors: None,
+        vectors: None,
+        multivectors: None,
+    };
+
+    let vectors: Vec<Vector> = (0..10)
+        .map(|i| Vector {
+            id: Uuid::new_v4(),
+            data: vec![i as f32, 1.0, 2.0, 3.0],
+            metadata: Some(
+                vec![("label".to_string(), serde_json::json!(format!("vector-{}", i)))]
+                    .into_iter()
+                    .collect(),
+            ),
+            sparse: None,
+            vectors: None,
+            multivectors: None,
+        })
+        .collect();
+
+    {
+        let store = VectorStore::new(temp_dir.path()).await.unwrap();
+        store.create_collection(&config).await.unwrap();
+        store.batch_insert("stats", &vectors).await.unwrap();
+
+        // An upsert doesn't change the count, a delete does
+        store.insert("stats", &vectors[0]).await.unwrap();
+        store.delete("stats", &vectors[1].id).await.unwrap();
+
+        let stats = store.get_collection_stats("stats").await.unwrap().unwrap();
+        assert_eq!(stats.vector_count, 9);
+        assert!(stats.memory.vectors >= 9 * 4 * 4);
+        assert!(stats.memory.index > 0);
+        assert!(stats.memory.payload > 0);
+        assert_eq!(stats.memory.quantized, 0);
+        assert_eq!(stats.memory_usage, stats.memory.total());
+
+        let server_stats = store.get_server_stats().await.unwrap();
+        assert_eq!(server_stats.total_vectors, 9);
+        assert_eq!(server_stats.memory, stats.memory);
+
+        store.sync().await.unwrap();
+    }
+
+    let metadata: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(temp_dir.path().join("stats").join("metadata.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(metadata["vector_count"], 9);
+
+    let store = VectorStore::new(temp_dir.path()).await.unwrap();
+    let stats = store.get_collection_stats("stats").await.unwrap().unwrap();
+    assert_eq!(stats.vector_count, 9);
+}
+
+#[tokio::test]
+async fn test_restrictive_filter_returns_full_page() {
+    use vectordb_common::filter::{Condition, FieldCondition, Filter, MatchKeyword, MatchValue};
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let store = VectorStore::new(temp_dir.path()).await.unwrap();
+
+    let config = CollectionConfig {
+        name: "filtered".to_string(),
+        dimension: 2,
+        distance_metric: DistanceMetric::Euclidean,
+        vector_type: VectorType::Float32,
+        index_config: IndexConfig::default(),
+        quantization: None,
+        sparse_vectors: None,
+        vectors: None,
+        multivectors: None,
+    };
+    store.create_collection(&config).await.unwrap();
+
+    // Only 1 in 100 vectors is tagged "rare", and none of them are near the query
+    let vectors: Vec<Vector> = (0..2000)
+        .map(|i| Vector {
+            id: Uuid::new_v4(),
+            data: vec![i as f32, 0.0],
+            metadata: Some(
+                vec![(
+                    "tag".to_string(),
+                    serde_json::json!(if i % 100 == 99 { "rare" } else { "common" }),
+                )]
+                .into_iter()
+                .collect(),
+            ),
+            sparse: None,
+            vectors: None,
+            multivectors: None,
+        })
+        .collect();
+    store.batch_insert("filtered", &vectors).await.unwrap();
+
+    let query = QueryRequest {
+        collection: "filtered".to_string(),
+        vector: vec![0.0, 0.0],
+        limit: 10,
+        ef_search: None,
+        filter: Some(Filter::Must(vec![Condition::Match(FieldCondition::MatchKeyword(
+            MatchKeyword {
+                key: "tag".to_string(),
+                value: MatchValue::Keyword("rare".to_string()),
+            },
+        ))])),
+        using: None,
+        multivector: None,
+        rescore: None,
+        oversampling: None,
+        exact: false,
+    };
+
+    let results = store.query(&query).await.unwrap();
+    let ids: Vec<Uuid> = results.iter().map(|r| r.id).collect();
+    let expected: Vec<Uuid> = (0..10).map(|i| vectors[i * 100 + 99].id).collect();
+    assert_eq!(ids, expected);
+}
+
+#[tokio::test]
+async fn test_payload_indexes_narrow_filters_and_persist() {
+    use vectordb_common::filter::{Condition, FieldCondition, Filter, MatchKeyword, MatchValue, RangeCondition};
+    use vectordb_common::{CountRequest, ScrollRequest};
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = CollectionConfig {
+        name: "indexed".to_string(),
+        dimension: 2,
+        distance_metric: DistanceMetric::Euclidean,
+        vector_type: VectorType::Float32,
+        index_config: IndexConfig::default(),
+        quantization: None,
+        sparse_vectors: None,
+        vectors: None,
+        multivectors: None,
+    };
+
+    let vectors: Vec<Vector> = (0..100)
+        .map(|i| Vector {
+            id: Uuid::new_v4(),
+            data: vec![i as f32, 0.0],
+            metadata: Some(
+                vec![
+                    ("tag".to_string(), serde_json::json!(if i % 10 == 0 { "rare" } else { "common" })),
+                    ("n".to_string(), serde_json::json!(i)),
+                ]
+                .into_iter()
+                .collect(),
+            ),
+            sparse: None,
+            vectors: None,
+            multivectors: None,
+        })
+        .collect();
+
+    let filter = Filter::Must(vec![
+        Condition::Match(FieldCondition::MatchKeyword(MatchKeyword {
+            key: "tag".to_string(),
+            value: MatchValue::Keyword("rare".to_string()),
+        })),
+        Condition::Match(FieldCondition::Range(RangeCondition {
+            key: "n".to_string(),
+            gte: Some(20.0),
+            gt: None,
+            lte: None,
+            lt: None,
+        })),
+    ]);
+    let count_request = CountRequest {
+        collection: "indexed".to_string(),
+        filter: Some(filter.clone()),
+        exact: true,
+    };
+
+    {
+        let store = VectorStore::new(temp_dir.path()).await.unwrap();
+        store.create_collection(&config).await.unwrap();
+        store.batch_insert("indexed", &vectors[..50]).await.unwrap();
+
+        // Vectors inserted before and after the index is declared are both covered
+        store.create_payload_index("indexed", "tag", PayloadFieldType::Keyword).await.unwrap();
+        store.create_payload_index("indexed", "n", PayloadFieldType::Integer).await.unwrap();
+        store.batch_insert("indexed", &vectors[50..]).await.unwrap();
+
+        // Matches are 20, 30, ..., 90
+        assert_eq!(store.count(&count_request).await.unwrap().count, 8);
+
+        let mut scroll = ScrollRequest {
+            collection: "indexed".to_string(),
+            filter: Some(filter.clone()),
+            limit: 5,
+            offset: None,
+            with_payload: true,
+            with_vectors: false,
+            order_by: None,
+        };
+        let first = store.scroll(&scroll).await.unwrap();
+        assert_eq!(first.points.len(), 5);
+        scroll.offset = first.next_offset.clone();
+        let second = store.scroll(&scroll).await.unwrap();
+        assert_eq!(second.points.len(), 3);
+        assert!(second.next_offset.is_none());
+
+        let mut seen: Vec<Uuid> = first.points.iter().chain(&second.points).map(|p| p.id).collect();
+        seen.sort();
+        seen.dedup();
+        assert_eq!(seen.len(), 8);
+
+        // Moving a vector out of the filter and deleting another are both reflected
+        let mut moved = vectors[20].clone();
+        moved.metadata.as_mut().unwrap().insert("tag".to_string(), serde_json::json!("common"));
+        store.update("indexed", &moved).await.unwrap();
+        store.delete("indexed", &vectors[30].id).await.unwrap();
+        assert_eq!(store.count(&count_request).await.unwrap().count, 6);
+
+        store.sync().await.unwrap();
+    }
+
+    let store = VectorStore::new(temp_dir.path()).await.unwrap();
+    let mut fields: Vec<_> = store
+        .list_payload_indexes("indexed")
+        .unwrap()
+        .into_iter()
+        .map(|info| (info.field_name, info.field_type, info.indexed_vectors))
+        .collect();
+    fields.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(
+        fields,
+        vec![
+            ("n".to_string(), PayloadFieldType::Integer, 99),
+            ("tag".to_string(), PayloadFieldType::Keyword, 99),
+        ]
+    );
+    assert_eq!(store.count(&count_request).await.unwrap().count, 6);
+
+    let query = QueryRequest {
+        collection: "indexed".to_string(),
+        vector: vec![0.0, 0.0],
+        limit: 3,
+        ef_search: None,
+        filter: Some(filter),
+        using: None,
+        multivector: None,
+        rescore: None,
+        oversampling: None,
+        exact: false,
+    };
+    let ids: Vec<Uuid> = store.query(&query).await.unwrap().iter().map(|r| r.id).collect();
+    assert_eq!(ids, vec![vectors[40].id, vectors[50].id, vectors[60].id]);
+
+    assert!(store.delete_payload_index("indexed", "tag").await.unwrap());
+    assert!(!store.delete_payload_index("indexed", "tag").await.unwrap());
+    assert_eq!(store.count(&count_request).await.unwrap().count, 6);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_payload_index_created_during_writes_covers_them() {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use vectordb_common::filter::{Condition, FieldCondition, Filter, MatchKeyword, MatchValue};
+    use vectordb_common::CountRequest;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let store = Arc::new(VectorStore::new(temp_dir.path()).await.unwrap());
+    let point = || Vector {
+        id: Uuid::new_v4(),
+        data: vec![1.0, 1.0],
+        metadata: Some([("color".to_string(), serde_json::json!("red"))].into_iter().collect()),
+        sparse: None,
+        vectors: None,
+        multivectors: None,
+    };
+
+    let red = Filter::Must(vec![Condition::Match(FieldCondition::MatchKeyword(MatchKeyword {
+        key: "color".to_string(),
+        value: MatchValue::Keyword("red".to_string()),
+    }))]);
+
+    // A write only goes missing when it lands between the replay and the
+    // build, so race several collections, several times over
+    for cycle in 0..8 {
+        let collections: Vec<String> = (0..8).map(|c| format!("racing_{}_{}", cycle, c)).collect();
+        let mut tasks = Vec::new();
+        for name in &collections {
+            store
+                .create_collection(&CollectionConfig {
+                    name: name.clone(),
+                    dimension: 2,
+                    distance_metric: DistanceMetric::Euclidean,
+                    vector_type: VectorType::Float32,
+                    index_config: IndexConfig {
+                        index_type: IndexType::Flat,
+                        ..IndexConfig::default()
+                    },
+                    quantization: None,
+                    sparse_vectors: None,
+                    vectors: None,
+                    multivectors: None,
+                })
+                .await
+                .unwrap();
+            store.batch_insert(name, &(0..64).map(|_| point()).collect::<Vec<_>>()).await.unwrap();
+
+            let indexed = Arc::new(AtomicBool::new(false));
+            for _ in 0..8 {
+                let (writer, collection, done) = (store.clone(), name.clone(), indexed.clone());
+                tasks.push(tokio::spawn(async move {
+                    // Keep writing a little past the index creation
+                    let mut after = 0;
+                    while after < 4 {
+                        if done.load(Ordering::SeqCst) {
+                            after += 1;
+                        }
+                        writer.insert(&collection, &point()).await.unwrap();
+                    }
+                }));
+            }
+            let (store, name) = (store.clone(), name.clone());
+            tasks.push(tokio::spawn(async move {
+                tokio::task::yield_now().await;
+                store.create_payload_index(&name, "color", PayloadFieldType::Keyword).await.unwrap();
+                indexed.store(true, Ordering::SeqCst);
+            }));
+        }
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        // Counting by the index finds every point written
+        for name in &collections {
+            let stored = store.get_collection_stats(name).await.unwrap().unwrap().vector_count;
+            let count = store
+                .count(&CountRequest { collection: name.clone(), filter: Some(red.clone()), exact: true })
+                .await
+                .unwrap();
+            assert_eq!(count.count, stored, "collection {}", name);
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_concurrent_payload_updates_reach_indexes_in_storage_order() {
+    use std::sync::Arc;
+    use vectordb_common::filter::{Condition, FieldCondition, Filter, MatchKeyword, MatchValue};
+    use vectordb_common::{CountRequest, PointSelector, SetPayloadRequest};
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let store = Arc::new(VectorStore::new(temp_dir.path()).await.unwrap());
+    let points: Vec<Vector> = (0..16)
+        .map(|i| Vector {
+            id: Uuid::new_v4(),
+            data: vec![i as f32, 1.0],
+            metadata: None,
+            sparse: None,
+            vectors: None,
+            multivectors: None,
+        })
+        .collect();
+    let ids: Vec<VectorId> = points.iter().map(|p| p.id).collect();
+    let writers = 8;
+    let color = |writer: usize| format!("color_{}", writer);
+
+    let collections: Vec<String> = (0..8).map(|c| format!("recolored_{}", c)).collect();
+    let mut tasks = Vec::new();
+    for name in &collections {
+        store
+            .create_collection(&CollectionConfig {
+                name: name.clone(),
+                dimension: 2,
+                distance_metric: DistanceMetric::Euclidean,
+                vector_type: VectorType::Float32,
+                index_config: IndexConfig {
+                    index_type: IndexType::Flat,
+                    ..IndexConfig::default()
+                },
+                quantization: None,
+                sparse_vectors: None,
+                vectors: None,
+                multivectors: None,
+            })
+            .await
+            .unwrap();
+        store.batch_insert(name, &points).await.unwrap();
+        store.create_payload_index(name, "color", PayloadFieldType::Keyword).await.unwrap();
+
+        // Every writer recolors every point, so their updates interleave
+        for writer in 0..writers {
+            let (store, request) = (store.clone(), SetPayloadRequest {
+                collection: name.clone(),
+                points: PointSelector::Ids(ids.clone()),
+                payload: [("color".to_string(), serde_json::json!(color(writer)))].into_iter().collect(),
+            });
+            tasks.push(tokio::spawn(async move {
+                for _ in 0..50 {
+                    store.set_payload(&request).await.unwrap();
+                }
+            }));
+        }
+    }
+    for task in tasks {
+        task.await.unwrap();
+    }
+
+    // Both indexes end up holding the payload storage kept last
+    for name in &collections {
+        let mut counted = 0;
+        for writer in 0..writers {
+            let filter = Filter::Must(vec![Condition::Match(FieldCondition::MatchKeyword(MatchKeyword {
+                key: "color".to_string(),
+                value: MatchValue::Keyword(color(writer)),
+            }))]);
+            counted += store
+                .count(&CountRequest { collection: name.clone(), filter: Some(filter), exact: true })
+                .await
+                .unwrap()
+                .count;
+        }
+        assert_eq!(counted, points.len(), "collection {}", name);
+
+        let query = QueryRequest {
+            collection: name.clone(),
+            vector: vec![0.0, 1.0],
+            limit: points.len(),
+            ef_search: None,
+            filter: None,
+            using: None,
+            multivector: None,
+            rescore: None,
+            oversampling: None,
+            exact: false,
+        };
+        for result in store.query(&query).await.unwrap() {
+            let stored = store.get(name, &result.id).await.unwrap().unwrap();
+            assert_eq!(result.metadata, stored.metadata, "collection {}", name);
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_text_index_matches_tokens_and_persists() {
+    use vectordb_common::filter::{Condition, FieldCondition, Filter, MatchText};
+    use vectordb_common::CountRequest;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = CollectionConfig {
+        name: "docs".to_string(),
+        dimension: 2,
+        distance_metric: DistanceMetric::Euclidean,
+        vector_type: VectorType::Float32,
+        index_config: IndexConfig::default(),
+        quantization: None,
+        sparse_vectors: None,
+        vectors: None,
+        multivectors: None,
+    };
+
+    let bodies = [
+        "Vector databases store embeddings",
+        "Embeddings: a practical guide to vector search",
+        "Cooking with cast iron",
+    ];
+    let vectors: Vec<Vector> = bodies
+        .iter()
+        .enumerate()
+        .map(|(i, body)| Vector {
+            id: Uuid::new_v4(),
+            data: vec![i as f32, 0.0],
+            metadata: Some(vec![("body".to_string(), serde_json::json!(body))].into_iter().collect()),
+            sparse: None,
+            vectors: None,
+            multivectors: None,
+        })
+        .collect();
+
+    let count = |text: &str| CountRequest {
+        collection: "docs".to_string(),
+        filter: Some(Filter::Must(vec![Condition::Match(FieldCondition::MatchText(MatchText {
+            key: "body".to_string(),
+            text: text.to_string(),
+        }))])),
+        exact: true,
+    };
+
+    {
+        let store = VectorStore::new(temp_dir.path()).await.unwrap();
+        store.create_collection(&config).await.unwrap();
+        store.batch_insert("docs", &vectors).await.unwrap();
+
+        // Without a text index this is a substring match
+        assert_eq!(store.count(&count("embeddings vector")).await.unwrap().count, 0);
+
+        let invalid = TextIndexParams {
+            min_token_len: Some(5),
+            max_token_len: Some(2),
+            ..Default::default()
+        };
+        assert!(store.create_text_index("docs", "body", invalid).await.is_err());
+
+        let params = TextIndexParams {
+            tokenizer: TokenizerType::Prefix,
+            stop_words_language: Some(StopWordsLanguage::English),
+            ..Default::default()
+        };
+        store.create_text_index("docs", "body", params).await.unwrap();
+        assert_eq!(store.count(&count("embeddings vector")).await.unwrap().count, 2);
+        assert_eq!(store.count(&count("the EMBED")).await.unwrap().count, 2);
+        assert_eq!(store.count(&count("cast iron vector")).await.unwrap().count, 0);
+
+        store.sync().await.unwrap();
+    }
+
+    let store = VectorStore::new(temp_dir.path()).await.unwrap();
+    let info = store.list_payload_indexes("docs").unwrap();
+    assert_eq!(info[0].field_type, PayloadFieldType::Text);
+    assert_eq!(info[0].text_params.as_ref().unwrap().tokenizer, TokenizerType::Prefix);
+    assert_eq!(store.count(&count("practical vec")).await.unwrap().count, 1);
+
+    let query = QueryRequest {
+        collection: "docs".to_string(),
+        vector: vec![2.0, 0.0],
+        limit: 1,
+        ef_search: None,
+        filter: count("embed").filter,
+        using: None,
+        multivector: None,
+        rescore: None,
+        oversampling: None,
+        exact: false,
+    };
+    let results = store.query(&query).await.unwrap();
+    assert_eq!(results[0].id, vectors[1].id);
+}
+
 #[tokio::test]
 async fn test_batch_operations() {
     let temp_dir = tempfile::tempdir().unwrap();
@@ -100,6 +910,9 @@ async fn test_batch_operations() {
         vector_type: VectorType::Float32,
         index_config: IndexConfig::default(),
         quantization: None,
+        sparse_vectors: None,
+        vectors: None,
+        multivectors: None,
     };
 
     store.create_collection(&config).await.unwrap();
@@ -110,6 +923,9 @@ async fn test_batch_operations() {
             id: Uuid::new_v4(),
             data: vec![i as f32, (i + 1) as f32],
             metadata: None,
+            sparse: None,
+            vectors: None,
+            multivectors: None,
         })
         .collect();
 
@@ -127,6 +943,9 @@ async fn test_batch_operations() {
             id: Uuid::new_v4(),
             data: vec![i as f32, (i + 1) as f32],
             metadata: None,
+            sparse: None,
+            vectors: None,
+            multivectors: None,
         });
     }
 
@@ -157,6 +976,9 @@ async fn test_search_query() {
         vector_type: VectorType::Float32,
         index_config: IndexConfig::default(),
         quantization: None,
+        sparse_vectors: None,
+        vectors: None,
+        multivectors: None,
     };
 
     store.create_collection(&config).await.unwrap();
@@ -167,16 +989,25 @@ async fn test_search_query() {
             id: Uuid::new_v4(),
             data: vec![1.0, 0.0, 0.0],
             metadata: None,
+            sparse: None,
+            vectors: None,
+            multivectors: None,
         },
         Vector {
             id: Uuid::new_v4(),
             data: vec![0.9, 0.1, 0.0],
             metadata: None,
+            sparse: None,
+            vectors: None,
+            multivectors: None,
         },
         Vector {
             id: Uuid::new_v4(),
             data: vec![0.0, 1.0, 0.0],
             metadata: None,
+            sparse: None,
+            vectors: None,
+            multivectors: None,
         },
     ];
 
@@ -189,6 +1020,11 @@ async fn test_search_query() {
         limit: 2,
         ef_search: None,
         filter: None,
+        using: None,
+        multivector: None,
+        rescore: None,
+        oversampling: None,
+        exact: false,
     };
 
     let results = store.query(&query_request).await.unwrap();
@@ -210,6 +1046,9 @@ async fn test_snapshot_management() {
         vector_type: VectorType::Float32,
         index_config: IndexConfig::default(),
         quantization: None,
+        sparse_vectors: None,
+        vectors: None,
+        multivectors: None,
     };
 
     store.create_collection(&config).await.unwrap();
@@ -219,6 +1058,9 @@ async fn test_snapshot_management() {
         id: Uuid::new_v4(),
         data: vec![1.0, 2.0],
         metadata: None,
+        sparse: None,
+        vectors: None,
+        multivectors: None,
     };
     store.insert("snapshot_test", &vector).await.unwrap();
 
@@ -228,23 +1070,17 @@ async fn test_snapshot_management() {
     assert!(snapshot.size_bytes > 0);
 
     // List snapshots
-    let snapshots = store.list_snapshots("snapshot_test").await.unwrap();
+    let snapshots = store.list_snapshots().unwrap();
     assert_eq!(snapshots.len(), 1);
 
     // Get snapshot
-    let retrieved = store
-        .get_snapshot("snapshot_test", &snapshot.name)
-        .await
-        .unwrap();
+    let retrieved = store.get_snapshot(&snapshot.name).unwrap();
     assert_eq!(retrieved.name, snapshot.name);
 
     // Delete snapshot
-    store
-        .delete_snapshot("snapshot_test", &snapshot.name)
-        .await
-        .unwrap();
+    store.delete_snapshot(&snapshot.name).unwrap();
 
-    let after_delete = store.list_snapshots("snapshot_test").await.unwrap();
+    let after_delete = store.list_snapshots().unwrap();
     assert_eq!(after_delete.len(), 0);
 }
 
@@ -260,6 +1096,9 @@ async fn test_recommend_api() {
         vector_type: VectorType::Float32,
         index_config: IndexConfig::default(),
         quantization: None,
+        sparse_vectors: None,
+        vectors: None,
+        multivectors: None,
     };
 
     store.create_collection(&config).await.unwrap();
@@ -274,16 +1113,25 @@ async fn test_recommend_api() {
             id: vec1_id,
             data: vec![1.0, 0.0, 0.0],
             metadata: None,
+            sparse: None,
+            vectors: None,
+            multivectors: None,
         },
         Vector {
             id: vec2_id,
             data: vec![0.9, 0.1, 0.0],
             metadata: None,
+            sparse: None,
+            vectors: None,
+            multivectors: None,
         },
         Vector {
             id: vec3_id,
             data: vec![0.0, 1.0, 0.0],
             metadata: None,
+            sparse: None,
+            vectors: None,
+            multivectors: None,
         },
     ];
 
@@ -306,3 +1154,1822 @@ async fn test_recommend_api() {
     let results = store.recommend(&recommend_request).await.unwrap();
     assert!(results.len() <= 2);
 }
+
+#[tokio::test]
+async fn test_search_groups_with_lookup() {
+    use vectordb_common::filter::{Condition, FieldCondition, Filter, MatchKeyword, MatchValue};
+    use vectordb_common::{GroupByRequest, WithLookup};
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let store = VectorStore::new(temp_dir.path()).await.unwrap();
+
+    let collection = |name: &str| CollectionConfig {
+        name: name.to_string(),
+        dimension: 2,
+        distance_metric: DistanceMetric::Euclidean,
+        vector_type: VectorType::Float32,
+        index_config: IndexConfig::default(),
+        quantization: None,
+        sparse_vectors: None,
+        vectors: None,
+        multivectors: None,
+    };
+    store.create_collection(&collection("chunks")).await.unwrap();
+    store.create_collection(&collection("documents")).await.unwrap();
+
+    // Four documents with five chunks each; document d sits around x = 10 * d
+    let documents: Vec<Vector> = (0..4)
+        .map(|d| Vector {
+            id: Uuid::new_v4(),
+            data: vec![d as f32, 0.0],
+            metadata: Some(vec![("title".to_string(), serde_json::json!(format!("doc {}", d)))].into_iter().collect()),
+            sparse: None,
+            vectors: None,
+            multivectors: None,
+        })
+        .collect();
+    store.batch_insert("documents", &documents).await.unwrap();
+
+    let chunks: Vec<Vector> = documents
+        .iter()
+        .enumerate()
+        .flat_map(|(d, doc)| {
+            (0..5).map(move |c| Vector {
+                id: Uuid::new_v4(),
+                data: vec![(10 * d + c) as f32, 0.0],
+                metadata: Some(
+                    vec![
+                        ("doc_id".to_string(), serde_json::json!(doc.id.to_string())),
+                        ("lang".to_string(), serde_json::json!(if d == 1 { "de" } else { "en" })),
+                    ]
+                    .into_iter()
+                    .collect(),
+                ),
+                sparse: None,
+                vectors: None,
+                multivectors: None,
+            })
+        })
+        .collect();
+    store.batch_insert("chunks", &chunks).await.unwrap();
+
+    let mut request = GroupByRequest {
+        collection: "chunks".to_string(),
+        vector: vec![0.0, 0.0],
+        group_by: "doc_id".to_string(),
+        group_size: 2,
+        limit: 3,
+        filter: None,
+        with_lookup: Some(WithLookup {
+            collection: "documents".to_string(),
+            with_payload: true,
+            with_vectors: false,
+        }),
+    };
+
+    let response = store.search_groups(&request).await.unwrap();
+    let group_ids: Vec<_> = response.groups.iter().map(|g| g.id.clone()).collect();
+    assert_eq!(
+        group_ids,
+        (0..3).map(|d| serde_json::json!(documents[d].id.to_string())).collect::<Vec<_>>()
+    );
+    for (d, group) in response.groups.iter().enumerate() {
+        let hit_ids: Vec<_> = group.hits.iter().map(|h| h.id).collect();
+        assert_eq!(hit_ids, vec![chunks[5 * d].id, chunks[5 * d + 1].id]);
+
+        let lookup = group.lookup.as_ref().unwrap();
+        assert_eq!(lookup.id, documents[d].id);
+        assert_eq!(lookup.payload.as_ref().unwrap()["title"], serde_json::json!(format!("doc {}", d)));
+        assert!(lookup.vector.is_none());
+    }
+
+    // Filtered out chunks never form or join a group
+    request.filter = Some(Filter::Must(vec![Condition::Match(FieldCondition::MatchKeyword(MatchKeyword {
+        key: "lang".to_string(),
+        value: MatchValue::Keyword("en".to_string()),
+    }))]));
+    request.limit = 10;
+    request.with_lookup = None;
+    let response = store.search_groups(&request).await.unwrap();
+    assert_eq!(response.groups.len(), 3);
+    assert!(response.groups.iter().all(|g| g.hits.len() == 2 && g.lookup.is_none()));
+    assert!(response.groups.iter().all(|g| g.id != serde_json::json!(documents[1].id.to_string())));
+
+    request.group_size = 0;
+    assert!(store.search_groups(&request).await.is_err());
+}
+
+#[tokio::test]
+async fn test_facet_counts_values() {
+    use vectordb_common::filter::{Condition, FieldCondition, Filter, MatchKeyword, MatchValue};
+    use vectordb_common::FacetRequest;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let store = VectorStore::new(temp_dir.path()).await.unwrap();
+    let config = CollectionConfig {
+        name: "products".to_string(),
+        dimension: 2,
+        distance_metric: DistanceMetric::Euclidean,
+        vector_type: VectorType::Float32,
+        index_config: IndexConfig::default(),
+        quantization: None,
+        sparse_vectors: None,
+        vectors: None,
+        multivectors: None,
+    };
+    store.create_collection(&config).await.unwrap();
+
+    // Brands: acme x6, globex x3, initech x1; every third product is on sale
+    let brands = ["acme", "acme", "globex", "acme", "initech", "acme", "globex", "acme", "globex", "acme"];
+    let vectors: Vec<Vector> = brands
+        .iter()
+        .enumerate()
+        .map(|(i, brand)| Vector {
+            id: Uuid::new_v4(),
+            data: vec![i as f32, 0.0],
+            metadata: Some(
+                vec![
+                    ("brand".to_string(), serde_json::json!(brand)),
+                    ("on_sale".to_string(), serde_json::json!(i % 3 == 0)),
+                    ("tags".to_string(), serde_json::json!(["new", "new", if i < 4 { "hot" } else { "cold" }])),
+                ]
+                .into_iter()
+                .collect(),
+            ),
+            sparse: None,
+            vectors: None,
+            multivectors: None,
+        })
+        .collect();
+    store.batch_insert("products", &vectors).await.unwrap();
+
+    let on_sale = Filter::Must(vec![Condition::Match(FieldCondition::MatchKeyword(MatchKeyword {
+        key: "on_sale".to_string(),
+        value: MatchValue::Bool(true),
+    }))]);
+    let facet = |field: &str, filter: Option<Filter>, limit: usize, exact: bool| FacetRequest {
+        collection: "products".to_string(),
+        field: field.to_string(),
+        filter,
+        limit,
+        exact,
+    };
+    let values = |response: vectordb_common::FacetResponse| {
+        response.values.into_iter().map(|v| (v.value, v.count)).collect::<Vec<_>>()
+    };
+
+    let expected = vec![(serde_json::json!("acme"), 6), (serde_json::json!("globex"), 3)];
+    assert_eq!(values(store.facet(&facet("brand", None, 2, true)).await.unwrap()), expected);
+
+    // Array elements count once per point
+    assert_eq!(
+        values(store.facet(&facet("tags", None, 10, true)).await.unwrap()),
+        vec![(serde_json::json!("new"), 10), (serde_json::json!("cold"), 6), (serde_json::json!("hot"), 4)]
+    );
+
+    // Products 0, 3, 6 and 9 are on sale
+    let on_sale_brands = vec![(serde_json::json!("acme"), 3), (serde_json::json!("globex"), 1)];
+    assert_eq!(values(store.facet(&facet("brand", Some(on_sale.clone()), 10, true)).await.unwrap()), on_sale_brands);
+
+    // Approximate counts come from the payload indexes once they exist
+    store.create_payload_index("products", "brand", PayloadFieldType::Keyword).await.unwrap();
+    store.create_payload_index("products", "on_sale", PayloadFieldType::Bool).await.unwrap();
+    assert_eq!(values(store.facet(&facet("brand", None, 2, false)).await.unwrap()), expected);
+    assert_eq!(values(store.facet(&facet("brand", Some(on_sale.clone()), 10, false)).await.unwrap()), on_sale_brands);
+
+    // Conditions the indexes cannot answer still apply to the candidates
+    let on_sale_not_globex = Filter::Must(vec![
+        Condition::Match(FieldCondition::MatchKeyword(MatchKeyword {
+            key: "on_sale".to_string(),
+            value: MatchValue::Bool(true),
+        })),
+        Condition::Filter(Box::new(Filter::MustNot(vec![Condition::Match(FieldCondition::MatchKeyword(
+            MatchKeyword {
+                key: "brand".to_string(),
+                value: MatchValue::Keyword("globex".to_string()),
+            },
+        ))]))),
+    ]);
+    for exact in [true, false] {
+        assert_eq!(
+            values(store.facet(&facet("brand", Some(on_sale_not_globex.clone()), 10, exact)).await.unwrap()),
+            vec![(serde_json::json!("acme"), 3)]
+        );
+    }
+
+    // An indexed array field counts the same in both modes
+    store.create_payload_index("products", "tags", PayloadFieldType::Keyword).await.unwrap();
+    for exact in [true, false] {
+        assert_eq!(
+            values(store.facet(&facet("tags", None, 10, exact)).await.unwrap()),
+            vec![(serde_json::json!("new"), 10), (serde_json::json!("cold"), 6), (serde_json::json!("hot"), 4)]
+        );
+        assert_eq!(
+            values(store.facet(&facet("tags", Some(on_sale.clone()), 10, exact)).await.unwrap()),
+            vec![(serde_json::json!("new"), 4), (serde_json::json!("cold"), 2), (serde_json::json!("hot"), 2)]
+        );
+    }
+
+    assert!(store.facet(&FacetRequest { collection: "missing".to_string(), ..facet("brand", None, 10, true) }).await.is_err());
+}
+
+#[tokio::test]
+async fn test_hybrid_search_fuses_dense_and_sparse() {
+    use vectordb_common::filter::{Condition, FieldCondition, Filter, MatchKeyword, MatchValue};
+    use vectordb_common::{FusionMethod, HybridSearchRequest, SparseVector, SparseVectorParams};
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = CollectionConfig {
+        name: "docs".to_string(),
+        dimension: 2,
+        distance_metric: DistanceMetric::Euclidean,
+        vector_type: VectorType::Float32,
+        index_config: IndexConfig::default(),
+        quantization: None,
+        sparse_vectors: Some(
+            vec![("keywords".to_string(), SparseVectorParams::default())]
+                .into_iter()
+                .collect(),
+        ),
+        vectors: None,
+        multivectors: None,
+    };
+
+    let point = |x: f32, lang: &str, keywords: Option<SparseVector>| Vector {
+        id: Uuid::new_v4(),
+        data: vec![x, 0.0],
+        metadata: Some(vec![("lang".to_string(), serde_json::json!(lang))].into_iter().collect()),
+        sparse: keywords.map(|k| vec![("keywords".to_string(), k)].into_iter().collect()),
+        vectors: None,
+        multivectors: None,
+    };
+    // Dense order from the origin is a, c, b, d; only b and c mention term 7
+    let a = point(0.1, "en", None);
+    let b = point(5.0, "en", Some(SparseVector::new(vec![7, 9], vec![1.0, 2.0])));
+    let c = point(1.0, "de", Some(SparseVector::new(vec![7], vec![3.0])));
+    let d = point(9.0, "en", None);
+
+    let ids = |points: Vec<vectordb_common::ScoredPoint>| points.into_iter().map(|p| p.id).collect::<Vec<_>>();
+    let request = |dense: Option<Vec<f32>>, filter: Option<Filter>| HybridSearchRequest {
+        collection: "docs".to_string(),
+        dense,
+        sparse: Some(SparseVector::new(vec![7], vec![1.0])),
+        sparse_name: None,
+        fusion: FusionMethod::ReciprocalRankFusion,
+        limit: 3,
+        filter,
+    };
+
+    {
+        let store = VectorStore::new(temp_dir.path()).await.unwrap();
+        store.create_collection(&config).await.unwrap();
+        store.batch_insert("docs", &[a.clone(), b.clone(), c.clone(), d.clone()]).await.unwrap();
+
+        // c ranks second on both sides, which beats first on one side only
+        let fused = store.hybrid_search(&request(Some(vec![0.0, 0.0]), None)).await.unwrap();
+        assert_eq!(ids(fused.clone()), vec![c.id, b.id, a.id]);
+        assert_eq!(fused[0].payload.as_ref().unwrap()["lang"], "de");
+
+        let english = Filter::Must(vec![Condition::Match(FieldCondition::MatchKeyword(MatchKeyword {
+            key: "lang".to_string(),
+            value: MatchValue::Keyword("en".to_string()),
+        }))]);
+        let sparse_only = store.hybrid_search(&request(None, Some(english))).await.unwrap();
+        assert_eq!(ids(sparse_only), vec![b.id]);
+
+        // Sparse vectors must belong to a declared space
+        let mut stray = point(2.0, "en", None);
+        stray.sparse = Some(vec![("tags".to_string(), SparseVector::new(vec![1], vec![1.0]))].into_iter().collect());
+        assert!(matches!(
+            store.insert("docs", &stray).await,
+            Err(vectordb_common::VectorDbError::InvalidInput { .. })
+        ));
+
+        store.sync().await.unwrap();
+    }
+
+    // Sparse indexes are rebuilt from storage on restart
+    let store = VectorStore::new(temp_dir.path()).await.unwrap();
+    assert_eq!(ids(store.hybrid_search(&request(None, None)).await.unwrap()), vec![c.id, b.id]);
+
+    // Replacing a point without its sparse vector takes it out of the sparse ranking
+    let mut plain_c = c.clone();
+    plain_c.sparse = None;
+    store.update("docs", &plain_c).await.unwrap();
+    assert_eq!(ids(store.hybrid_search(&request(None, None)).await.unwrap()), vec![b.id]);
+}
+
+#[tokio::test]
+async fn test_named_vectors_are_searched_by_name() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = CollectionConfig {
+        name: "products".to_string(),
+        dimension: 2,
+        distance_metric: DistanceMetric::Euclidean,
+        vector_type: VectorType::Float32,
+        index_config: IndexConfig::default(),
+        quantization: None,
+        sparse_vectors: None,
+        vectors: Some(
+            vec![
+                ("title".to_string(), VectorParams {
+                    dimension: 3,
+                    distance_metric: DistanceMetric::Euclidean,
+                    index_config: None,
+                }),
+                ("image".to_string(), VectorParams {
+                    dimension: 4,
+                    distance_metric: DistanceMetric::Cosine,
+                    index_config: None,
+                }),
+            ]
+            .into_iter()
+            .collect(),
+        ),
+        multivectors: None,
+    };
+
+    let point = |x: f32, named: Vec<(&str, Vec<f32>)>| Vector {
+        id: Uuid::new_v4(),
+        data: vec![x, 0.0],
+        metadata: None,
+        sparse: None,
+        vectors: Some(named.into_iter().map(|(name, data)| (name.to_string(), data)).collect()),
+        multivectors: None,
+    };
+    // Points may carry any subset of the declared spaces
+    let a = point(0.0, vec![("title", vec![9.0, 0.0, 0.0]), ("image", vec![1.0, 0.0, 0.0, 0.0])]);
+    let b = point(5.0, vec![("title", vec![1.0, 0.0, 0.0])]);
+    let c = point(9.0, vec![("image", vec![0.0, 1.0, 0.0, 0.0])]);
+
+    let query = |vector: Vec<f32>, using: Option<&str>| QueryRequest {
+        collection: "products".to_string(),
+        vector,
+        limit: 10,
+        ef_search: None,
+        filter: None,
+        using: using.map(str::to_string),
+        multivector: None,
+        rescore: None,
+        oversampling: None,
+        exact: false,
+    };
+    let ids = |results: Vec<QueryResult>| results.into_iter().map(|r| r.id).collect::<Vec<_>>();
+
+    {
+        let store = VectorStore::new(temp_dir.path()).await.unwrap();
+        store.create_collection(&config).await.unwrap();
+        store.batch_insert("products", &[a.clone(), b.clone(), c.clone()]).await.unwrap();
+
+        // Each space ranks only the points that have a vector in it
+        assert_eq!(ids(store.query(&query(vec![0.0, 0.0, 0.0], Some("title"))).await.unwrap()), vec![b.id, a.id]);
+        assert_eq!(ids(store.query(&query(vec![0.0, 1.0, 0.0, 0.0], Some("image"))).await.unwrap()), vec![c.id, a.id]);
+        assert_eq!(ids(store.query(&query(vec![0.0, 0.0], None)).await.unwrap()), vec![a.id, b.id, c.id]);
+
+        // Dimensions are checked against the named space
+        assert!(matches!(
+            store.query(&query(vec![0.0, 0.0], Some("title"))).await,
+            Err(vectordb_common::VectorDbError::InvalidDimension { expected: 3, actual: 2 })
+        ));
+        assert!(matches!(
+            store.query(&query(vec![0.0, 0.0], Some("audio"))).await,
+            Err(vectordb_common::VectorDbError::InvalidInput { .. })
+        ));
+        let wrong = point(1.0, vec![("image", vec![1.0, 0.0])]);
+        assert!(matches!(
+            store.insert("products", &wrong).await,
+            Err(vectordb_common::VectorDbError::InvalidDimension { expected: 4, actual: 2 })
+        ));
+
+        store.sync().await.unwrap();
+    }
+
+    let dump_path = temp_dir.path().join("products").join("named.dump");
+    let old_dump_path = temp_dir.path().join("named.dump.old");
+    assert!(dump_path.exists());
+    std::fs::copy(&dump_path, &old_dump_path).unwrap();
+
+    // Named vectors survive a restart and their indexes are loaded
+    {
+        let store = VectorStore::new(temp_dir.path()).await.unwrap();
+        let stored = store.get("products", &a.id).await.unwrap().unwrap();
+        assert_eq!(stored.vectors, a.vectors);
+        assert_eq!(ids(store.query(&query(vec![0.0, 0.0, 0.0], Some("title"))).await.unwrap()), vec![b.id, a.id]);
+
+        // Replacing a point without a named vector takes it out of that space
+        let mut image_only = a.clone();
+        image_only.vectors = Some(vec![("image".to_string(), vec![1.0, 0.0, 0.0, 0.0])].into_iter().collect());
+        store.update("products", &image_only).await.unwrap();
+        assert_eq!(ids(store.query(&query(vec![0.0, 0.0, 0.0], Some("title"))).await.unwrap()), vec![b.id]);
+
+        store.delete("products", &c.id).await.unwrap();
+        assert_eq!(ids(store.query(&query(vec![0.0, 1.0, 0.0, 0.0], Some("image"))).await.unwrap()), vec![a.id]);
+        store.sync().await.unwrap();
+    }
+
+    // Put back the dump from before the last writes, so loading has to
+    // replay them from the data file
+    std::fs::copy(&old_dump_path, &dump_path).unwrap();
+
+    let store = VectorStore::new(temp_dir.path()).await.unwrap();
+    assert_eq!(ids(store.query(&query(vec![0.0, 0.0, 0.0], Some("title"))).await.unwrap()), vec![b.id]);
+    assert_eq!(ids(store.query(&query(vec![0.0, 1.0, 0.0, 0.0], Some("image"))).await.unwrap()), vec![a.id]);
+}
+
+#[tokio::test]
+async fn test_points_with_only_named_vectors() {
+    use vectordb_common::ExportRequest;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = |name: &str, named: bool| CollectionConfig {
+        name: name.to_string(),
+        dimension: 2,
+        distance_metric: DistanceMetric::Euclidean,
+        vector_type: VectorType::Float32,
+        index_config: IndexConfig::default(),
+        quantization: None,
+        sparse_vectors: None,
+        vectors: named.then(|| {
+            vec![("title".to_string(), VectorParams {
+                dimension: 3,
+                distance_metric: DistanceMetric::Euclidean,
+                index_config: None,
+            })]
+            .into_iter()
+            .collect()
+        }),
+        multivectors: None,
+    };
+
+    let point = |data: Vec<f32>, title: Option<Vec<f32>>| Vector {
+        id: Uuid::new_v4(),
+        data,
+        metadata: None,
+        sparse: None,
+        vectors: title.map(|title| vec![("title".to_string(), title)].into_iter().collect()),
+        multivectors: None,
+    };
+    let full = point(vec![0.0, 0.0], Some(vec![2.0, 0.0, 0.0]));
+    let titled = point(Vec::new(), Some(vec![1.0, 0.0, 0.0]));
+    let bare = point(Vec::new(), None);
+    let upserted = point(Vec::new(), Some(vec![3.0, 0.0, 0.0]));
+
+    let query = |vector: Vec<f32>, using: Option<&str>, exact: bool| QueryRequest {
+        collection: "articles".to_string(),
+        vector,
+        limit: 10,
+        ef_search: None,
+        filter: None,
+        using: using.map(str::to_string),
+        multivector: None,
+        rescore: None,
+        oversampling: None,
+        exact,
+    };
+    let ids = |results: Vec<QueryResult>| results.into_iter().map(|r| r.id).collect::<Vec<_>>();
+
+    {
+        let store = std::sync::Arc::new(VectorStore::new(temp_dir.path()).await.unwrap());
+        store.create_collection(&config("articles", true)).await.unwrap();
+        store.insert("articles", &titled).await.unwrap();
+        store.batch_insert("articles", &[full.clone(), bare.clone()]).await.unwrap();
+        store.batch_upsert("articles", std::slice::from_ref(&upserted)).await.unwrap();
+
+        // Only points with a default vector are in the default index
+        for exact in [false, true] {
+            assert_eq!(ids(store.query(&query(vec![0.0, 0.0], None, exact)).await.unwrap()), vec![full.id]);
+        }
+        assert_eq!(
+            ids(store.query(&query(vec![0.0, 0.0, 0.0], Some("title"), false)).await.unwrap()),
+            vec![titled.id, full.id, upserted.id]
+        );
+        let stats = store.get_collection_stats("articles").await.unwrap().unwrap();
+        assert_eq!(stats.vector_count, 4);
+
+        // Upserting a point without its default vector takes it out of the default index
+        let mut emptied = full.clone();
+        emptied.data = Vec::new();
+        store.batch_upsert("articles", &[emptied]).await.unwrap();
+        assert!(store.query(&query(vec![0.0, 0.0], None, false)).await.unwrap().is_empty());
+        store.batch_upsert("articles", std::slice::from_ref(&full)).await.unwrap();
+
+        // Exported points go back in as they are
+        let mut batches = store.export(ExportRequest {
+            collection: "articles".to_string(),
+            filter: None,
+            with_vectors: true,
+            with_payload: true,
+            batch_size: 8,
+        });
+        let mut exported = Vec::new();
+        while let Some(batch) = batches.recv().await {
+            exported.extend(batch.unwrap());
+        }
+        assert_eq!(exported.iter().filter(|v| v.data.is_empty()).count(), 3);
+        store.create_collection(&config("copy", true)).await.unwrap();
+        store.batch_insert("copy", &exported).await.unwrap();
+        let copied = store.get("copy", &titled.id).await.unwrap().unwrap();
+        assert!(copied.data.is_empty());
+        assert_eq!(copied.vectors, titled.vectors);
+
+        // Without named spaces the default vector is required
+        store.create_collection(&config("plain", false)).await.unwrap();
+        assert!(matches!(
+            store.insert("plain", &bare).await,
+            Err(vectordb_common::VectorDbError::InvalidDimension { expected: 2, actual: 0 })
+        ));
+        let mut wrong = titled.clone();
+        wrong.data = vec![1.0];
+        assert!(matches!(
+            store.insert("articles", &wrong).await,
+            Err(vectordb_common::VectorDbError::InvalidDimension { expected: 2, actual: 1 })
+        ));
+
+        store.sync().await.unwrap();
+    }
+
+    // The persisted default index still leaves them out after a restart
+    let store = VectorStore::new(temp_dir.path()).await.unwrap();
+    assert_eq!(ids(store.query(&query(vec![0.0, 0.0], None, false)).await.unwrap()), vec![full.id]);
+    assert_eq!(
+        ids(store.query(&query(vec![0.0, 0.0, 0.0], Some("title"), false)).await.unwrap()),
+        vec![titled.id, full.id, upserted.id]
+    );
+}
+
+#[tokio::test]
+async fn test_multivector_max_sim_rescore_and_brute_force() {
+    use vectordb_common::filter::{Condition, FieldCondition, Filter, MatchKeyword, MatchValue};
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = CollectionConfig {
+        name: "passages".to_string(),
+        dimension: 2,
+        distance_metric: DistanceMetric::Euclidean,
+        vector_type: VectorType::Float32,
+        index_config: IndexConfig::default(),
+        quantization: None,
+        sparse_vectors: None,
+        vectors: None,
+        multivectors: Some(
+            vec![("tokens".to_string(), MultiVectorParams {
+                dimension: 2,
+                distance_metric: DistanceMetric::DotProduct,
+            })]
+            .into_iter()
+            .collect(),
+        ),
+    };
+
+    let point = |x: f32, lang: &str, tokens: Option<Vec<Vec<f32>>>| Vector {
+        id: Uuid::new_v4(),
+        data: vec![x, 0.0],
+        metadata: Some(vec![("lang".to_string(), serde_json::json!(lang))].into_iter().collect()),
+        sparse: None,
+        vectors: None,
+        multivectors: tokens.map(|t| vec![("tokens".to_string(), t)].into_iter().collect()),
+    };
+    // Dense order from the origin is a, b, c, d; MaxSim against the query
+    // below is a = 1, b = 3, c = 2 and d = 5, while e has no tokens at all
+    let a = point(0.1, "en", Some(vec![vec![1.0, 0.0]]));
+    let b = point(0.2, "en", Some(vec![vec![1.0, 0.0], vec![0.0, 2.0]]));
+    let c = point(0.3, "de", Some(vec![vec![2.0, 0.0]]));
+    let d = point(9.0, "en", Some(vec![vec![2.0, 3.0]]));
+    let e = point(0.0, "en", None);
+
+    let store = VectorStore::new(temp_dir.path()).await.unwrap();
+    store.create_collection(&config).await.unwrap();
+    store.batch_insert("passages", &[a.clone(), b.clone(), c.clone(), d.clone(), e.clone()]).await.unwrap();
+
+    let query = |mode: MultiVectorMode, limit: usize, filter: Option<Filter>| QueryRequest {
+        collection: "passages".to_string(),
+        vector: vec![0.0, 0.0],
+        limit,
+        ef_search: None,
+        filter,
+        using: None,
+        multivector: Some(MultiVectorQuery {
+            using: "tokens".to_string(),
+            vectors: vec![vec![1.0, 0.0], vec![0.0, 1.0]],
+            mode,
+        }),
+        rescore: None,
+        oversampling: None,
+        exact: false,
+    };
+    let ids = |results: Vec<QueryResult>| results.into_iter().map(|r| r.id).collect::<Vec<_>>();
+
+    // Rescoring reorders the dense candidates (e, a, b and c for one result);
+    // d would score best but is too far away to be one
+    let rescored = store.query(&query(MultiVectorMode::Rescore, 1, None)).await.unwrap();
+    assert_eq!(ids(rescored.clone()), vec![b.id]);
+    assert!((rescored[0].distance + 3.0).abs() < 1e-6);
+
+    // Brute force scores every point that has tokens
+    let scanned = store.query(&query(MultiVectorMode::BruteForce, 10, None)).await.unwrap();
+    assert_eq!(ids(scanned), vec![d.id, b.id, c.id, a.id]);
+
+    let english = Filter::Must(vec![Condition::Match(FieldCondition::MatchKeyword(MatchKeyword {
+        key: "lang".to_string(),
+        value: MatchValue::Keyword("en".to_string()),
+    }))]);
+    let mut brute_force = query(MultiVectorMode::BruteForce, 2, Some(english));
+    brute_force.vector = Vec::new();
+    assert_eq!(ids(store.query(&brute_force).await.unwrap()), vec![d.id, b.id]);
+
+    // Query and stored rows are checked against the space's dimension
+    let mut wrong = query(MultiVectorMode::BruteForce, 2, None);
+    wrong.multivector.as_mut().unwrap().vectors = vec![vec![1.0, 0.0, 0.0]];
+    assert!(matches!(
+        store.query(&wrong).await,
+        Err(vectordb_common::VectorDbError::InvalidDimension { expected: 2, actual: 3 })
+    ));
+    let ragged = point(1.0, "en", Some(vec![vec![1.0, 0.0], vec![1.0]]));
+    assert!(matches!(
+        store.insert("passages", &ragged).await,
+        Err(vectordb_common::VectorDbError::InvalidDimension { expected: 2, actual: 1 })
+    ));
+    let empty = point(1.0, "en", Some(Vec::new()));
+    assert!(matches!(
+        store.insert("passages", &empty).await,
+        Err(vectordb_common::VectorDbError::InvalidInput { .. })
+    ));
+
+    let stored = store.get("passages", &b.id).await.unwrap().unwrap();
+    assert_eq!(stored.multivectors, b.multivectors);
+}
+
+#[tokio::test]
+async fn test_scalar_quantized_collection_rescores_with_originals() {
+    use vectordb_common::quantization::{QuantizationConfig, ScalarQuantizationConfig, ScalarType};
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = CollectionConfig {
+        name: "quantized".to_string(),
+        dimension: 2,
+        distance_metric: DistanceMetric::Euclidean,
+        vector_type: VectorType::Float32,
+        index_config: IndexConfig::default(),
+        quantization: Some(QuantizationConfig::Scalar(ScalarQuantizationConfig {
+            quantization_type: ScalarType::Int8,
+            always_ram: true,
+            fit_threshold: 100,
+        })),
+        sparse_vectors: None,
+        vectors: None,
+        multivectors: None,
+    };
+
+    // A 20x20 grid spanning [0, 100], so one code step is about 0.4
+    let points: Vec<Vector> = (0..400)
+        .map(|i| Vector {
+            id: Uuid::new_v4(),
+            data: vec![(i % 20) as f32 * 100.0 / 19.0, (i / 20) as f32 * 100.0 / 19.0],
+            metadata: None,
+            sparse: None,
+            vectors: None,
+            multivectors: None,
+        })
+        .collect();
+
+    let store = VectorStore::new(temp_dir.path()).await.unwrap();
+    store.create_collection(&config).await.unwrap();
+    store.batch_insert("quantized", &points).await.unwrap();
+
+    let query_vector = vec![42.3, 57.9];
+    let mut expected: Vec<(Uuid, f32)> = points
+        .iter()
+        .map(|p| (p.id, vectordb_common::distance::euclidean_distance(&query_vector, &p.data)))
+        .collect();
+    expected.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+    let query = |rescore: Option<bool>, oversampling: Option<f32>| QueryRequest {
+        collection: "quantized".to_string(),
+        vector: query_vector.clone(),
+        limit: 5,
+        ef_search: None,
+        filter: None,
+        using: None,
+        multivector: None,
+        rescore,
+        oversampling,
+        exact: false,
+    };
+
+    // Rescored results carry the exact distances of the original vectors
+    let rescored = store.query(&query(None, Some(3.0))).await.unwrap();
+    assert_eq!(rescored.len(), 5);
+    assert_eq!(rescored[0].id, expected[0].0);
+    for result in &rescored {
+        let exact = expected.iter().find(|(id, _)| *id == result.id).unwrap().1;
+        assert_eq!(result.distance, exact);
+    }
+
+    // Without rescoring the distances come from the int8 codes
+    let approximate = store.query(&query(Some(false), None)).await.unwrap();
+    assert_eq!(approximate.len(), 5);
+    assert!((approximate[0].distance - expected[0].1).abs() < 1.0);
+    assert!(approximate.iter().any(|r| {
+        let exact = expected.iter().find(|(id, _)| *id == r.id).unwrap().1;
+        r.distance != exact
+    }));
+
+    assert!(store.query(&query(None, Some(0.5))).await.is_err());
+
+    // Only the codes are held in memory, and they survive a restart
+    let stats = store.get_collection_stats("quantized").await.unwrap().unwrap();
+    assert_eq!(stats.memory.vectors, 0);
+    assert_eq!(stats.memory.quantized, 400 * 2);
+
+    store.persist_index("quantized").await.unwrap();
+    drop(store);
+    let store = VectorStore::new(temp_dir.path()).await.unwrap();
+    let reloaded = store.query(&query(None, Some(3.0))).await.unwrap();
+    assert_eq!(reloaded[0].id, expected[0].0);
+    let stats = store.get_collection_stats("quantized").await.unwrap().unwrap();
+    assert_eq!(stats.memory.quantized, 400 * 2);
+}
+
+#[tokio::test]
+async fn test_scalar_quantizer_fitted_to_points_inserted_one_at_a_time() {
+    use vectordb_common::quantization::{QuantizationConfig, ScalarQuantizationConfig, ScalarType};
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = CollectionConfig {
+        name: "trickle".to_string(),
+        dimension: 2,
+        distance_metric: DistanceMetric::Euclidean,
+        vector_type: VectorType::Float32,
+        index_config: IndexConfig::default(),
+        quantization: Some(QuantizationConfig::Scalar(ScalarQuantizationConfig {
+            quantization_type: ScalarType::Int8,
+            always_ram: true,
+            fit_threshold: 100,
+        })),
+        sparse_vectors: None,
+        vectors: None,
+        multivectors: None,
+    };
+
+    // The 20x20 grid over [0, 100] again, in a scattered order
+    let points: Vec<Vector> = (0..400)
+        .map(|i| (i * 131) % 400)
+        .map(|i| Vector {
+            id: Uuid::new_v4(),
+            data: vec![(i % 20) as f32 * 100.0 / 19.0, (i / 20) as f32 * 100.0 / 19.0],
+            metadata: None,
+            sparse: None,
+            vectors: None,
+            multivectors: None,
+        })
+        .collect();
+
+    let store = VectorStore::new(temp_dir.path()).await.unwrap();
+    store.create_collection(&config).await.unwrap();
+    for point in &points {
+        store.insert("trickle", point).await.unwrap();
+    }
+
+    let query_vector = vec![42.3, 57.9];
+    let expected = points
+        .iter()
+        .map(|p| (p.id, vectordb_common::distance::euclidean_distance(&query_vector, &p.data)))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .unwrap();
+
+    // The codes span the whole grid rather than the first point, so the
+    // approximate distances stay close to the exact ones
+    let approximate = store
+        .query(&QueryRequest {
+            collection: "trickle".to_string(),
+            vector: query_vector.clone(),
+            limit: 5,
+            ef_search: None,
+            filter: None,
+            using: None,
+            multivector: None,
+            rescore: Some(false),
+            oversampling: None,
+            exact: false,
+        })
+        .await
+        .unwrap();
+    assert_eq!(approximate[0].id, expected.0);
+    assert!((approximate[0].distance - expected.1).abs() < 1.0);
+
+    let stats = store.get_collection_stats("trickle").await.unwrap().unwrap();
+    assert_eq!(stats.memory.vectors, 0);
+    assert_eq!(stats.memory.quantized, 400 * 2);
+}
+
+#[tokio::test]
+async fn test_product_quantized_collection_trains_codebooks() {
+    use vectordb_common::quantization::{ProductQuantizationConfig, QuantizationConfig, TrainingStatus};
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let pq_config = ProductQuantizationConfig {
+        num_segments: 2,
+        num_centroids: 16,
+        compression: true,
+        always_ram: false,
+        training_threshold: 200,
+        training_sample: 100,
+    };
+    let config = CollectionConfig {
+        name: "pq".to_string(),
+        dimension: 4,
+        distance_metric: DistanceMetric::Euclidean,
+        vector_type: VectorType::Float32,
+        index_config: IndexConfig::default(),
+        quantization: Some(QuantizationConfig::Product(pq_config.clone())),
+        sparse_vectors: None,
+        vectors: None,
+        multivectors: None,
+    };
+
+    let points: Vec<Vector> = (0..300)
+        .map(|i| Vector {
+            id: Uuid::new_v4(),
+            data: vec![(i % 10) as f32, (i / 10 % 10) as f32, (i / 100) as f32, (i % 7) as f32],
+            metadata: None,
+            sparse: None,
+            vectors: None,
+            multivectors: None,
+        })
+        .collect();
+
+    let store = VectorStore::new(temp_dir.path()).await.unwrap();
+
+    // Segments must divide the dimension
+    let mut invalid = config.clone();
+    invalid.name = "invalid".to_string();
+    invalid.quantization = Some(QuantizationConfig::Product(ProductQuantizationConfig {
+        num_segments: 3,
+        ..pq_config.clone()
+    }));
+    assert!(store.create_collection(&invalid).await.is_err());
+
+    store.create_collection(&config).await.unwrap();
+    store.batch_insert("pq", &points[..150]).await.unwrap();
+    let stats = store.get_collection_stats("pq").await.unwrap().unwrap();
+    assert_eq!(stats.quantization, Some(TrainingStatus::Untrained { vectors: 150, threshold: 200 }));
+    assert!(!temp_dir.path().join("pq").join("pq_codebooks.bin").exists());
+
+    // Crossing the threshold trains, in the background, on every third of
+    // the 250 vectors and encodes everything stored
+    store.batch_insert("pq", &points[150..250]).await.unwrap();
+    let mut stats = store.get_collection_stats("pq").await.unwrap().unwrap();
+    for _ in 0..500 {
+        if matches!(stats.quantization, Some(TrainingStatus::Trained { .. })) {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        stats = store.get_collection_stats("pq").await.unwrap().unwrap();
+    }
+    assert_eq!(stats.quantization, Some(TrainingStatus::Trained { sample_size: 84 }));
+    assert!(temp_dir.path().join("pq").join("pq_codebooks.bin").exists());
+    assert_eq!(stats.memory.vectors, 0);
+
+    // New vectors are encoded with the trained codebooks
+    store.batch_insert("pq", &points[250..]).await.unwrap();
+
+    let query_vector = vec![3.2, 6.9, 1.1, 4.0];
+    let nearest = points
+        .iter()
+        .min_by(|a, b| {
+            let da = vectordb_common::distance::euclidean_distance(&query_vector, &a.data);
+            let db = vectordb_common::distance::euclidean_distance(&query_vector, &b.data);
+            da.total_cmp(&db)
+        })
+        .unwrap();
+    let query = QueryRequest {
+        collection: "pq".to_string(),
+        vector: query_vector.clone(),
+        limit: 5,
+        ef_search: None,
+        filter: None,
+        using: None,
+        multivector: None,
+        rescore: None,
+        oversampling: Some(10.0),
+        exact: false,
+    };
+    let results = store.query(&query).await.unwrap();
+    assert_eq!(results.len(), 5);
+    assert_eq!(results[0].id, nearest.id);
+
+    // Retraining on demand dumps the re-encoded index, which is reloaded
+    // after a restart. The background training reports the codebooks as
+    // soon as they are in use, and is only done once its own dump is written.
+    let mut retrained = store.train_quantizer("pq").await;
+    for _ in 0..500 {
+        if !matches!(retrained, Err(vectordb_common::VectorDbError::InvalidInput { .. })) {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        retrained = store.train_quantizer("pq").await;
+    }
+    let status = retrained.unwrap();
+    assert_eq!(status, TrainingStatus::Trained { sample_size: 100 });
+    assert!(temp_dir.path().join("pq").join("hnsw.dump").exists());
+
+    drop(store);
+    let store = VectorStore::new(temp_dir.path()).await.unwrap();
+    let stats = store.get_collection_stats("pq").await.unwrap().unwrap();
+    assert_eq!(stats.quantization, Some(TrainingStatus::Trained { sample_size: 100 }));
+    let results = store.query(&query).await.unwrap();
+    assert_eq!(results[0].id, nearest.id);
+
+    let mut plain = config.clone();
+    plain.name = "plain".to_string();
+    plain.quantization = None;
+    store.create_collection(&plain).await.unwrap();
+    assert!(store.train_quantizer("plain").await.is_err());
+    let stats = store.get_collection_stats("plain").await.unwrap().unwrap();
+    assert_eq!(stats.quantization, None);
+}
+
+#[tokio::test]
+async fn test_binary_quantized_collection_rescores_hamming_candidates() {
+    use vectordb_common::quantization::{BinaryQuantizationConfig, QuantizationConfig};
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let binary_config = BinaryQuantizationConfig {
+        always_ram: true,
+        oversampling: 4.0,
+    };
+    let config = CollectionConfig {
+        name: "binary".to_string(),
+        dimension: 128,
+        distance_metric: DistanceMetric::Cosine,
+        vector_type: VectorType::Float32,
+        index_config: IndexConfig::default(),
+        quantization: Some(QuantizationConfig::Binary(binary_config.clone())),
+        sparse_vectors: None,
+        vectors: None,
+        multivectors: None,
+    };
+    let mut mapped = config.clone();
+    mapped.name = "binary_mapped".to_string();
+    mapped.quantization = Some(QuantizationConfig::Binary(BinaryQuantizationConfig {
+        always_ram: false,
+        ..binary_config.clone()
+    }));
+
+    // Deterministic noise in [-1, 1)
+    let points: Vec<Vector> = (0..500)
+        .map(|i| Vector {
+            id: Uuid::new_v4(),
+            data: (0..128)
+                .map(|d| ((((i * 128 + d) as f32) * 12.9898).sin() * 43758.547).fract())
+                .collect(),
+            metadata: None,
+            sparse: None,
+            vectors: None,
+            multivectors: None,
+        })
+        .collect();
+
+    let store = VectorStore::new(temp_dir.path()).await.unwrap();
+
+    let mut invalid = config.clone();
+    invalid.name = "invalid".to_string();
+    invalid.quantization = Some(QuantizationConfig::Binary(BinaryQuantizationConfig {
+        oversampling: 0.5,
+        ..binary_config.clone()
+    }));
+    assert!(store.create_collection(&invalid).await.is_err());
+
+    store.create_collection(&config).await.unwrap();
+    store.create_collection(&mapped).await.unwrap();
+    store.batch_insert("binary", &points).await.unwrap();
+    store.batch_insert("binary_mapped", &points).await.unwrap();
+
+    let query_vector: Vec<f32> = points[123].data.iter().map(|v| v * 0.9 + 0.01).collect();
+    let exact = |id: Uuid| {
+        let point = points.iter().find(|p| p.id == id).unwrap();
+        1.0 - vectordb_common::distance::cosine_similarity(&query_vector, &point.data)
+    };
+    let query = |collection: &str, rescore: Option<bool>| QueryRequest {
+        collection: collection.to_string(),
+        vector: query_vector.clone(),
+        limit: 5,
+        ef_search: None,
+        filter: None,
+        using: None,
+        multivector: None,
+        rescore,
+        oversampling: None,
+        exact: false,
+    };
+
+    // Hamming candidates are rescored with the original vectors
+    for collection in ["binary", "binary_mapped"] {
+        let results = store.query(&query(collection, None)).await.unwrap();
+        assert_eq!(results.len(), 5);
+        assert_eq!(results[0].id, points[123].id);
+        for result in &results {
+            assert!((result.distance - exact(result.id)).abs() < 1e-5);
+        }
+    }
+
+    // Without rescoring the distances are Hamming distances between bit codes
+    let hamming = store.query(&query("binary", Some(false))).await.unwrap();
+    assert_eq!(hamming.len(), 5);
+    assert!(hamming.iter().all(|r| r.distance.fract() == 0.0 && r.distance <= 128.0));
+    assert!(hamming.windows(2).all(|w| w[0].distance <= w[1].distance));
+
+    // Codes take one bit per dimension, and none of it on the heap when mapped
+    let stats = store.get_collection_stats("binary").await.unwrap().unwrap();
+    assert_eq!(stats.vector_count, 500);
+    assert_eq!(stats.memory.vectors, 0);
+    assert_eq!(stats.memory.quantized, 500 * 16);
+    let stats = store.get_collection_stats("binary_mapped").await.unwrap().unwrap();
+    assert_eq!(stats.vector_count, 500);
+    assert_eq!(stats.memory.quantized, 0);
+
+    // Codes are dumped and reloaded after a restart
+    for collection in ["binary", "binary_mapped"] {
+        store.persist_index(collection).await.unwrap();
+        assert!(temp_dir.path().join(collection).join("hnsw.dump").exists());
+    }
+    store.delete("binary_mapped", &points[123].id).await.unwrap();
+    store.sync().await.unwrap();
+    drop(store);
+    let store = VectorStore::new(temp_dir.path()).await.unwrap();
+    let results = store.query(&query("binary", None)).await.unwrap();
+    assert_eq!(results[0].id, points[123].id);
+    let results = store.query(&query("binary_mapped", None)).await.unwrap();
+    assert!(results.iter().all(|r| r.id != points[123].id));
+    let stats = store.get_collection_stats("binary").await.unwrap().unwrap();
+    assert_eq!(stats.memory.quantized, 500 * 16);
+    let stats = store.get_collection_stats("binary_mapped").await.unwrap().unwrap();
+    assert_eq!((stats.vector_count, stats.memory.quantized), (499, 0));
+}
+
+#[tokio::test]
+async fn test_float16_and_int8_collections_store_packed_vectors() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = |name: &str, vector_type: VectorType| CollectionConfig {
+        name: name.to_string(),
+        dimension: 64,
+        distance_metric: DistanceMetric::Euclidean,
+        vector_type,
+        index_config: IndexConfig::default(),
+        quantization: None,
+        sparse_vectors: None,
+        vectors: None,
+        multivectors: None,
+    };
+    let collections = [
+        ("float32", VectorType::Float32),
+        ("float16", VectorType::Float16),
+        ("int8", VectorType::Int8),
+    ];
+
+    // Deterministic noise in [-1, 1), enough of it for the int8 index to fit
+    // its quantizer to
+    let points: Vec<Vector> = (0..256)
+        .map(|i| Vector {
+            id: Uuid::new_v4(),
+            data: (0..64)
+                .map(|d| ((((i * 64 + d) as f32) * 12.9898).sin() * 43758.547).fract())
+                .collect(),
+            metadata: None,
+            sparse: None,
+            vectors: None,
+            multivectors: None,
+        })
+        .collect();
+
+    let store = VectorStore::new(temp_dir.path()).await.unwrap();
+    for (name, vector_type) in collections {
+        store.create_collection(&config(name, vector_type)).await.unwrap();
+        store.batch_insert(name, &points).await.unwrap();
+    }
+
+    let query = |collection: &str, rescore: Option<bool>| QueryRequest {
+        collection: collection.to_string(),
+        vector: points[42].data.clone(),
+        limit: 5,
+        ef_search: None,
+        filter: None,
+        using: None,
+        multivector: None,
+        rescore,
+        oversampling: None,
+        exact: false,
+    };
+    let euclidean = |a: &[f32], b: &[f32]| {
+        a.iter().zip(b).map(|(x, y)| (x - y).powi(2)).sum::<f32>().sqrt()
+    };
+
+    for (name, tolerance) in [("float32", 0.0), ("float16", 1e-3), ("int8", 1.0 / 127.0)] {
+        // Vectors read back rounded to the element type
+        let stored = store.get(name, &points[42].id).await.unwrap().unwrap();
+        for (stored, original) in stored.data.iter().zip(&points[42].data) {
+            assert!((stored - original).abs() <= tolerance, "{}: {} vs {}", name, stored, original);
+        }
+
+        let results = store.query(&query(name, None)).await.unwrap();
+        assert_eq!(results.len(), 5);
+        assert_eq!(results[0].id, points[42].id, "{}", name);
+    }
+
+    // Int8 candidates come from the index's codes and are rescored with the
+    // stored vectors
+    let results = store.query(&query("int8", None)).await.unwrap();
+    for result in &results {
+        let stored = store.get("int8", &result.id).await.unwrap().unwrap();
+        assert!((result.distance - euclidean(&points[42].data, &stored.data)).abs() < 1e-5);
+    }
+
+    // The index holds two bytes per component for Float16 and one for Int8
+    let stats = store.get_collection_stats("float16").await.unwrap().unwrap();
+    assert_eq!(stats.memory.vectors, 256 * 64 * 2);
+    let stats = store.get_collection_stats("int8").await.unwrap().unwrap();
+    assert_eq!(stats.memory.vectors, 0);
+    assert_eq!(stats.memory.quantized, 256 * 64);
+
+    // And so does the data file
+    let mut data_bytes = std::collections::HashMap::new();
+    for (name, _) in collections {
+        let report = store.compact_collection(name).await.unwrap();
+        data_bytes.insert(name, report.bytes_after);
+    }
+    // Each record saves two or three bytes per component, less a few bytes
+    // of packing overhead
+    assert!(data_bytes["float32"] - data_bytes["float16"] >= 256 * (64 * 2 - 16));
+    assert!(data_bytes["float32"] - data_bytes["int8"] >= 256 * (64 * 3 - 16));
+
+    // Packed records and the half precision index survive a restart
+    store.persist_index("float16").await.unwrap();
+    drop(store);
+    let store = VectorStore::new(temp_dir.path()).await.unwrap();
+    for (name, _) in collections {
+        let results = store.query(&query(name, None)).await.unwrap();
+        assert_eq!(results[0].id, points[42].id, "{}", name);
+    }
+    let stats = store.get_collection_stats("float16").await.unwrap().unwrap();
+    assert_eq!(stats.vector_count, 256);
+    assert_eq!(stats.memory.vectors, 256 * 64 * 2);
+}
+
+#[tokio::test]
+async fn test_flat_and_ivf_index_types() {
+    use vectordb_common::quantization::{QuantizationConfig, TrainingStatus};
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let config = |name: &str, index_config: serde_json::Value| CollectionConfig {
+        name: name.to_string(),
+        dimension: 16,
+        distance_metric: DistanceMetric::Cosine,
+        vector_type: VectorType::Float32,
+        index_config: serde_json::from_value(index_config).unwrap(),
+        quantization: None,
+        sparse_vectors: None,
+        vectors: None,
+        multivectors: None,
+    };
+    let hnsw = serde_json::json!({"max_connections": 16, "ef_construction": 200, "ef_search": 50, "max_layer": 16});
+    let with = |extra: serde_json::Value| {
+        let mut index_config = hnsw.clone();
+        index_config.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+        index_config
+    };
+    let ivf = serde_json::json!({"nlist": 16, "nprobe": 4, "training_threshold": 300});
+    let collections = [
+        config("flat", with(serde_json::json!({"index_type": "flat"}))),
+        config("ivf_flat", with(serde_json::json!({"index_type": "ivf_flat", "ivf": ivf}))),
+        config("ivf_pq", with(serde_json::json!({"index_type": "ivf_pq", "ivf": ivf}))),
+    ];
+
+    let points: Vec<Vector> = (0..600)
+        .map(|i| Vector {
+            id: Uuid::new_v4(),
+            data: (0..16)
+                .map(|d| ((((i * 16 + d) as f32) * 12.9898).sin() * 43758.547).fract())
+                .collect(),
+            metadata: None,
+            sparse: None,
+            vectors: None,
+            multivectors: None,
+        })
+        .collect();
+
+    let store = VectorStore::new(temp_dir.path()).await.unwrap();
+
+    // IVF-PQ segments must divide the dimension, and quantization picks its own index
+    let mut invalid = config("invalid", with(serde_json::json!({"index_type": "ivf_pq", "ivf": {"pq_segments": 5}})));
+    assert!(store.create_collection(&invalid).await.is_err());
+    invalid.index_config.index_type = IndexType::Flat;
+    invalid.quantization = Some(serde_json::from_value::<QuantizationConfig>(serde_json::json!({"type": "scalar"})).unwrap());
+    assert!(store.create_collection(&invalid).await.is_err());
+
+    for config in &collections {
+        store.create_collection(config).await.unwrap();
+        store.batch_insert(&config.name, &points).await.unwrap();
+    }
+
+    let query_vector: Vec<f32> = points[123].data.iter().map(|v| v * 0.9 + 0.01).collect();
+    let mut exact: Vec<(Uuid, f32)> = points
+        .iter()
+        .map(|p| (p.id, 1.0 - vectordb_common::distance::cosine_similarity(&query_vector, &p.data)))
+        .collect();
+    exact.sort_by(|a, b| a.1.total_cmp(&b.1));
+    let query = |collection: &str| QueryRequest {
+        collection: collection.to_string(),
+        vector: query_vector.clone(),
+        limit: 10,
+        ef_search: None,
+        filter: None,
+        using: None,
+        multivector: None,
+        rescore: None,
+        oversampling: None,
+        exact: false,
+    };
+
+    // The flat index is exact
+    let results = store.query(&query("flat")).await.unwrap();
+    let ids: Vec<Uuid> = results.iter().map(|r| r.id).collect();
+    assert_eq!(ids, exact[..10].iter().map(|(id, _)| *id).collect::<Vec<_>>());
+    for (result, (_, distance)) in results.iter().zip(&exact) {
+        assert!((result.distance - distance).abs() < 1e-5);
+    }
+
+    // IVF indexes train once they reach the threshold; IVF-PQ candidates are
+    // rescored with the stored vectors
+    for collection in ["ivf_flat", "ivf_pq"] {
+        let stats = store.get_collection_stats(collection).await.unwrap().unwrap();
+        assert_eq!(stats.quantization, Some(TrainingStatus::Trained { sample_size: 300 }));
+
+        let results = store.query(&query(collection)).await.unwrap();
+        assert_eq!(results.len(), 10);
+        assert_eq!(results[0].id, points[123].id, "{}", collection);
+        assert!((results[0].distance - exact[0].1).abs() < 1e-5, "{}", collection);
+    }
+    let stats = store.get_collection_stats("ivf_pq").await.unwrap().unwrap();
+    assert_eq!(stats.memory.vectors, 0);
+
+    // Flat and IVF indexes are dumped and reloaded rather than rebuilt
+    store.persist_indexes().await.unwrap();
+    drop(store);
+    for config in &collections {
+        assert!(temp_dir.path().join(&config.name).join("hnsw.dump").exists());
+    }
+    let store = VectorStore::new(temp_dir.path()).await.unwrap();
+    for config in &collections {
+        let results = store.query(&query(&config.name)).await.unwrap();
+        assert_eq!(results[0].id, points[123].id, "{}", config.name);
+        let stats = store.get_collection_stats(&config.name).await.unwrap().unwrap();
+        assert_eq!(stats.vector_count, 600);
+    }
+    let stats = store.get_collection_stats("ivf_pq").await.unwrap().unwrap();
+    assert_eq!(stats.quantization, Some(TrainingStatus::Trained { sample_size: 300 }));
+}
+
+#[tokio::test]
+async fn test_exact_query_matches_brute_force() {
+    use vectordb_common::filter::{Condition, FieldCondition, Filter, MatchKeyword, MatchValue};
+    use vectordb_common::quantization::QuantizationConfig;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let store = VectorStore::new(temp_dir.path()).await.unwrap();
+    let config = CollectionConfig {
+        name: "exact".to_string(),
+        dimension: 16,
+        distance_metric: DistanceMetric::Euclidean,
+        vector_type: VectorType::Float32,
+        index_config: IndexConfig::default(),
+        quantization: Some(serde_json::from_value::<QuantizationConfig>(serde_json::json!({"type": "scalar"})).unwrap()),
+        sparse_vectors: None,
+        vectors: Some(std::collections::BTreeMap::from([("title".to_string(), VectorParams {
+            dimension: 4,
+            distance_metric: DistanceMetric::Cosine,
+            index_config: None,
+        })])),
+        multivectors: None,
+    };
+    store.create_collection(&config).await.unwrap();
+
+    // Enough points that a scan reads storage in several chunks
+    let value = |i: usize, d: usize| ((((i * 16 + d) as f32) * 12.9898).sin() * 43758.547).fract();
+    let points: Vec<Vector> = (0..1500)
+        .map(|i| Vector {
+            id: Uuid::new_v4(),
+            data: (0..16).map(|d| value(i, d)).collect(),
+            metadata: Some(std::collections::HashMap::from([(
+                "parity".to_string(),
+                serde_json::json!(if i % 2 == 0 { "even" } else { "odd" }),
+            )])),
+            sparse: None,
+            // Every third point leaves the named space out
+            vectors: (i % 3 != 0).then(|| {
+                std::collections::BTreeMap::from([("title".to_string(), (0..4).map(|d| value(i + 1000, d)).collect())])
+            }),
+            multivectors: None,
+        })
+        .collect();
+    store.batch_insert("exact", &points).await.unwrap();
+
+    let query_vector: Vec<f32> = points[7].data.iter().map(|v| v * 0.5).collect();
+    let query = |filter: Option<Filter>| QueryRequest {
+        collection: "exact".to_string(),
+        vector: query_vector.clone(),
+        limit: 20,
+        ef_search: None,
+        filter,
+        using: None,
+        multivector: None,
+        rescore: Some(false),
+        oversampling: None,
+        exact: true,
+    };
+    let brute_force = |keep: &dyn Fn(usize) -> bool| {
+        let mut expected: Vec<(Uuid, f32)> = points
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| keep(*i))
+            .map(|(_, p)| (p.id, vectordb_common::distance::euclidean_distance(&query_vector, &p.data)))
+            .collect();
+        expected.sort_by(|a, b| a.1.total_cmp(&b.1));
+        expected.truncate(20);
+        expected
+    };
+    let check = |results: Vec<QueryResult>, expected: Vec<(Uuid, f32)>| {
+        assert_eq!(results.len(), expected.len());
+        for (result, (id, distance)) in results.iter().zip(&expected) {
+            assert_eq!(result.id, *id);
+            assert!((result.distance - distance).abs() < 1e-5);
+            assert!(result.metadata.is_some());
+        }
+    };
+
+    // Exact queries ignore quantization and rescoring and match a full scan
+    check(store.query(&query(None)).await.unwrap(), brute_force(&|_| true));
+
+    // The filter keeps the semantics it has in an index search
+    let even = Filter::Must(vec![Condition::Match(FieldCondition::MatchKeyword(MatchKeyword {
+        key: "parity".to_string(),
+        value: MatchValue::Keyword("even".to_string()),
+    }))]);
+    check(store.query(&query(Some(even.clone()))).await.unwrap(), brute_force(&|i| i % 2 == 0));
+    let ann = store.query(&QueryRequest { exact: false, ..query(Some(even.clone())) }).await.unwrap();
+    assert!(ann.iter().all(|r| r.metadata.as_ref().unwrap()["parity"] == "even"));
+
+    // Named spaces are scanned with their own metric, skipping points without them
+    let title = vec![0.3, -0.2, 0.5, 0.1];
+    let mut named = query(None);
+    named.using = Some("title".to_string());
+    named.vector = title.clone();
+    let results = store.query(&named).await.unwrap();
+    let mut expected: Vec<(Uuid, f32)> = points
+        .iter()
+        .filter_map(|p| {
+            let data = p.vectors.as_ref()?.get("title")?;
+            Some((p.id, 1.0 - vectordb_common::distance::cosine_similarity(&title, data)))
+        })
+        .collect();
+    expected.sort_by(|a, b| a.1.total_cmp(&b.1));
+    expected.truncate(20);
+    check(results, expected);
+
+    // The query vector is still checked against the space's dimension
+    named.vector = query_vector.clone();
+    assert!(store.query(&named).await.is_err());
+}
+
+#[tokio::test]
+async fn test_evaluate_recall_against_exact_search() {
+    use vectordb_common::EvaluateRequest;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let store = VectorStore::new(temp_dir.path()).await.unwrap();
+    let config = |name: &str, index_type: IndexType| CollectionConfig {
+        name: name.to_string(),
+        dimension: 16,
+        distance_metric: DistanceMetric::Cosine,
+        vector_type: VectorType::Float32,
+        index_config: IndexConfig { index_type, ..IndexConfig::default() },
+        quantization: None,
+        sparse_vectors: None,
+        vectors: None,
+        multivectors: None,
+    };
+    let points: Vec<Vector> = (0..1000)
+        .map(|i| Vector {
+            id: Uuid::new_v4(),
+            data: (0..16)
+                .map(|d| ((((i * 16 + d) as f32) * 12.9898).sin() * 43758.547).fract())
+                .collect(),
+            metadata: None,
+            sparse: None,
+            vectors: None,
+            multivectors: None,
+        })
+        .collect();
+    for (name, index_type) in [("hnsw", IndexType::Hnsw), ("flat", IndexType::Flat)] {
+        store.create_collection(&config(name, index_type)).await.unwrap();
+        store.batch_insert(name, &points).await.unwrap();
+    }
+    let request = |collection: &str, ef_search: Vec<usize>| EvaluateRequest {
+        collection: collection.to_string(),
+        queries: Vec::new(),
+        sample: 40,
+        limit: 10,
+        ef_search,
+        using: None,
+    };
+
+    let report = store.evaluate(&request("hnsw", vec![10, 200])).await.unwrap();
+    assert_eq!(report.queries, 40);
+    assert_eq!(report.limit, 10);
+    assert_eq!(report.runs.iter().map(|r| r.ef_search).collect::<Vec<_>>(), vec![10, 200]);
+    let (narrow, wide) = (&report.runs[0], &report.runs[1]);
+    assert!(wide.recall >= narrow.recall && wide.recall > 0.9, "recall {} / {}", narrow.recall, wide.recall);
+    assert!(wide.visited_mean > narrow.visited_mean);
+    assert!(narrow.latency.p50_ms <= narrow.latency.p99_ms && narrow.latency.p99_ms <= narrow.latency.max_ms);
+
+    // A flat index is exact and compares the query with every vector; the
+    // collection's own ef_search is used when none is given
+    let report = store.evaluate(&request("flat", Vec::new())).await.unwrap();
+    assert_eq!(report.runs.len(), 1);
+    assert_eq!(report.runs[0].ef_search, IndexConfig::default().ef_search);
+    assert_eq!(report.runs[0].recall, 1.0);
+    assert_eq!(report.runs[0].visited_max, 1000);
+
+    // Supplied queries replace the sample and are checked against the dimension
+    let mut supplied = request("hnsw", vec![50]);
+    supplied.queries = vec![vec![0.5; 16], vec![-0.5; 16]];
+    assert_eq!(store.evaluate(&supplied).await.unwrap().queries, 2);
+    supplied.queries = vec![vec![0.5; 3]];
+    assert!(store.evaluate(&supplied).await.is_err());
+
+    assert!(store.evaluate(&EvaluateRequest { limit: 0, ..request("hnsw", vec![50]) }).await.is_err());
+    store.create_collection(&config("empty", IndexType::Hnsw)).await.unwrap();
+    assert!(store.evaluate(&request("empty", vec![50])).await.is_err());
+}
+
+#[tokio::test]
+async fn test_export_streams_live_points_in_batches() {
+    use vectordb_common::filter::{Condition, FieldCondition, Filter, RangeCondition};
+    use vectordb_common::ExportRequest;
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let store = std::sync::Arc::new(VectorStore::new(temp_dir.path()).await.unwrap());
+    store
+        .create_collection(&CollectionConfig {
+            name: "dump".to_string(),
+            dimension: 2,
+            distance_metric: DistanceMetric::Euclidean,
+            vector_type: VectorType::Float32,
+            index_config: IndexConfig::default(),
+            quantization: None,
+            sparse_vectors: None,
+            vectors: None,
+            multivectors: None,
+        })
+        .await
+        .unwrap();
+    let points: Vec<Vector> = (0..50)
+        .map(|i| Vector {
+            id: Uuid::new_v4(),
+            data: vec![i as f32, 1.0],
+            metadata: Some([("n".to_string(), serde_json::json!(i))].into_iter().collect()),
+            sparse: None,
+            vectors: None,
+            multivectors: None,
+        })
+        .collect();
+    store.batch_insert("dump", &points).await.unwrap();
+
+    // Superseded and deleted records are skipped
+    let mut updated = points[3].clone();
+    updated.data = vec![-3.0, 1.0];
+    store.update("dump", &updated).await.unwrap();
+    store.delete("dump", &points[4].id).await.unwrap();
+
+    let export = |filter, with_vectors| ExportRequest {
+        collection: "dump".to_string(),
+        filter,
+        with_vectors,
+        with_payload: true,
+        batch_size: 8,
+    };
+
+    let mut batches = store.export(export(None, true));
+    let mut exported = Vec::new();
+    while let Some(batch) = batches.recv().await {
+        let batch = batch.unwrap();
+        assert!(!batch.is_empty() && batch.len() <= 8);
+        exported.extend(batch);
+    }
+    assert_eq!(exported.len(), 49);
+    assert!(exported.iter().all(|v| v.id != points[4].id));
+    let found = exported.iter().find(|v| v.id == points[3].id).unwrap();
+    assert_eq!(found.data, vec![-3.0, 1.0]);
+
+    let filter = Filter::Must(vec![Condition::Match(FieldCondition::Range(RangeCondition {
+        key: "n".to_string(),
+        gte: Some(40.0),
+        gt: None,
+        lte: None,
+        lt: None,
+    }))]);
+    let mut batches = store.export(export(Some(filter), false));
+    let mut exported = Vec::new();
+    while let Some(batch) = batches.recv().await {
+        exported.extend(batch.unwrap());
+    }
+    assert_eq!(exported.len(), 10);
+    assert!(exported.iter().all(|v| v.data.is_empty() && v.metadata.is_some()));
+
+    let mut missing = store.export(ExportRequest { collection: "missing".to_string(), ..export(None, true) });
+    assert!(missing.recv().await.unwrap().is_err());
+    assert!(missing.recv().await.is_none());
+}
+
+#[tokio::test]
+async fn test_scroll_cursor_is_stable_under_writes() {
+    use vectordb_common::{Direction, OrderBy, ScrollRequest, VectorDbError};
+
+    async fn scroll_values(store: &VectorStore, mut scroll: ScrollRequest) -> Vec<i64> {
+        let mut values = Vec::new();
+        loop {
+            let page = store.scroll(&scroll).await.unwrap();
+            values.extend(page.points.iter().map(|p| p.payload.as_ref().unwrap()["n"].as_i64().unwrap()));
+            match page.next_offset {
+                Some(offset) => scroll.offset = Some(offset),
+                None => return values,
+            }
+        }
+    }
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let store = VectorStore::new(temp_dir.path()).await.unwrap();
+    store
+        .create_collection(&CollectionConfig {
+            name: "pages".to_string(),
+            dimension: 2,
+            distance_metric: DistanceMetric::Euclidean,
+            vector_type: VectorType::Float32,
+            index_config: IndexConfig::default(),
+            quantization: None,
+            sparse_vectors: None,
+            vectors: None,
+            multivectors: None,
+        })
+        .await
+        .unwrap();
+    let point = |n: i64| Vector {
+        id: Uuid::new_v4(),
+        data: vec![n as f32, 1.0],
+        metadata: Some([("n".to_string(), serde_json::json!(n))].into_iter().collect()),
+        sparse: None,
+        vectors: None,
+        multivectors: None,
+    };
+    let points: Vec<Vector> = (0..30).map(point).collect();
+    store.batch_insert("pages", &points).await.unwrap();
+
+    let mut scroll = ScrollRequest {
+        collection: "pages".to_string(),
+        filter: None,
+        limit: 7,
+        offset: None,
+        with_payload: true,
+        with_vectors: false,
+        order_by: None,
+    };
+
+    // Writes between pages neither repeat nor skip the points that stay
+    let first = store.scroll(&scroll).await.unwrap();
+    assert_eq!(first.points.len(), 7);
+    let last_seen = first.points.last().unwrap().id;
+    let mut sorted: Vec<Uuid> = points.iter().map(|v| v.id).collect();
+    sorted.sort();
+    let deleted = sorted[20];
+    let deleted_n = points.iter().position(|v| v.id == deleted).unwrap() as i64;
+    store.delete("pages", &deleted).await.unwrap();
+    let extra: Vec<Vector> = (30..35).map(point).collect();
+    store.batch_insert("pages", &extra).await.unwrap();
+
+    let mut seen: Vec<Uuid> = first.points.iter().map(|p| p.id).collect();
+    scroll.offset = first.next_offset;
+    while scroll.offset.is_some() {
+        let page = store.scroll(&scroll).await.unwrap();
+        assert!(page.points.len() <= 7);
+        seen.extend(page.points.iter().map(|p| p.id));
+        scroll.offset = page.next_offset;
+    }
+    assert!(seen.windows(2).all(|w| w[0] < w[1]));
+    assert!(!seen.contains(&deleted));
+    assert!(sorted.iter().filter(|id| **id != deleted).all(|id| seen.contains(id)));
+    assert!(extra.iter().filter(|v| v.id > last_seen).all(|v| seen.contains(&v.id)));
+
+    // Ordering by a payload field needs an index on it
+    scroll.offset = None;
+    scroll.order_by = Some(OrderBy { key: "n".to_string(), direction: Direction::Desc });
+    assert!(matches!(store.scroll(&scroll).await, Err(VectorDbError::InvalidInput { .. })));
+    store.create_payload_index("pages", "n", PayloadFieldType::Integer).await.unwrap();
+
+    let desc = scroll_values(&store, scroll.clone()).await;
+    assert_eq!(desc, (0..35).rev().filter(|n| *n != deleted_n).collect::<Vec<_>>());
+    let asc = scroll_values(&store, ScrollRequest {
+        order_by: Some(OrderBy { key: "n".to_string(), direction: Direction::Asc }),
+        ..scroll.clone()
+    })
+    .await;
+    assert_eq!(asc, (0..35).filter(|n| *n != deleted_n).collect::<Vec<_>>());
+
+    // A point with an array value is listed once, by its smallest element
+    // ascending and its largest descending
+    let tagged: Vec<Vector> = (0..10)
+        .map(|n: i64| {
+            let ranks = if n % 2 == 0 { serde_json::json!([n + 100, n]) } else { serde_json::json!([n]) };
+            let mut vector = point(n);
+            vector.metadata.as_mut().unwrap().insert("ranks".to_string(), ranks);
+            vector
+        })
+        .collect();
+    let config = store.get_collection_config("pages").unwrap().unwrap();
+    store.create_collection(&CollectionConfig { name: "tagged".to_string(), ..config }).await.unwrap();
+    store.batch_insert("tagged", &tagged).await.unwrap();
+    store.create_payload_index("tagged", "ranks", PayloadFieldType::Integer).await.unwrap();
+    let by_ranks = |direction| ScrollRequest {
+        collection: "tagged".to_string(),
+        limit: 3,
+        order_by: Some(OrderBy { key: "ranks".to_string(), direction }),
+        ..scroll.clone()
+    };
+    assert_eq!(scroll_values(&store, by_ranks(Direction::Asc)).await, (0..10).collect::<Vec<_>>());
+    assert_eq!(scroll_values(&store, by_ranks(Direction::Desc)).await, vec![8, 6, 4, 2, 0, 9, 7, 5, 3, 1]);
+
+    // Cursors are opaque and tied to the ordering that issued them
+    let page = store.scroll(&scroll).await.unwrap();
+    let id_ordered = ScrollRequest { order_by: None, offset: page.next_offset, ..scroll.clone() };
+    assert!(matches!(store.scroll(&id_ordered).await, Err(VectorDbError::InvalidInput { .. })));
+    let garbage = ScrollRequest { offset: Some("not a cursor".to_string()), ..scroll };
+    assert!(matches!(store.scroll(&garbage).await, Err(VectorDbError::InvalidInput { .. })));
+}
+
+#[tokio::test]
+async fn test_payload_updates_by_id_and_filter_persist() {
+    use vectordb_common::filter::{Condition, FieldCondition, Filter, MatchKeyword, MatchValue, RangeCondition};
+    use vectordb_common::{
+        ClearPayloadRequest, CountRequest, DeletePayloadKeysRequest, PointSelector, SetPayloadRequest, VectorDbError,
+    };
+
+    let temp_dir = tempfile::tempdir().unwrap();
+    let points: Vec<Vector> = (0..40)
+        .map(|i| Vector {
+            id: Uuid::new_v4(),
+            data: vec![i as f32, 1.0],
+            metadata: Some(
+                [
+                    ("tag".to_string(), serde_json::json!(if i % 2 == 0 { "even" } else { "odd" })),
+                    ("n".to_string(), serde_json::json!(i)),
+                ]
+                .into_iter()
+                .collect(),
+            ),
+            sparse: None,
+            vectors: None,
+            multivectors: None,
+        })
+        .collect();
+    let tag_is = |tag: &str| {
+        Filter::Must(vec![Condition::Match(FieldCondition::MatchKeyword(MatchKeyword {
+            key: "tag".to_string(),
+            value: MatchValue::Keyword(tag.to_string()),
+        }))])
+    };
+    let count = |tag: &str| CountRequest {
+        collection: "retag".to_string(),
+        filter: Some(tag_is(tag)),
+        exact: true,
+    };
+    let query = |tag: &str| QueryRequest {
+        collection: "retag".to_string(),
+        vector: vec![0.0, 1.0],
+        limit: 40,
+        ef_search: None,
+        filter: Some(tag_is(tag)),
+        using: None,
+        multivector: None,
+        rescore: None,
+        oversampling: None,
+        exact: false,
+    };
+    let payload = |pairs: &[(&str, serde_json::Value)]| {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.clone())).collect()
+    };
+
+    {
+        let store = VectorStore::new(temp_dir.path()).await.unwrap();
+        store
+            .create_collection(&CollectionConfig {
+                name: "retag".to_string(),
+                dimension: 2,
+                distance_metric: DistanceMetric::Euclidean,
+                vector_type: VectorType::Float32,
+                index_config: IndexConfig::default(),
+                quantization: None,
+                sparse_vectors: None,
+                vectors: None,
+                multivectors: None,
+            })
+            .await
+            .unwrap();
+        store.batch_insert("retag", &points).await.unwrap();
+        store.create_payload_index("retag", "tag", PayloadFieldType::Keyword).await.unwrap();
+
+        // Set merges into the payload; IDs with no point are skipped
+        let set = SetPayloadRequest {
+            collection: "retag".to_string(),
+            points: PointSelector::Ids(vec![points[0].id, points[1].id, Uuid::new_v4()]),
+            payload: payload(&[("tag", serde_json::json!("hot")), ("extra", serde_json::json!(true))]),
+        };
+        assert_eq!(store.set_payload(&set).await.unwrap().updated, 2);
+        let first = store.get("retag", &points[0].id).await.unwrap().unwrap();
+        assert_eq!(first.data, points[0].data);
+        let metadata = first.metadata.unwrap();
+        assert_eq!(metadata["tag"], "hot");
+        assert_eq!(metadata["n"], 0);
+        assert_eq!(metadata["extra"], true);
+
+        // Both the payload index and the search index see the new payload
+        assert_eq!(store.count(&count("hot")).await.unwrap().count, 2);
+        assert_eq!(store.count(&count("even")).await.unwrap().count, 19);
+        let hits = store.query(&query("hot")).await.unwrap();
+        assert_eq!(hits.len(), 2);
+        assert!(hits.iter().all(|hit| hit.metadata.as_ref().unwrap()["extra"] == true));
+
+        // Filter selection: every odd point gets retagged
+        let retag = SetPayloadRequest {
+            collection: "retag".to_string(),
+            points: PointSelector::Filter(tag_is("odd")),
+            payload: payload(&[("tag", serde_json::json!("cold"))]),
+        };
+        assert_eq!(store.set_payload(&retag).await.unwrap().updated, 19);
+        assert_eq!(store.count(&count("odd")).await.unwrap().count, 0);
+        assert_eq!(store.count(&count("cold")).await.unwrap().count, 19);
+        assert_eq!(store.query(&query("cold")).await.unwrap().len(), 19);
+
+        // Overwrite replaces the payload wholesale
+        let overwrite = SetPayloadRequest {
+            collection: "retag".to_string(),
+            points: PointSelector::Ids(vec![points[2].id]),
+            payload: payload(&[("tag", serde_json::json!("new"))]),
+        };
+        assert_eq!(store.overwrite_payload(&overwrite).await.unwrap().updated, 1);
+        let metadata = store.get("retag", &points[2].id).await.unwrap().unwrap().metadata.unwrap();
+        assert_eq!(metadata.len(), 1);
+
+        let delete_keys = DeletePayloadKeysRequest {
+            collection: "retag".to_string(),
+            points: PointSelector::Ids(vec![points[0].id]),
+            keys: vec!["extra".to_string(), "missing".to_string()],
+        };
+        assert_eq!(store.delete_payload_keys(&delete_keys).await.unwrap().updated, 1);
+        let metadata = store.get("retag", &points[0].id).await.unwrap().unwrap().metadata.unwrap();
+        assert!(!metadata.contains_key("extra"));
+
+        // A filter the payload indexes cannot narrow pages through every point
+        let unindexed = DeletePayloadKeysRequest {
+            collection: "retag".to_string(),
+            points: PointSelector::Filter(Filter::Must(vec![Condition::Match(FieldCondition::Range(
+                RangeCondition { key: "n".to_string(), gte: Some(38.0), gt: None, lte: None, lt: None },
+            ))])),
+            keys: vec!["n".to_string()],
+        };
+        assert_eq!(store.delete_payload_keys(&unindexed).await.unwrap().updated, 2);
+        let metadata = store.get("retag", &points[39].id).await.unwrap().unwrap().metadata.unwrap();
+        assert_eq!(metadata["tag"], "cold");
+        assert!(!metadata.contains_key("n"));
+
+        let clear = ClearPayloadRequest {
+            collection: "retag".to_string(),
+            points: PointSelector::Ids(vec![points[4].id]),
+        };
+        assert_eq!(store.clear_payload(&clear).await.unwrap().updated, 1);
+        assert!(store.get("retag", &points[4].id).await.unwrap().unwrap().metadata.is_none());
+        assert_eq!(store.count(&count("even")).await.unwrap().count, 17);
+
+        let missing = ClearPayloadRequest { collection: "missing".to_string(), ..clear };
+        assert!(matches!(store.clear_payload(&missing).await, Err(VectorDbError::CollectionNotFound { .. })));
+
+        store.sync().await.unwrap();
+    }
+
+    // Every update survives a restart, in storage and in the indexes
+    let store = VectorStore::new(temp_dir.path()).await.unwrap();
+    assert_eq!(store.count(&count("cold")).await.unwrap().count, 19);
+    assert_eq!(store.count(&count("even")).await.unwrap().count, 17);
+    assert_eq!(store.query(&query("hot")).await.unwrap().len(), 2);
+    assert_eq!(store.query(&query("new")).await.unwrap().len(), 1);
+    assert!(store.get("retag", &points[4].id).await.unwrap().unwrap().metadata.is_none());
+    let stats = store.get_collection_stats("retag").await.unwrap().unwrap();
+    assert_eq!(stats.vector_count, 40);
+}